@@ -0,0 +1,25 @@
+#!/bin/bash
+#
+# Clanker Spanker - Demo gh
+# Stand-in for the gh CLI in demo mode: every command succeeds without
+# touching GitHub, and reads come back empty
+#
+
+for arg in "$@"; do
+  case "$arg" in
+    --jq | --log-failed) exit 0 ;;
+  esac
+done
+
+case "$1" in
+  api) echo '{}' ;;
+  *)
+    for arg in "$@"; do
+      if [ "$arg" = "--json" ]; then
+        echo '[]'
+        break
+      fi
+    done
+    ;;
+esac
+exit 0
@@ -0,0 +1,53 @@
+#!/bin/bash
+#
+# Clanker Spanker - Demo Monitor
+# Stand-in for monitor-pr-loop.sh in demo mode: replays a believable run on a
+# fast clock without touching git, GitHub or an AI CLI
+#
+# Usage: ./demo-monitor.sh <PR_NUMBER> <REPO> [MAX_ITERATIONS] [INTERVAL_MINUTES]
+#
+
+PR_NUM="${1:?Usage: $0 <PR_NUMBER> <REPO> [MAX_ITERATIONS] [INTERVAL_MINUTES]}"
+REPO="${2:?Usage: $0 <PR_NUMBER> <REPO> [MAX_ITERATIONS] [INTERVAL_MINUTES]}"
+MAX_ITER="${3:-10}"
+AI_PROVIDER="${CLANKER_AI_PROVIDER:-claude}"
+# Seconds per scripted step (the real script waits minutes)
+TICK="${CLANKER_DEMO_TICK:-2}"
+
+# Replay at most three iterations: two fixes, then a clean check
+ITERATIONS=$((MAX_ITER < 3 ? MAX_ITER : 3))
+
+# Fake but stable 40-character head for each iteration
+demo_sha() {
+  printf '%07x%033x' "$((PR_NUM * 16 + $1))" "$PR_NUM"
+}
+
+head=$(demo_sha 0)
+for ((i = 1; i <= ITERATIONS; i++)); do
+  echo "@@ITERATION:$i/$MAX_ITER@@"
+  echo "@@HEAD:$head@@"
+  echo "Checking $REPO#$PR_NUM for unresolved comments and failing checks..."
+  sleep "$TICK"
+
+  if ((i == ITERATIONS)); then
+    break
+  fi
+
+  echo "Found 1 unresolved review thread; asking $AI_PROVIDER for a fix..."
+  sleep "$TICK"
+  head=$(demo_sha "$i")
+  echo "@@PUSHING:$head@@"
+  echo "Committed and pushed ${head:0:7}"
+  echo "@@PUSHED:$head@@"
+  echo "@@SLEEPING:1@@"
+  sleep "$TICK"
+done
+
+if ((ITERATIONS < 3)); then
+  echo "Reached max iterations ($MAX_ITER)"
+  echo "@@STATUS:max_iterations@@"
+  exit 1
+fi
+
+echo "No unresolved comments and all checks passing"
+echo "@@STATUS:clean@@"
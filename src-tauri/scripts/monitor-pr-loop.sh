@@ -3,11 +3,15 @@
 # Clanker Spanker - PR Monitor Loop
 # Monitors PR for new comments and auto-fixes them via Claude or Codex
 #
-# Usage: ./monitor-pr-loop.sh <PR_NUMBER> <REPO> [MAX_ITERATIONS] [INTERVAL_MINUTES]
+# Usage: ./monitor-pr-loop.sh <PR_NUMBER> <REPO> [MAX_ITERATIONS] [INTERVAL_MINUTES] [REPO_DIR]
 #
 
 set -e
 
+# SIGUSR1 from the app (e.g. after system wake) cuts the current sleep short.
+# Installed first: until then the signal's default action would kill the script.
+trap 'echo "@@CHECK_NOW@@"' USR1
+
 # ANSI color codes
 RED='\033[0;31m'
 GREEN='\033[0;32m'
@@ -19,10 +23,12 @@ DIM='\033[2m'
 BOLD='\033[1m'
 RESET='\033[0m'
 
-PR_NUM="${1:?Usage: $0 <PR_NUMBER> <REPO> [MAX_ITERATIONS] [INTERVAL_MINUTES]}"
-REPO="${2:?Usage: $0 <PR_NUMBER> <REPO> [MAX_ITERATIONS] [INTERVAL_MINUTES]}"
+PR_NUM="${1:?Usage: $0 <PR_NUMBER> <REPO> [MAX_ITERATIONS] [INTERVAL_MINUTES] [REPO_DIR]}"
+REPO="${2:?Usage: $0 <PR_NUMBER> <REPO> [MAX_ITERATIONS] [INTERVAL_MINUTES] [REPO_DIR]}"
 MAX_ITER="${3:-10}"
 INTERVAL="${4:-15}"
+# Local clone configured for the repo in the app, empty to search for one
+CONFIGURED_REPO_DIR="${5:-}"
 AI_PROVIDER="${CLANKER_AI_PROVIDER:-claude}"
 AI_MODEL="${CLANKER_AI_MODEL:-}"
 DIRTY_WORKTREE_POLICY="${CLANKER_DIRTY_WORKTREE_POLICY:-abort}"
@@ -30,9 +36,18 @@ ONE_TIME_CLEAN_MODE="${CLANKER_ONE_TIME_CLEAN_MODE:-false}"
 HISTORY_CLEAN_MODE="${CLANKER_HISTORY_CLEAN_MODE:-cleanup_commit}"
 HISTORY_SCOPE_CLASSIFIER="${CLANKER_HISTORY_SCOPE_CLASSIFIER:-ai}"
 SKIP_CI_FIX="${CLANKER_SKIP_CI_FIX:-false}"
+# auto_fix: fix conflicts, CI and comments | watch: only poll and report, never modify
+MONITOR_MODE="${CLANKER_MONITOR_MODE:-auto_fix}"
+# owner/name of the fork the PR's head branch lives in, empty for same-repo PRs
+HEAD_REPO="${CLANKER_HEAD_REPO:-}"
+# Local Clanker Spanker API (host:port), empty when the API server is off
+API_ADDRESS="${CLANKER_API_ADDRESS:-}"
+API_TOKEN="${CLANKER_API_TOKEN:-}"
 QUICK_RETRY_INTERVAL=2
 RUN_CODEX_LAST_RESULT="unknown" # changed | no_changes | blocked | unknown
 MERGE_FIX_CREATED_COMMIT=0
+CHANGES_PUSHED=0
+LAST_FAILURE_STATUS="" # push_failed | conflicts_unresolved (reported if max iterations is reached)
 EXPECTED_PR_HEAD_BRANCH=""
 EXPECTED_PR_HEAD_OID=""
 PR_BASE_REF=""
@@ -110,47 +125,58 @@ get_repo_for_dir() {
 OWNER_VARIANTS=("$OWNER" "${OWNER%HQ}AI" "${OWNER%HQ}" "${OWNER}AI")
 REPO_PATHS=()
 
-# First priority: Conductor workspaces (use most recently modified)
-# Find all workspaces for this repo and pick the newest
-# Note: Conductor uses git worktrees, so .git is a file not a directory
-CONDUCTOR_WORKSPACE_DIR="$HOME/conductor/workspaces/$REPO_NAME"
-if [ -d "$CONDUCTOR_WORKSPACE_DIR" ]; then
-  # Find the most recently modified workspace with a .git entry (worktree file or repo dir)
-  NEWEST_WORKSPACE=$(find "$CONDUCTOR_WORKSPACE_DIR" -maxdepth 2 -name ".git" 2>/dev/null | \
-    xargs -I{} dirname {} | \
-    xargs -I{} stat -f "%m %N" {} 2>/dev/null | \
-    sort -rn | head -1 | cut -d' ' -f2-)
-  if [ -n "$NEWEST_WORKSPACE" ]; then
-    REPO_PATHS+=("$NEWEST_WORKSPACE")
+REPO_DIR=""
+
+# The clone configured for this repo in the app is used as-is (the app has
+# already checked it is a clone of $REPO); otherwise search for a clone
+if [ -n "$CONFIGURED_REPO_DIR" ] && is_git_repo_dir "$CONFIGURED_REPO_DIR"; then
+  REPO_DIR="$CONFIGURED_REPO_DIR"
+else
+  if [ -n "$CONFIGURED_REPO_DIR" ]; then
+    echo -e "${YELLOW}⚠️  Warning: Configured clone $CONFIGURED_REPO_DIR is not a git repository; searching instead${RESET}"
+  fi
+
+  # Conductor workspaces (use most recently modified)
+  # Find all workspaces for this repo and pick the newest
+  # Note: Conductor uses git worktrees, so .git is a file not a directory
+  CONDUCTOR_WORKSPACE_DIR="$HOME/conductor/workspaces/$REPO_NAME"
+  if [ -d "$CONDUCTOR_WORKSPACE_DIR" ]; then
+    # Find the most recently modified workspace with a .git entry (worktree file or repo dir)
+    NEWEST_WORKSPACE=$(find "$CONDUCTOR_WORKSPACE_DIR" -maxdepth 2 -name ".git" 2>/dev/null | \
+      xargs -I{} dirname {} | \
+      xargs -I{} stat -f "%m %N" {} 2>/dev/null | \
+      sort -rn | head -1 | cut -d' ' -f2-)
+    if [ -n "$NEWEST_WORKSPACE" ]; then
+      REPO_PATHS+=("$NEWEST_WORKSPACE")
+    fi
   fi
-fi
 
-# Then check owner variant paths
-for ov in "${OWNER_VARIANTS[@]}"; do
+  # Then check owner variant paths
+  for ov in "${OWNER_VARIANTS[@]}"; do
+    REPO_PATHS+=(
+      "$HOME/$ov/$REPO_NAME"
+      "$HOME/repos/$ov/$REPO_NAME"
+      "$HOME/code/$ov/$REPO_NAME"
+    )
+  done
   REPO_PATHS+=(
-    "$HOME/$ov/$REPO_NAME"
-    "$HOME/repos/$ov/$REPO_NAME"
-    "$HOME/code/$ov/$REPO_NAME"
+    "$HOME/repos/$REPO_NAME"
+    "$HOME/code/$REPO_NAME"
+    "$HOME/projects/$REPO_NAME"
+    "$HOME/workspace/$REPO_NAME"
+    "$HOME/ghq/github.com/$OWNER/$REPO_NAME"
   )
-done
-REPO_PATHS+=(
-  "$HOME/repos/$REPO_NAME"
-  "$HOME/code/$REPO_NAME"
-  "$HOME/projects/$REPO_NAME"
-  "$HOME/workspace/$REPO_NAME"
-  "$HOME/ghq/github.com/$OWNER/$REPO_NAME"
-)
 
-REPO_DIR=""
-for path in "${REPO_PATHS[@]}"; do
-  # Handle glob patterns
-  for expanded in $path; do
-    if is_git_repo_dir "$expanded"; then
-      REPO_DIR="$expanded"
-      break 2
-    fi
+  for path in "${REPO_PATHS[@]}"; do
+    # Handle glob patterns
+    for expanded in $path; do
+      if is_git_repo_dir "$expanded"; then
+        REPO_DIR="$expanded"
+        break 2
+      fi
+    done
   done
-done
+fi
 
 if [ -z "$REPO_DIR" ]; then
   echo -e "${YELLOW}⚠️  Warning: Could not find local clone of $REPO${RESET}"
@@ -226,6 +252,8 @@ GRAPHQL_QUERY='query($owner: String!, $repo: String!, $number: Int!, $cursor: St
 
 # Threads file for passing unresolved-thread context to the AI provider
 THREADS_FILE="$STATE_DIR/pr-${OWNER}-${REPO_NAME}-${PR_NUM}-threads.json"
+# Failed CI log excerpts fetched from the app for CI fix mode
+CI_LOGS_FILE="$STATE_DIR/pr-${OWNER}-${REPO_NAME}-${PR_NUM}-ci-logs.json"
 HISTORY_ALLOWLIST_FILE="$STATE_DIR/pr-${OWNER}-${REPO_NAME}-${PR_NUM}-history-allowlist.txt"
 HISTORY_DROP_FILE="$STATE_DIR/pr-${OWNER}-${REPO_NAME}-${PR_NUM}-history-drop.txt"
 HISTORY_CANONICAL_ROOTS_FILE="$STATE_DIR/pr-${OWNER}-${REPO_NAME}-${PR_NUM}-history-canonical-roots.txt"
@@ -290,13 +318,26 @@ push_head_to_pr_branch() {
     return 1
   fi
 
+  local local_head remote_head
+  local_head=$(git -C "$REPO_DIR" rev-parse HEAD 2>/dev/null || true)
+
+  # Announce the head before pushing, so the app doesn't mistake it for
+  # someone else's push if it sees the new head before @@PUSHED
+  if [ -n "$local_head" ]; then
+    echo "@@PUSHING:$local_head@@"
+  fi
+
   if ! (cd "$REPO_DIR" && git push origin "HEAD:refs/heads/$EXPECTED_PR_HEAD_BRANCH"); then
     echo -e "${RED}❌ git push to PR head branch failed after ${action_label}.${RESET}"
+    LAST_FAILURE_STATUS="push_failed"
     return 1
   fi
+  CHANGES_PUSHED=1
+  # Tell the app this head is ours, so it isn't mistaken for someone else's push
+  if [ -n "$local_head" ]; then
+    echo "@@PUSHED:$local_head@@"
+  fi
 
-  local local_head remote_head
-  local_head=$(git -C "$REPO_DIR" rev-parse HEAD 2>/dev/null || true)
   remote_head=$(git -C "$REPO_DIR" ls-remote --heads origin "$EXPECTED_PR_HEAD_BRANCH" 2>/dev/null | awk 'NR==1 {print $1}')
   if [ -z "$local_head" ] || [ -z "$remote_head" ] || [ "$local_head" != "$remote_head" ]; then
     echo -e "${RED}❌ Remote PR head branch is not aligned after push for ${action_label}.${RESET}"
@@ -308,6 +349,21 @@ push_head_to_pr_branch() {
   return 0
 }
 
+# Push commits an AI run made (via push_head_to_pr_branch, so the app knows they're ours).
+# Pushing a head that's already on the remote is a harmless no-op.
+push_if_head_moved() {
+  local before_head="$1"
+  local action_label="$2"
+  local after_head
+  after_head=$(git -C "$REPO_DIR" rev-parse HEAD 2>/dev/null || true)
+  if [ -z "$after_head" ] || [ "$after_head" = "$before_head" ]; then
+    return 0
+  fi
+
+  echo -e "${DIM}🚀 Pushing commit(s) to PR head branch (${EXPECTED_PR_HEAD_BRANCH}) for ${action_label}...${RESET}"
+  push_head_to_pr_branch "$action_label"
+}
+
 setup_monitor_worktree() {
   if [ -z "$EXPECTED_PR_HEAD_BRANCH" ]; then
     echo -e "${RED}❌ Cannot create monitor worktree: PR head branch is unknown.${RESET}"
@@ -375,7 +431,7 @@ cleanup_monitor_artifacts() {
   fi
   CLEANUP_DONE=1
 
-  rm -f "$STATE_FILE" "$THREADS_FILE" "$HISTORY_ALLOWLIST_FILE" "$HISTORY_DROP_FILE" "$HISTORY_CANONICAL_ROOTS_FILE" >/dev/null 2>&1 || true
+  rm -f "$STATE_FILE" "$THREADS_FILE" "$CI_LOGS_FILE" "$HISTORY_ALLOWLIST_FILE" "$HISTORY_DROP_FILE" "$HISTORY_CANONICAL_ROOTS_FILE" >/dev/null 2>&1 || true
 
   if [ -n "${MONITOR_WORKTREE_DIR:-}" ] && [ -d "$MONITOR_WORKTREE_DIR" ]; then
     if [ -n "${BASE_REPO_DIR:-}" ] && git -C "$BASE_REPO_DIR" rev-parse --is-inside-work-tree >/dev/null 2>&1; then
@@ -459,6 +515,7 @@ ensure_workspace_ready() {
 
 preflight_checks() {
   local failed=0
+  local preflight_status=""
   local worktree_ready=0
 
   for cmd in gh jq git; do
@@ -468,18 +525,21 @@ preflight_checks() {
     fi
   done
 
-  if [ "$AI_PROVIDER" = "claude" ] && ! command -v claude &> /dev/null; then
+  if [ "$MONITOR_MODE" = "watch" ]; then
+    : # No AI CLI is run in watch mode
+  elif [ "$AI_PROVIDER" = "claude" ] && ! command -v claude &> /dev/null; then
     echo -e "${RED}❌ Claude provider selected but claude CLI is not installed.${RESET}"
     failed=1
   fi
 
-  if [ "$AI_PROVIDER" = "codex" ] && ! command -v codex &> /dev/null; then
+  if [ "$MONITOR_MODE" != "watch" ] && [ "$AI_PROVIDER" = "codex" ] && ! command -v codex &> /dev/null; then
     echo -e "${RED}❌ Codex provider selected but codex CLI is not installed.${RESET}"
     failed=1
   fi
 
   if ! gh auth status > /dev/null 2>&1; then
     echo -e "${RED}❌ gh is not authenticated. Run: gh auth login${RESET}"
+    preflight_status="auth_expired"
     failed=1
   fi
 
@@ -494,7 +554,7 @@ preflight_checks() {
   if [ "$failed" -eq 0 ] && [ "$PR_STATE" != "OPEN" ]; then
     local state_lower
     state_lower=$(echo "$PR_STATE" | tr '[:upper:]' '[:lower:]')
-    echo "@@STATUS:clean@@"
+    echo "@@STATUS:pr_not_open@@"
     if [ -n "$PR_MERGED_AT" ]; then
       echo -e "${GREEN}${BOLD}✅ PR is already ${state_lower} (merged at ${PR_MERGED_AT}). Nothing to monitor.${RESET}"
     else
@@ -508,6 +568,16 @@ preflight_checks() {
     exit 0
   fi
 
+  if [ "$MONITOR_MODE" = "watch" ]; then
+    # Watch mode never touches a checkout, so no worktree is needed
+    if [ "$failed" -ne 0 ]; then
+      echo "@@STATUS:${preflight_status:-preflight_failed}@@"
+      echo -e "${RED}❌ Preflight checks failed; monitor aborted.${RESET}"
+      exit 1
+    fi
+    return 0
+  fi
+
   if ! git -C "$REPO_DIR" rev-parse --is-inside-work-tree > /dev/null 2>&1; then
     echo -e "${RED}❌ REPO_DIR is not a git repo: $REPO_DIR${RESET}"
     failed=1
@@ -541,6 +611,7 @@ preflight_checks() {
   fi
 
   if [ "$failed" -ne 0 ]; then
+    echo "@@STATUS:${preflight_status:-preflight_failed}@@"
     echo -e "${RED}❌ Preflight checks failed; monitor aborted.${RESET}"
     exit 1
   fi
@@ -563,6 +634,13 @@ echo -e "${CYAN}│${RESET}  ${DIM}One-time clean mode:${RESET} $ONE_TIME_CLEAN_
 echo -e "${CYAN}│${RESET}  ${DIM}History clean strategy:${RESET} $HISTORY_CLEAN_MODE (non-rewrite)"
 echo -e "${CYAN}│${RESET}  ${DIM}History scope classifier:${RESET} $HISTORY_SCOPE_CLASSIFIER"
 echo -e "${CYAN}│${RESET}  ${DIM}Skip CI fix:${RESET} $SKIP_CI_FIX"
+echo -e "${CYAN}│${RESET}  ${DIM}Mode:${RESET} $MONITOR_MODE"
+if [ "$MONITOR_MODE" = "watch" ]; then
+  echo -e "${CYAN}│${RESET}  ${YELLOW}Watch mode: reporting only, no fixes will be made${RESET}"
+fi
+if [ -n "$HEAD_REPO" ]; then
+  echo -e "${CYAN}│${RESET}  ${YELLOW}Head branch is in the fork $HEAD_REPO; pushes may need its remote${RESET}"
+fi
 echo -e "${CYAN}│${RESET}  ${DIM}Checking every ${INTERVAL}m | Max $MAX_ITER iterations${RESET}"
 echo -e "${CYAN}╰─────────────────────────────────────────────────────────────╯${RESET}"
 echo ""
@@ -985,7 +1063,7 @@ EOF
         codex_cmd+=(-m "$AI_MODEL")
       fi
       codex_cmd+=("$prompt")
-      if ! (cd "$REPO_DIR" && "${codex_cmd[@]}" >"$output_tmp" 2>&1); then
+      if ! (cd "$REPO_DIR" && "${codex_cmd[@]}" </dev/null >"$output_tmp" 2>&1); then
         rm -f "$output_tmp"
         decision_error_commits=$((decision_error_commits + 1))
         rm -f "$candidate_set"
@@ -996,7 +1074,7 @@ EOF
       if [ -n "$AI_MODEL" ]; then
         claude_cmd+=(--model "$AI_MODEL")
       fi
-      if ! (cd "$REPO_DIR" && "${claude_cmd[@]}" 2>&1 | \
+      if ! (cd "$REPO_DIR" && "${claude_cmd[@]}" </dev/null 2>&1 | \
         jq -r --unbuffered 'select(.type) | if .type == "assistant" then (.message.content[]?.text // empty) elif .type == "result" then (.result.content[]?.text // empty) elif .type == "content_block_delta" then (.delta.text // empty) else empty end' \
         >"$output_tmp" 2>/dev/null); then
         rm -f "$output_tmp"
@@ -1501,7 +1579,7 @@ run_claude_prompt() {
     claude_cmd+=(--model "$AI_MODEL")
   fi
 
-  (cd "$REPO_DIR" && "${claude_cmd[@]}" 2>&1) | \
+  (cd "$REPO_DIR" && "${claude_cmd[@]}" </dev/null 2>&1) | \
     jq -r --unbuffered 'select(.type) | if .type == "assistant" then (.message.content[]?.text // empty) elif .type == "result" then (.result.content[]?.text // empty) elif .type == "content_block_delta" then (.delta.text // empty) else empty end' 2>/dev/null || true
 
   return 0
@@ -1533,7 +1611,7 @@ run_codex_prompt() {
   local output_file
   output_file=$(mktemp)
 
-  if ! (cd "$REPO_DIR" && "${codex_cmd[@]}" 2>&1 | tee "$output_file"); then
+  if ! (cd "$REPO_DIR" && "${codex_cmd[@]}" </dev/null 2>&1 | tee "$output_file"); then
     rm -f "$output_file"
     echo -e "${RED}❌ Codex run failed for ${action_label}.${RESET}"
     return 1
@@ -1625,7 +1703,7 @@ Prefer preserving intended behavior from both sides, remove conflict markers, an
 
 Requirements:
 1) Keep all work inside this checkout at: $REPO_DIR
-2) If changes are required, commit them and ensure they are pushed to PR head branch: $EXPECTED_PR_HEAD_BRANCH.
+2) If changes are required, commit them. Do NOT push; the monitor pushes your commits.
 3) Do NOT leave tracked uncommitted changes.
 4) At the very end, print exactly one status line:
    - @@RUN_RESULT:CHANGED@@
@@ -1635,7 +1713,8 @@ Requirements:
   else
     run_claude_prompt "Fix merge conflicts for PR #$PR_NUM in $REPO.
 Resolve the conflicting files in this local repository with minimal, correct changes.
-Run relevant validation, commit any required fixes, and push to the current branch." "merge fix" || return 1
+Run relevant validation and commit any required fixes. Do not push; the monitor pushes your commits." "merge fix" || return 1
+    push_if_head_moved "$before_head" "merge fix" || return 1
   fi
 
   local post_merge_status
@@ -1643,6 +1722,7 @@ Run relevant validation, commit any required fixes, and push to the current bran
   echo "@@MERGE_STATUS:$post_merge_status@@"
   if [ "$post_merge_status" = "conflicting" ]; then
     echo -e "${RED}❌ Merge conflicts remain after merge fix flow.${RESET}"
+    LAST_FAILURE_STATUS="conflicts_unresolved"
     return 1
   fi
 
@@ -1664,17 +1744,46 @@ Run relevant validation, commit any required fixes, and push to the current bran
   return 0
 }
 
+# Fetch failed CI log excerpts from the app API into CI_LOGS_FILE.
+# Returns 1 (leaving no file) when the API is unavailable or has nothing useful.
+fetch_ci_failure_logs() {
+  rm -f "$CI_LOGS_FILE"
+  if [ -z "$API_ADDRESS" ] || ! command -v curl &> /dev/null; then
+    return 1
+  fi
+
+  local pr_id_encoded
+  pr_id_encoded=$(jq -rn --arg id "$REPO#$PR_NUM" '$id | @uri')
+
+  local response
+  response=$(curl -sf --max-time 60 -H "X-Clanker-Token: $API_TOKEN" \
+    "http://$API_ADDRESS/api/pr/$pr_id_encoded/ci-logs?maxLines=80" 2>/dev/null || true)
+  if ! echo "$response" | jq -e '.success and ((.data // []) | length > 0)' > /dev/null 2>&1; then
+    return 1
+  fi
+
+  echo "$response" | jq '.data' > "$CI_LOGS_FILE"
+  return 0
+}
+
 # Function to run CI fixing flow with selected provider
 run_fix_ci() {
   echo -e "${CYAN}🔧 Running CI fix flow...${RESET}"
 
+  local ci_logs_hint=""
+  if fetch_ci_failure_logs; then
+    echo -e "${DIM}📄 Failed CI log excerpts saved to $CI_LOGS_FILE${RESET}"
+    ci_logs_hint="
+Failed CI log excerpts (workflow/job/step/excerpt/url) are in: $CI_LOGS_FILE"
+  fi
+
   if [ "$AI_PROVIDER" = "codex" ]; then
     run_codex_prompt "Fix failing CI for PR #$PR_NUM in $REPO.
-Inspect failing CI checks, apply minimal fixes in this local repository, run relevant validation, and summarize what changed.
+Inspect failing CI checks, apply minimal fixes in this local repository, run relevant validation, and summarize what changed.$ci_logs_hint
 
 Requirements:
 1) Keep all work inside this checkout at: $REPO_DIR
-2) If changes are required, commit them and ensure they are pushed to PR head branch: $EXPECTED_PR_HEAD_BRANCH.
+2) If changes are required, commit them. Do NOT push; the monitor pushes your commits.
 3) Do NOT leave tracked uncommitted changes.
 4) At the very end, print exactly one status line:
    - @@RUN_RESULT:CHANGED@@
@@ -1682,7 +1791,10 @@ Requirements:
    - @@RUN_RESULT:BLOCKED:<reason>@@
 5) Use NO_CHANGES only when no commit is needed and nothing is blocked." "CI fix" "true"
   else
-    run_claude_prompt "/fix-ci --pr $PR_NUM" "CI fix"
+    local before_head
+    before_head=$(git -C "$REPO_DIR" rev-parse HEAD 2>/dev/null || true)
+    run_claude_prompt "/fix-ci --pr $PR_NUM$ci_logs_hint" "CI fix" || return 1
+    push_if_head_moved "$before_head" "CI fix"
   fi
 }
 
@@ -1700,7 +1812,7 @@ Hard constraints:
 2) Treat the thread file as the source of truth for scope. Do NOT inspect, list, or reference any other PR.
 3) Allowed GitHub actions are limited to this PR (#$PR_NUM), primarily resolving SKIP/FIX threads when appropriate.
 4) Apply only focused fixes for unresolved threads from the file.
-5) If code changes are needed, commit and push to PR head branch: $EXPECTED_PR_HEAD_BRANCH
+5) If code changes are needed, commit them. Do NOT push; the monitor pushes your commits.
 6) Do NOT leave tracked uncommitted changes.
 7) At the very end, print exactly one status line:
    - @@RUN_RESULT:CHANGED@@
@@ -1736,7 +1848,7 @@ Hard constraints:
   fi
 }
 
-if [ "$ONE_TIME_CLEAN_MODE" = "true" ]; then
+if [ "$ONE_TIME_CLEAN_MODE" = "true" ] && [ "$MONITOR_MODE" != "watch" ]; then
   run_one_time_history_clean_mode
 fi
 
@@ -1744,17 +1856,37 @@ fi
 for iter in $(seq 1 $MAX_ITER); do
   iteration_failed=0
   MERGE_FIX_CREATED_COMMIT=0
+  LAST_FAILURE_STATUS=""
   CI_PENDING_WAIT_SKIPPED=0
 
   # Emit parseable iteration marker for dashboard
   echo "@@ITERATION:$iter/$MAX_ITER@@"
 
+  # Wait out connectivity loss rather than failing every network step
+  if command -v curl >/dev/null 2>&1; then
+    offline_reported=0
+    while ! curl -s -o /dev/null --max-time 5 https://api.github.com; do
+      if [ "$offline_reported" -eq 0 ]; then
+        echo "@@OFFLINE@@"
+        echo -e "${YELLOW}⚠️ GitHub is unreachable. Waiting for connectivity...${RESET}"
+        offline_reported=1
+      fi
+      sleep 60
+    done
+  fi
+
+  # Emit the PR head this iteration checks, so runs can be traced to a push
+  iteration_head=$(gh pr view "$PR_NUM" --repo "$REPO" --json headRefOid --jq '.headRefOid' 2>/dev/null || true)
+  if [ -n "$iteration_head" ]; then
+    echo "@@HEAD:$iteration_head@@"
+  fi
+
   echo ""
   echo -e "${DIM}━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━${RESET}"
   echo -e "${BOLD}🔍 Iteration ${MAGENTA}$iter${RESET}${BOLD}/${DIM}$MAX_ITER${RESET} ${DIM}- $(date '+%H:%M:%S')${RESET}"
   echo -e "${DIM}━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━${RESET}"
 
-  if ! ensure_workspace_ready "iteration $iter"; then
+  if [ "$MONITOR_MODE" != "watch" ] && ! ensure_workspace_ready "iteration $iter"; then
     echo "@@AGENT_ERROR:workspace_state@@"
     echo "@@STATUS:workspace_blocked@@"
     echo -e "${RED}❌ Workspace is not in a safe state for monitoring. Stopping monitor.${RESET}"
@@ -1777,7 +1909,9 @@ for iter in $(seq 1 $MAX_ITER); do
   echo -e "🔀 Merge Status: ${merge_color}${merge_status}${RESET}"
   echo "@@MERGE_STATUS:$merge_status@@"
 
-  if [ "$merge_status" = "conflicting" ]; then
+  if [ "$merge_status" = "conflicting" ] && [ "$MONITOR_MODE" = "watch" ]; then
+    echo -e "${RED}❌ Merge conflicts detected${RESET} ${DIM}(watch mode - not fixing)${RESET}"
+  elif [ "$merge_status" = "conflicting" ]; then
     echo -e "${RED}❌ Merge conflicts detected - fixing first...${RESET}"
     if ! run_fix_merge; then
       iteration_failed=1
@@ -1838,7 +1972,10 @@ for iter in $(seq 1 $MAX_ITER); do
   # STEP 3: Fix CI if failed (unless SKIP_CI_FIX is enabled)
   # ═══════════════════════════════════════════════════════════════
   if [ "$ci_status" = "failure" ]; then
-    if [ "$SKIP_CI_FIX" = "true" ]; then
+    if [ "$MONITOR_MODE" = "watch" ]; then
+      echo ""
+      echo -e "${RED}❌ CI failing${RESET} ${DIM}(watch mode - not fixing)${RESET}"
+    elif [ "$SKIP_CI_FIX" = "true" ]; then
       echo ""
       echo -e "${YELLOW}⏭️ CI failing but SKIP_CI_FIX is enabled - skipping fix${RESET}"
     else
@@ -1885,7 +2022,11 @@ for iter in $(seq 1 $MAX_ITER); do
 
   # Check if PR is clean (no comments, CI passing, and no merge conflicts)
   if [ "$current_count" -eq 0 ] && [ "$ci_status" = "success" ] && [ "$merge_status" = "clean" ]; then
-    echo "@@STATUS:clean@@"
+    if [ "$CHANGES_PUSHED" -eq 0 ]; then
+      echo "@@STATUS:no_changes_needed@@"
+    else
+      echo "@@STATUS:clean@@"
+    fi
     echo -e "${GREEN}${BOLD}✅ PR is clean!${RESET} ${GREEN}No unresolved comments, CI passing, and mergeable.${RESET}"
     echo ""
     echo -e "${GREEN}╭─────────────────────────────────────────────────────────────╮${RESET}"
@@ -1909,7 +2050,13 @@ for iter in $(seq 1 $MAX_ITER); do
   # ═══════════════════════════════════════════════════════════════
   # STEP 5: Fix comments if any
   # ═══════════════════════════════════════════════════════════════
-  if [ "$current_count" -gt 0 ]; then
+  if [ "$current_count" -gt 0 ] && [ "$MONITOR_MODE" = "watch" ]; then
+    echo -e "${YELLOW}📊 Unresolved: ${BOLD}$current_count${RESET}${YELLOW} comments | New: $new_count${RESET}"
+    echo "@@COMMENTS_FOUND:$current_count@@"
+    echo -e "${DIM}👀 Watch mode - leaving comments for the author${RESET}"
+    # Remember what was reported so only newer threads count as new next time
+    update_state "$iter" "$current_ids"
+  elif [ "$current_count" -gt 0 ]; then
     echo -e "${YELLOW}📊 Unresolved: ${BOLD}$current_count${RESET}${YELLOW} comments | New: $new_count${RESET}"
     echo "@@COMMENTS_FOUND:$current_count@@"
     echo ""
@@ -1979,12 +2126,16 @@ for iter in $(seq 1 $MAX_ITER); do
     echo ""
     echo -e "${BLUE}💤 Sleeping ${sleep_interval} minutes...${RESET} ${DIM}Next check at $next_time${RESET}"
     echo "@@SLEEPING:${sleep_interval}@@"
-    sleep $((sleep_interval * 60))
+    # Background sleep + wait so a USR1 check-now signal interrupts it
+    sleep $((sleep_interval * 60)) &
+    sleep_pid=$!
+    wait "$sleep_pid" || true
+    kill "$sleep_pid" 2>/dev/null || true
   fi
 done
 
 echo ""
-echo "@@STATUS:max_iterations@@"
+echo "@@STATUS:${LAST_FAILURE_STATUS:-max_iterations}@@"
 echo -e "${YELLOW}╭─────────────────────────────────────────────────────────────╮${RESET}"
 echo -e "${YELLOW}│${RESET}  ${YELLOW}${BOLD}⚠️  Monitor Complete${RESET} - PR ${MAGENTA}#$PR_NUM${RESET}"
 echo -e "${YELLOW}│${RESET}  ${DIM}Iterations: $MAX_ITER | Exit: Max iterations reached${RESET}"
@@ -2,15 +2,52 @@
 //!
 //! Listens on port 7890 and provides endpoints to start/stop monitors.
 
-use crate::db::AppState;
+use crate::checks;
+use crate::db::{self, AppState};
 use crate::monitor;
 use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
 use std::thread;
-use tauri::{AppHandle, Emitter, Manager, Runtime};
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager, Runtime, State};
 use tiny_http::{Header, Method, Response, Server};
 
 const API_PORT: u16 = 7890;
 
+/// Header carrying the `api_token` setting. `Authorization: Bearer <token>` is accepted too.
+pub const API_TOKEN_HEADER: &str = "X-Clanker-Token";
+
+/// Handle to the running server, used to unblock its accept loop on stop
+static API_SERVER: Mutex<Option<Arc<Server>>> = Mutex::new(None);
+
+/// Address the server actually bound to, or None if it isn't running
+static API_ADDRESS: Mutex<Option<SocketAddr>> = Mutex::new(None);
+
+/// App events forwarded to `GET /api/events` clients
+const STREAMED_EVENTS: &[&str] = &[
+    "monitor:state-changed",
+    "monitor:completed",
+    "pr:added",
+    "pr:updated",
+    "pr:removed",
+];
+
+/// Most event stream clients served at once (each holds a thread)
+const MAX_EVENT_CLIENTS: usize = 16;
+
+/// Comment line sent when a stream has been idle this long, so dead clients are noticed
+const EVENT_KEEPALIVE: Duration = Duration::from_secs(15);
+
+/// One sender per connected event stream client, fed with ready SSE frames
+static EVENT_CLIENTS: Mutex<Vec<mpsc::Sender<String>>> = Mutex::new(Vec::new());
+
+/// Whether the app event listeners feeding EVENT_CLIENTS are registered
+static EVENT_LISTENERS: AtomicBool = AtomicBool::new(false);
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct StartMonitorRequest {
@@ -18,6 +55,12 @@ struct StartMonitorRequest {
     repo: String,
     max_iterations: Option<i32>,
     interval_minutes: Option<i32>,
+    tags: Option<Vec<String>>,
+    notification_level: Option<String>,
+    /// "auto_fix" (default) or "watch"
+    mode: Option<String>,
+    /// Start even if the PR is closed, merged or can't be fetched
+    force: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -26,6 +69,9 @@ struct ApiResponse<T> {
     success: bool,
     data: Option<T>,
     error: Option<String>,
+    /// Per-field problems of a rejected request (422)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    field_errors: Option<Vec<monitor::FieldError>>,
 }
 
 impl<T: Serialize> ApiResponse<T> {
@@ -34,6 +80,7 @@ impl<T: Serialize> ApiResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            field_errors: None,
         })
         .unwrap_or_else(|_| r#"{"success":false,"error":"Serialization error"}"#.to_string())
     }
@@ -43,29 +90,136 @@ impl<T: Serialize> ApiResponse<T> {
             success: false,
             data: None,
             error: Some(msg.to_string()),
+            field_errors: None,
+        })
+        .unwrap_or_else(|_| r#"{"success":false,"error":"Serialization error"}"#.to_string())
+    }
+
+    fn invalid(errors: Vec<monitor::FieldError>) -> String {
+        serde_json::to_string(&ApiResponse::<()> {
+            success: false,
+            data: None,
+            error: Some(monitor::describe_field_errors(&errors)),
+            field_errors: Some(errors),
         })
         .unwrap_or_else(|_| r#"{"success":false,"error":"Serialization error"}"#.to_string())
     }
 }
 
-/// Start the HTTP API server in a background thread
-pub fn start_api_server<R: Runtime + 'static>(app: AppHandle<R>) {
-    thread::spawn(move || {
-        let server = match Server::http(format!("127.0.0.1:{}", API_PORT)) {
-            Ok(s) => s,
-            Err(e) => {
-                eprintln!("Failed to start API server on port {}: {}", API_PORT, e);
-                return;
-            }
-        };
+/// Start the HTTP API server in a background thread.
+/// Does nothing if the server is already running.
+pub fn spawn_api_server<R: Runtime + 'static>(app: AppHandle<R>) -> Result<(), String> {
+    let mut guard = API_SERVER
+        .lock()
+        .map_err(|e| format!("Failed to lock API server: {}", e))?;
+    if guard.is_some() {
+        return Ok(());
+    }
+
+    let server = Server::http(format!("127.0.0.1:{}", API_PORT))
+        .map(Arc::new)
+        .map_err(|e| format!("Failed to start API server on port {}: {}", API_PORT, e))?;
+    *guard = Some(Arc::clone(&server));
 
-        println!("Clanker Spanker API listening on http://127.0.0.1:{}", API_PORT);
+    let addr = server.server_addr().to_ip();
+    if let Ok(mut address) = API_ADDRESS.lock() {
+        *address = addr;
+    }
 
+    if let Some(addr) = addr {
+        println!("Clanker Spanker API listening on http://{}", addr);
+    }
+
+    register_event_listeners(&app);
+
+    thread::spawn(move || {
+        // Ends when the server is unblocked by shutdown_api_server
         for mut request in server.incoming_requests() {
+            // Event streams stay open, so they get a thread of their own
+            let is_event_stream = *request.method() == Method::Get
+                && request.url().split('?').next() == Some("/api/events");
+            if is_event_stream && is_authorized(&app, &request) {
+                serve_event_stream(request);
+                continue;
+            }
+
             let response = handle_request(&app, &mut request);
             let _ = request.respond(response);
         }
+        println!("Clanker Spanker API stopped");
     });
+
+    Ok(())
+}
+
+/// Forward the streamed app events to event stream clients (once per process)
+fn register_event_listeners<R: Runtime>(app: &AppHandle<R>) {
+    if !EVENT_LISTENERS.swap(true, Ordering::SeqCst) {
+        for &name in STREAMED_EVENTS {
+            app.listen_any(name, move |event| publish_event(name, event.payload()));
+        }
+    }
+}
+
+/// Stop the HTTP API server, releasing the port once the accept loop exits
+pub fn shutdown_api_server() -> Result<(), String> {
+    let server = API_SERVER
+        .lock()
+        .map_err(|e| format!("Failed to lock API server: {}", e))?
+        .take();
+    if let Some(server) = server {
+        server.unblock();
+    }
+    // Dropping the senders ends the open event streams
+    if let Ok(mut clients) = EVENT_CLIENTS.lock() {
+        clients.clear();
+    }
+    if let Ok(mut address) = API_ADDRESS.lock() {
+        *address = None;
+    }
+    Ok(())
+}
+
+/// Whether the HTTP API server is currently running
+pub fn is_api_server_running() -> bool {
+    API_SERVER.lock().map(|s| s.is_some()).unwrap_or(false)
+}
+
+/// Get the bound API address (e.g. "127.0.0.1:7890"), or None if the server isn't running
+#[tauri::command]
+pub fn get_api_address() -> Option<String> {
+    API_ADDRESS
+        .lock()
+        .ok()
+        .and_then(|address| address.map(|a| a.to_string()))
+}
+
+/// Enable the HTTP API server and start it
+#[tauri::command]
+pub fn start_api_server(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    {
+        let conn = state.conn();
+        db::set_setting(&conn, db::API_ENABLED_SETTING_KEY, "true")
+            .map_err(|e| format!("Database error: {}", e))?;
+    }
+    spawn_api_server(app)
+}
+
+/// Disable the HTTP API server and stop it
+#[tauri::command]
+pub fn stop_api_server(state: State<'_, AppState>) -> Result<(), String> {
+    {
+        let conn = state.conn();
+        db::set_setting(&conn, db::API_ENABLED_SETTING_KEY, "false")
+            .map_err(|e| format!("Database error: {}", e))?;
+    }
+    shutdown_api_server()
+}
+
+/// Get whether the HTTP API server is currently listening
+#[tauri::command]
+pub fn get_api_server_status() -> bool {
+    is_api_server_running()
 }
 
 fn handle_request<R: Runtime>(
@@ -80,7 +234,11 @@ fn handle_request<R: Runtime>(
         Header::from_bytes(&b"Access-Control-Allow-Origin"[..], &b"*"[..]).unwrap(),
         Header::from_bytes(&b"Access-Control-Allow-Methods"[..], &b"GET, POST, OPTIONS"[..])
             .unwrap(),
-        Header::from_bytes(&b"Access-Control-Allow-Headers"[..], &b"Content-Type"[..]).unwrap(),
+        Header::from_bytes(
+            &b"Access-Control-Allow-Headers"[..],
+            format!("Content-Type, Authorization, {}", API_TOKEN_HEADER).as_bytes(),
+        )
+        .unwrap(),
         Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap(),
     ];
 
@@ -93,38 +251,106 @@ fn handle_request<R: Runtime>(
         return response;
     }
 
-    let (status, body) = match (method, path.as_str()) {
+    let (route, query) = path.split_once('?').unwrap_or((path.as_str(), ""));
+
+    let (status, body) = match (method, route) {
         // Health check
-        (Method::Get, "/api/health") => (200, r#"{"status":"ok"}"#.to_string()),
+        (Method::Get, "/api/health") => {
+            let (paused, rate_limit) = app
+                .try_state::<AppState>()
+                .map(|state| {
+                    let conn = state.conn();
+                    let sample = db::get_rate_limit_samples(&conn, 1).ok().and_then(|mut s| s.pop());
+                    (db::get_monitors_paused(&conn), sample)
+                })
+                .unwrap_or_default();
+            (
+                200,
+                serde_json::json!({
+                    "status": "ok",
+                    "monitorsPaused": paused,
+                    "rateLimitRemaining": rate_limit.map(|sample| sample.remaining),
+                    "rateLimitLow": crate::rate_usage::is_low(),
+                })
+                .to_string(),
+            )
+        }
+
+        // Everything else requires the api_token, when one is configured
+        _ if !is_authorized(app, request) => (
+            401,
+            ApiResponse::<()>::error(&format!("Missing or invalid {} header", API_TOKEN_HEADER)),
+        ),
 
-        // Start monitor
+        // Start monitor (JSON body, form-encoded body, or query parameters)
         (Method::Post, "/api/monitor/start") => {
             let mut body = String::new();
             if request.as_reader().read_to_string(&mut body).is_err() {
                 (400, ApiResponse::<()>::error("Failed to read request body"))
             } else {
-                match serde_json::from_str::<StartMonitorRequest>(&body) {
+                let content_type = header_value(request, "Content-Type").unwrap_or_default();
+                match parse_start_monitor_request(&content_type, &body, query) {
                     Ok(req) => handle_start_monitor(app, req),
-                    Err(e) => (400, ApiResponse::<()>::error(&format!("Invalid JSON: {}", e))),
+                    Err(e) => (400, ApiResponse::<()>::error(&e)),
                 }
             }
         }
 
+        // Compact counts for widgets and Shortcuts
+        (Method::Get, "/api/summary") => handle_summary(app),
+
         // Stop monitor by PR ID
         (Method::Post, path) if path.starts_with("/api/monitor/stop/") => {
             let pr_id = path.trim_start_matches("/api/monitor/stop/");
-            handle_stop_monitor(app, pr_id)
+            handle_stop_monitor(app, &percent_decode(pr_id))
         }
 
         // Get monitor status by PR ID
         (Method::Get, path) if path.starts_with("/api/monitor/status/") => {
             let pr_id = path.trim_start_matches("/api/monitor/status/");
-            handle_get_monitor(app, pr_id)
+            handle_get_monitor(app, &percent_decode(pr_id))
+        }
+
+        // Tail of the PR's active (or most recent) monitor log (?lines=N)
+        (Method::Get, path) if path.starts_with("/api/monitor/logs/") => {
+            let pr_id = path.trim_start_matches("/api/monitor/logs/");
+            let lines = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("lines="))
+                .and_then(|v| v.parse::<usize>().ok());
+            handle_monitor_logs(app, &percent_decode(pr_id), lines)
         }
 
         // List all monitors
         (Method::Get, "/api/monitors") => handle_list_monitors(app),
 
+        // Re-run failed CI checks for a PR (prId must be URL-encoded)
+        (Method::Post, path)
+            if path.starts_with("/api/pr/") && path.ends_with("/rerun-checks") =>
+        {
+            let pr_id = path
+                .trim_start_matches("/api/pr/")
+                .trim_end_matches("/rerun-checks");
+            handle_rerun_checks(app, &percent_decode(pr_id))
+        }
+
+        // Failed CI log excerpts for a PR (?maxLines=N, prId must be URL-encoded)
+        (Method::Get, route) if route.starts_with("/api/pr/") && route.ends_with("/ci-logs") => {
+            match route
+                .strip_prefix("/api/pr/")
+                .and_then(|rest| rest.strip_suffix("/ci-logs"))
+            {
+                Some(pr_id) => {
+                    let max_lines = query
+                        .split('&')
+                        .find_map(|pair| pair.strip_prefix("maxLines="))
+                        .and_then(|v| v.parse::<usize>().ok());
+                    handle_ci_logs(app, &percent_decode(pr_id), max_lines)
+                }
+                None => (404, ApiResponse::<()>::error("Not found")),
+            }
+        }
+
         // 404
         _ => (404, ApiResponse::<()>::error("Not found")),
     };
@@ -137,6 +363,188 @@ fn handle_request<R: Runtime>(
     response
 }
 
+/// Format an app event as a Server-Sent Events frame
+fn sse_frame(event: &str, payload: &str) -> String {
+    let mut frame = format!("event: {}\n", event);
+    for line in payload.lines() {
+        frame.push_str("data: ");
+        frame.push_str(line);
+        frame.push('\n');
+    }
+    frame.push('\n');
+    frame
+}
+
+/// Send an event to every connected stream, forgetting clients that went away
+fn publish_event(event: &str, payload: &str) {
+    let frame = sse_frame(event, payload);
+    if let Ok(mut clients) = EVENT_CLIENTS.lock() {
+        clients.retain(|client| client.send(frame.clone()).is_ok());
+    }
+}
+
+/// Register a new stream client, or None when the client limit is reached
+fn subscribe_events() -> Option<mpsc::Receiver<String>> {
+    let mut clients = EVENT_CLIENTS.lock().ok()?;
+    if clients.len() >= MAX_EVENT_CLIENTS {
+        return None;
+    }
+    let (tx, rx) = mpsc::channel();
+    clients.push(tx);
+    Some(rx)
+}
+
+/// Answer `GET /api/events` with a text/event-stream response that stays open.
+/// The response is written by hand: tiny_http only hands over the socket.
+fn serve_event_stream(request: tiny_http::Request) {
+    let Some(events) = subscribe_events() else {
+        let response = Response::from_string(ApiResponse::<()>::error("Too many event streams"))
+            .with_status_code(503);
+        let _ = request.respond(response);
+        return;
+    };
+
+    thread::spawn(move || {
+        let mut writer = request.into_writer();
+        let head = "HTTP/1.1 200 OK\r\n\
+                    Content-Type: text/event-stream\r\n\
+                    Cache-Control: no-cache\r\n\
+                    Connection: keep-alive\r\n\
+                    Access-Control-Allow-Origin: *\r\n\r\n\
+                    : connected\n\n";
+        if writer.write_all(head.as_bytes()).and_then(|_| writer.flush()).is_err() {
+            return;
+        }
+
+        loop {
+            let frame = match events.recv_timeout(EVENT_KEEPALIVE) {
+                Ok(frame) => frame,
+                Err(mpsc::RecvTimeoutError::Timeout) => ": keepalive\n\n".to_string(),
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            };
+            // A failed write means the client disconnected; dropping `events`
+            // lets the next publish drop its sender
+            if writer.write_all(frame.as_bytes()).and_then(|_| writer.flush()).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Read a request header by (case-insensitive) name
+fn header_value(request: &tiny_http::Request, name: &str) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.as_str().as_str().eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str().to_string())
+}
+
+/// Check the request's token against the `api_token` setting (open when unset)
+fn is_authorized<R: Runtime>(app: &AppHandle<R>, request: &tiny_http::Request) -> bool {
+    let expected = app
+        .try_state::<AppState>()
+        .and_then(|state| db::get_api_token(&state.conn()));
+    let Some(expected) = expected else {
+        return true;
+    };
+
+    let provided = header_value(request, API_TOKEN_HEADER).or_else(|| {
+        header_value(request, "Authorization")
+            .and_then(|v| v.strip_prefix("Bearer ").map(|t| t.to_string()))
+    });
+    provided.is_some_and(|token| tokens_match(token.trim(), &expected))
+}
+
+/// Compare tokens without bailing out at the first differing byte
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Parse a monitor start request from a JSON body, a form-encoded body, or
+/// (with an empty body) query parameters. Form and query input accept
+/// `prNumber`, `repo`, `maxIterations`, `intervalMinutes`, `notificationLevel`,
+/// `force` and comma-separated `tags`.
+fn parse_start_monitor_request(
+    content_type: &str,
+    body: &str,
+    query: &str,
+) -> Result<StartMonitorRequest, String> {
+    let body = body.trim();
+    if content_type.starts_with("application/json") || body.starts_with('{') {
+        return serde_json::from_str(body).map_err(|e| format!("Invalid JSON: {}", e));
+    }
+
+    let input = if body.is_empty() { query } else { body };
+    let fields: std::collections::HashMap<String, String> = input
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (form_decode(key), form_decode(value)))
+        .collect();
+    let field = |name: &str| fields.get(name).map(|v| v.trim()).filter(|v| !v.is_empty());
+    let number = |name: &str| -> Result<Option<i32>, String> {
+        field(name)
+            .map(|v| v.parse::<i32>().map_err(|_| format!("Invalid {}: {}", name, v)))
+            .transpose()
+    };
+
+    Ok(StartMonitorRequest {
+        pr_number: number("prNumber")?.ok_or("Missing prNumber")?,
+        repo: field("repo").ok_or("Missing repo")?.to_string(),
+        max_iterations: number("maxIterations")?,
+        interval_minutes: number("intervalMinutes")?,
+        tags: field("tags").map(|v| v.split(',').map(|t| t.trim().to_string()).collect()),
+        notification_level: field("notificationLevel").map(str::to_string),
+        mode: field("mode").map(str::to_string),
+        force: field("force").map(|v| v == "true" || v == "1"),
+    })
+}
+
+/// Decode an application/x-www-form-urlencoded component
+fn form_decode(input: &str) -> String {
+    percent_decode(&input.replace('+', " "))
+}
+
+fn handle_summary<R: Runtime>(app: &AppHandle<R>) -> (i32, String) {
+    let state = match app.try_state::<AppState>() {
+        Some(s) => s,
+        None => return (500, ApiResponse::<()>::error("App state not available")),
+    };
+
+    let conn = state.conn();
+
+    let next_check_at: Option<String> = conn
+        .query_row(
+            "SELECT MIN(next_check_at) FROM monitors WHERE status IN ('running', 'sleeping')",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(None);
+    let queued_monitors: i32 = conn
+        .query_row("SELECT COUNT(*) FROM monitor_queue", [], |row| row.get(0))
+        .unwrap_or(0);
+    let blocked_monitors: i32 = conn
+        .query_row("SELECT COUNT(*) FROM monitors WHERE status = 'blocked'", [], |row| row.get(0))
+        .unwrap_or(0);
+
+    (
+        200,
+        ApiResponse::success(serde_json::json!({
+            "attention": db::get_attention_count(&conn, None).unwrap_or(0),
+            "unread": db::get_unread_counts(&conn).map(|c| c.total).unwrap_or(0),
+            "activeMonitors": db::count_active_monitors(&conn).unwrap_or(0),
+            "queuedMonitors": queued_monitors,
+            "blockedMonitors": blocked_monitors,
+            "nextCheckAt": next_check_at,
+        })),
+    )
+}
+
 fn handle_start_monitor<R: Runtime>(
     app: &AppHandle<R>,
     req: StartMonitorRequest,
@@ -146,64 +554,104 @@ fn handle_start_monitor<R: Runtime>(
         None => return (500, ApiResponse::<()>::error("App state not available")),
     };
 
-    let pr_id = format!("{}#{}", req.repo, req.pr_number);
+    let params = monitor::MonitorStartParams {
+        pr_number: req.pr_number,
+        repo: req.repo,
+        max_iterations: req.max_iterations,
+        interval_minutes: req.interval_minutes,
+        tags: req.tags,
+        notification_level: req.notification_level,
+        mode: req.mode,
+        allow_queue: false,
+        force: req.force.unwrap_or(false),
+    };
 
-    // Fetch and cache PR metadata so it shows up in the dashboard
-    if let Err(e) = fetch_and_cache_pr(&state, req.pr_number, &req.repo) {
-        eprintln!("Warning: Failed to cache PR: {}", e);
-        // Continue anyway - monitor can still work
+    // The core validates before the values reach gh's command line or the DB
+    match monitor::start_monitor_core(app, &state, params) {
+        Ok(monitor) => (200, ApiResponse::success(monitor)),
+        Err(monitor::MonitorStartError::Invalid(errors)) => (422, ApiResponse::<()>::invalid(errors)),
+        Err(e @ monitor::MonitorStartError::AlreadyRunning(_)) => (409, ApiResponse::<()>::error(&e.to_string())),
+        Err(e) => (400, ApiResponse::<()>::error(&e.to_string())),
     }
+}
 
-    // Use the monitor module's start_monitor logic
-    match start_monitor_internal(
-        app,
-        &state,
-        pr_id.clone(),
-        req.pr_number,
-        req.repo.clone(),
-        req.max_iterations,
-        req.interval_minutes,
-    ) {
-        Ok(monitor) => {
-            // PR is already cached by fetch_and_cache_pr above — no need to
-            // emit pr:refresh which triggers a full forceRefresh fetch and
-            // burns GraphQL rate limit on stale-PR checks.
-            (200, ApiResponse::success(monitor))
-        }
-        Err(e) => (400, ApiResponse::<()>::error(&e)),
+/// Fetch and cache a PR about to be monitored. Fails if the PR can't be
+/// fetched (`Unavailable`) or isn't open (`Refused`), unless `force` is set
+/// (then failures are only logged).
+/// The card keeps its column; starting the monitor moves it. Returns a warning
+/// to show with the started monitor, e.g. for a PR from a fork.
+pub(crate) fn ensure_pr_monitorable(
+    state: &AppState,
+    pr_number: i32,
+    repo: &str,
+    force: bool,
+) -> Result<Option<String>, monitor::MonitorStartError> {
+    use monitor::MonitorStartError::{Refused, Unavailable};
+
+    // Every gh call below would fail anyway; say why instead
+    if crate::environment::gh_auth_missing(state) {
+        return Err(Unavailable(crate::environment::GH_AUTH_ERROR.to_string()));
     }
-}
 
-/// Fetch a single PR from GitHub and cache it
-fn fetch_and_cache_pr(state: &AppState, pr_number: i32, repo: &str) -> Result<(), String> {
-    use std::process::Command;
+    let pr_id = format!("{}#{}", repo, pr_number);
+    let problem = match fetch_and_cache_pr(state, pr_number, repo) {
+        Ok(pr_state) if pr_state == "open" => {
+            let warning = fork_warning(state, &pr_id);
+            if let Some(warning) = &warning {
+                eprintln!("Warning: {}", warning);
+            }
+            return Ok(warning);
+        }
+        Ok(pr_state) => Refused(format!("PR {} is {}", pr_id, pr_state)),
+        Err(e) => Unavailable(format!("Failed to fetch PR {}: {}", pr_id, e)),
+    };
 
-    let output = Command::new("gh")
-        .args([
-            "pr",
-            "view",
-            &pr_number.to_string(),
-            "--repo",
-            repo,
-            "--json",
-            "number,title,url,state,isDraft,author,headRefName,baseRefName,labels,reviewDecision,statusCheckRollup,createdAt,updatedAt",
-        ])
-        .output()
-        .map_err(|e| format!("Failed to execute gh CLI: {}", e))?;
+    if force {
+        eprintln!("Warning: {} (starting monitor anyway)", problem);
+        Ok(Some(problem.to_string()))
+    } else {
+        Err(problem)
+    }
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("gh CLI error: {}", stderr));
+/// What to warn about when monitoring a PR from a fork (None if it isn't a
+/// fork or the `allow_fork_monitors` setting is on)
+fn fork_warning(state: &AppState, pr_id: &str) -> Option<String> {
+    let conn = state.conn();
+    if db::get_allow_fork_monitors(&conn) {
+        return None;
     }
+    let head_repo: Option<String> = conn
+        .query_row(
+            "SELECT head_repo FROM pr_cache WHERE id = ?1 AND is_fork = 1",
+            [pr_id],
+            |row| row.get(0),
+        )
+        .ok()?;
+    Some(format!(
+        "PR {} comes from the fork {}; fixes may not be pushable there \
+         (turn on allow_fork_monitors to silence this warning)",
+        pr_id,
+        head_repo.as_deref().unwrap_or("(unknown)")
+    ))
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let gh_pr: serde_json::Value =
-        serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+/// Fetch a single PR from GitHub and cache it, leaving its column alone (a
+/// new PR lands in the default column). Returns its state ("open", "closed"
+/// or "merged").
+pub(crate) fn fetch_and_cache_pr(
+    state: &AppState,
+    pr_number: i32,
+    repo: &str,
+) -> Result<String, String> {
+    let gh_pr = state.github.view_pr(
+        repo,
+        pr_number,
+        "number,title,url,state,isDraft,author,headRefName,headRepository,headRepositoryOwner,\
+         isCrossRepository,baseRefName,labels,reviewDecision,statusCheckRollup,createdAt,updatedAt",
+    )?;
 
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    let conn = state.conn();
 
     let pr_id = format!("{}#{}", repo, pr_number);
     let title = gh_pr["title"].as_str().unwrap_or("Unknown");
@@ -213,30 +661,17 @@ fn fetch_and_cache_pr(state: &AppState, pr_number: i32, repo: &str) -> Result<()
     let is_draft = gh_pr["isDraft"].as_bool().unwrap_or(false);
     let branch = gh_pr["headRefName"].as_str().unwrap_or("");
     let base_branch = gh_pr["baseRefName"].as_str().unwrap_or("main");
+    let head_repo = crate::head_repo_path(
+        gh_pr["headRepositoryOwner"]["login"].as_str(),
+        gh_pr["headRepository"]["name"].as_str(),
+    );
+    let is_fork = gh_pr["isCrossRepository"].as_bool().unwrap_or(false);
     let created_at = gh_pr["createdAt"].as_str().unwrap_or("");
     let updated_at = gh_pr["updatedAt"].as_str().unwrap_or("");
 
     // Determine CI status
-    let ci_status = gh_pr["statusCheckRollup"]
-        .as_array()
-        .and_then(|checks| {
-            if checks.iter().any(|c| {
-                c["conclusion"].as_str() == Some("FAILURE")
-                    || c["state"].as_str() == Some("FAILURE")
-            }) {
-                Some("failing")
-            } else if checks.iter().any(|c| {
-                c["status"].as_str() == Some("QUEUED")
-                    || c["status"].as_str() == Some("IN_PROGRESS")
-                    || c["state"].as_str() == Some("PENDING")
-            }) {
-                Some("pending")
-            } else if !checks.is_empty() {
-                Some("passing")
-            } else {
-                None
-            }
-        });
+    let checks = serde_json::from_value(gh_pr["statusCheckRollup"].clone()).ok();
+    let ci_status = crate::determine_ci_status(&checks);
 
     // Determine review status
     let review_status = match gh_pr["reviewDecision"].as_str() {
@@ -261,19 +696,26 @@ fn fetch_and_cache_pr(state: &AppState, pr_number: i32, repo: &str) -> Result<()
             id, number, repo, title, url, author, state, is_draft,
             ci_status, ci_url, review_status, reviewers, comments_count,
             unresolved_threads, labels, branch, base_branch, created_at,
-            updated_at, column_assignment, cached_at
+            updated_at, head_repo, is_fork, cached_at
         ) VALUES (
             ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
-            ?14, ?15, ?16, ?17, ?18, ?19, 'monitoring', datetime('now')
+            ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, datetime('now')
         )
         ON CONFLICT(id) DO UPDATE SET
             title = excluded.title,
             state = excluded.state,
             is_draft = excluded.is_draft,
-            ci_status = excluded.ci_status,
+            -- A manual override (db::set_ci_override) holds until it expires
+            ci_status = CASE WHEN datetime(pr_cache.ci_override_until) > datetime('now')
+                             THEN pr_cache.ci_status ELSE excluded.ci_status END,
+            ci_status_fetched = CASE WHEN datetime(pr_cache.ci_override_until) > datetime('now')
+                                     THEN excluded.ci_status END,
+            ci_override_until = CASE WHEN datetime(pr_cache.ci_override_until) > datetime('now')
+                                     THEN pr_cache.ci_override_until END,
             review_status = excluded.review_status,
             updated_at = excluded.updated_at,
-            column_assignment = 'monitoring',
+            head_repo = excluded.head_repo,
+            is_fork = excluded.is_fork,
             cached_at = datetime('now')
         "#,
         rusqlite::params![
@@ -296,11 +738,13 @@ fn fetch_and_cache_pr(state: &AppState, pr_number: i32, repo: &str) -> Result<()
             base_branch,
             created_at,
             updated_at,
+            head_repo,
+            is_fork,
         ],
     )
     .map_err(|e| format!("Failed to cache PR: {}", e))?;
 
-    Ok(())
+    Ok(state_str)
 }
 
 fn handle_stop_monitor<R: Runtime>(app: &AppHandle<R>, pr_id: &str) -> (i32, String) {
@@ -310,14 +754,11 @@ fn handle_stop_monitor<R: Runtime>(app: &AppHandle<R>, pr_id: &str) -> (i32, Str
     };
 
     // Find active monitor for this PR
-    let conn = match state.db.lock() {
-        Ok(c) => c,
-        Err(e) => return (500, ApiResponse::<()>::error(&format!("DB lock error: {}", e))),
-    };
+    let conn = state.conn();
 
     let monitor_id: Option<String> = conn
         .query_row(
-            "SELECT id FROM monitors WHERE pr_id = ?1 AND status IN ('running', 'sleeping')",
+            "SELECT id FROM monitors WHERE pr_id = ?1 AND status IN ('running', 'sleeping', 'blocked')",
             [pr_id],
             |row| row.get(0),
         )
@@ -333,13 +774,12 @@ fn handle_stop_monitor<R: Runtime>(app: &AppHandle<R>, pr_id: &str) -> (i32, Str
             }
 
             // Update database
-            if let Ok(conn) = state.db.lock() {
-                let now = chrono::Utc::now().to_rfc3339();
-                let _ = conn.execute(
-                    "UPDATE monitors SET status = 'stopped', ended_at = ?1, exit_reason = 'api_stopped' WHERE id = ?2",
-                    rusqlite::params![now, id],
-                );
-            }
+            let conn = state.conn();
+            let now = chrono::Utc::now().to_rfc3339();
+            let _ = conn.execute(
+                "UPDATE monitors SET status = 'stopped', ended_at = ?1, exit_reason = 'api_stopped' WHERE id = ?2",
+                rusqlite::params![now, id],
+            );
 
             (200, ApiResponse::success(serde_json::json!({"stopped": true, "monitorId": id})))
         }
@@ -347,25 +787,73 @@ fn handle_stop_monitor<R: Runtime>(app: &AppHandle<R>, pr_id: &str) -> (i32, Str
     }
 }
 
-fn handle_get_monitor<R: Runtime>(app: &AppHandle<R>, pr_id: &str) -> (i32, String) {
+fn handle_rerun_checks<R: Runtime>(app: &AppHandle<R>, pr_id: &str) -> (i32, String) {
     let state = match app.try_state::<AppState>() {
         Some(s) => s,
         None => return (500, ApiResponse::<()>::error("App state not available")),
     };
 
-    let conn = match state.db.lock() {
-        Ok(c) => c,
-        Err(e) => return (500, ApiResponse::<()>::error(&format!("DB lock error: {}", e))),
+    match checks::rerun_failed_checks_internal(app, &state, pr_id) {
+        Ok(result) => (200, ApiResponse::success(result)),
+        Err(e) => (400, ApiResponse::<()>::error(&e)),
+    }
+}
+
+fn handle_ci_logs<R: Runtime>(
+    app: &AppHandle<R>,
+    pr_id: &str,
+    max_lines: Option<usize>,
+) -> (i32, String) {
+    let state = match app.try_state::<AppState>() {
+        Some(s) => s,
+        None => return (500, ApiResponse::<()>::error("App state not available")),
     };
 
+    match checks::get_ci_failure_logs_internal(&state, pr_id, max_lines) {
+        Ok(logs) => (200, ApiResponse::success(logs)),
+        Err(e) => (400, ApiResponse::<()>::error(&e)),
+    }
+}
+
+/// Decode %XX escapes in a path segment (e.g. "owner%2Frepo%2312")
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(b) = input
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn handle_get_monitor<R: Runtime>(app: &AppHandle<R>, pr_id: &str) -> (i32, String) {
+    let state = match app.try_state::<AppState>() {
+        Some(s) => s,
+        None => return (500, ApiResponse::<()>::error("App state not available")),
+    };
+
+    let conn = state.conn();
+
     let result = conn.query_row(
         r#"
         SELECT id, pr_id, pr_number, repo, pid, status, iteration, max_iterations,
                interval_minutes, started_at, last_check_at, next_check_at, ended_at,
-               comments_fixed, exit_reason, log_file
+               comments_fixed, exit_reason, log_file, tags, notification_level, head_sha, paused,
+               mode
         FROM monitors
         WHERE pr_id = ?1
-        ORDER BY started_at DESC
+        ORDER BY started_at DESC, start_seq DESC
         LIMIT 1
         "#,
         [pr_id],
@@ -387,12 +875,21 @@ fn handle_get_monitor<R: Runtime>(app: &AppHandle<R>, pr_id: &str) -> (i32, Stri
                 "commentsFixed": row.get::<_, i32>(13)?,
                 "exitReason": row.get::<_, Option<String>>(14)?,
                 "logFile": row.get::<_, String>(15)?,
+                "tags": monitor::parse_tags(&row.get::<_, String>(16)?),
+                "notificationLevel": row.get::<_, String>(17)?,
+                "headSha": row.get::<_, Option<String>>(18)?,
+                "paused": row.get::<_, bool>(19)?,
+                "mode": row.get::<_, String>(20)?,
             }))
         },
     );
 
     match result {
-        Ok(monitor) => (200, ApiResponse::success(monitor)),
+        Ok(mut monitor) => {
+            let id = monitor["id"].as_str().unwrap_or_default();
+            monitor["estimatedCompletionAt"] = monitor::estimated_completion_at(&conn, id).into();
+            (200, ApiResponse::success(monitor))
+        }
         Err(rusqlite::Error::QueryReturnedNoRows) => {
             (404, ApiResponse::<()>::error("No monitor found for this PR"))
         }
@@ -400,30 +897,77 @@ fn handle_get_monitor<R: Runtime>(app: &AppHandle<R>, pr_id: &str) -> (i32, Stri
     }
 }
 
-fn handle_list_monitors<R: Runtime>(app: &AppHandle<R>) -> (i32, String) {
+fn handle_monitor_logs<R: Runtime>(
+    app: &AppHandle<R>,
+    pr_id: &str,
+    lines: Option<usize>,
+) -> (i32, String) {
     let state = match app.try_state::<AppState>() {
         Some(s) => s,
         None => return (500, ApiResponse::<()>::error("App state not available")),
     };
 
-    let conn = match state.db.lock() {
-        Ok(c) => c,
-        Err(e) => return (500, ApiResponse::<()>::error(&format!("DB lock error: {}", e))),
+    let found = {
+        let conn = state.conn();
+        let active = ["running".to_string(), "sleeping".to_string()];
+        match monitor::recent_monitor_for_pr(&conn, pr_id, Some(&active)) {
+            Ok(None) => monitor::recent_monitor_for_pr(&conn, pr_id, None),
+            other => other,
+        }
+    };
+    let monitor = match found {
+        Ok(Some(recent)) => recent.monitor,
+        Ok(None) => return (404, ApiResponse::<()>::error("No monitor found for this PR")),
+        Err(e) => return (500, ApiResponse::<()>::error(&e)),
+    };
+
+    let lines = lines
+        .unwrap_or(monitor::DEFAULT_LOG_TAIL_LINES)
+        .clamp(1, monitor::MAX_LOG_TAIL_LINES);
+    let tail = if monitor.log_file.is_empty() {
+        Err(std::io::ErrorKind::NotFound.into())
+    } else {
+        monitor::tail_log(std::path::Path::new(&monitor.log_file), lines)
+    };
+    match tail {
+        Ok(tail) => (
+            200,
+            ApiResponse::success(serde_json::json!({
+                "monitorId": monitor.id,
+                "status": monitor.status,
+                "logFile": monitor.log_file,
+                "lines": tail,
+            })),
+        ),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (
+            404,
+            ApiResponse::<()>::error(&format!("Monitor {} has no log file yet", monitor.id)),
+        ),
+        Err(e) => (500, ApiResponse::<()>::error(&format!("Failed to read log file: {}", e))),
+    }
+}
+
+fn handle_list_monitors<R: Runtime>(app: &AppHandle<R>) -> (i32, String) {
+    let state = match app.try_state::<AppState>() {
+        Some(s) => s,
+        None => return (500, ApiResponse::<()>::error("App state not available")),
     };
 
+    let conn = state.conn();
+
     let mut stmt = match conn.prepare(
         r#"
-        SELECT id, pr_id, pr_number, repo, status, iteration, max_iterations
+        SELECT id, pr_id, pr_number, repo, status, iteration, max_iterations, tags
         FROM monitors
         WHERE status IN ('running', 'sleeping')
-        ORDER BY started_at DESC
+        ORDER BY started_at DESC, start_seq DESC
         "#,
     ) {
         Ok(s) => s,
         Err(e) => return (500, ApiResponse::<()>::error(&format!("Query error: {}", e))),
     };
 
-    let monitors: Vec<serde_json::Value> = stmt
+    let mut monitors: Vec<serde_json::Value> = stmt
         .query_map([], |row| {
             Ok(serde_json::json!({
                 "id": row.get::<_, String>(0)?,
@@ -433,158 +977,189 @@ fn handle_list_monitors<R: Runtime>(app: &AppHandle<R>) -> (i32, String) {
                 "status": row.get::<_, String>(4)?,
                 "iteration": row.get::<_, i32>(5)?,
                 "maxIterations": row.get::<_, i32>(6)?,
+                "tags": monitor::parse_tags(&row.get::<_, String>(7)?),
             }))
         })
         .ok()
         .map(|iter| iter.filter_map(|r| r.ok()).collect())
         .unwrap_or_default();
 
+    for monitor in &mut monitors {
+        let id = monitor["id"].as_str().unwrap_or_default();
+        monitor["estimatedCompletionAt"] = monitor::estimated_completion_at(&conn, id).into();
+    }
+
     (200, ApiResponse::success(monitors))
 }
 
-/// Internal function to start a monitor (mirrors monitor::start_monitor but without State wrapper)
-fn start_monitor_internal<R: Runtime>(
-    app: &AppHandle<R>,
-    state: &AppState,
-    pr_id: String,
-    pr_number: i32,
-    repo: String,
-    max_iterations: Option<i32>,
-    interval_minutes: Option<i32>,
-) -> Result<monitor::Monitor, String> {
-    use chrono::{Duration, Utc};
-    use uuid::Uuid;
-
-    let id = Uuid::new_v4().to_string();
-    let max_iter = max_iterations.unwrap_or(10);
-    let interval = interval_minutes.unwrap_or(15);
-    let (ai_provider, ai_model, dirty_worktree_policy, skip_ci_fix) = {
-        let conn = state
-            .db
-            .lock()
-            .map_err(|e| format!("Failed to lock database: {}", e))?;
-        let (ai_provider, ai_model) = crate::db::get_ai_config(&conn);
-        let dirty_worktree_policy = crate::db::get_monitor_dirty_worktree_policy(&conn);
-        let skip_ci_fix = crate::db::get_skip_ci_fix(&conn);
-        (ai_provider, ai_model, dirty_worktree_policy, skip_ci_fix)
-    };
-    let now = Utc::now();
-    let started_at = now.to_rfc3339();
-    let next_check = (now + Duration::minutes(interval as i64)).to_rfc3339();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Create log file path
-    let log_dir = dirs::data_local_dir()
-        .ok_or_else(|| "Failed to get local data directory".to_string())?
-        .join("com.clanker-spanker.app")
-        .join("logs");
+    #[test]
+    fn test_parse_start_monitor_request() {
+        let req = parse_start_monitor_request(
+            "application/json",
+            r#"{"prNumber": 12, "repo": "o/r", "tags": ["a"]}"#,
+            "",
+        )
+        .unwrap();
+        assert_eq!((req.pr_number, req.repo.as_str()), (12, "o/r"));
 
-    std::fs::create_dir_all(&log_dir)
-        .map_err(|e| format!("Failed to create log directory: {}", e))?;
+        let req = parse_start_monitor_request(
+            "application/x-www-form-urlencoded",
+            "prNumber=7&repo=octo%2Fwidgets&intervalMinutes=5&tags=ci,+review&notificationLevel=silent&mode=watch",
+            "",
+        )
+        .unwrap();
+        assert_eq!(req.pr_number, 7);
+        assert_eq!(req.repo, "octo/widgets");
+        assert_eq!(req.interval_minutes, Some(5));
+        assert_eq!(req.tags, Some(vec!["ci".to_string(), "review".to_string()]));
+        assert_eq!(req.notification_level.as_deref(), Some("silent"));
+        assert_eq!(req.mode.as_deref(), Some("watch"));
+
+        // Empty body falls back to query parameters
+        let req = parse_start_monitor_request("", "", "repo=o/r&prNumber=3").unwrap();
+        assert_eq!((req.pr_number, req.max_iterations), (3, None));
+
+        assert!(parse_start_monitor_request("", "", "repo=o/r").is_err());
+        assert!(parse_start_monitor_request("", "prNumber=x&repo=o/r", "").is_err());
+    }
 
-    let log_file = log_dir
-        .join(format!("monitor-{}-{}.log", pr_number, &id))
-        .to_string_lossy()
-        .to_string();
+    #[test]
+    fn test_event_stream_publish() {
+        assert_eq!(
+            sse_frame("pr:added", "{\"a\":1}\n{\"b\":2}"),
+            "event: pr:added\ndata: {\"a\":1}\ndata: {\"b\":2}\n\n"
+        );
 
-    // Database operations
-    {
-        let conn = state
-            .db
-            .lock()
-            .map_err(|e| format!("Failed to lock database: {}", e))?;
+        let first = subscribe_events().unwrap();
+        let second = subscribe_events().unwrap();
+        publish_event("monitor:state-changed", r#"{"activeCount":1}"#);
+        let expected = "event: monitor:state-changed\ndata: {\"activeCount\":1}\n\n";
+        assert_eq!(first.try_recv().unwrap(), expected);
+        assert_eq!(second.try_recv().unwrap(), expected);
+
+        // A disconnected client is dropped on the next publish
+        drop(second);
+        publish_event("monitor:completed", "{}");
+        assert_eq!(EVENT_CLIENTS.lock().unwrap().len(), 1);
+        assert!(first.try_recv().is_ok());
+
+        // PR changes the app emits reach the stream
+        let app = tauri::test::mock_app();
+        register_event_listeners(app.handle());
+        let changes = crate::pr_events::PrChanges {
+            removed: vec!["o/r#1".to_string()],
+            ..Default::default()
+        };
+        crate::pr_events::emit_changes(app.handle(), &changes);
+        let frame = first.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(frame, "event: pr:removed\ndata: [\"o/r#1\"]\n\n");
+    }
 
-        // Check for existing active monitor
-        let existing: Option<String> = conn
-            .query_row(
-                "SELECT id FROM monitors WHERE pr_id = ?1 AND status IN ('running', 'sleeping')",
-                [&pr_id],
-                |row| row.get(0),
-            )
-            .ok();
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secreT", "secret"));
+        assert!(!tokens_match("secret2", "secret"));
+    }
 
-        if existing.is_some() {
-            return Err(format!("Monitor already running for PR: {}", pr_id));
-        }
+    #[test]
+    fn test_handle_start_monitor_status() {
+        use crate::github::mock::MockGithub;
+        use std::sync::Arc;
+
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_view("o/r", 1, serde_json::json!({"state": "OPEN", "title": "Open"}));
+        let dir = std::env::temp_dir().join(format!("clanker-api-start-{}", uuid::Uuid::new_v4()));
+        let mut state = AppState::for_tests(github);
+        state.data_dir = Some(dir.clone());
+        state
+            .conn()
+            .execute_batch(
+                "INSERT INTO monitors (id, pr_id, pr_number, repo, status, started_at, log_file)
+                 VALUES ('m1', 'o/r#1', 1, 'o/r', 'running', '2026-01-01T00:00:00Z', '')",
+            )
+            .unwrap();
+        let app = tauri::test::mock_app();
+        app.manage(state);
+        let start = |pr_number: i32| {
+            let req = serde_json::from_value(serde_json::json!({"prNumber": pr_number, "repo": "o/r"}))
+                .unwrap();
+            handle_start_monitor(app.handle(), req).0
+        };
 
-        conn.execute(
-            r#"
-            INSERT INTO monitors (
-                id, pr_id, pr_number, repo, status, iteration, max_iterations,
-                interval_minutes, started_at, next_check_at, log_file
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
-            "#,
-            rusqlite::params![
-                id,
-                pr_id,
-                pr_number,
-                repo,
-                "running",
-                0,
-                max_iter,
-                interval,
-                started_at,
-                next_check,
-                log_file
-            ],
-        )
-        .map_err(|e| format!("Failed to create monitor: {}", e))?;
+        assert_eq!(start(0), 422);
+        assert_eq!(start(1), 409);
+        // Not on the mock, so the PR can't be fetched
+        assert_eq!(start(2), 400);
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
-    // Spawn the monitor process
-    let pid = state.processes.spawn_monitor(
-        app,
-        &id,
-        &pr_id,
-        pr_number,
-        &repo,
-        max_iter,
-        interval,
-        &ai_provider,
-        ai_model.as_deref(),
-        &dirty_worktree_policy,
-        &skip_ci_fix,
-    )?;
+    #[test]
+    fn test_ensure_pr_monitorable() {
+        use crate::github::mock::MockGithub;
+        use std::sync::Arc;
 
-    // Update the PID
-    {
-        let conn = state
-            .db
-            .lock()
-            .map_err(|e| format!("Failed to lock database: {}", e))?;
-
-        conn.execute(
-            "UPDATE monitors SET pid = ?1 WHERE id = ?2",
-            rusqlite::params![pid as i32, id],
-        )
-        .map_err(|e| format!("Failed to update monitor PID: {}", e))?;
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_view("o/r", 1, serde_json::json!({"state": "OPEN", "title": "Open"}));
+        github.set_view("o/r", 2, serde_json::json!({"state": "MERGED", "title": "Merged"}));
+        let state = AppState::for_tests(github);
+
+        assert!(ensure_pr_monitorable(&state, 1, "o/r", false).is_ok());
+        let column: String = state
+            .conn()
+            .query_row(
+                "SELECT column_assignment FROM pr_cache WHERE id = 'o/r#1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        // Only a started monitor moves the card
+        assert_eq!(column, "todo");
+
+        let merged = ensure_pr_monitorable(&state, 2, "o/r", false).unwrap_err();
+        assert!(merged.is_permanent());
+        assert_eq!(merged.to_string(), "PR o/r#2 is merged");
+        // Can't be fetched right now; a queued start is kept for later
+        assert!(!ensure_pr_monitorable(&state, 3, "o/r", false).unwrap_err().is_permanent());
+        // Forced starts go ahead regardless
+        assert!(ensure_pr_monitorable(&state, 2, "o/r", true).is_ok());
+        assert!(ensure_pr_monitorable(&state, 3, "o/r", true).is_ok());
     }
 
-    // Emit state change event
-    if let Ok(count) = monitor::get_active_monitor_count(state) {
-        crate::tray::update_tray_status(count);
-        crate::dock::set_dock_badge(if count > 0 { Some(count) } else { None });
-        let _ = app.emit(
-            "monitor:state-changed",
-            monitor::MonitorStatePayload { active_count: count },
+    #[test]
+    fn test_ensure_pr_monitorable_fork() {
+        use crate::github::mock::MockGithub;
+        use std::sync::Arc;
+
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_view(
+            "o/r",
+            4,
+            serde_json::json!({
+                "state": "OPEN",
+                "isCrossRepository": true,
+                "headRepository": {"name": "r"},
+                "headRepositoryOwner": {"login": "bob"},
+            }),
         );
+        let state = AppState::for_tests(github);
+
+        // Started anyway, with a warning
+        let warning = ensure_pr_monitorable(&state, 4, "o/r", false).unwrap().unwrap();
+        assert!(warning.contains("fork bob/r"), "{}", warning);
+        let (head_repo, is_fork): (Option<String>, bool) = state
+            .conn()
+            .query_row("SELECT head_repo, is_fork FROM pr_cache WHERE id = 'o/r#4'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((head_repo.as_deref(), is_fork), (Some("bob/r"), true));
+
+        db::set_setting(&state.conn(), db::ALLOW_FORK_MONITORS_SETTING_KEY, "true").unwrap();
+        assert!(matches!(ensure_pr_monitorable(&state, 4, "o/r", false), Ok(None)));
     }
-
-    Ok(monitor::Monitor {
-        id,
-        pr_id,
-        pr_number,
-        repo,
-        pid: Some(pid as i32),
-        status: "running".to_string(),
-        iteration: 0,
-        max_iterations: max_iter,
-        interval_minutes: interval,
-        started_at,
-        last_check_at: None,
-        next_check_at: Some(next_check),
-        ended_at: None,
-        comments_fixed: 0,
-        exit_reason: None,
-        log_file,
-    })
 }
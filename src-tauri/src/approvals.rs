@@ -0,0 +1,174 @@
+//! Reacting to approvals
+//!
+//! When a fetch sees a PR's review status turn "approved", the repo's
+//! `on_approved` setting decides what happens: "notify" (default) raises a
+//! notification, "stop_monitor" also stops the PR's monitor, and "auto_merge"
+//! merges it through `merge_pr` with the repo's merge method once CI is
+//! passing (re-checked whenever CI changes on an approved PR). Only CI as
+//! fetched from GitHub counts; a manual override never triggers a merge.
+//! Stops and merges are written to audit_log.
+
+use crate::db::{self, AppState};
+use crate::monitor;
+use crate::notifications;
+use crate::pr_events::PrChanges;
+use tauri::{AppHandle, Manager};
+
+/// What to do for one approved PR
+#[derive(Debug, Default, PartialEq)]
+struct Plan {
+    notify: bool,
+    stop_monitor: bool,
+    merge: bool,
+}
+
+/// Decide the reaction for an approved PR given what changed in this fetch
+fn plan(
+    on_approved: &str,
+    review_changed: bool,
+    ci_changed: bool,
+    ci_status: Option<&str>,
+) -> Plan {
+    let green = ci_status == Some("passing");
+    match on_approved {
+        "auto_merge" => Plan {
+            // A merge notifies on its own; otherwise say it is waiting for CI
+            notify: review_changed && !green,
+            stop_monitor: false,
+            merge: green && (review_changed || ci_changed),
+        },
+        "stop_monitor" => Plan {
+            notify: review_changed,
+            stop_monitor: review_changed,
+            merge: false,
+        },
+        _ => Plan {
+            notify: review_changed,
+            ..Default::default()
+        },
+    }
+}
+
+/// Apply each repo's `on_approved` action to the PRs a fetch changed
+pub fn handle_changes(app: &AppHandle, changes: &PrChanges) {
+    for update in &changes.updated {
+        let review_changed = update.changes.contains_key("reviewStatus");
+        let ci_changed = update.changes.contains_key("ciStatus");
+        if !review_changed && !ci_changed {
+            continue;
+        }
+
+        let state = app.state::<AppState>();
+        let (number, ci_status, on_approved) = {
+            let conn = state.conn();
+            let pr: Option<(i32, String, String, Option<String>, String)> = conn
+                .query_row(
+                    &format!(
+                        "SELECT number, repo, state, {}, review_status FROM pr_cache WHERE id = ?1",
+                        db::FETCHED_CI_STATUS_SQL
+                    ),
+                    [&update.pr_id],
+                    |row| {
+                        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+                    },
+                )
+                .ok();
+            let Some((number, repo, pr_state, ci_status, review_status)) = pr else {
+                continue;
+            };
+            if pr_state != "open" || review_status != "approved" {
+                continue;
+            }
+            (number, ci_status, db::get_repo_on_approved(&conn, &repo))
+        };
+
+        let plan = plan(&on_approved, review_changed, ci_changed, ci_status.as_deref());
+        let pr_id = update.pr_id.clone();
+
+        if plan.stop_monitor {
+            match monitor::auto_stop_monitor_for_pr(app, &state, &pr_id, "pr_approved") {
+                Ok(Some(monitor_id)) => audit(&state, &pr_id, "stop_monitor", &monitor_id),
+                Ok(None) => {}
+                Err(e) => eprintln!("Failed to stop monitor for approved {}: {}", pr_id, e),
+            }
+        }
+
+        if plan.notify {
+            let message = match on_approved.as_str() {
+                "auto_merge" => format!("PR #{} approved; it will merge once CI passes", number),
+                "stop_monitor" => format!("PR #{} approved; its monitor was stopped", number),
+                _ => format!("PR #{} was approved", number),
+            };
+            let _ = notifications::notify_pr_approved(app.clone(), number, pr_id.clone(), message);
+        }
+
+        if plan.merge {
+            println!("Auto-merging approved PR {}", pr_id);
+            let merged =
+                crate::merge_pr(app.clone(), state.clone(), pr_id.clone(), None, false, None);
+            let message = match merged {
+                Ok(()) => {
+                    audit(&state, &pr_id, "auto_merge", "Merged with the repo's merge method");
+                    format!("PR #{} approved and merged automatically", number)
+                }
+                Err(e) => {
+                    eprintln!("Auto-merge of {} failed: {}", pr_id, e);
+                    audit(&state, &pr_id, "auto_merge_failed", &e);
+                    format!("PR #{} approved but auto-merge failed: {}", number, e)
+                }
+            };
+            let _ = notifications::notify_pr_approved(app.clone(), number, pr_id, message);
+        }
+    }
+}
+
+fn audit(state: &AppState, pr_id: &str, action: &str, detail: &str) {
+    if let Err(e) = db::record_audit(&state.conn(), pr_id, action, Some(detail)) {
+        eprintln!("Failed to record audit entry: {}", e);
+    }
+}
+
+/// Actions the app took on its own (auto-merges, stopped monitors), newest first
+#[tauri::command]
+pub fn get_audit_log(
+    state: tauri::State<'_, AppState>,
+    pr_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<db::AuditEntry>, String> {
+    db::get_audit_log(&state.conn(), pr_id.as_deref(), limit.unwrap_or(100))
+        .map_err(|e| format!("Failed to get audit log: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan() {
+        let notify_only = Plan {
+            notify: true,
+            ..Default::default()
+        };
+        assert_eq!(plan("notify", true, false, None), notify_only);
+        assert_eq!(plan("notify", false, true, Some("passing")), Plan::default());
+
+        assert_eq!(
+            plan("stop_monitor", true, false, None),
+            Plan {
+                notify: true,
+                stop_monitor: true,
+                merge: false
+            }
+        );
+
+        // Approved while CI is pending: wait, then merge once it turns green
+        assert_eq!(plan("auto_merge", true, false, Some("pending")), notify_only);
+        let merge = Plan {
+            merge: true,
+            ..Default::default()
+        };
+        assert_eq!(plan("auto_merge", false, true, Some("passing")), merge);
+        assert_eq!(plan("auto_merge", true, true, Some("passing")), merge);
+        assert_eq!(plan("auto_merge", false, true, Some("failing")), Plan::default());
+    }
+}
@@ -0,0 +1,141 @@
+//! Startup board snapshot
+//!
+//! On launch the frontend needs repos, a handful of settings, cached PRs,
+//! active monitors, unread counts and fetch freshness. Asking for each
+//! separately takes the DB lock once per call and lets the window fill in
+//! piecemeal; `get_board_snapshot` reads all of it under one lock and returns
+//! it in a single response. The individual commands remain for targeted
+//! refreshes.
+
+use crate::db::{self, AppState};
+use crate::monitor::{self, Monitor};
+use crate::PR;
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::State;
+
+/// Settings the board reads on startup, already parsed
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardSettings {
+    pub theme: Option<String>,
+    /// Saved filter state (JSON written by the frontend)
+    pub pr_filters: Option<serde_json::Value>,
+    pub sleep_prevention_enabled: bool,
+    pub ai_provider: String,
+    /// Model override for the provider, if any
+    pub ai_model: Option<String>,
+    pub monitor_dirty_worktree_policy: String,
+    pub skip_ci_fix: String,
+    pub monitors_paused: bool,
+    pub stale_data_threshold_minutes: i64,
+}
+
+/// Everything the board shows on startup
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardSnapshot {
+    pub repos: Vec<String>,
+    /// Empty when the board shows every repo
+    pub selected_repo: String,
+    pub settings: BoardSettings,
+    /// Cached PRs of the selected repo (all configured repos if none is selected)
+    pub prs: Vec<PR>,
+    pub active_monitors: Vec<Monitor>,
+    pub unread: db::UnreadCounts,
+    pub fetch_status: Vec<db::FetchStatus>,
+}
+
+fn read_settings(conn: &Connection) -> BoardSettings {
+    let (ai_provider, ai_model) = db::get_ai_config(conn);
+    BoardSettings {
+        theme: db::get_setting_value(conn, "theme"),
+        pr_filters: db::get_setting_value(conn, "pr_filters")
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        sleep_prevention_enabled: db::get_setting_value(conn, "sleep_prevention_enabled")
+            .is_some_and(|v| v == "true"),
+        ai_provider,
+        ai_model,
+        monitor_dirty_worktree_policy: db::get_monitor_dirty_worktree_policy(conn),
+        skip_ci_fix: db::get_skip_ci_fix(conn),
+        monitors_paused: db::get_monitors_paused(conn),
+        stale_data_threshold_minutes: db::get_stale_data_threshold_minutes(conn),
+    }
+}
+
+fn snapshot(conn: &Connection) -> Result<BoardSnapshot, String> {
+    let repos: Vec<String> = db::get_setting(conn, "repos")
+        .map_err(|e| format!("Database error: {}", e))?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let selected_repo = db::get_setting_value(conn, "selected_repo").unwrap_or_default();
+    let settings = read_settings(conn);
+
+    let selected = Some(selected_repo.clone()).filter(|r| !r.is_empty());
+    let prs = crate::cached_board_prs(conn, selected, None, false)?;
+    let active_monitors = monitor::list_monitors(conn, Some("active".to_string()), None, None)?;
+    let unread = db::get_unread_counts(conn)
+        .map_err(|e| format!("Failed to count unread PRs: {}", e))?;
+    let fetch_status =
+        db::get_fetch_status(conn, Utc::now(), settings.stale_data_threshold_minutes)
+            .map_err(|e| format!("Failed to read fetch status: {}", e))?;
+
+    Ok(BoardSnapshot {
+        repos,
+        selected_repo,
+        settings,
+        prs,
+        active_monitors,
+        unread,
+        fetch_status,
+    })
+}
+
+/// Everything the board needs on startup in one call (one DB lock)
+#[tauri::command]
+pub fn get_board_snapshot(state: State<'_, AppState>) -> Result<BoardSnapshot, String> {
+    snapshot(&state.conn())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot() {
+        let conn = Connection::open_in_memory().unwrap();
+        db::init_schema(&conn).unwrap();
+        db::set_setting(&conn, "repos", r#"["o/a", "https://github.com/o/b"]"#).unwrap();
+        db::set_setting(&conn, "theme", "dark").unwrap();
+        db::set_setting(&conn, "pr_filters", r#"{"author": "me"}"#).unwrap();
+        let prs = [("o/a#1", 1, "o/a"), ("o/b#2", 2, "o/b"), ("o/gone#3", 3, "o/gone")];
+        for (id, number, repo) in prs {
+            conn.execute(
+                "INSERT INTO pr_cache (id, number, repo, title, url, author, state, review_status,
+                                       branch, base_branch, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, 't', 'u', 'a', 'open', 'pending', 'b', 'main', '', '')",
+                rusqlite::params![id, number, repo],
+            )
+            .unwrap();
+        }
+
+        let board = snapshot(&conn).unwrap();
+        assert_eq!(board.settings.theme.as_deref(), Some("dark"));
+        assert_eq!(board.settings.pr_filters, Some(serde_json::json!({"author": "me"})));
+        assert_eq!(board.settings.ai_provider, "claude");
+        assert!(!board.settings.monitors_paused);
+        // No selected repo: every configured repo, but not ones since removed
+        let mut ids: Vec<String> = board.prs.iter().map(|pr| pr.id.clone()).collect();
+        ids.sort();
+        assert_eq!(ids, ["o/a#1", "o/b#2"]);
+        // The removed repo's cached PR isn't counted as unread either
+        assert_eq!(board.unread.total, 2);
+
+        db::set_setting(&conn, "selected_repo", "o/b").unwrap();
+        let board = snapshot(&conn).unwrap();
+        assert_eq!(board.selected_repo, "o/b");
+        assert_eq!(board.prs.len(), 1);
+        assert!(board.active_monitors.is_empty());
+    }
+}
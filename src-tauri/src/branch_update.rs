@@ -0,0 +1,118 @@
+//! Bringing a PR branch up to date with its base
+//!
+//! - `merge`: GitHub's update-branch API (merges base into the head branch)
+//! - `rebase`: rebases in the repo's configured local checkout and pushes with
+//!   `--force-with-lease`. Refuses on conflicts and always returns the checkout
+//!   to the branch it was on.
+
+use crate::api;
+use crate::db::{self, AppState};
+use std::path::Path;
+use std::process::Command;
+use tauri::State;
+
+/// Update a PR's branch with its base. `strategy` is "merge" | "rebase".
+#[tauri::command]
+pub fn update_pr_branch(
+    state: State<'_, AppState>,
+    pr_id: String,
+    strategy: String,
+) -> Result<(), String> {
+    let (repo, number, branch, base_branch, local_path) = {
+        let conn = state.conn();
+        let (repo, number, branch, base_branch): (String, i32, String, String) = conn
+            .query_row(
+                "SELECT repo, number, branch, base_branch FROM pr_cache WHERE id = ?1",
+                [&pr_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .map_err(|e| format!("PR not found in cache: {} ({})", pr_id, e))?;
+        let local_path = db::get_repo_local_path(&conn, &repo);
+        (repo, number, branch, base_branch, local_path)
+    };
+
+    match strategy.as_str() {
+        "merge" => state.github.update_branch(&repo, number)?,
+        "rebase" => {
+            let local_path = local_path.ok_or_else(|| {
+                format!("Rebase requires a local_path setting for {}", repo)
+            })?;
+            rebase_locally(&local_path, &branch, &base_branch)?;
+        }
+        other => return Err(format!("Invalid update strategy: {}", other)),
+    }
+
+    // Refresh mergeable/CI state for the card
+    api::fetch_and_cache_pr(&state, number, &repo).map(|_| ())
+}
+
+pub(crate) fn run_git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to execute git: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn rebase_locally(dir: &Path, branch: &str, base_branch: &str) -> Result<(), String> {
+    if !run_git(dir, &["status", "--porcelain"])?.is_empty() {
+        return Err(format!(
+            "Local checkout at {} has uncommitted changes",
+            dir.display()
+        ));
+    }
+
+    // Remember where the checkout was so it can be restored (branch name, or SHA if detached)
+    let original = match run_git(dir, &["symbolic-ref", "--quiet", "--short", "HEAD"]) {
+        Ok(name) => name,
+        Err(_) => run_git(dir, &["rev-parse", "HEAD"])?,
+    };
+
+    run_git(dir, &["fetch", "origin", base_branch, branch])?;
+    let remote_head = run_git(dir, &["rev-parse", &format!("origin/{}", branch)])?;
+
+    run_git(dir, &["checkout", "--quiet", "--detach", &remote_head])?;
+    let result = rebase_and_push(dir, branch, base_branch, &remote_head);
+    let restored = run_git(dir, &["checkout", "--quiet", &original]);
+
+    result?;
+    restored.map(|_| ())
+}
+
+fn rebase_and_push(
+    dir: &Path,
+    branch: &str,
+    base_branch: &str,
+    remote_head: &str,
+) -> Result<(), String> {
+    if run_git(dir, &["rebase", &format!("origin/{}", base_branch)]).is_err() {
+        let conflicts = run_git(dir, &["diff", "--name-only", "--diff-filter=U"])
+            .unwrap_or_default()
+            .lines()
+            .collect::<Vec<_>>()
+            .join(", ");
+        let _ = run_git(dir, &["rebase", "--abort"]);
+        return Err(format!("Rebase onto {} has conflicts: {}", base_branch, conflicts));
+    }
+
+    // Only overwrite the remote branch if nobody pushed since we fetched
+    run_git(
+        dir,
+        &[
+            "push",
+            &format!("--force-with-lease=refs/heads/{}:{}", branch, remote_head),
+            "origin",
+            &format!("HEAD:refs/heads/{}", branch),
+        ],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,904 @@
+//! CI checks for a PR: summarizing them, re-running failed ones and fetching
+//! their logs
+//!
+//! Only GitHub Actions runs can be re-triggered or have their logs fetched
+//! through `gh run`. Checks reported by other providers are skipped or
+//! returned with their URL only.
+
+use crate::db::{self, AppState};
+use crate::github::GithubClient;
+use crate::CheckOutcome;
+use crate::pr_events;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Runtime, State};
+
+/// Default number of log lines kept per failed step
+const DEFAULT_CI_LOG_LINES: usize = 50;
+/// Upper bound on lines kept per failed step (also what gets cached)
+const MAX_CI_LOG_LINES: usize = 500;
+/// Upper bound on the combined size of all excerpts returned
+const MAX_CI_LOG_BYTES: usize = 64 * 1024;
+/// Check runs requested per page in `get_ci_summary` (all pages are fetched)
+const CHECK_RUNS_PER_PAGE: usize = 100;
+/// Recent workflow runs sampled when suggesting a monitor interval
+const INTERVAL_SAMPLE_RUNS: usize = 50;
+/// Bounds for a suggested monitor interval, in minutes
+const MIN_SUGGESTED_INTERVAL: i32 = 5;
+const MAX_SUGGESTED_INTERVAL: i32 = 120;
+/// Default and longest lifetime of a manual CI status override, in minutes
+const DEFAULT_CI_OVERRIDE_MINUTES: i64 = 30;
+const MAX_CI_OVERRIDE_MINUTES: i64 = 24 * 60;
+
+#[derive(Debug, Deserialize)]
+struct PrCheck {
+    name: String,
+    #[serde(default)]
+    bucket: String,
+    #[serde(default)]
+    link: String,
+    #[serde(default)]
+    workflow: String,
+}
+
+/// Log excerpt for a failed CI step (or just the URL for non-Actions checks)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiFailureLog {
+    pub workflow: Option<String>,
+    pub job: String,
+    pub step: Option<String>,
+    pub excerpt: String,
+    pub url: String,
+}
+
+/// Failed-step log lines per run attempt ("<run id>#<attempt>"), oldest
+/// first. A finished attempt's logs don't change; a re-run is a new attempt.
+static CI_LOG_CACHE: Mutex<Vec<(String, Vec<StepLog>)>> = Mutex::new(Vec::new());
+/// Run attempts whose logs are kept in CI_LOG_CACHE
+const MAX_CACHED_CI_LOGS: usize = 32;
+
+#[derive(Debug, Clone, PartialEq)]
+struct StepLog {
+    job: String,
+    step: String,
+    lines: Vec<String>,
+}
+
+/// Overall CI state of a head commit
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CiState {
+    /// Neither commit statuses nor check runs were reported
+    NoChecks,
+    Pending,
+    Passing,
+    Failing,
+    /// A check is waiting for someone to approve it
+    ActionRequired,
+}
+
+/// One commit status or check run behind a `CiSummary`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiCheck {
+    pub name: String,
+    /// "status" (commit status API) or "check_run"
+    pub source: String,
+    /// "pending" | "success" | "failure" | "action_required" | "skipped"
+    pub state: String,
+    pub url: Option<String>,
+}
+
+/// CI state of a PR's head commit, with the checks that decided it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiSummary {
+    pub head_sha: String,
+    pub state: CiState,
+    pub checks: Vec<CiCheck>,
+}
+
+/// A GitHub Actions run that was re-triggered
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RerunRun {
+    pub run_id: String,
+    pub checks: Vec<String>,
+}
+
+/// A run that gh refused to re-trigger
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RerunFailure {
+    pub run_id: String,
+    pub error: String,
+}
+
+/// Outcome of re-running a PR's failed checks
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RerunChecksResult {
+    pub rerun: Vec<RerunRun>,
+    /// Failing checks that aren't GitHub Actions runs
+    pub skipped: Vec<String>,
+    pub failed: Vec<RerunFailure>,
+}
+
+/// Summarize CI for a PR's head commit from its commit statuses and check runs.
+/// Unlike the cached `ciStatus`, this tells "no CI configured" apart from
+/// "not fetched yet".
+#[tauri::command]
+pub fn get_ci_summary(state: State<'_, AppState>, pr_id: String) -> Result<CiSummary, String> {
+    let (repo, _) = lookup_pr(&state, &pr_id)?;
+    let head_sha = crate::head_sha_for_pr(&state, &pr_id)?
+        .ok_or_else(|| format!("Head commit of {} is unknown", pr_id))?;
+
+    let status = state.github.api(&format!("repos/{}/commits/{}/status", repo, head_sha))?;
+    let check_runs = api_paginated(
+        state.github.as_ref(),
+        &format!("repos/{}/commits/{}/check-runs?per_page={}", repo, head_sha, CHECK_RUNS_PER_PAGE),
+        "check_runs",
+    )?;
+
+    let checks = collect_ci_checks(&status, &check_runs);
+    Ok(CiSummary {
+        head_sha,
+        state: ci_state(&checks),
+        checks,
+    })
+}
+
+/// `gh api --paginate` for a list endpoint that wraps its items in an object
+/// (`{"total_count": .., "<key>": [..]}`): gh prints one object per page,
+/// whose `key` arrays are joined here
+fn api_paginated(github: &dyn GithubClient, path: &str, key: &str) -> Result<serde_json::Value, String> {
+    let mut items = Vec::new();
+    for page in github.api_pages(path)? {
+        if let Some(serde_json::Value::Array(page_items)) = page.get(key) {
+            items.extend(page_items.iter().cloned());
+        }
+    }
+    Ok(serde_json::json!({ key: items }))
+}
+
+/// `CiCheck::state` for a check, classified like the cached CI status
+/// (`determine_ci_status`) so both agree on e.g. cancelled runs
+fn check_state(check: &crate::StatusCheck) -> &'static str {
+    match crate::check_outcome(check) {
+        CheckOutcome::Failing => "failure",
+        CheckOutcome::ActionRequired => "action_required",
+        CheckOutcome::Passing => "success",
+        CheckOutcome::Ignored => "skipped",
+        CheckOutcome::Pending | CheckOutcome::Unknown => "pending",
+    }
+}
+
+/// Normalize the combined status (`statuses`) and check runs (`check_runs`)
+/// of a commit into one list
+fn collect_ci_checks(status: &serde_json::Value, check_runs: &serde_json::Value) -> Vec<CiCheck> {
+    let text = |value: &serde_json::Value, key: &str| value[key].as_str().unwrap_or("").to_string();
+    let field = |value: &serde_json::Value, key: &str| value[key].as_str().map(str::to_string);
+    let url = |value: &serde_json::Value, key: &str| {
+        value[key].as_str().filter(|u| !u.is_empty()).map(str::to_string)
+    };
+
+    let statuses = status["statuses"].as_array().into_iter().flatten().map(|s| {
+        let check = crate::StatusCheck {
+            state: field(s, "state"),
+            status: None,
+            conclusion: None,
+            is_required: None,
+        };
+        CiCheck {
+            name: text(s, "context"),
+            source: "status".to_string(),
+            state: check_state(&check).to_string(),
+            url: url(s, "target_url"),
+        }
+    });
+
+    let runs = check_runs["check_runs"].as_array().into_iter().flatten().map(|run| {
+        let check = crate::StatusCheck {
+            state: None,
+            status: field(run, "status"),
+            conclusion: field(run, "conclusion"),
+            is_required: None,
+        };
+        CiCheck {
+            name: text(run, "name"),
+            source: "check_run".to_string(),
+            state: check_state(&check).to_string(),
+            url: url(run, "html_url"),
+        }
+    });
+
+    statuses.chain(runs).collect()
+}
+
+/// Failing beats waiting on an action beats pending beats passing; skipped
+/// checks don't count either way
+fn ci_state(checks: &[CiCheck]) -> CiState {
+    if checks.is_empty() {
+        CiState::NoChecks
+    } else if checks.iter().any(|c| c.state == "failure") {
+        CiState::Failing
+    } else if checks.iter().any(|c| c.state == "action_required") {
+        CiState::ActionRequired
+    } else if checks.iter().any(|c| c.state == "pending") {
+        CiState::Pending
+    } else {
+        CiState::Passing
+    }
+}
+
+/// Re-run the failed GitHub Actions jobs for a PR
+#[tauri::command]
+pub fn rerun_failed_checks(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    pr_id: String,
+) -> Result<RerunChecksResult, String> {
+    rerun_failed_checks_internal(&app, &state, &pr_id)
+}
+
+/// Shared by the Tauri command and the HTTP API
+pub(crate) fn rerun_failed_checks_internal<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AppState,
+    pr_id: &str,
+) -> Result<RerunChecksResult, String> {
+    let (repo, number) = lookup_pr(state, pr_id)?;
+    let checks = list_pr_checks(state, &repo, number)?;
+
+    let mut result = RerunChecksResult::default();
+    let mut runs: Vec<RerunRun> = Vec::new();
+
+    for check in checks.into_iter().filter(|c| c.bucket == "fail") {
+        match parse_run_id(&check.link) {
+            Some(run_id) => match runs.iter_mut().find(|r| r.run_id == run_id) {
+                Some(run) => run.checks.push(check.name),
+                None => runs.push(RerunRun {
+                    run_id,
+                    checks: vec![check.name],
+                }),
+            },
+            None => result.skipped.push(check.name),
+        }
+    }
+
+    for run in runs {
+        match state.github.rerun_failed_jobs(&repo, &run.run_id) {
+            Ok(()) => result.rerun.push(run),
+            Err(error) => result.failed.push(RerunFailure {
+                run_id: run.run_id,
+                error,
+            }),
+        }
+    }
+
+    if !result.rerun.is_empty() {
+        let conn = state.conn();
+        let before = pr_events::snapshot_pr(&conn, pr_id);
+        db::set_fetched_ci_status(&conn, pr_id, "pending")
+            .map_err(|e| format!("Failed to update CI status: {}", e))?;
+        pr_events::emit_pr_diff(app, &conn, pr_id, &before);
+    }
+
+    Ok(result)
+}
+
+/// Get log excerpts for a PR's failed CI steps
+#[tauri::command]
+pub fn get_ci_failure_logs(
+    state: State<'_, AppState>,
+    pr_id: String,
+    max_lines: Option<usize>,
+) -> Result<Vec<CiFailureLog>, String> {
+    get_ci_failure_logs_internal(&state, &pr_id, max_lines)
+}
+
+/// Shared by the Tauri command and the HTTP API
+pub(crate) fn get_ci_failure_logs_internal(
+    state: &AppState,
+    pr_id: &str,
+    max_lines: Option<usize>,
+) -> Result<Vec<CiFailureLog>, String> {
+    let max_lines = max_lines
+        .unwrap_or(DEFAULT_CI_LOG_LINES)
+        .clamp(1, MAX_CI_LOG_LINES);
+    let (repo, number) = lookup_pr(state, pr_id)?;
+    let checks = list_pr_checks(state, &repo, number)?;
+
+    let mut logs = Vec::new();
+    let mut seen_runs: Vec<String> = Vec::new();
+    let mut remaining_bytes = MAX_CI_LOG_BYTES;
+
+    for check in checks.iter().filter(|c| c.bucket == "fail") {
+        let Some(run_id) = parse_run_id(&check.link) else {
+            logs.push(CiFailureLog {
+                workflow: None,
+                job: check.name.clone(),
+                step: None,
+                excerpt: String::new(),
+                url: check.link.clone(),
+            });
+            continue;
+        };
+        if seen_runs.contains(&run_id) {
+            continue;
+        }
+        seen_runs.push(run_id.clone());
+
+        let step_logs = match fetch_run_failed_logs(state.github.as_ref(), &repo, &run_id) {
+            Ok(step_logs) => step_logs,
+            Err(e) => {
+                // One unreadable run shouldn't hide the others' excerpts
+                eprintln!("{}", e);
+                logs.push(CiFailureLog {
+                    workflow: (!check.workflow.is_empty()).then(|| check.workflow.clone()),
+                    job: check.name.clone(),
+                    step: None,
+                    excerpt: String::new(),
+                    url: check.link.clone(),
+                });
+                continue;
+            }
+        };
+        for step_log in step_logs {
+            // Link to the failed job when gh reported it, otherwise the run
+            let url = checks
+                .iter()
+                .find(|c| c.name == step_log.job && parse_run_id(&c.link).as_ref() == Some(&run_id))
+                .map(|c| c.link.clone())
+                .unwrap_or_else(|| format!("https://github.com/{}/actions/runs/{}", repo, run_id));
+
+            let start = step_log.lines.len().saturating_sub(max_lines);
+            let mut excerpt = step_log.lines[start..].join("\n");
+            if excerpt.len() > remaining_bytes {
+                // Keep the end of the log, where the error usually is
+                let mut cut = excerpt.len() - remaining_bytes;
+                while !excerpt.is_char_boundary(cut) {
+                    cut += 1;
+                }
+                excerpt = excerpt[cut..].to_string();
+            }
+            remaining_bytes -= excerpt.len();
+
+            logs.push(CiFailureLog {
+                workflow: (!check.workflow.is_empty()).then(|| check.workflow.clone()),
+                job: step_log.job,
+                step: Some(step_log.step),
+                excerpt,
+                url,
+            });
+
+            if remaining_bytes == 0 {
+                return Ok(logs);
+            }
+        }
+    }
+
+    Ok(logs)
+}
+
+/// A manual ci_status override on a cached PR
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiOverride {
+    pub pr_id: String,
+    pub ci_status: String,
+    /// Fetches overwrite ci_status again after this (RFC 3339)
+    pub until: String,
+}
+
+/// Override a cached PR's ci_status by hand, for trying out the board or when
+/// GitHub's rollup is wrong. Fetches keep the override for `ttl_minutes`
+/// (default 30); `clear_pr_ci_override` ends it early.
+#[tauri::command]
+pub fn set_pr_ci_override(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    pr_id: String,
+    status: String,
+    ttl_minutes: Option<i64>,
+) -> Result<CiOverride, String> {
+    let conn = state.conn();
+    let before = pr_events::snapshot_pr(&conn, &pr_id);
+    let result = set_ci_override(&conn, &pr_id, &status, ttl_minutes, chrono::Utc::now())?;
+    pr_events::emit_pr_diff(&app, &conn, &pr_id, &before);
+    Ok(result)
+}
+
+fn set_ci_override(
+    conn: &rusqlite::Connection,
+    pr_id: &str,
+    status: &str,
+    ttl_minutes: Option<i64>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<CiOverride, String> {
+    let status = status.trim().to_lowercase();
+    if !crate::CI_STATUSES.contains(&status.as_str()) {
+        return Err(format!(
+            "Invalid CI status: {} (expected one of: {})",
+            status,
+            crate::CI_STATUSES.join(", ")
+        ));
+    }
+    let ttl = ttl_minutes.unwrap_or(DEFAULT_CI_OVERRIDE_MINUTES).clamp(1, MAX_CI_OVERRIDE_MINUTES);
+    let until = (now + chrono::Duration::minutes(ttl)).to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+
+    let updated = db::set_ci_override(conn, pr_id, &status, &until)
+        .map_err(|e| format!("Failed to override CI status: {}", e))?;
+    if updated == 0 {
+        return Err(format!("PR not found in cache: {}", pr_id));
+    }
+    Ok(CiOverride {
+        pr_id: pr_id.to_string(),
+        ci_status: status,
+        until,
+    })
+}
+
+/// Drop a PR's ci_status override, restoring the status last fetched from
+/// GitHub. Returns whether there was one.
+#[tauri::command]
+pub fn clear_pr_ci_override(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    pr_id: String,
+) -> Result<bool, String> {
+    let conn = state.conn();
+    let before = pr_events::snapshot_pr(&conn, &pr_id);
+    let cleared = db::clear_ci_override(&conn, &pr_id)
+        .map_err(|e| format!("Failed to clear CI status override: {}", e))?;
+    pr_events::emit_pr_diff(&app, &conn, &pr_id, &before);
+    Ok(cleared > 0)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkflowRun {
+    #[serde(default)]
+    head_sha: String,
+    started_at: Option<String>,
+    updated_at: Option<String>,
+}
+
+/// Suggested monitor interval and the CI timings behind it
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntervalSuggestion {
+    pub interval_minutes: i32,
+    /// "ci_runs" when derived from run data, "default" when none was available
+    pub source: String,
+    pub median_ci_minutes: Option<f64>,
+    /// Wall-clock CI time per commit (all workflow runs on the same SHA), in minutes
+    pub ci_minutes_per_commit: Vec<f64>,
+}
+
+/// Suggest a monitor `interval_minutes` for a repo: a bit longer than the
+/// median time CI takes to finish for a commit, so checks land after CI is done
+#[tauri::command]
+pub fn suggest_monitor_interval(
+    state: State<'_, AppState>,
+    repo: String,
+) -> Result<IntervalSuggestion, String> {
+    let runs: Vec<WorkflowRun> =
+        serde_json::from_value(state.github.completed_runs(&repo, INTERVAL_SAMPLE_RUNS)?)
+            .map_err(|e| format!("Failed to parse workflow runs: {}", e))?;
+    Ok(suggest_interval(&ci_minutes_per_commit(&runs)))
+}
+
+/// Time from the first run starting to the last run finishing, per head SHA
+fn ci_minutes_per_commit(runs: &[WorkflowRun]) -> Vec<f64> {
+    use chrono::{DateTime, Utc};
+
+    let parse = |ts: &Option<String>| {
+        ts.as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc))
+    };
+
+    let mut spans: HashMap<&str, (DateTime<Utc>, DateTime<Utc>)> = HashMap::new();
+    for run in runs {
+        let (Some(started), Some(finished)) = (parse(&run.started_at), parse(&run.updated_at)) else {
+            continue;
+        };
+        if finished < started {
+            continue;
+        }
+        let span = spans.entry(run.head_sha.as_str()).or_insert((started, finished));
+        span.0 = span.0.min(started);
+        span.1 = span.1.max(finished);
+    }
+
+    let mut minutes: Vec<f64> = spans
+        .values()
+        .map(|(started, finished)| (*finished - *started).num_seconds() as f64 / 60.0)
+        .collect();
+    minutes.sort_by(|a, b| a.total_cmp(b));
+    minutes
+}
+
+fn suggest_interval(ci_minutes: &[f64]) -> IntervalSuggestion {
+    let median = match ci_minutes.len() {
+        0 => None,
+        n if n % 2 == 1 => Some(ci_minutes[n / 2]),
+        n => Some((ci_minutes[n / 2 - 1] + ci_minutes[n / 2]) / 2.0),
+    };
+
+    let (interval_minutes, source) = match median {
+        // ~20% headroom plus a couple of minutes for checks to report back
+        Some(median) => (
+            ((median * 1.2).ceil() as i32 + 2).clamp(MIN_SUGGESTED_INTERVAL, MAX_SUGGESTED_INTERVAL),
+            "ci_runs",
+        ),
+        None => (crate::monitor::DEFAULT_INTERVAL_MINUTES, "default"),
+    };
+
+    IntervalSuggestion {
+        interval_minutes,
+        source: source.to_string(),
+        median_ci_minutes: median.map(|m| (m * 10.0).round() / 10.0),
+        ci_minutes_per_commit: ci_minutes.iter().map(|m| (m * 10.0).round() / 10.0).collect(),
+    }
+}
+
+fn lookup_pr(state: &AppState, pr_id: &str) -> Result<(String, i32), String> {
+    let conn = state.conn();
+    conn.query_row(
+        "SELECT repo, number FROM pr_cache WHERE id = ?1",
+        [pr_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .map_err(|e| format!("PR not found in cache: {} ({})", pr_id, e))
+}
+
+fn list_pr_checks(state: &AppState, repo: &str, number: i32) -> Result<Vec<PrCheck>, String> {
+    serde_json::from_value(state.github.checks(repo, number)?)
+        .map_err(|e| format!("Failed to parse checks: {}", e))
+}
+
+/// Fetch (or reuse) the failed-step logs for a run's latest attempt
+fn fetch_run_failed_logs(
+    github: &dyn GithubClient,
+    repo: &str,
+    run_id: &str,
+) -> Result<Vec<StepLog>, String> {
+    let attempt = github.api(&format!("repos/{}/actions/runs/{}", repo, run_id))?["run_attempt"]
+        .as_i64()
+        .unwrap_or(1);
+    let key = format!("{}#{}", run_id, attempt);
+    if let Ok(cache) = CI_LOG_CACHE.lock() {
+        if let Some((_, logs)) = cache.iter().find(|(cached, _)| *cached == key) {
+            return Ok(logs.clone());
+        }
+    }
+
+    let logs = parse_failed_log(&github.failed_run_log(repo, run_id, attempt)?);
+    if let Ok(mut cache) = CI_LOG_CACHE.lock() {
+        cache_logs(&mut cache, key, logs.clone());
+    }
+    Ok(logs)
+}
+
+/// Add a run attempt's logs to the cache, dropping the oldest beyond MAX_CACHED_CI_LOGS
+fn cache_logs(cache: &mut Vec<(String, Vec<StepLog>)>, key: String, logs: Vec<StepLog>) {
+    cache.retain(|(cached, _)| *cached != key);
+    cache.push((key, logs));
+    if cache.len() > MAX_CACHED_CI_LOGS {
+        let excess = cache.len() - MAX_CACHED_CI_LOGS;
+        cache.drain(..excess);
+    }
+}
+
+/// Group `gh run view --log-failed` output ("job<TAB>step<TAB>timestamp line")
+/// by job and step, keeping the last MAX_CI_LOG_LINES lines of each
+fn parse_failed_log(raw: &str) -> Vec<StepLog> {
+    let mut steps: Vec<StepLog> = Vec::new();
+
+    for line in raw.lines() {
+        let mut parts = line.splitn(3, '\t');
+        let (Some(job), Some(step), Some(text)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+
+        // Drop the leading timestamp (and BOM gh sometimes leaves on the first line)
+        let text = text.trim_start_matches('\u{feff}');
+        let text = match text.split_once(' ') {
+            Some((ts, rest)) if ts.ends_with('Z') && ts.contains('T') => rest,
+            _ => text,
+        };
+
+        let entry = match steps.iter_mut().position(|s| s.job == job && s.step == step) {
+            Some(index) => &mut steps[index],
+            None => {
+                steps.push(StepLog {
+                    job: job.to_string(),
+                    step: step.to_string(),
+                    lines: Vec::new(),
+                });
+                steps.last_mut().unwrap()
+            }
+        };
+        entry.lines.push(text.to_string());
+        if entry.lines.len() > MAX_CI_LOG_LINES {
+            entry.lines.remove(0);
+        }
+    }
+
+    steps
+}
+
+/// Extract the run ID from an Actions job link
+/// (e.g. https://github.com/owner/repo/actions/runs/123/job/456)
+fn parse_run_id(link: &str) -> Option<String> {
+    let (_, rest) = link.split_once("/actions/runs/")?;
+    let run_id = rest.split(['/', '?', '#']).next()?;
+    if run_id.is_empty() || !run_id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(run_id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::mock::MockGithub;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_suggest_interval() {
+        let run = |sha: &str, start: &str, end: &str| WorkflowRun {
+            head_sha: sha.to_string(),
+            started_at: Some(start.to_string()),
+            updated_at: Some(end.to_string()),
+        };
+        let runs = vec![
+            // Two workflows on the same commit: CI is done when the slower one finishes
+            run("a", "2024-01-01T10:00:00Z", "2024-01-01T10:06:00Z"),
+            run("a", "2024-01-01T10:01:00Z", "2024-01-01T10:10:00Z"),
+            run("b", "2024-01-01T11:00:00Z", "2024-01-01T11:08:00Z"),
+            run("c", "2024-01-01T12:00:00Z", "2024-01-01T12:20:00Z"),
+        ];
+
+        let minutes = ci_minutes_per_commit(&runs);
+        assert_eq!(minutes, vec![8.0, 10.0, 20.0]);
+
+        let suggestion = suggest_interval(&minutes);
+        assert_eq!(suggestion.median_ci_minutes, Some(10.0));
+        assert_eq!(suggestion.interval_minutes, 14);
+        assert_eq!(suggestion.source, "ci_runs");
+
+        let fallback = suggest_interval(&[]);
+        assert_eq!(fallback.interval_minutes, crate::monitor::DEFAULT_INTERVAL_MINUTES);
+        assert_eq!(fallback.source, "default");
+    }
+
+    #[test]
+    fn test_ci_summary() {
+        use serde_json::json;
+
+        let none = collect_ci_checks(&json!({"statuses": []}), &json!({"check_runs": []}));
+        assert_eq!(ci_state(&none), CiState::NoChecks);
+
+        let status = json!({"statuses": [
+            {"context": "ci/circle", "state": "success", "target_url": "https://circleci.com/1"},
+            {"context": "deploy", "state": "pending", "target_url": null},
+        ]});
+        let runs = json!({"check_runs": [
+            {"name": "build", "status": "completed", "conclusion": "success", "html_url": "https://github.com/r/1"},
+            {"name": "lint", "status": "completed", "conclusion": "skipped", "html_url": ""},
+            {"name": "test", "status": "in_progress", "conclusion": null},
+        ]});
+        let checks = collect_ci_checks(&status, &runs);
+        let states: Vec<(&str, &str, &str)> = checks
+            .iter()
+            .map(|c| (c.name.as_str(), c.source.as_str(), c.state.as_str()))
+            .collect();
+        assert_eq!(
+            states,
+            [
+                ("ci/circle", "status", "success"),
+                ("deploy", "status", "pending"),
+                ("build", "check_run", "success"),
+                ("lint", "check_run", "skipped"),
+                ("test", "check_run", "pending"),
+            ]
+        );
+        assert_eq!(checks[0].url.as_deref(), Some("https://circleci.com/1"));
+        assert_eq!(checks[3].url, None);
+        assert_eq!(ci_state(&checks), CiState::Pending);
+
+        let failing = collect_ci_checks(
+            &json!({"statuses": [{"context": "ci", "state": "error"}]}),
+            &json!({"check_runs": [{"name": "build", "status": "completed", "conclusion": "timed_out"}]}),
+        );
+        assert_eq!(ci_state(&failing), CiState::Failing);
+
+        // Same reading as the cached status: cancelled runs don't fail CI,
+        // runs waiting for approval hold it
+        let runs = json!({"check_runs": [
+            {"name": "old", "status": "completed", "conclusion": "cancelled"},
+            {"name": "deploy", "status": "completed", "conclusion": "action_required"},
+        ]});
+        let held = collect_ci_checks(&json!({}), &runs);
+        let states: Vec<&str> = held.iter().map(|c| c.state.as_str()).collect();
+        assert_eq!(states, ["skipped", "action_required"]);
+        assert_eq!(ci_state(&held), CiState::ActionRequired);
+
+        // Only skipped checks: nothing is blocking
+        let skipped = collect_ci_checks(
+            &json!({}),
+            &json!({"check_runs": [{"name": "lint", "status": "completed", "conclusion": "neutral"}]}),
+        );
+        assert_eq!(ci_state(&skipped), CiState::Passing);
+    }
+
+    #[test]
+    fn test_parse_run_id() {
+        assert_eq!(
+            parse_run_id("https://github.com/o/r/actions/runs/123/job/456"),
+            Some("123".to_string())
+        );
+        assert_eq!(
+            parse_run_id("https://github.com/o/r/actions/runs/789"),
+            Some("789".to_string())
+        );
+        assert_eq!(parse_run_id("https://circleci.com/gh/o/r/42"), None);
+        assert_eq!(parse_run_id(""), None);
+    }
+
+    #[test]
+    fn test_cache_logs() {
+        let step = |job: &str| StepLog {
+            job: job.to_string(),
+            step: "test".to_string(),
+            lines: vec![],
+        };
+        let mut cache = Vec::new();
+        // A re-run is a separate entry; caching the same attempt again replaces it
+        cache_logs(&mut cache, "7#1".to_string(), vec![step("build")]);
+        cache_logs(&mut cache, "7#2".to_string(), vec![step("lint")]);
+        cache_logs(&mut cache, "7#2".to_string(), vec![step("lint")]);
+        let keys: Vec<&str> = cache.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["7#1", "7#2"]);
+
+        // Oldest entries go first once the cache is full
+        for run in 0..MAX_CACHED_CI_LOGS {
+            cache_logs(&mut cache, format!("{}#1", run), vec![step("build")]);
+        }
+        assert_eq!(cache.len(), MAX_CACHED_CI_LOGS);
+        assert_eq!(cache[0].0, "0#1");
+    }
+
+    #[test]
+    fn test_parse_failed_log() {
+        let raw = "build\tRun tests\t\u{feff}2024-05-01T10:00:00.1234567Z running 3 tests\n\
+                   build\tRun tests\t2024-05-01T10:00:01.0000000Z error: assertion failed\n\
+                   lint\tClippy\t2024-05-01T10:00:02.0000000Z warning: unused\n\
+                   not a log line\n";
+        let steps = parse_failed_log(raw);
+
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].job, "build");
+        assert_eq!(steps[0].step, "Run tests");
+        assert_eq!(steps[0].lines, vec!["running 3 tests", "error: assertion failed"]);
+        assert_eq!(steps[1].lines, vec!["warning: unused"]);
+    }
+
+    #[test]
+    fn test_ci_override() {
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_prs(
+            "o/r",
+            serde_json::json!([{
+                "number": 1, "title": "PR 1", "url": "https://github.com/o/r/pull/1",
+                "state": "OPEN", "isDraft": false, "author": {"login": "alice"},
+                "headRefName": "b", "headRefOid": "sha1", "baseRefName": "main",
+                "labels": [], "reviewRequests": [],
+                "createdAt": "2026-01-01T00:00:00Z", "updatedAt": "2026-01-01T00:00:00Z",
+                "statusCheckRollup": [{"conclusion": "FAILURE"}],
+            }]),
+        );
+        let state = AppState::for_tests(github);
+        let fetch = || {
+            let prs = crate::fetch_prs_internal(&state, &["o/r".to_string()], true).unwrap().prs;
+            (prs[0].ci_status.clone().unwrap(), prs[0].ci_override_until.clone())
+        };
+        assert_eq!(fetch(), ("failing".to_string(), None));
+
+        let now = chrono::Utc::now();
+        assert!(set_ci_override(&state.conn(), "o/r#1", "green", None, now)
+            .unwrap_err()
+            .contains("Invalid CI status"));
+        assert!(set_ci_override(&state.conn(), "o/r#2", "passing", None, now).is_err());
+
+        // Fetches keep the override until it expires or is cleared
+        let set = set_ci_override(&state.conn(), "o/r#1", " Passing ", None, now).unwrap();
+        assert_eq!(set.ci_status, "passing");
+        assert_eq!(fetch(), ("passing".to_string(), Some(set.until.clone())));
+        // ...but merge decisions still see the failing CI GitHub reported
+        let fetched: Option<String> = state
+            .conn()
+            .query_row(
+                &format!("SELECT {} FROM pr_cache WHERE id = 'o/r#1'", db::FETCHED_CI_STATUS_SQL),
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(fetched.as_deref(), Some("failing"));
+        set_ci_override(&state.conn(), "o/r#1", "pending", Some(5), now).unwrap();
+        assert_eq!(fetch().0, "pending");
+        assert_eq!(db::clear_ci_override(&state.conn(), "o/r#1").unwrap(), 1);
+        assert_eq!(fetch(), ("failing".to_string(), None));
+
+        let earlier = now - chrono::Duration::hours(2);
+        set_ci_override(&state.conn(), "o/r#1", "passing", None, earlier).unwrap();
+        assert_eq!(fetch(), ("failing".to_string(), None));
+        assert_eq!(db::clear_ci_override(&state.conn(), "o/r#1").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_rerun_failed_checks() {
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_prs(
+            "o/r",
+            serde_json::json!([{
+                "number": 1, "title": "PR 1", "url": "https://github.com/o/r/pull/1",
+                "state": "OPEN", "isDraft": false, "author": {"login": "alice"},
+                "headRefName": "b", "headRefOid": "sha1", "baseRefName": "main",
+                "labels": [], "reviewRequests": [],
+                "createdAt": "2026-01-01T00:00:00Z", "updatedAt": "2026-01-01T00:00:00Z",
+                "statusCheckRollup": [{"conclusion": "FAILURE"}],
+            }]),
+        );
+        let state = AppState::for_tests(github.clone());
+        crate::fetch_prs_internal(&state, &["o/r".to_string()], true).unwrap();
+        let app = tauri::test::mock_app();
+
+        let check = |name: &str, bucket: &str, link: &str| {
+            serde_json::json!({"name": name, "bucket": bucket, "link": link, "workflow": "ci"})
+        };
+        github.set_view(
+            "o/r",
+            1,
+            serde_json::json!({"checks": [
+                check("build", "fail", "https://github.com/o/r/actions/runs/10/job/1"),
+                check("test", "fail", "https://github.com/o/r/actions/runs/10/job/2"),
+                check("lint", "pass", "https://github.com/o/r/actions/runs/11/job/3"),
+                check("circle", "fail", "https://circleci.com/gh/o/r/42"),
+            ]}),
+        );
+
+        // Both failed jobs of run 10 go through the client in one rerun
+        let result = rerun_failed_checks_internal(app.handle(), &state, "o/r#1").unwrap();
+        assert_eq!(*github.reruns.lock().unwrap(), ["10"]);
+        assert_eq!(result.rerun.len(), 1);
+        assert_eq!(result.rerun[0].checks, ["build", "test"]);
+        assert_eq!(result.skipped, ["circle"]);
+        assert!(result.failed.is_empty());
+
+        let ci = || -> (Option<String>, Option<String>) {
+            state
+                .conn()
+                .query_row(
+                    &format!(
+                        "SELECT ci_status, {} FROM pr_cache WHERE id = 'o/r#1'",
+                        db::FETCHED_CI_STATUS_SQL
+                    ),
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .unwrap()
+        };
+        assert_eq!(ci(), (Some("pending".to_string()), Some("pending".to_string())));
+
+        // A manual override stays on the card; only the fetched status moves
+        set_ci_override(&state.conn(), "o/r#1", "passing", None, chrono::Utc::now()).unwrap();
+        rerun_failed_checks_internal(app.handle(), &state, "o/r#1").unwrap();
+        assert_eq!(ci(), (Some("passing".to_string()), Some("pending".to_string())));
+    }
+}
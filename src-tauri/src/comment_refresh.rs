@@ -0,0 +1,209 @@
+//! Comment refresh for monitored PRs
+//!
+//! While a monitor runs, only its script sees fresh review threads; the
+//! board's unresolved counts would stay put until the next manual comment
+//! fetch. Each `@@ITERATION` marker schedules a refresh of the PR's comments
+//! a little later (repeated markers push it back), and a background thread
+//! runs the due ones while the PR's monitor is still active, emitting
+//! `pr:updated` for any change. A PR whose comments were fetched under a
+//! minute ago, by this or any other path, is skipped. Like the PR refresh,
+//! refreshes wait while offline or down to the rate-limit reserve.
+
+use crate::connectivity;
+use crate::db::AppState;
+use crate::monitor;
+use crate::pr_events;
+use crate::throttle::{self, Throttle};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+/// Delay after an iteration marker, giving the script time to push and reply
+const DEBOUNCE: Duration = Duration::from_secs(30);
+
+/// Comments fetched more recently than this are not fetched again
+const MIN_FETCH_GAP: Duration = Duration::from_secs(60);
+
+/// How often the background thread looks for due refreshes
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A PR whose comments should be re-fetched
+#[derive(Debug, Clone, PartialEq)]
+struct DueRefresh {
+    pr_id: String,
+    pr_number: i32,
+    repo: String,
+}
+
+#[derive(Default)]
+struct RefreshQueue {
+    /// Pending refreshes and when they are due, by PR id
+    pending: HashMap<String, (DueRefresh, Instant)>,
+    /// When each PR's comments were last fetched
+    last_fetch: HashMap<String, Instant>,
+}
+
+impl RefreshQueue {
+    fn schedule(&mut self, refresh: DueRefresh, now: Instant) {
+        self.pending.insert(refresh.pr_id.clone(), (refresh, now + DEBOUNCE));
+    }
+
+    fn record_fetch(&mut self, pr_id: &str, now: Instant) {
+        self.last_fetch.insert(pr_id.to_string(), now);
+    }
+
+    /// Remove and return the refreshes due at `now`, dropping those whose
+    /// comments were fetched too recently. Fetch times older than the gap no
+    /// longer matter and are forgotten, so PRs that left the board don't linger.
+    fn take_due(&mut self, now: Instant) -> Vec<DueRefresh> {
+        self.last_fetch.retain(|_, at| now.duration_since(*at) < MIN_FETCH_GAP);
+
+        let due: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|(_, (_, at))| *at <= now)
+            .map(|(pr_id, _)| pr_id.clone())
+            .collect();
+        due.into_iter()
+            .filter_map(|pr_id| self.pending.remove(&pr_id))
+            .map(|(refresh, _)| refresh)
+            .filter(|refresh| {
+                self.last_fetch
+                    .get(&refresh.pr_id)
+                    .is_none_or(|at| now.duration_since(*at) >= MIN_FETCH_GAP)
+            })
+            .collect()
+    }
+}
+
+static QUEUE: Mutex<Option<RefreshQueue>> = Mutex::new(None);
+
+fn with_queue<T>(f: impl FnOnce(&mut RefreshQueue) -> T) -> Option<T> {
+    let mut queue = QUEUE.lock().ok()?;
+    Some(f(queue.get_or_insert_with(RefreshQueue::default)))
+}
+
+/// Schedule a comment refresh for a monitored PR (called on iteration markers)
+pub fn schedule(pr_id: &str, pr_number: i32, repo: &str) {
+    let refresh = DueRefresh {
+        pr_id: pr_id.to_string(),
+        pr_number,
+        repo: repo.to_string(),
+    };
+    with_queue(|queue| queue.schedule(refresh, Instant::now()));
+}
+
+/// Note that a PR's comments were just fetched
+pub fn record_fetch(pr_id: &str) {
+    with_queue(|queue| queue.record_fetch(pr_id, Instant::now()));
+}
+
+/// Start the background thread that runs due comment refreshes
+pub fn start_comment_refresher(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let due = with_queue(|queue| queue.take_due(Instant::now())).unwrap_or_default();
+        if due.is_empty() {
+            continue;
+        }
+        let Some(state) = app.try_state::<AppState>() else {
+            continue;
+        };
+        if !connectivity::is_online() {
+            reschedule(due);
+            continue;
+        }
+
+        let mut due = due.into_iter();
+        while let Some(refresh) = due.next() {
+            match throttle::check(&state, "comment refresh") {
+                Throttle::Proceed => {}
+                Throttle::Delay(delay) => thread::sleep(delay),
+                Throttle::Defer => {
+                    reschedule(std::iter::once(refresh).chain(due).collect());
+                    break;
+                }
+            }
+            refresh_comments(&app, &state, &refresh);
+        }
+    });
+}
+
+/// Put refreshes that couldn't run back in the queue for another try
+fn reschedule(refreshes: Vec<DueRefresh>) {
+    let now = Instant::now();
+    with_queue(|queue| {
+        for refresh in refreshes {
+            queue.schedule(refresh, now);
+        }
+    });
+}
+
+fn refresh_comments(app: &AppHandle, state: &AppState, refresh: &DueRefresh) {
+    let before = {
+        let conn = state.conn();
+        // The monitor may have finished while the refresh was pending
+        if !monitor::has_active_monitor(&conn, &refresh.pr_id) {
+            return;
+        }
+        pr_events::snapshot_pr(&conn, &refresh.pr_id)
+    };
+
+    match monitor::fetch_pr_comments_internal(state, refresh.pr_number, &refresh.repo) {
+        Ok(_) => pr_events::emit_pr_diff(app, &state.conn(), &refresh.pr_id, &before),
+        Err(e) => eprintln!("Failed to refresh comments for {}: {}", refresh.pr_id, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn refresh(pr_id: &str) -> DueRefresh {
+        DueRefresh {
+            pr_id: pr_id.to_string(),
+            pr_number: 1,
+            repo: "o/r".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_take_due() {
+        let mut queue = RefreshQueue::default();
+        let start = Instant::now();
+
+        queue.schedule(refresh("o/r#1"), start);
+        assert!(queue.take_due(start).is_empty());
+        // A second marker pushes the refresh back
+        queue.schedule(refresh("o/r#1"), start + Duration::from_secs(20));
+        assert!(queue.take_due(start + DEBOUNCE).is_empty());
+        assert_eq!(queue.take_due(start + Duration::from_secs(50)), [refresh("o/r#1")]);
+        assert!(queue.take_due(start + Duration::from_secs(60)).is_empty());
+
+        // Skipped when the comments were fetched under a minute before it is due
+        queue.record_fetch("o/r#2", start);
+        queue.schedule(refresh("o/r#2"), start);
+        assert!(queue.take_due(start + DEBOUNCE).is_empty());
+        queue.schedule(refresh("o/r#2"), start + Duration::from_secs(40));
+        assert_eq!(queue.take_due(start + Duration::from_secs(70)), [refresh("o/r#2")]);
+    }
+
+    #[test]
+    fn test_take_due_forgets_old_fetches() {
+        let mut queue = RefreshQueue::default();
+        let start = Instant::now();
+
+        queue.record_fetch("o/r#1", start);
+        queue.record_fetch("o/r#2", start + Duration::from_secs(30));
+        queue.take_due(start + Duration::from_secs(45));
+        assert_eq!(queue.last_fetch.len(), 2);
+
+        queue.take_due(start + MIN_FETCH_GAP);
+        assert_eq!(queue.last_fetch.keys().collect::<Vec<_>>(), ["o/r#2"]);
+        queue.take_due(start + Duration::from_secs(90));
+        assert!(queue.last_fetch.is_empty());
+    }
+}
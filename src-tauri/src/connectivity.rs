@@ -0,0 +1,241 @@
+//! Offline detection
+//!
+//! A cheap probe (resolve and connect, with a short timeout, to whatever gh
+//! would talk to: the HTTPS proxy if one is configured, else the API host of
+//! `GH_HOST`) whose result is cached for a minute. Probes run in the
+//! background, so asking whether we're online never blocks. While offline,
+//! `fetch_prs` serves cached PRs instead of spawning gh processes that would
+//! only time out. A watcher re-probes every minute, emits
+//! `connectivity:changed` on every transition and refreshes the configured
+//! repos once the connection is back.
+
+use crate::db::AppState;
+use crate::settings;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Upper bound for one probe (DNS lookup plus TCP connect)
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long a probe result is trusted
+const PROBE_TTL: Duration = Duration::from_secs(60);
+
+/// Last probe: when it ran and whether it succeeded
+static LAST_PROBE: Mutex<Option<(Instant, bool)>> = Mutex::new(None);
+
+/// When the current offline stretch began
+static OFFLINE_SINCE: Mutex<Option<DateTime<Utc>>> = Mutex::new(None);
+
+/// Set while a background probe started by `is_online` runs
+static PROBING: AtomicBool = AtomicBool::new(false);
+
+/// Connectivity as last probed
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityStatus {
+    pub online: bool,
+    /// Set while offline
+    pub offline_since: Option<String>,
+}
+
+/// `host:port` the probe connects to, as gh would: the HTTPS proxy from
+/// `HTTPS_PROXY`/`ALL_PROXY` unless `NO_PROXY` exempts the API host, else the
+/// API host itself (api.github.com, or `GH_HOST` for GitHub Enterprise)
+fn probe_target(env: impl Fn(&str) -> Option<String>) -> String {
+    let var = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| env(name))
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    let host = match var(&["GH_HOST"]) {
+        Some(host) if !host.eq_ignore_ascii_case("github.com") => host_of(&host).0,
+        _ => "api.github.com".to_string(),
+    };
+    let no_proxy = var(&["NO_PROXY", "no_proxy"]).is_some_and(|list| {
+        list.split(',').map(str::trim).any(|entry| {
+            let entry = entry.trim_start_matches('.');
+            entry == "*" || (!entry.is_empty() && (host == entry || host.ends_with(&format!(".{}", entry))))
+        })
+    });
+
+    match var(&["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]).filter(|_| !no_proxy) {
+        Some(proxy) => {
+            let (proxy_host, port) = host_of(&proxy);
+            let default_port = match proxy.split_once("://").map(|(scheme, _)| scheme) {
+                Some("https") => 443,
+                Some(scheme) if scheme.starts_with("socks") => 1080,
+                _ => 80,
+            };
+            format!("{}:{}", proxy_host, port.unwrap_or(default_port))
+        }
+        None => format!("{}:443", host),
+    }
+}
+
+/// Host and port of a URL or bare `host[:port]`, without scheme, credentials or path
+fn host_of(url: &str) -> (String, Option<u16>) {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let authority = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    // The port follows the last ':' unless that is inside an IPv6 literal
+    match authority.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => (host.to_string(), port.parse().ok()),
+        _ => (authority.to_string(), None),
+    }
+}
+
+/// Try to reach GitHub. `to_socket_addrs` has no timeout of its own, so the
+/// lookup runs on a helper thread that is abandoned if it takes too long.
+fn probe() -> bool {
+    let target = probe_target(|name| std::env::var(name).ok());
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let reachable = target
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addrs| addrs.next())
+            .is_some_and(|addr| TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).is_ok());
+        let _ = tx.send(reachable);
+    });
+    rx.recv_timeout(PROBE_TIMEOUT * 2).unwrap_or(false)
+}
+
+/// A cached probe result, if it is still fresh at `now`
+fn fresh_result(last: Option<(Instant, bool)>, now: Instant) -> Option<bool> {
+    last.filter(|(at, _)| now.duration_since(*at) < PROBE_TTL)
+        .map(|(_, online)| online)
+}
+
+/// Probe now and cache the result. Returns (online, changed since last probe).
+pub fn check_now() -> (bool, bool) {
+    let online = probe();
+    let previous = LAST_PROBE
+        .lock()
+        .map(|mut last| last.replace((Instant::now(), online)))
+        .unwrap_or(None);
+
+    if let Ok(mut since) = OFFLINE_SINCE.lock() {
+        match (online, since.is_some()) {
+            (false, false) => *since = Some(Utc::now()),
+            (true, true) => *since = None,
+            _ => {}
+        }
+    }
+
+    let changed = previous.is_some_and(|(_, was_online)| was_online != online);
+    (online, changed)
+}
+
+/// Whether GitHub is reachable as of the last probe. Never blocks: a stale
+/// result starts a background probe and is used until that finishes (before
+/// the first probe, GitHub is assumed reachable).
+pub fn is_online() -> bool {
+    let last = LAST_PROBE.lock().map(|last| *last).unwrap_or(None);
+    if let Some(online) = fresh_result(last, Instant::now()) {
+        return online;
+    }
+    if !PROBING.swap(true, Ordering::SeqCst) {
+        thread::spawn(|| {
+            check_now();
+            PROBING.store(false, Ordering::SeqCst);
+        });
+    }
+    last.is_none_or(|(_, online)| online)
+}
+
+/// Current connectivity (a stale result is refreshed in the background)
+#[tauri::command]
+pub fn get_connectivity_status() -> ConnectivityStatus {
+    let online = is_online();
+    let offline_since = OFFLINE_SINCE
+        .lock()
+        .map(|since| since.map(|t| t.to_rfc3339()))
+        .unwrap_or(None);
+    ConnectivityStatus {
+        online,
+        offline_since,
+    }
+}
+
+/// Refresh PRs for all configured repos (used when connectivity returns)
+pub fn refresh_configured_repos(app: &AppHandle) {
+    match settings::get_repos(app.state::<AppState>()) {
+        Ok(repos) if !repos.is_empty() => {
+            if let Err(e) = crate::fetch_prs(app.clone(), app.state::<AppState>(), None, Some(repos), None) {
+                eprintln!("Failed to refresh PRs: {}", e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Failed to load repos: {}", e),
+    }
+}
+
+/// Start the background probe that reports transitions and refreshes on reconnect
+pub fn start_connectivity_watcher(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(PROBE_TTL);
+
+        let (online, changed) = check_now();
+        if !changed {
+            continue;
+        }
+
+        println!("Connectivity changed: {}", if online { "online" } else { "offline" });
+        let _ = app.emit("connectivity:changed", get_connectivity_status());
+        if online && app.try_state::<AppState>().is_some() {
+            refresh_configured_repos(&app);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fresh_result() {
+        let now = Instant::now();
+        assert_eq!(fresh_result(None, now), None);
+        assert_eq!(fresh_result(Some((now, false)), now + Duration::from_secs(10)), Some(false));
+        assert_eq!(fresh_result(Some((now, true)), now + PROBE_TTL), None);
+    }
+
+    #[test]
+    fn test_probe_target() {
+        let target = |vars: &[(&str, &str)]| {
+            let vars: Vec<(String, String)> =
+                vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+            probe_target(|name| vars.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone()))
+        };
+
+        assert_eq!(target(&[]), "api.github.com:443");
+        assert_eq!(target(&[("GH_HOST", "github.com")]), "api.github.com:443");
+        assert_eq!(target(&[("GH_HOST", "ghe.corp.example")]), "ghe.corp.example:443");
+        assert_eq!(
+            target(&[("HTTPS_PROXY", "http://user:pw@proxy.local:3128/")]),
+            "proxy.local:3128"
+        );
+        assert_eq!(target(&[("https_proxy", "https://proxy.local")]), "proxy.local:443");
+        assert_eq!(target(&[("ALL_PROXY", "socks5://[::1]")]), "[::1]:1080");
+        assert_eq!(target(&[("HTTPS_PROXY", "proxy.local")]), "proxy.local:80");
+        // NO_PROXY exempting the API host means a direct connection
+        assert_eq!(
+            target(&[("GH_HOST", "ghe.corp.example"), ("HTTPS_PROXY", "proxy.local:3128"), ("NO_PROXY", ".corp.example")]),
+            "ghe.corp.example:443"
+        );
+        assert_eq!(
+            target(&[("HTTPS_PROXY", "proxy.local:3128"), ("NO_PROXY", "localhost")]),
+            "proxy.local:3128"
+        );
+    }
+}
@@ -1,53 +1,221 @@
+use crate::github::{GhCli, GithubClient};
 use crate::process::ProcessRegistry;
-use rusqlite::{Connection, Result as SqliteResult};
-use std::path::PathBuf;
-use std::sync::Mutex;
+use rusqlite::{Connection, OptionalExtension, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex, MutexGuard};
 
 pub const AI_PROVIDER_SETTING_KEY: &str = "ai_provider";
 pub const AI_MODEL_CLAUDE_SETTING_KEY: &str = "ai_model_claude";
 pub const AI_MODEL_CODEX_SETTING_KEY: &str = "ai_model_codex";
 pub const MONITOR_DIRTY_WORKTREE_POLICY_SETTING_KEY: &str = "monitor_dirty_worktree_policy";
 pub const SKIP_CI_FIX_SETTING_KEY: &str = "skip_ci_fix";
+pub const QUIT_BEHAVIOR_SETTING_KEY: &str = "quit_behavior";
+pub const CLOSE_BEHAVIOR_SETTING_KEY: &str = "close_behavior";
+pub const API_ENABLED_SETTING_KEY: &str = "api_enabled";
+pub const API_TOKEN_SETTING_KEY: &str = "api_token";
+pub const REFRESH_ON_WAKE_SETTING_KEY: &str = "refresh_on_wake";
+pub const REVIEW_WAITING_THRESHOLD_HOURS_SETTING_KEY: &str = "review_waiting_threshold_hours";
+pub const STALE_DATA_THRESHOLD_MINUTES_SETTING_KEY: &str = "stale_data_threshold_minutes";
+pub const RATE_LIMIT_RESERVE_SETTING_KEY: &str = "rate_limit_reserve";
+pub const RATE_LIMIT_WARNING_THRESHOLD_SETTING_KEY: &str = "rate_limit_warning_threshold";
+pub const ATTENTION_EXCLUDES_ACKNOWLEDGED_SETTING_KEY: &str = "attention_excludes_acknowledged";
+pub const DOCK_BADGE_MODE_SETTING_KEY: &str = "dock_badge_mode";
+pub const AUTO_DISMISS_MERGED_AFTER_MINUTES_SETTING_KEY: &str = "auto_dismiss_merged_after_minutes";
+pub const REFETCH_COMMENTS_ON_FORCE_PUSH_SETTING_KEY: &str = "refetch_comments_on_force_push";
+pub const KEEP_RESOLVED_HISTORY_SETTING_KEY: &str = "keep_resolved_history";
+pub const EXTERNAL_PUSH_ACTION_SETTING_KEY: &str = "external_push_action";
+pub const DEMO_MODE_SETTING_KEY: &str = "demo_mode";
+pub const MUTED_AUTHORS_SETTING_KEY: &str = "muted_authors";
+pub const MY_TEAMS_SETTING_KEY: &str = "my_teams";
+pub const SUPPRESS_NOTIFICATIONS_WHEN_FOCUSED_SETTING_KEY: &str = "suppress_notifications_when_focused";
+pub const MONITORS_PAUSED_SETTING_KEY: &str = "monitors_paused";
+pub const MAX_CONCURRENT_MONITORS_SETTING_KEY: &str = "max_concurrent_monitors";
+pub const ALLOW_FORK_MONITORS_SETTING_KEY: &str = "allow_fork_monitors";
+pub const MONITOR_INTERVAL_JITTER_PERCENT_SETTING_KEY: &str = "monitor_interval_jitter_percent";
+pub const DONE_ARCHIVE_AFTER_DAYS_SETTING_KEY: &str = "done_archive_after_days";
+pub const DONE_DELETE_AFTER_DAYS_SETTING_KEY: &str = "done_delete_after_days";
+pub const GH_PATH_SETTING_KEY: &str = "gh_path";
+pub const MONITOR_SCRIPT_PATH_SETTING_KEY: &str = "monitor_script_path";
+pub const MONITOR_AUTO_ANSWERS_SETTING_KEY: &str = "monitor_auto_answers";
+pub const MERGE_METHOD_REPO_SETTING_KEY: &str = "merge_method";
+pub const LOCAL_PATH_REPO_SETTING_KEY: &str = "local_path";
+pub const ON_APPROVED_REPO_SETTING_KEY: &str = "on_approved";
+pub const ALIAS_REPO_SETTING_KEY: &str = "alias";
 
-/// Application state holding the database connection and process registry
+/// Application state holding the database connection, process registry and GitHub client
 pub struct AppState {
     pub db: Mutex<Connection>,
     pub processes: ProcessRegistry,
+    pub github: Arc<dyn GithubClient>,
+    /// Set while the last environment check found gh logged out; monitor
+    /// starts are refused (or blocked) until a later check passes
+    pub gh_auth_missing: AtomicBool,
+    /// Where monitor logs go instead of `app_data_dir()` (tests use a temp dir)
+    pub data_dir: Option<PathBuf>,
 }
 
 impl AppState {
     pub fn new(db_path: PathBuf) -> Result<Self, String> {
-        let conn = Connection::open(&db_path)
-            .map_err(|e| format!("Failed to open database: {}", e))?;
-
-        // SQLite performance and memory settings
-        conn.execute_batch(
-            "PRAGMA foreign_keys = ON;
-             PRAGMA journal_mode = WAL;
-             PRAGMA cache_size = -2000;
-             PRAGMA temp_store = MEMORY;
-             PRAGMA busy_timeout = 5000;"
-        ).map_err(|e| format!("Failed to set database pragmas: {}", e))?;
+        let conn = open_connection(&db_path)?;
 
         Ok(Self {
             db: Mutex::new(conn),
             processes: ProcessRegistry::new(),
+            github: Arc::new(GhCli::default()),
+            gh_auth_missing: AtomicBool::new(false),
+            data_dir: None,
+        })
+    }
+
+    /// The app data directory, or this state's override of it
+    pub fn data_dir(&self) -> Result<PathBuf, String> {
+        match &self.data_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => app_data_dir(),
+        }
+    }
+
+    /// Lock the database connection. A panic while the lock was held poisons
+    /// the mutex; the connection itself is still usable (SQLite rolls back any
+    /// unfinished statement), so recover the guard instead of failing every
+    /// later command until restart.
+    pub fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.db.lock().unwrap_or_else(|poisoned| {
+            eprintln!("Warning: database lock was poisoned by a panic; recovering");
+            self.db.clear_poison();
+            poisoned.into_inner()
         })
     }
+
+    /// In-memory state with the given GitHub client, for tests
+    #[cfg(test)]
+    pub fn for_tests(github: Arc<dyn GithubClient>) -> Self {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        Self {
+            db: Mutex::new(conn),
+            processes: ProcessRegistry::new(),
+            github,
+            gh_auth_missing: AtomicBool::new(false),
+            data_dir: None,
+        }
+    }
+
+    /// In-memory state backed by an empty mock GitHub, for tests
+    #[cfg(test)]
+    pub fn for_tests_default() -> Self {
+        Self::for_tests(Arc::new(crate::github::mock::MockGithub::new("me")))
+    }
 }
 
-/// Get the database path in the app data directory
-pub fn get_db_path() -> Result<PathBuf, String> {
-    let data_dir = dirs::data_local_dir()
-        .ok_or_else(|| "Failed to get local data directory".to_string())?;
+/// Open a database file with the app's SQLite settings
+pub fn open_connection(db_path: &Path) -> Result<Connection, String> {
+    let conn = Connection::open(db_path)
+        .map_err(|e| format!("Failed to open database: {}", e))?;
+
+    // SQLite performance and memory settings
+    conn.execute_batch(
+        "PRAGMA foreign_keys = ON;
+         PRAGMA journal_mode = WAL;
+         PRAGMA cache_size = -2000;
+         PRAGMA temp_store = MEMORY;
+         PRAGMA busy_timeout = 5000;"
+    ).map_err(|e| format!("Failed to set database pragmas: {}", e))?;
+
+    Ok(conn)
+}
+
+/// Bundle identifier; names the app data directory
+const APP_IDENTIFIER: &str = "com.clanker-spanker.app";
+
+/// Environment variable that relocates the app data directory
+pub const DATA_DIR_ENV: &str = "CLANKER_DATA_DIR";
+
+/// Settings needed before the DB can be opened, kept in the config dir
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BootstrapConfig {
+    data_dir: Option<PathBuf>,
+}
+
+fn bootstrap_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_IDENTIFIER).join("bootstrap.json"))
+}
+
+/// Read the bootstrap config, treating a missing or malformed file as empty
+fn read_bootstrap_config(path: &Path) -> BootstrapConfig {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn write_bootstrap_config(path: &Path, config: &BootstrapConfig) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize bootstrap config: {}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write bootstrap config: {}", e))
+}
 
-    let app_dir = data_dir.join("com.clanker-spanker.app");
+/// Persist a custom data directory (None restores the default). Takes effect on restart.
+pub fn set_configured_data_dir(dir: Option<PathBuf>) -> Result<(), String> {
+    let path = bootstrap_config_path().ok_or_else(|| "Failed to get config directory".to_string())?;
+    let mut config = read_bootstrap_config(&path);
+    config.data_dir = dir;
+    write_bootstrap_config(&path, &config)
+}
+
+/// Create `dir` if needed and check that files can be written to it
+pub fn ensure_dir_writable(dir: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+    let probe = dir.join(format!(".clanker-write-test-{}", std::process::id()));
+    std::fs::write(&probe, b"ok").map_err(|e| format!("{} is not writable: {}", dir.display(), e))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+/// The app data directory (DB, logs, scripts), created if missing.
+/// `CLANKER_DATA_DIR` wins over the bootstrap config's `dataDir`, which wins
+/// over the default under the local data dir.
+pub fn app_data_dir() -> Result<PathBuf, String> {
+    let configured = bootstrap_config_path().and_then(|path| read_bootstrap_config(&path).data_dir);
+    let app_dir =
+        resolve_app_data_dir(std::env::var_os(DATA_DIR_ENV), configured, dirs::data_local_dir())
+            .ok_or_else(|| "Failed to get local data directory".to_string())?;
 
-    // Create app directory if it doesn't exist
     std::fs::create_dir_all(&app_dir)
         .map_err(|e| format!("Failed to create app directory: {}", e))?;
 
-    Ok(app_dir.join("clanker-spanker.db"))
+    Ok(app_dir)
+}
+
+fn resolve_app_data_dir(
+    override_dir: Option<std::ffi::OsString>,
+    configured_dir: Option<PathBuf>,
+    data_local_dir: Option<PathBuf>,
+) -> Option<PathBuf> {
+    override_dir
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| configured_dir.filter(|dir| !dir.as_os_str().is_empty()))
+        .or_else(|| data_local_dir.map(|dir| dir.join(APP_IDENTIFIER)))
+}
+
+/// Get the database path in the app data directory
+pub fn get_db_path() -> Result<PathBuf, String> {
+    Ok(app_data_dir()?.join("clanker-spanker.db"))
+}
+
+/// Get the path of the separate database demo mode runs on
+pub fn get_demo_db_path() -> Result<PathBuf, String> {
+    Ok(app_data_dir()?.join("demo.db"))
 }
 
 /// Initialize the database schema
@@ -72,6 +240,7 @@ pub fn init_schema(conn: &Connection) -> SqliteResult<()> {
             comments_fixed INTEGER NOT NULL DEFAULT 0,
             exit_reason TEXT,
             log_file TEXT NOT NULL,
+            tags TEXT NOT NULL DEFAULT '[]',
             created_at TEXT NOT NULL DEFAULT (datetime('now'))
         );
 
@@ -112,6 +281,15 @@ pub fn init_schema(conn: &Connection) -> SqliteResult<()> {
             ('ai_model_codex', ''),
             ('monitor_dirty_worktree_policy', 'abort');
 
+        -- repo_settings: Per-repository overrides (e.g. merge method)
+        CREATE TABLE IF NOT EXISTS repo_settings (
+            repo TEXT NOT NULL,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (repo, key)
+        );
+
         -- pr_cache: Cached PR metadata for incremental fetching
         CREATE TABLE IF NOT EXISTS pr_cache (
             id TEXT PRIMARY KEY,
@@ -134,6 +312,7 @@ pub fn init_schema(conn: &Connection) -> SqliteResult<()> {
             created_at TEXT NOT NULL,
             updated_at TEXT NOT NULL,
             column_assignment TEXT NOT NULL DEFAULT 'todo',
+            head_sha TEXT,
             cached_at TEXT NOT NULL DEFAULT (datetime('now')),
             UNIQUE(repo, number)
         );
@@ -146,7 +325,8 @@ pub fn init_schema(conn: &Connection) -> SqliteResult<()> {
         CREATE TABLE IF NOT EXISTS fetch_metadata (
             repo TEXT PRIMARY KEY,
             last_fetch_at TEXT NOT NULL,
-            last_fetch_count INTEGER DEFAULT 0
+            last_fetch_count INTEGER DEFAULT 0,
+            last_fetch_ms INTEGER
         );
 
         -- pr_comments: Unresolved review thread comments
@@ -169,7 +349,155 @@ pub fn init_schema(conn: &Connection) -> SqliteResult<()> {
         CREATE INDEX IF NOT EXISTS idx_pr_comments_pr ON pr_comments(pr_id);
         CREATE INDEX IF NOT EXISTS idx_pr_comments_unresolved ON pr_comments(pr_id, is_resolved);
         CREATE INDEX IF NOT EXISTS idx_pr_comments_thread ON pr_comments(thread_id);
+
+        -- Monitor starts waiting for a free slot under max_concurrent_monitors
+        CREATE TABLE IF NOT EXISTS monitor_queue (
+            pr_id TEXT PRIMARY KEY,
+            pr_number INTEGER NOT NULL,
+            repo TEXT NOT NULL,
+            params TEXT NOT NULL DEFAULT '{}',
+            queued_at TEXT NOT NULL
+        );
+
+        -- audit_log: Actions the app took on its own (e.g. auto-merges)
+        CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            pr_id TEXT NOT NULL,
+            action TEXT NOT NULL,
+            detail TEXT,
+            created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_audit_log_pr ON audit_log(pr_id);
+
+        -- notification_log: Notifications that were shown, for history and search
+        CREATE TABLE IF NOT EXISTS notification_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            notification_type TEXT NOT NULL,
+            pr_id TEXT NOT NULL,
+            pr_number INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );
+
+        -- rate_limit_samples: GraphQL budget after each PR fetch cycle
+        CREATE TABLE IF NOT EXISTS rate_limit_samples (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            remaining INTEGER NOT NULL,
+            rate_limit INTEGER NOT NULL,
+            reset_at TEXT NOT NULL,
+            gh_calls INTEGER NOT NULL DEFAULT 0,
+            sampled_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );
         "#,
+    )?;
+
+    // Columns added after the initial schema (existing databases need them too)
+    add_column_if_missing(conn, "fetch_metadata", "last_fetch_ms", "INTEGER")?;
+    add_column_if_missing(conn, "fetch_metadata", "last_error", "TEXT")?;
+    add_column_if_missing(conn, "fetch_metadata", "last_error_at", "TEXT")?;
+    add_column_if_missing(conn, "monitors", "tags", "TEXT NOT NULL DEFAULT '[]'")?;
+    add_column_if_missing(conn, "monitors", "notification_level", "TEXT NOT NULL DEFAULT 'default'")?;
+    migrate_notification_level_default(conn)?;
+    add_column_if_missing(conn, "monitors", "head_sha", "TEXT")?;
+    // Head the script is pushing right now (`@@PUSHING`), until `@@PUSHED` or the next `@@HEAD`
+    add_column_if_missing(conn, "monitors", "pushing_sha", "TEXT")?;
+    add_column_if_missing(conn, "monitors", "paused", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "monitors", "paused_at", "TEXT")?;
+    add_column_if_missing(conn, "monitors", "paused_seconds", "INTEGER NOT NULL DEFAULT 0")?;
+    // interval_minutes with the monitor's jitter applied; what the script sleeps
+    add_column_if_missing(conn, "monitors", "effective_interval_minutes", "INTEGER")?;
+    add_column_if_missing(conn, "monitors", "mode", "TEXT NOT NULL DEFAULT 'auto_fix'")?;
+    // Local clone the script ran in (its REPO_DIR argument), if one was configured
+    add_column_if_missing(conn, "monitors", "working_dir", "TEXT")?;
+    // started_at has one-second resolution, so runs started in the same second
+    // are ordered by start_seq, which increases with every insert
+    add_column_if_missing(conn, "monitors", "start_seq", "INTEGER")?;
+    conn.execute_batch(
+        "UPDATE monitors SET start_seq = rowid WHERE start_seq IS NULL;
+         CREATE TRIGGER IF NOT EXISTS monitors_start_seq AFTER INSERT ON monitors
+         WHEN NEW.start_seq IS NULL
+         BEGIN
+             UPDATE monitors SET start_seq = (SELECT COALESCE(MAX(start_seq), 0) + 1 FROM monitors)
+             WHERE rowid = NEW.rowid;
+         END;",
+    )?;
+    add_column_if_missing(conn, "monitor_logs", "head_sha", "TEXT")?;
+    add_column_if_missing(conn, "pr_cache", "head_sha", "TEXT")?;
+    add_column_if_missing(conn, "pr_cache", "previous_head_sha", "TEXT")?;
+    add_column_if_missing(conn, "pr_cache", "merged_at", "TEXT")?;
+    add_column_if_missing(conn, "pr_cache", "last_seen_at", "TEXT")?;
+    add_column_if_missing(conn, "pr_cache", "parent_pr_id", "TEXT")?;
+    add_column_if_missing(conn, "pr_cache", "needs_rebase", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "pr_cache", "review_requested_at", "TEXT")?;
+    add_column_if_missing(conn, "pr_cache", "muted", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "pr_cache", "archived", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "pr_cache", "archived_at", "TEXT")?;
+    add_column_if_missing(conn, "pr_cache", "reviewer_teams", "TEXT NOT NULL DEFAULT '[]'")?;
+    add_column_if_missing(conn, "pr_cache", "head_repo", "TEXT")?;
+    add_column_if_missing(conn, "pr_cache", "is_fork", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "pr_cache", "changes_requested_by", "TEXT NOT NULL DEFAULT '[]'")?;
+    // Manual ci_status override (see set_ci_override)
+    add_column_if_missing(conn, "pr_cache", "ci_override_until", "TEXT")?;
+    add_column_if_missing(conn, "pr_cache", "ci_status_fetched", "TEXT")?;
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_pr_cache_unread
+         ON pr_cache(repo, column_assignment, updated_at, last_seen_at);",
+    )?;
+    add_column_if_missing(conn, "pr_comments", "is_stale", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "pr_comments", "acknowledged", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "pr_comments", "gone_from_github", "INTEGER NOT NULL DEFAULT 0")?;
+    // The per-repo working_dir setting duplicated local_path and took
+    // precedence over it; fold it in
+    conn.execute_batch(
+        "INSERT INTO repo_settings (repo, key, value, updated_at)
+         SELECT repo, 'local_path', value, updated_at FROM repo_settings
+         WHERE key = 'working_dir' AND trim(value) != ''
+         ON CONFLICT(repo, key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at;
+         DELETE FROM repo_settings WHERE key = 'working_dir';",
+    )?;
+
+    Ok(())
+}
+
+/// Add a column to an existing table unless it is already present
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> SqliteResult<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|r| r.ok())
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))?;
+    }
+    Ok(())
+}
+
+/// notification_level used to default to 'all', which stood for "use the global
+/// settings" before 'default' existed. Rebuild the column with the 'default'
+/// default and carry those rows over; the new default makes this run only once.
+fn migrate_notification_level_default(conn: &Connection) -> SqliteResult<()> {
+    let old_default: Option<String> = conn
+        .query_row(
+            "SELECT dflt_value FROM pragma_table_info('monitors') WHERE name = 'notification_level'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    if old_default.as_deref() != Some("'all'") {
+        return Ok(());
+    }
+
+    conn.execute_batch(
+        "BEGIN;
+         ALTER TABLE monitors RENAME COLUMN notification_level TO notification_level_old;
+         ALTER TABLE monitors ADD COLUMN notification_level TEXT NOT NULL DEFAULT 'default';
+         UPDATE monitors SET notification_level =
+             CASE notification_level_old WHEN 'all' THEN 'default' ELSE notification_level_old END;
+         ALTER TABLE monitors DROP COLUMN notification_level_old;
+         COMMIT;",
     )
 }
 
@@ -200,6 +528,16 @@ pub fn get_setting_value(conn: &Connection, key: &str) -> Option<String> {
     get_setting(conn, key).ok().flatten()
 }
 
+/// Configured repos (the `repos` setting) as `owner/name`
+pub fn get_configured_repos(conn: &Connection) -> SqliteResult<Vec<String>> {
+    Ok(get_setting(conn, "repos")?
+        .and_then(|json| serde_json::from_str::<Vec<String>>(&json).ok())
+        .unwrap_or_default()
+        .iter()
+        .map(|r| crate::parse_repo_path(r))
+        .collect())
+}
+
 /// Set a setting value
 pub fn set_setting(conn: &Connection, key: &str, value: &str) -> SqliteResult<()> {
     conn.execute(
@@ -209,6 +547,40 @@ pub fn set_setting(conn: &Connection, key: &str, value: &str) -> SqliteResult<()
     Ok(())
 }
 
+/// Get a per-repo setting value
+pub fn get_repo_setting(conn: &Connection, repo: &str, key: &str) -> SqliteResult<Option<String>> {
+    let mut stmt = conn.prepare("SELECT value FROM repo_settings WHERE repo = ?1 AND key = ?2")?;
+    let mut rows = stmt.query([repo, key])?;
+
+    if let Some(row) = rows.next()? {
+        Ok(Some(row.get(0)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Set a per-repo setting value
+pub fn set_repo_setting(conn: &Connection, repo: &str, key: &str, value: &str) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO repo_settings (repo, key, value, updated_at) VALUES (?1, ?2, ?3, datetime('now'))",
+        [repo, key, value],
+    )?;
+    Ok(())
+}
+
+/// Remove a per-repo setting
+pub fn delete_repo_setting(conn: &Connection, repo: &str, key: &str) -> SqliteResult<()> {
+    conn.execute("DELETE FROM repo_settings WHERE repo = ?1 AND key = ?2", [repo, key])?;
+    Ok(())
+}
+
+/// Display aliases of the repos that have one (repo -> alias)
+pub fn get_repo_aliases(conn: &Connection) -> SqliteResult<std::collections::BTreeMap<String, String>> {
+    let mut stmt = conn.prepare("SELECT repo, value FROM repo_settings WHERE key = ?1 AND value != ''")?;
+    let rows = stmt.query_map([ALIAS_REPO_SETTING_KEY], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
 /// Resolve AI provider/model settings with safe defaults.
 pub fn get_ai_config(conn: &Connection) -> (String, Option<String>) {
     let provider_raw = get_setting_value(conn, AI_PROVIDER_SETTING_KEY)
@@ -264,144 +636,1695 @@ pub fn get_skip_ci_fix(conn: &Connection) -> String {
     }
 }
 
-/// Get the last fetch time for a repo
-pub fn get_last_fetch(conn: &Connection, repo: &str) -> SqliteResult<Option<String>> {
-    let mut stmt = conn.prepare("SELECT last_fetch_at FROM fetch_metadata WHERE repo = ?1")?;
-    let mut rows = stmt.query([repo])?;
+/// Resolve what happens to running monitors when the app quits.
+/// Valid values: "kill" | "wait" | "detach"
+pub fn get_quit_behavior(conn: &Connection) -> String {
+    let raw = get_setting_value(conn, QUIT_BEHAVIOR_SETTING_KEY)
+        .unwrap_or_else(|| "kill".to_string())
+        .trim()
+        .to_lowercase();
 
-    if let Some(row) = rows.next()? {
-        Ok(Some(row.get(0)?))
-    } else {
-        Ok(None)
+    match raw.as_str() {
+        "wait" | "detach" => raw,
+        _ => "kill".to_string(),
     }
 }
 
-/// Update the last fetch time for a repo
-pub fn set_last_fetch(conn: &Connection, repo: &str, timestamp: &str, count: i32) -> SqliteResult<()> {
-    conn.execute(
-        "INSERT OR REPLACE INTO fetch_metadata (repo, last_fetch_at, last_fetch_count) VALUES (?1, ?2, ?3)",
-        rusqlite::params![repo, timestamp, count],
-    )?;
-    Ok(())
+/// Resolve what closing the main window does.
+/// Valid values: "hide" (to the tray) | "quit"
+pub fn get_close_behavior(conn: &Connection) -> String {
+    match get_setting_value(conn, CLOSE_BEHAVIOR_SETTING_KEY)
+        .map(|v| v.trim().to_lowercase())
+        .as_deref()
+    {
+        Some("quit") => "quit".to_string(),
+        _ => "hide".to_string(),
+    }
 }
 
-/// Clear all PR cache (for debugging or forced refresh)
-pub fn clear_pr_cache(conn: &Connection, repo: Option<&str>) -> SqliteResult<()> {
-    if let Some(repo) = repo {
-        conn.execute("DELETE FROM pr_cache WHERE repo = ?1", [repo])?;
-        conn.execute("DELETE FROM fetch_metadata WHERE repo = ?1", [repo])?;
-    } else {
-        conn.execute("DELETE FROM pr_cache", [])?;
-        conn.execute("DELETE FROM fetch_metadata", [])?;
-    }
-    Ok(())
+/// Whether the HTTP API server should run. Defaults to enabled.
+pub fn get_api_enabled(conn: &Connection) -> bool {
+    get_setting_value(conn, API_ENABLED_SETTING_KEY)
+        .map(|v| v.trim() != "false")
+        .unwrap_or(true)
 }
 
-/// Delete stale PRs that are no longer open
-pub fn delete_stale_prs(conn: &Connection, repo: &str, active_pr_ids: &[String]) -> SqliteResult<usize> {
-    if active_pr_ids.is_empty() {
-        return Ok(0);
+/// Whether PRs should be refreshed automatically after system wake. Defaults to enabled.
+pub fn get_refresh_on_wake(conn: &Connection) -> bool {
+    get_setting_value(conn, REFRESH_ON_WAKE_SETTING_KEY)
+        .map(|v| v.trim() != "false")
+        .unwrap_or(true)
+}
+
+/// Whether cached review comments are re-fetched after a force-push. Defaults to enabled.
+pub fn get_refetch_comments_on_force_push(conn: &Connection) -> bool {
+    get_setting_value(conn, REFETCH_COMMENTS_ON_FORCE_PUSH_SETTING_KEY)
+        .map(|v| v.trim() != "false")
+        .unwrap_or(true)
+}
+
+/// What to do when someone else pushes to a monitored PR mid-run.
+/// Valid values: "pause" (default) | "notify"
+pub fn get_external_push_action(conn: &Connection) -> String {
+    match get_setting_value(conn, EXTERNAL_PUSH_ACTION_SETTING_KEY)
+        .map(|v| v.trim().to_lowercase())
+        .as_deref()
+    {
+        Some("notify") => "notify".to_string(),
+        _ => "pause".to_string(),
     }
+}
 
-    // Build placeholders for IN clause
-    let placeholders: Vec<String> = (0..active_pr_ids.len())
-        .map(|i| format!("?{}", i + 2))
-        .collect();
-    let placeholders_str = placeholders.join(",");
+/// Whether to start in demo mode (fixture GitHub, scripted monitors).
+/// Takes effect on restart. Defaults to disabled.
+pub fn get_demo_mode(conn: &Connection) -> bool {
+    get_setting_value(conn, DEMO_MODE_SETTING_KEY)
+        .map(|v| v.trim() == "true")
+        .unwrap_or(false)
+}
 
-    let sql = format!(
-        "DELETE FROM pr_cache WHERE repo = ?1 AND id NOT IN ({})",
-        placeholders_str
-    );
+/// Whether resolved threads GitHub stops returning are kept (flagged gone_from_github)
+/// instead of deleted on the next comment fetch. Defaults to disabled.
+pub fn get_keep_resolved_history(conn: &Connection) -> bool {
+    get_setting_value(conn, KEEP_RESOLVED_HISTORY_SETTING_KEY)
+        .map(|v| v.trim() == "true")
+        .unwrap_or(false)
+}
 
-    let mut params: Vec<&dyn rusqlite::ToSql> = vec![&repo];
-    for id in active_pr_ids {
-        params.push(id);
-    }
+/// Whether monitors start on PRs whose head branch is in a fork without a warning.
+/// Defaults to disabled: the monitor script pushes fixes to the PR's own repository.
+pub fn get_allow_fork_monitors(conn: &Connection) -> bool {
+    get_setting_value(conn, ALLOW_FORK_MONITORS_SETTING_KEY).is_some_and(|v| v.trim() == "true")
+}
 
-    let deleted = conn.execute(&sql, rusqlite::params_from_iter(params))?;
-    Ok(deleted)
+/// Whether OS notifications are skipped while the main window is focused. Defaults to disabled.
+pub fn get_suppress_notifications_when_focused(conn: &Connection) -> bool {
+    get_setting_value(conn, SUPPRESS_NOTIFICATIONS_WHEN_FOCUSED_SETTING_KEY)
+        .map(|v| v.trim() == "true")
+        .unwrap_or(false)
 }
 
-/// Get stale PR IDs (PRs in cache that are not in the active list)
-pub fn get_stale_pr_ids(conn: &Connection, repo: &str, active_pr_ids: &[String]) -> SqliteResult<Vec<(String, i32)>> {
-    if active_pr_ids.is_empty() {
-        // If no active PRs, all cached PRs for this repo are stale
-        let mut stmt = conn.prepare("SELECT id, number FROM pr_cache WHERE repo = ?1 AND state = 'open'")?;
-        let rows = stmt.query_map([repo], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?))
-        })?;
-        return rows.collect();
+/// Whether unresolved threads that were all acknowledged locally stop counting
+/// towards the attention count. Defaults to disabled.
+pub fn get_attention_excludes_acknowledged(conn: &Connection) -> bool {
+    get_setting_value(conn, ATTENTION_EXCLUDES_ACKNOWLEDGED_SETTING_KEY)
+        .map(|v| v.trim() == "true")
+        .unwrap_or(false)
+}
+
+/// Whether the global pause-all switch is on. Defaults to off.
+pub fn get_monitors_paused(conn: &Connection) -> bool {
+    get_setting_value(conn, MONITORS_PAUSED_SETTING_KEY)
+        .map(|v| v.trim() == "true")
+        .unwrap_or(false)
+}
+
+/// A reply typed into a monitor's stdin when a line of its output contains `pattern`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutoAnswer {
+    pub pattern: String,
+    pub response: String,
+}
+
+/// Prompts monitors answer on their own (a JSON array of {pattern, response}).
+/// Unset or malformed means none.
+pub fn get_monitor_auto_answers(conn: &Connection) -> Vec<AutoAnswer> {
+    get_setting_value(conn, MONITOR_AUTO_ANSWERS_SETTING_KEY)
+        .and_then(|json| serde_json::from_str::<Vec<AutoAnswer>>(&json).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|answer| !answer.pattern.is_empty())
+        .collect()
+}
+
+/// gh binary to run instead of `gh` from PATH (unset or blank = PATH lookup)
+pub fn get_gh_path(conn: &Connection) -> Option<PathBuf> {
+    get_setting_value(conn, GH_PATH_SETTING_KEY)
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Script to run for monitors instead of the bundled monitor-pr-loop.sh
+/// (unset or blank = bundled script)
+pub fn get_monitor_script_path(conn: &Connection) -> Option<PathBuf> {
+    get_setting_value(conn, MONITOR_SCRIPT_PATH_SETTING_KEY)
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// What the dock badge counts.
+/// Valid values: "monitors" (active monitors) | "attention" (PRs needing attention)
+/// | "unread" (PRs with unseen activity) | "off"
+pub fn get_dock_badge_mode(conn: &Connection) -> String {
+    let raw = get_setting_value(conn, DOCK_BADGE_MODE_SETTING_KEY)
+        .unwrap_or_else(|| "monitors".to_string())
+        .trim()
+        .to_lowercase();
+
+    match raw.as_str() {
+        "attention" | "unread" | "off" => raw,
+        _ => "monitors".to_string(),
     }
+}
 
-    // Build placeholders for NOT IN clause
-    let placeholders: Vec<String> = (0..active_pr_ids.len())
-        .map(|i| format!("?{}", i + 2))
-        .collect();
-    let placeholders_str = placeholders.join(",");
+/// Hours a review request can wait before the PR counts as needing attention.
+/// Returns None when unset or 0 (waiting time doesn't affect attention).
+pub fn get_review_waiting_threshold_hours(conn: &Connection) -> Option<f64> {
+    get_setting_value(conn, REVIEW_WAITING_THRESHOLD_HOURS_SETTING_KEY)
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|hours| *hours > 0.0)
+}
 
-    let sql = format!(
-        "SELECT id, number FROM pr_cache WHERE repo = ?1 AND state = 'open' AND id NOT IN ({})",
-        placeholders_str
-    );
+/// Minutes since the last successful fetch after which a repo's data counts as
+/// stale. Defaults to 60; 0 or invalid values fall back to the default.
+pub fn get_stale_data_threshold_minutes(conn: &Connection) -> i64 {
+    get_setting_value(conn, STALE_DATA_THRESHOLD_MINUTES_SETTING_KEY)
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|minutes| *minutes > 0)
+        .unwrap_or(60)
+}
 
-    let mut stmt = conn.prepare(&sql)?;
+/// GraphQL requests to keep in hand: bulk fetches slow down as the remaining
+/// budget approaches this and are deferred once it is reached (default 200)
+pub fn get_rate_limit_reserve(conn: &Connection) -> i64 {
+    get_setting_value(conn, RATE_LIMIT_RESERVE_SETTING_KEY)
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|reserve| *reserve >= 0)
+        .unwrap_or(200)
+}
 
-    let mut param_values: Vec<String> = vec![repo.to_string()];
-    param_values.extend(active_pr_ids.iter().cloned());
+/// Remaining GraphQL requests below which a warning is shown and auto-refresh slows down
+pub fn get_rate_limit_warning_threshold(conn: &Connection) -> i64 {
+    get_setting_value(conn, RATE_LIMIT_WARNING_THRESHOLD_SETTING_KEY)
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|threshold| *threshold >= 0)
+        .unwrap_or(1000)
+}
 
-    let rows = stmt.query_map(rusqlite::params_from_iter(param_values), |row| {
-        Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?))
-    })?;
+/// Minutes after merging before a PR is dismissed automatically.
+/// Returns None when unset or 0 (merged PRs stay until dismissed by hand).
+pub fn get_auto_dismiss_merged_after_minutes(conn: &Connection) -> Option<i64> {
+    get_setting_value(conn, AUTO_DISMISS_MERGED_AFTER_MINUTES_SETTING_KEY)
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|minutes| *minutes > 0)
+}
 
-    rows.collect()
+/// Token required on HTTP API requests, if one is configured
+pub fn get_api_token(conn: &Connection) -> Option<String> {
+    get_setting_value(conn, API_TOKEN_SETTING_KEY)
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
 }
 
-/// Update a PR's state and category in the cache
-pub fn update_pr_state(conn: &Connection, pr_id: &str, state: &str, category: &str) -> SqliteResult<usize> {
-    conn.execute(
-        "UPDATE pr_cache SET state = ?1, column_assignment = ?2 WHERE id = ?3",
-        [state, category, pr_id],
-    )
+/// Maximum number of monitors running at once (None when unset or 0: no limit)
+pub fn get_max_concurrent_monitors(conn: &Connection) -> Option<i32> {
+    get_setting_value(conn, MAX_CONCURRENT_MONITORS_SETTING_KEY)
+        .and_then(|v| v.trim().parse::<i32>().ok())
+        .filter(|max| *max > 0)
 }
 
-/// Dismiss a PR (remove from cache)
-pub fn dismiss_pr(conn: &Connection, pr_id: &str) -> SqliteResult<usize> {
-    conn.execute("DELETE FROM pr_cache WHERE id = ?1", [pr_id])
+/// How far (in percent, up to 50) each monitor's interval may be randomly
+/// shortened or lengthened so monitors started together don't poll together
+/// (default 20, 0 disables)
+pub fn get_monitor_interval_jitter_percent(conn: &Connection) -> i64 {
+    get_setting_value(conn, MONITOR_INTERVAL_JITTER_PERCENT_SETTING_KEY)
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|percent| *percent >= 0)
+        .map_or(20, |percent| percent.min(50))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Days a done PR stays on the board before it is archived (None when unset or 0)
+pub fn get_done_archive_after_days(conn: &Connection) -> Option<i64> {
+    get_setting_value(conn, DONE_ARCHIVE_AFTER_DAYS_SETTING_KEY)
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|days| *days > 0)
+}
 
-    #[test]
-    fn test_schema_creation() {
-        let conn = Connection::open_in_memory().unwrap();
-        init_schema(&conn).unwrap();
+/// Days after which a done PR is deleted from the cache outright (None when unset or 0)
+pub fn get_done_delete_after_days(conn: &Connection) -> Option<i64> {
+    get_setting_value(conn, DONE_DELETE_AFTER_DAYS_SETTING_KEY)
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|days| *days > 0)
+}
 
-        // Verify tables exist
-        let count: i32 = conn
-            .query_row(
-                "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='monitors'",
-                [],
-                |row| row.get(0),
-            )
-            .unwrap();
-        assert_eq!(count, 1);
+/// What to do when a repo's PR becomes approved.
+/// Valid values: "notify" (default) | "stop_monitor" | "auto_merge"
+pub fn get_repo_on_approved(conn: &Connection, repo: &str) -> String {
+    let raw = get_repo_setting(conn, repo, ON_APPROVED_REPO_SETTING_KEY)
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+
+    match raw.as_str() {
+        "stop_monitor" | "auto_merge" => raw,
+        _ => "notify".to_string(),
     }
+}
 
-    #[test]
-    fn test_settings() {
-        let conn = Connection::open_in_memory().unwrap();
-        init_schema(&conn).unwrap();
+/// Resolve the default merge method for a repo.
+/// Valid values: "squash" | "merge" | "rebase"
+pub fn get_repo_merge_method(conn: &Connection, repo: &str) -> String {
+    let raw = get_repo_setting(conn, repo, MERGE_METHOD_REPO_SETTING_KEY)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "squash".to_string())
+        .trim()
+        .to_lowercase();
 
-        // Test default setting
-        let selected_repo = get_setting(&conn, "selected_repo").unwrap();
-        assert_eq!(selected_repo, Some("".to_string()));
+    match raw.as_str() {
+        "merge" | "rebase" => raw,
+        _ => "squash".to_string(),
+    }
+}
 
-        // Test setting update
-        set_setting(&conn, "selected_repo", "owner/repo").unwrap();
-        let selected_repo = get_setting(&conn, "selected_repo").unwrap();
-        assert_eq!(selected_repo, Some("owner/repo".to_string()));
+/// Local checkout configured for a repo (`local_path`), where its monitors
+/// run. Returned as configured; callers validate it before use.
+pub fn get_repo_clone_path(conn: &Connection, repo: &str) -> Option<PathBuf> {
+    get_repo_setting(conn, repo, LOCAL_PATH_REPO_SETTING_KEY)
+        .ok()
+        .flatten()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Local checkout configured for a repo, if it exists on disk
+pub fn get_repo_local_path(conn: &Connection, repo: &str) -> Option<PathBuf> {
+    get_repo_clone_path(conn, repo).filter(|path| path.is_dir())
+}
+
+/// Mark active monitors as stopped with the given exit reason.
+/// When `monitor_ids` is provided, only those monitors are updated.
+pub fn stop_active_monitors(
+    conn: &Connection,
+    exit_reason: &str,
+    monitor_ids: Option<&[String]>,
+) -> SqliteResult<usize> {
+    let now = chrono::Utc::now().to_rfc3339();
+    match monitor_ids {
+        Some(ids) => {
+            let mut updated = 0;
+            for id in ids {
+                updated += conn.execute(
+                    "UPDATE monitors SET status = 'stopped', ended_at = ?1, exit_reason = ?2 WHERE id = ?3 AND status IN ('running', 'sleeping')",
+                    rusqlite::params![now, exit_reason, id],
+                )?;
+            }
+            Ok(updated)
+        }
+        None => conn.execute(
+            "UPDATE monitors SET status = 'stopped', ended_at = ?1, exit_reason = ?2 WHERE status IN ('running', 'sleeping')",
+            rusqlite::params![now, exit_reason],
+        ),
+    }
+}
+
+/// Record the PR head a monitor's current iteration checks: logged against the
+/// iteration in monitor_logs and kept as the monitor's latest seen head
+pub fn record_monitor_head(conn: &Connection, monitor_id: &str, head_sha: &str) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO monitor_logs (monitor_id, iteration, action, message, head_sha)
+         SELECT id, iteration, 'check_started', 'Checking head ' || substr(?2, 1, 7), ?2
+         FROM monitors WHERE id = ?1",
+        [monitor_id, head_sha],
+    )?;
+    conn.execute(
+        "UPDATE monitors SET head_sha = ?1, pushing_sha = NULL WHERE id = ?2",
+        [head_sha, monitor_id],
+    )?;
+    Ok(())
+}
+
+/// Record a commit the monitor's script is about to push (`@@PUSHING`), so a
+/// fetch that sees it before `@@PUSHED` doesn't take it for an external push
+pub fn record_monitor_pushing(conn: &Connection, monitor_id: &str, head_sha: &str) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE monitors SET pushing_sha = ?1 WHERE id = ?2",
+        [head_sha, monitor_id],
+    )?;
+    Ok(())
+}
+
+/// Record a commit the monitor's script pushed (`@@PUSHED`) as the head it knows about
+pub fn record_monitor_push(conn: &Connection, monitor_id: &str, head_sha: &str) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO monitor_logs (monitor_id, iteration, action, message, head_sha)
+         SELECT id, iteration, 'pushed', 'Pushed ' || substr(?2, 1, 7), ?2
+         FROM monitors WHERE id = ?1",
+        [monitor_id, head_sha],
+    )?;
+    conn.execute(
+        "UPDATE monitors SET head_sha = ?1, pushing_sha = NULL WHERE id = ?2",
+        [head_sha, monitor_id],
+    )?;
+    Ok(())
+}
+
+/// Record a push to the monitored PR that the monitor didn't make
+pub fn record_external_push(
+    conn: &Connection,
+    monitor_id: &str,
+    previous_sha: &str,
+    head_sha: &str,
+) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO monitor_logs (monitor_id, iteration, action, message, head_sha)
+         SELECT id, iteration, 'external_push',
+                'External push: ' || substr(?2, 1, 7) || ' -> ' || substr(?3, 1, 7), ?3
+         FROM monitors WHERE id = ?1",
+        [monitor_id, previous_sha, head_sha],
+    )?;
+    conn.execute(
+        "UPDATE monitors SET head_sha = ?1 WHERE id = ?2",
+        [head_sha, monitor_id],
+    )?;
+    Ok(())
+}
+
+/// An action the app took on its own, from audit_log
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub id: i64,
+    pub pr_id: String,
+    pub action: String,
+    pub detail: Option<String>,
+    pub created_at: String,
+}
+
+/// Record an action the app took on its own
+pub fn record_audit(
+    conn: &Connection,
+    pr_id: &str,
+    action: &str,
+    detail: Option<&str>,
+) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO audit_log (pr_id, action, detail) VALUES (?1, ?2, ?3)",
+        rusqlite::params![pr_id, action, detail],
+    )?;
+    Ok(())
+}
+
+/// Audit entries, newest first, optionally for one PR
+pub fn get_audit_log(
+    conn: &Connection,
+    pr_id: Option<&str>,
+    limit: i64,
+) -> SqliteResult<Vec<AuditEntry>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, pr_id, action, detail, created_at FROM audit_log
+         WHERE ?1 IS NULL OR pr_id = ?1
+         ORDER BY id DESC LIMIT ?2",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![pr_id, limit], |row| {
+        Ok(AuditEntry {
+            id: row.get(0)?,
+            pr_id: row.get(1)?,
+            action: row.get(2)?,
+            detail: row.get(3)?,
+            created_at: row.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Record a notification that was shown
+pub fn record_notification(
+    conn: &Connection,
+    notification_type: &str,
+    pr_id: &str,
+    pr_number: i32,
+    title: &str,
+) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO notification_log (notification_type, pr_id, pr_number, title)
+         VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![notification_type, pr_id, pr_number, title],
+    )?;
+    // Only recent history is kept
+    conn.execute(
+        "DELETE FROM notification_log WHERE id <= (SELECT MAX(id) - 500 FROM notification_log)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Rate-limit budget after a fetch cycle, and how many gh calls the cycle made
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitSample {
+    pub remaining: i64,
+    pub limit: i64,
+    pub reset_at: String,
+    pub gh_calls: i64,
+    pub sampled_at: String,
+}
+
+/// Record a rate-limit sample, keeping the most recent 500
+pub fn record_rate_limit_sample(
+    conn: &Connection,
+    remaining: i64,
+    limit: i64,
+    reset_at: &str,
+    gh_calls: i64,
+) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO rate_limit_samples (remaining, rate_limit, reset_at, gh_calls)
+         VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![remaining, limit, reset_at, gh_calls],
+    )?;
+    conn.execute(
+        "DELETE FROM rate_limit_samples WHERE id <= (SELECT MAX(id) - 500 FROM rate_limit_samples)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Most recent rate-limit samples, newest first
+pub fn get_rate_limit_samples(conn: &Connection, limit: i64) -> SqliteResult<Vec<RateLimitSample>> {
+    let mut stmt = conn.prepare(
+        "SELECT remaining, rate_limit, reset_at, gh_calls, sampled_at
+         FROM rate_limit_samples ORDER BY id DESC LIMIT ?1",
+    )?;
+    let rows = stmt.query_map([limit], |row| {
+        Ok(RateLimitSample {
+            remaining: row.get(0)?,
+            limit: row.get(1)?,
+            reset_at: row.get(2)?,
+            gh_calls: row.get(3)?,
+            sampled_at: row.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Notification level of the PR's running or sleeping monitor, if it has one
+pub fn get_monitor_notification_level(conn: &Connection, pr_id: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT notification_level FROM monitors
+         WHERE pr_id = ?1 AND status IN ('running', 'sleeping')
+         ORDER BY started_at DESC, start_seq DESC LIMIT 1",
+        [pr_id],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+/// Notification level of one monitor, whatever its status
+pub fn get_notification_level_for_monitor(conn: &Connection, monitor_id: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT notification_level FROM monitors WHERE id = ?1",
+        [monitor_id],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+/// Count monitors that are currently running or sleeping
+pub fn count_active_monitors(conn: &Connection) -> SqliteResult<i32> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM monitors WHERE status IN ('running', 'sleeping')",
+        [],
+        |row| row.get(0),
+    )
+}
+
+/// Mark active monitors whose process no longer exists (or whose pid now
+/// belongs to another process) as failed. Monitors left running by a detached
+/// quit keep their status.
+pub fn reconcile_dead_monitors(conn: &Connection) -> SqliteResult<usize> {
+    let active: Vec<(String, Option<i32>, i32, String)> = {
+        let mut stmt = conn.prepare(
+            "SELECT id, pid, pr_number, repo FROM monitors WHERE status IN ('running', 'sleeping')",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?;
+        rows.collect::<SqliteResult<_>>()?
+    };
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut failed = 0;
+    for (id, pid, pr_number, repo) in active {
+        if pid.is_some_and(|pid| crate::process::is_monitor_process(pid, pr_number, &repo)) {
+            continue;
+        }
+        failed += conn.execute(
+            "UPDATE monitors SET status = 'failed', exit_reason = 'app_restart', ended_at = ?1 WHERE id = ?2",
+            rusqlite::params![now, id],
+        )?;
+    }
+    Ok(failed)
+}
+
+/// Get the last fetch time for a repo
+pub fn get_last_fetch(conn: &Connection, repo: &str) -> SqliteResult<Option<String>> {
+    // A repo whose fetches have only failed has an empty last_fetch_at
+    let mut stmt =
+        conn.prepare("SELECT NULLIF(last_fetch_at, '') FROM fetch_metadata WHERE repo = ?1")?;
+    let mut rows = stmt.query([repo])?;
+
+    if let Some(row) = rows.next()? {
+        Ok(row.get(0)?)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Record a failed fetch attempt for a repo. Cleared by the next `set_last_fetch`.
+pub fn record_fetch_error(
+    conn: &Connection,
+    repo: &str,
+    error: &str,
+    timestamp: &str,
+) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO fetch_metadata (repo, last_fetch_at, last_error, last_error_at)
+         VALUES (?1, '', ?2, ?3)
+         ON CONFLICT(repo) DO UPDATE SET
+             last_error = excluded.last_error,
+             last_error_at = excluded.last_error_at",
+        rusqlite::params![repo, error, timestamp],
+    )?;
+    Ok(())
+}
+
+/// Update the last fetch time for a repo (also clears any recorded fetch error)
+pub fn set_last_fetch(
+    conn: &Connection,
+    repo: &str,
+    timestamp: &str,
+    count: i32,
+    duration_ms: i64,
+) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO fetch_metadata (repo, last_fetch_at, last_fetch_count, last_fetch_ms) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![repo, timestamp, count, duration_ms],
+    )?;
+    Ok(())
+}
+
+/// Per-repo fetch bookkeeping
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchMetadata {
+    pub repo: String,
+    pub last_fetch_at: String,
+    pub last_fetch_count: i32,
+    pub last_fetch_ms: Option<i64>,
+}
+
+/// Get fetch metadata for all repos (or a single repo)
+pub fn get_fetch_metadata(conn: &Connection, repo: Option<&str>) -> SqliteResult<Vec<FetchMetadata>> {
+    let mut stmt = conn.prepare(
+        "SELECT repo, last_fetch_at, COALESCE(last_fetch_count, 0), last_fetch_ms
+         FROM fetch_metadata
+         WHERE (?1 IS NULL OR repo = ?1) AND last_fetch_at != ''
+         ORDER BY repo",
+    )?;
+
+    let rows = stmt.query_map([repo], |row| {
+        Ok(FetchMetadata {
+            repo: row.get(0)?,
+            last_fetch_at: row.get(1)?,
+            last_fetch_count: row.get(2)?,
+            last_fetch_ms: row.get(3)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// How fresh a repo's cached PRs are
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchStatus {
+    pub repo: String,
+    /// Last successful fetch (None if no fetch has succeeded yet)
+    pub last_fetch_at: Option<String>,
+    pub last_fetch_failed: bool,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<String>,
+    /// No successful fetch within `threshold_minutes`
+    pub stale: bool,
+}
+
+/// Freshness of every repo with fetch metadata, as of `now`
+pub fn get_fetch_status(
+    conn: &Connection,
+    now: chrono::DateTime<chrono::Utc>,
+    threshold_minutes: i64,
+) -> SqliteResult<Vec<FetchStatus>> {
+    let mut stmt = conn.prepare(
+        "SELECT repo, NULLIF(last_fetch_at, ''), last_error, last_error_at
+         FROM fetch_metadata
+         ORDER BY repo",
+    )?;
+
+    let rows = stmt.query_map([], |row| {
+        let last_fetch_at: Option<String> = row.get(1)?;
+        let last_error: Option<String> = row.get(2)?;
+        let stale = last_fetch_at
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .is_none_or(|t| {
+                now - t.with_timezone(&chrono::Utc) > chrono::Duration::minutes(threshold_minutes)
+            });
+        Ok(FetchStatus {
+            repo: row.get(0)?,
+            last_fetch_at,
+            last_fetch_failed: last_error.is_some(),
+            last_error,
+            last_error_at: row.get(3)?,
+            stale,
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Clear all PR cache (for debugging or forced refresh)
+pub fn clear_pr_cache(conn: &Connection, repo: Option<&str>) -> SqliteResult<()> {
+    if let Some(repo) = repo {
+        conn.execute("DELETE FROM pr_cache WHERE repo = ?1", [repo])?;
+        conn.execute("DELETE FROM fetch_metadata WHERE repo = ?1", [repo])?;
+    } else {
+        conn.execute("DELETE FROM pr_cache", [])?;
+        conn.execute("DELETE FROM fetch_metadata", [])?;
+    }
+    Ok(())
+}
+
+/// Cached data removed along with a repo
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovedRepoData {
+    pub prs: usize,
+    pub comments: usize,
+    pub queued_monitors: usize,
+    pub repo_settings: usize,
+}
+
+/// Delete everything cached for a repo: PRs (and their comments), fetch metadata,
+/// queued monitor starts and per-repo settings. Monitor history is kept.
+pub fn delete_repo_data(conn: &Connection, repo: &str) -> SqliteResult<RemovedRepoData> {
+    let comments = conn.execute(
+        "DELETE FROM pr_comments WHERE pr_id IN (SELECT id FROM pr_cache WHERE repo = ?1)",
+        [repo],
+    )?;
+    let prs = conn.execute("DELETE FROM pr_cache WHERE repo = ?1", [repo])?;
+    conn.execute("DELETE FROM fetch_metadata WHERE repo = ?1", [repo])?;
+    let queued_monitors = conn.execute("DELETE FROM monitor_queue WHERE repo = ?1", [repo])?;
+    let repo_settings = conn.execute("DELETE FROM repo_settings WHERE repo = ?1", [repo])?;
+    Ok(RemovedRepoData {
+        prs,
+        comments,
+        queued_monitors,
+        repo_settings,
+    })
+}
+
+/// Delete stale PRs that are no longer open
+pub fn delete_stale_prs(conn: &Connection, repo: &str, active_pr_ids: &[String]) -> SqliteResult<usize> {
+    if active_pr_ids.is_empty() {
+        return Ok(0);
+    }
+
+    // Build placeholders for IN clause
+    let placeholders: Vec<String> = (0..active_pr_ids.len())
+        .map(|i| format!("?{}", i + 2))
+        .collect();
+    let placeholders_str = placeholders.join(",");
+
+    let sql = format!(
+        "DELETE FROM pr_cache WHERE repo = ?1 AND id NOT IN ({})",
+        placeholders_str
+    );
+
+    let mut params: Vec<&dyn rusqlite::ToSql> = vec![&repo];
+    for id in active_pr_ids {
+        params.push(id);
+    }
+
+    let deleted = conn.execute(&sql, rusqlite::params_from_iter(params))?;
+    Ok(deleted)
+}
+
+/// Cached (updated_at, comments_count, unresolved_threads) per PR id for a repo
+pub fn get_cached_thread_counts(
+    conn: &Connection,
+    repo: &str,
+) -> SqliteResult<HashMap<String, (String, i32, i32)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, updated_at, comments_count, unresolved_threads FROM pr_cache WHERE repo = ?1",
+    )?;
+    let rows = stmt.query_map([repo], |row| {
+        Ok((row.get::<_, String>(0)?, (row.get(1)?, row.get(2)?, row.get(3)?)))
+    })?;
+    rows.collect()
+}
+
+/// Get stale PR IDs (PRs in cache that are not in the active list)
+pub fn get_stale_pr_ids(conn: &Connection, repo: &str, active_pr_ids: &[String]) -> SqliteResult<Vec<(String, i32)>> {
+    if active_pr_ids.is_empty() {
+        // If no active PRs, all cached PRs for this repo are stale
+        let mut stmt = conn.prepare("SELECT id, number FROM pr_cache WHERE repo = ?1 AND state = 'open'")?;
+        let rows = stmt.query_map([repo], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?))
+        })?;
+        return rows.collect();
+    }
+
+    // Build placeholders for NOT IN clause
+    let placeholders: Vec<String> = (0..active_pr_ids.len())
+        .map(|i| format!("?{}", i + 2))
+        .collect();
+    let placeholders_str = placeholders.join(",");
+
+    let sql = format!(
+        "SELECT id, number FROM pr_cache WHERE repo = ?1 AND state = 'open' AND id NOT IN ({})",
+        placeholders_str
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+
+    let mut param_values: Vec<String> = vec![repo.to_string()];
+    param_values.extend(active_pr_ids.iter().cloned());
+
+    let rows = stmt.query_map(rusqlite::params_from_iter(param_values), |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?))
+    })?;
+
+    rows.collect()
+}
+
+/// Put a PR's card in the monitoring column (once a monitor runs for it)
+pub fn move_pr_to_monitoring(conn: &Connection, pr_id: &str) -> SqliteResult<usize> {
+    conn.execute(
+        "UPDATE pr_cache SET column_assignment = 'monitoring' WHERE id = ?1",
+        [pr_id],
+    )
+}
+
+/// Update a PR's state and category in the cache
+pub fn update_pr_state(conn: &Connection, pr_id: &str, state: &str, category: &str) -> SqliteResult<usize> {
+    let updated = conn.execute(
+        "UPDATE pr_cache SET state = ?1, column_assignment = ?2 WHERE id = ?3",
+        [state, category, pr_id],
+    )?;
+
+    // PRs stacked on a merged/closed parent have to be rebased onto the new base
+    if state != "open" {
+        conn.execute(
+            "UPDATE pr_cache SET needs_rebase = 1 WHERE parent_pr_id = ?1 AND state = 'open'",
+            [pr_id],
+        )?;
+    }
+
+    Ok(updated)
+}
+
+/// pr_cache's ci_status as last fetched from GitHub, ignoring a manual override.
+/// Merge decisions read this so an override can never stand in for real CI.
+pub const FETCHED_CI_STATUS_SQL: &str =
+    "CASE WHEN ci_override_until IS NOT NULL THEN ci_status_fetched ELSE ci_status END";
+
+/// Pin a cached PR's ci_status until `until` (RFC 3339). Fetches leave
+/// ci_status alone while the override lasts and keep what GitHub reported in
+/// ci_status_fetched, which `clear_ci_override` restores.
+pub fn set_ci_override(conn: &Connection, pr_id: &str, status: &str, until: &str) -> SqliteResult<usize> {
+    conn.execute(
+        "UPDATE pr_cache SET
+             ci_status_fetched = CASE WHEN ci_override_until IS NULL THEN ci_status
+                                      ELSE ci_status_fetched END,
+             ci_status = ?1,
+             ci_override_until = ?2
+         WHERE id = ?3",
+        [status, until, pr_id],
+    )
+}
+
+/// Record a CI status seen outside a fetch (e.g. right after a rerun). Like a
+/// fetch, it only lands in ci_status_fetched while an override lasts.
+pub fn set_fetched_ci_status(conn: &Connection, pr_id: &str, status: &str) -> SqliteResult<usize> {
+    conn.execute(
+        "UPDATE pr_cache SET
+             ci_status = CASE WHEN datetime(ci_override_until) > datetime('now')
+                              THEN ci_status ELSE ?1 END,
+             ci_status_fetched = CASE WHEN datetime(ci_override_until) > datetime('now')
+                                      THEN ?1 END,
+             ci_override_until = CASE WHEN datetime(ci_override_until) > datetime('now')
+                                      THEN ci_override_until END
+         WHERE id = ?2",
+        [status, pr_id],
+    )
+}
+
+/// Drop a PR's ci_status override, restoring the last status fetched from GitHub
+pub fn clear_ci_override(conn: &Connection, pr_id: &str) -> SqliteResult<usize> {
+    conn.execute(
+        "UPDATE pr_cache SET ci_status = ci_status_fetched, ci_status_fetched = NULL,
+                             ci_override_until = NULL
+         WHERE id = ?1 AND ci_override_until IS NOT NULL",
+        [pr_id],
+    )
+}
+
+/// Link stacked PRs in a repo: a PR whose base branch is another cached PR's
+/// head branch gets that PR as its parent
+pub fn link_stacked_prs(conn: &Connection, repo: &str) -> SqliteResult<usize> {
+    conn.execute(
+        "UPDATE pr_cache SET parent_pr_id = (
+             SELECT parent.id FROM pr_cache parent
+             WHERE parent.repo = pr_cache.repo
+               AND parent.branch = pr_cache.base_branch
+               AND parent.id != pr_cache.id
+             ORDER BY parent.state = 'open' DESC, parent.updated_at DESC
+             LIMIT 1
+         )
+         WHERE repo = ?1",
+        [repo],
+    )
+}
+
+/// Count open PRs needing attention: changes requested, failing CI, unresolved
+/// threads, or (with review_waiting_threshold_hours set) my review waiting too long
+pub fn get_attention_count(conn: &Connection, repo: Option<&str>) -> SqliteResult<i32> {
+    let threshold_hours = get_review_waiting_threshold_hours(conn);
+    let exclude_acknowledged = get_attention_excludes_acknowledged(conn);
+    conn.query_row(
+        "SELECT COUNT(*) FROM pr_cache
+         WHERE state = 'open' AND muted = 0
+           AND (review_status = 'changes_requested' OR ci_status = 'failing'
+                OR unresolved_threads > CASE WHEN ?3 THEN
+                    (SELECT COUNT(*) FROM pr_comments c
+                     WHERE c.pr_id = pr_cache.id AND c.is_resolved = 0 AND c.acknowledged = 1)
+                    ELSE 0 END
+                OR (?2 IS NOT NULL AND review_requested_at IS NOT NULL
+                    AND (julianday('now') - julianday(review_requested_at)) * 24 > ?2))
+           AND (?1 IS NULL OR repo = ?1)",
+        rusqlite::params![repo, threshold_hours, exclude_acknowledged],
+        |row| row.get(0),
+    )
+}
+
+/// Track when my review was requested on a PR.
+/// The timestamp is set the first time the request is seen and cleared once it
+/// goes away (e.g. after I review), so a later re-request starts a fresh wait.
+pub fn record_review_request(conn: &Connection, pr_id: &str, requested: bool) -> SqliteResult<usize> {
+    if requested {
+        conn.execute(
+            "UPDATE pr_cache SET review_requested_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE id = ?1 AND review_requested_at IS NULL",
+            [pr_id],
+        )
+    } else {
+        conn.execute(
+            "UPDATE pr_cache SET review_requested_at = NULL WHERE id = ?1",
+            [pr_id],
+        )
+    }
+}
+
+/// Teams I belong to, as lowercase `org/slug`, from a JSON array setting.
+/// Review requests to these teams count as requests to me.
+pub fn get_my_teams(conn: &Connection) -> Vec<String> {
+    get_setting_value(conn, MY_TEAMS_SETTING_KEY)
+        .and_then(|v| serde_json::from_str::<Vec<String>>(&v).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|team| team.trim().trim_start_matches('@').to_lowercase())
+        .filter(|team| !team.is_empty())
+        .collect()
+}
+
+/// Muted author patterns: a JSON array of exact logins or globs like `*[bot]`
+pub fn get_muted_authors(conn: &Connection) -> Vec<String> {
+    get_setting_value(conn, MUTED_AUTHORS_SETTING_KEY)
+        .and_then(|v| serde_json::from_str::<Vec<String>>(&v).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Whether `login` matches any muted pattern (case-insensitive; `*` and `?` wildcards)
+pub fn is_author_muted(patterns: &[String], login: &str) -> bool {
+    let login: Vec<char> = login.to_lowercase().chars().collect();
+    patterns.iter().any(|pattern| {
+        let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+        glob_match(&pattern, &login)
+    })
+}
+
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text index it is currently matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, t));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Re-evaluate the `muted` flag on every cached PR against the current
+/// `muted_authors` setting, so muting and unmuting apply retroactively.
+pub fn refresh_muted_prs(conn: &Connection) -> SqliteResult<usize> {
+    let patterns = get_muted_authors(conn);
+    let rows: Vec<(String, String, bool)> = {
+        let mut stmt = conn.prepare("SELECT id, author, muted FROM pr_cache")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get::<_, i32>(2)? != 0))
+        })?;
+        rows.collect::<SqliteResult<_>>()?
+    };
+
+    let mut changed = 0;
+    for (id, author, muted) in rows {
+        let should_mute = is_author_muted(&patterns, &author);
+        if should_mute != muted {
+            conn.execute(
+                "UPDATE pr_cache SET muted = ?1 WHERE id = ?2",
+                rusqlite::params![should_mute as i32, id],
+            )?;
+            changed += 1;
+        }
+    }
+    Ok(changed)
+}
+
+/// Whether a cached PR is from a muted author
+pub fn is_pr_muted(conn: &Connection, pr_id: &str) -> bool {
+    conn.query_row("SELECT muted FROM pr_cache WHERE id = ?1", [pr_id], |row| {
+        row.get::<_, i32>(0)
+    })
+    .map(|muted| muted != 0)
+    .unwrap_or(false)
+}
+
+/// Unread PR counts (activity since the PR was last viewed)
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnreadCounts {
+    pub total: i32,
+    pub by_repo: HashMap<String, i32>,
+    pub by_category: HashMap<String, i32>,
+}
+
+/// Mark a PR as seen now
+pub fn mark_pr_seen(conn: &Connection, pr_id: &str) -> SqliteResult<usize> {
+    conn.execute(
+        "UPDATE pr_cache SET last_seen_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = ?1",
+        [pr_id],
+    )
+}
+
+/// Count PRs of the configured repos updated since they were last seen, per
+/// repo and per category. Dismissed PRs are removed from the cache, so they
+/// never count; rows left behind by a removed repo don't either.
+pub fn get_unread_counts(conn: &Connection) -> SqliteResult<UnreadCounts> {
+    let repos = serde_json::to_string(&get_configured_repos(conn)?).unwrap_or_default();
+    let mut stmt = conn.prepare(
+        "SELECT repo, column_assignment, COUNT(*) FROM pr_cache
+         WHERE muted = 0 AND archived = 0 AND (last_seen_at IS NULL OR updated_at > last_seen_at)
+           AND repo IN (SELECT value FROM json_each(?1))
+         GROUP BY repo, column_assignment",
+    )?;
+    let rows = stmt.query_map([repos], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i32>(2)?))
+    })?;
+
+    let mut counts = UnreadCounts::default();
+    for row in rows {
+        let (repo, category, count) = row?;
+        counts.total += count;
+        *counts.by_repo.entry(repo).or_insert(0) += count;
+        *counts.by_category.entry(category).or_insert(0) += count;
+    }
+    Ok(counts)
+}
+
+/// Record when a PR was merged
+pub fn set_pr_merged_at(conn: &Connection, pr_id: &str, merged_at: &str) -> SqliteResult<usize> {
+    conn.execute(
+        "UPDATE pr_cache SET merged_at = ?1 WHERE id = ?2",
+        [merged_at, pr_id],
+    )
+}
+
+/// Dismiss merged PRs in a repo that were merged more than `minutes` ago
+pub fn dismiss_expired_merged_prs(conn: &Connection, repo: &str, minutes: i64) -> SqliteResult<usize> {
+    conn.execute(
+        "DELETE FROM pr_cache
+         WHERE repo = ?1 AND state = 'merged' AND merged_at IS NOT NULL
+           AND julianday(merged_at) <= julianday('now', ?2)",
+        rusqlite::params![repo, format!("-{} minutes", minutes)],
+    )
+}
+
+/// Retention pass for done PRs (merged/closed): archive those older than
+/// `archive_after_days` and delete those older than `delete_after_days`.
+/// Age is measured from the merge time, or the last update for closed PRs.
+/// A PR is only archived once (until it is reopened), so unarchiving it keeps
+/// it on the board.
+/// Returns (archived, deleted).
+pub fn apply_done_retention(
+    conn: &Connection,
+    archive_after_days: Option<i64>,
+    delete_after_days: Option<i64>,
+) -> SqliteResult<(usize, usize)> {
+    let mut archived = 0;
+    if let Some(days) = archive_after_days {
+        archived = conn.execute(
+            "UPDATE pr_cache
+             SET archived = 1, archived_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE state != 'open' AND archived_at IS NULL
+               AND julianday(COALESCE(merged_at, updated_at)) <= julianday('now', ?1)",
+            [format!("-{} days", days)],
+        )?;
+    }
+
+    let mut deleted = 0;
+    if let Some(days) = delete_after_days {
+        deleted = conn.execute(
+            "DELETE FROM pr_cache
+             WHERE state != 'open'
+               AND julianday(COALESCE(merged_at, updated_at)) <= julianday('now', ?1)",
+            [format!("-{} days", days)],
+        )?;
+    }
+
+    Ok((archived, deleted))
+}
+
+/// Put an archived PR back on the board
+pub fn unarchive_pr(conn: &Connection, pr_id: &str) -> SqliteResult<usize> {
+    conn.execute("UPDATE pr_cache SET archived = 0 WHERE id = ?1", [pr_id])
+}
+
+/// Dismiss all PRs in the done column, optionally limited to one repo.
+/// Open PRs are never removed, and monitor history is kept.
+pub fn clear_done_prs(conn: &Connection, repo: Option<&str>) -> SqliteResult<usize> {
+    conn.execute(
+        "DELETE FROM pr_cache
+         WHERE state != 'open'
+           AND (state IN ('merged', 'closed') OR column_assignment = 'done')
+           AND (?1 IS NULL OR repo = ?1)",
+        [repo],
+    )
+}
+
+/// Dismiss a PR (remove from cache)
+pub fn dismiss_pr(conn: &Connection, pr_id: &str) -> SqliteResult<usize> {
+    conn.execute("DELETE FROM pr_cache WHERE id = ?1", [pr_id])
+}
+
+/// Record a PR's head SHA as seen by a fetch.
+/// Returns the previously cached SHA when it differs from `head_sha`, which is
+/// also kept in `previous_head_sha`. Call before the PR row is upserted.
+pub fn record_head_sha(conn: &Connection, pr_id: &str, head_sha: &str) -> SqliteResult<Option<String>> {
+    let current: Option<String> = conn
+        .query_row("SELECT head_sha FROM pr_cache WHERE id = ?1", [pr_id], |row| row.get(0))
+        .optional()?
+        .flatten();
+
+    match current {
+        Some(previous) if previous != head_sha => {
+            // A new head also means any pending stack rebase has been pushed
+            conn.execute(
+                "UPDATE pr_cache SET previous_head_sha = ?1, needs_rebase = 0 WHERE id = ?2",
+                [&previous, pr_id],
+            )?;
+            Ok(Some(previous))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Flag a PR's cached review comments as possibly outdated (e.g. after a force-push)
+pub fn mark_comments_stale(conn: &Connection, pr_id: &str) -> SqliteResult<usize> {
+    conn.execute("UPDATE pr_comments SET is_stale = 1 WHERE pr_id = ?1", [pr_id])
+}
+
+/// Set or clear the local acknowledged flag on one review comment
+pub fn set_comment_acknowledged(
+    conn: &Connection,
+    comment_id: &str,
+    acknowledged: bool,
+) -> SqliteResult<usize> {
+    conn.execute(
+        "UPDATE pr_comments SET acknowledged = ?2 WHERE id = ?1",
+        rusqlite::params![comment_id, acknowledged],
+    )
+}
+
+/// Acknowledge every unresolved review comment on a PR
+pub fn acknowledge_all_comments(conn: &Connection, pr_id: &str) -> SqliteResult<usize> {
+    conn.execute(
+        "UPDATE pr_comments SET acknowledged = 1 WHERE pr_id = ?1 AND is_resolved = 0",
+        [pr_id],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_app_data_dir() {
+        let local = Some(PathBuf::from("/home/me/.local/share"));
+        let configured = Some(PathBuf::from("/Volumes/ext/clanker"));
+        assert_eq!(
+            resolve_app_data_dir(None, None, local.clone()),
+            Some(PathBuf::from("/home/me/.local/share/com.clanker-spanker.app"))
+        );
+        assert_eq!(
+            resolve_app_data_dir(Some("/tmp/clanker".into()), configured.clone(), local.clone()),
+            Some(PathBuf::from("/tmp/clanker"))
+        );
+        assert_eq!(resolve_app_data_dir(None, configured.clone(), local.clone()), configured);
+        // Empty overrides are ignored
+        assert_eq!(
+            resolve_app_data_dir(Some("".into()), Some(PathBuf::new()), local),
+            Some(PathBuf::from("/home/me/.local/share/com.clanker-spanker.app"))
+        );
+        assert_eq!(
+            resolve_app_data_dir(Some("/tmp/clanker".into()), None, None),
+            Some(PathBuf::from("/tmp/clanker"))
+        );
+        assert_eq!(resolve_app_data_dir(None, None, None), None);
+    }
+
+    #[test]
+    fn test_bootstrap_config_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("clanker-bootstrap-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("nested").join("bootstrap.json");
+        assert!(read_bootstrap_config(&path).data_dir.is_none());
+
+        let config = BootstrapConfig {
+            data_dir: Some(dir.join("data")),
+        };
+        write_bootstrap_config(&path, &config).unwrap();
+        assert_eq!(read_bootstrap_config(&path).data_dir, Some(dir.join("data")));
+
+        ensure_dir_writable(&dir.join("data")).unwrap();
+        assert!(dir.join("data").is_dir());
+        assert!(ensure_dir_writable(&path.join("under-a-file")).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_conn_recovers_from_poisoned_lock() {
+        let state = AppState::for_tests(Arc::new(GhCli::default()));
+        set_setting(&state.conn(), "k", "v").unwrap();
+
+        let poisoned = std::thread::scope(|s| {
+            s.spawn(|| {
+                let _conn = state.db.lock().unwrap();
+                panic!("panicked while holding the database lock");
+            })
+            .join()
+            .is_err()
+        });
+        assert!(poisoned);
+        assert!(state.db.is_poisoned());
+
+        assert_eq!(get_setting_value(&state.conn(), "k").as_deref(), Some("v"));
+        assert!(!state.db.is_poisoned());
+    }
+
+    #[test]
+    fn test_schema_creation() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        // Verify tables exist
+        let count: i32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='monitors'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_fetch_status() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        let now = chrono::DateTime::parse_from_rfc3339("2024-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        set_last_fetch(&conn, "o/fresh", "2024-01-01T11:30:00Z", 3, 100).unwrap();
+        set_last_fetch(&conn, "o/old", "2024-01-01T09:00:00Z", 3, 100).unwrap();
+        record_fetch_error(&conn, "o/old", "HTTP 502", "2024-01-01T11:55:00Z").unwrap();
+        record_fetch_error(&conn, "o/new", "not found", "2024-01-01T11:55:00Z").unwrap();
+
+        let status = get_fetch_status(&conn, now, 60).unwrap();
+        let summary: Vec<(&str, bool, bool)> = status
+            .iter()
+            .map(|s| (s.repo.as_str(), s.last_fetch_failed, s.stale))
+            .collect();
+        assert_eq!(summary, [("o/fresh", false, false), ("o/new", true, true), ("o/old", true, true)]);
+        assert_eq!(status[2].last_fetch_at.as_deref(), Some("2024-01-01T09:00:00Z"));
+        assert_eq!(status[2].last_error.as_deref(), Some("HTTP 502"));
+
+        // A repo that never fetched successfully has no incremental cursor
+        assert_eq!(get_last_fetch(&conn, "o/new").unwrap(), None);
+        assert_eq!(get_fetch_metadata(&conn, None).unwrap().len(), 2);
+
+        // The next successful fetch clears the error
+        set_last_fetch(&conn, "o/old", "2024-01-01T12:00:00Z", 3, 100).unwrap();
+        let old = get_fetch_status(&conn, now, 60).unwrap().remove(2);
+        assert!(!old.last_fetch_failed && !old.stale);
+    }
+
+    #[test]
+    fn test_settings() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        // Test default setting
+        let selected_repo = get_setting(&conn, "selected_repo").unwrap();
+        assert_eq!(selected_repo, Some("".to_string()));
+
+        // Test setting update
+        set_setting(&conn, "selected_repo", "owner/repo").unwrap();
+        let selected_repo = get_setting(&conn, "selected_repo").unwrap();
+        assert_eq!(selected_repo, Some("owner/repo".to_string()));
+
+        assert_eq!(get_close_behavior(&conn), "hide");
+        set_setting(&conn, CLOSE_BEHAVIOR_SETTING_KEY, " Quit ").unwrap();
+        assert_eq!(get_close_behavior(&conn), "quit");
+        set_setting(&conn, CLOSE_BEHAVIOR_SETTING_KEY, "minimize").unwrap();
+        assert_eq!(get_close_behavior(&conn), "hide");
+    }
+
+    #[test]
+    fn test_working_dir_folds_into_local_path() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repo_settings (repo, key, value) VALUES
+                 ('o/a', 'working_dir', '/clones/a'),
+                 ('o/b', 'working_dir', '/clones/b'), ('o/b', 'local_path', '/old/b'),
+                 ('o/c', 'local_path', '/clones/c'), ('o/c', 'working_dir', ' ')",
+        )
+        .unwrap();
+        init_schema(&conn).unwrap();
+
+        let clone = |repo: &str| get_repo_clone_path(&conn, repo).map(|p| p.display().to_string());
+        assert_eq!(clone("o/a").as_deref(), Some("/clones/a"));
+        // working_dir took precedence, so it wins
+        assert_eq!(clone("o/b").as_deref(), Some("/clones/b"));
+        assert_eq!(clone("o/c").as_deref(), Some("/clones/c"));
+        let left: i32 = conn
+            .query_row("SELECT COUNT(*) FROM repo_settings WHERE key = 'working_dir'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(left, 0);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_reconcile_dead_monitors() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        let insert = "INSERT INTO monitors (id, pr_id, pr_number, repo, pid, status, started_at, log_file)
+                      VALUES (?1, 'owner/repo#1', 1, 'owner/repo', ?2, 'running', datetime('now'), '/tmp/log')";
+        // Beyond any kernel pid_max, so never a live process
+        conn.execute(insert, rusqlite::params!["mon-dead", i32::MAX]).unwrap();
+        // Alive, but the pid was reused by something that isn't a monitor
+        conn.execute(insert, rusqlite::params!["mon-reused", std::process::id() as i32])
+            .unwrap();
+        // Looks like a detached monitor script: own process group, PR on the command line
+        let mut monitor = {
+            use std::os::unix::process::CommandExt;
+            std::process::Command::new("sh")
+                .args(["-c", "sleep 10; :", "monitor-pr-loop.sh", "1", "owner/repo", "10", "5"])
+                .process_group(0)
+                .spawn()
+                .unwrap()
+        };
+        conn.execute(insert, rusqlite::params!["mon-alive", monitor.id() as i32]).unwrap();
+
+        let failed = reconcile_dead_monitors(&conn).unwrap();
+        let _ = monitor.kill();
+        let _ = monitor.wait();
+        assert_eq!(failed, 2);
+        assert_eq!(count_active_monitors(&conn).unwrap(), 1);
+        let reused: String = conn
+            .query_row("SELECT status FROM monitors WHERE id = 'mon-reused'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(reused, "failed");
+
+        let (status, reason): (String, String) = conn
+            .query_row(
+                "SELECT status, exit_reason FROM monitors WHERE id = 'mon-dead'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(status, "failed");
+        assert_eq!(reason, "app_restart");
+    }
+
+    #[test]
+    fn test_notification_level_migration() {
+        // A database from before 'default' existed, when 'all' meant "use the globals"
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE monitors (
+                 id TEXT PRIMARY KEY, pr_id TEXT NOT NULL, pr_number INTEGER NOT NULL,
+                 repo TEXT NOT NULL, pid INTEGER, status TEXT NOT NULL DEFAULT 'running',
+                 started_at TEXT NOT NULL, log_file TEXT NOT NULL,
+                 created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                 notification_level TEXT NOT NULL DEFAULT 'all'
+             );
+             INSERT INTO monitors (id, pr_id, pr_number, repo, started_at, log_file, notification_level)
+             VALUES ('m1', 'o/r#1', 1, 'o/r', '2026-01-01T00:00:00Z', '', 'all'),
+                    ('m2', 'o/r#2', 2, 'o/r', '2026-01-01T00:00:00Z', '', 'silent');",
+        )
+        .unwrap();
+        init_schema(&conn).unwrap();
+
+        assert_eq!(get_monitor_notification_level(&conn, "o/r#1").as_deref(), Some("default"));
+        assert_eq!(get_monitor_notification_level(&conn, "o/r#2").as_deref(), Some("silent"));
+        let insert = "INSERT INTO monitors (id, pr_id, pr_number, repo, started_at, log_file)
+                      VALUES ('m3', 'o/r#3', 3, 'o/r', '2026-01-01T00:00:00Z', '')";
+        conn.execute(insert, []).unwrap();
+        assert_eq!(get_monitor_notification_level(&conn, "o/r#3").as_deref(), Some("default"));
+
+        // Explicit 'all' chosen after the migration is left alone on the next start
+        conn.execute("UPDATE monitors SET notification_level = 'all' WHERE id = 'm1'", []).unwrap();
+        init_schema(&conn).unwrap();
+        assert_eq!(get_monitor_notification_level(&conn, "o/r#1").as_deref(), Some("all"));
+
+        // Only running or sleeping monitors have a say
+        conn.execute("UPDATE monitors SET status = 'completed' WHERE id = 'm1'", []).unwrap();
+        assert_eq!(get_monitor_notification_level(&conn, "o/r#1"), None);
+        assert_eq!(get_notification_level_for_monitor(&conn, "m1").as_deref(), Some("all"));
+    }
+
+    #[test]
+    fn test_record_head_sha() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO pr_cache (id, number, repo, title, url, author, state, review_status, branch, base_branch, created_at, updated_at, head_sha)
+             VALUES ('o/r#1', 1, 'o/r', 't', 'u', 'a', 'open', 'pending', 'b', 'main', '', '', 'aaa')",
+            [],
+        )
+        .unwrap();
+
+        assert_eq!(record_head_sha(&conn, "o/r#1", "aaa").unwrap(), None);
+        assert_eq!(record_head_sha(&conn, "o/r#1", "bbb").unwrap(), Some("aaa".to_string()));
+        assert_eq!(record_head_sha(&conn, "o/r#2", "ccc").unwrap(), None);
+
+        let previous: Option<String> = conn
+            .query_row("SELECT previous_head_sha FROM pr_cache WHERE id = 'o/r#1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(previous, Some("aaa".to_string()));
+    }
+
+    #[test]
+    fn test_dismiss_expired_merged_prs() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        for (id, state) in [("o/r#1", "merged"), ("o/r#2", "merged"), ("o/r#3", "closed"), ("o/r#4", "open")] {
+            let number: i32 = id[4..].parse().unwrap();
+            conn.execute(
+                "INSERT INTO pr_cache (id, number, repo, title, url, author, state, review_status, branch, base_branch, created_at, updated_at)
+                 VALUES (?1, ?2, 'o/r', 't', 'u', 'a', ?3, 'pending', 'b', 'main', '', '')",
+                rusqlite::params![id, number, state],
+            )
+            .unwrap();
+        }
+        set_pr_merged_at(&conn, "o/r#1", "2020-01-01T00:00:00Z").unwrap();
+        set_pr_merged_at(&conn, "o/r#2", &chrono::Utc::now().to_rfc3339()).unwrap();
+
+        conn.execute("UPDATE pr_cache SET ci_status = 'failing'", []).unwrap();
+        assert_eq!(get_attention_count(&conn, None).unwrap(), 1);
+        assert_eq!(get_attention_count(&conn, Some("other/repo")).unwrap(), 0);
+
+        assert_eq!(dismiss_expired_merged_prs(&conn, "o/r", 30).unwrap(), 1);
+        conn.execute(
+            "INSERT INTO monitors (id, pr_id, pr_number, repo, status, started_at, log_file) VALUES ('m1', 'o/r#3', 3, 'o/r', 'completed', '', '')",
+            [],
+        )
+        .unwrap();
+        assert_eq!(clear_done_prs(&conn, Some("other/repo")).unwrap(), 0);
+        assert_eq!(clear_done_prs(&conn, Some("o/r")).unwrap(), 2);
+
+        let monitors: i32 = conn
+            .query_row("SELECT COUNT(*) FROM monitors", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(monitors, 1);
+
+        let remaining: i32 = conn
+            .query_row("SELECT COUNT(*) FROM pr_cache", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(remaining, 1);
+    }
+
+    #[test]
+    fn test_unread_counts() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        set_setting(&conn, "repos", r#"["a/x", "https://github.com/b/y"]"#).unwrap();
+
+        for (id, number, repo, category) in [
+            ("a/x#1", 1, "a/x", "todo"),
+            ("a/x#2", 2, "a/x", "done"),
+            ("b/y#1", 1, "b/y", "todo"),
+            ("gone/z#1", 1, "gone/z", "todo"),
+        ] {
+            conn.execute(
+                "INSERT INTO pr_cache (id, number, repo, title, url, author, state, review_status, branch, base_branch, created_at, updated_at, column_assignment)
+                 VALUES (?1, ?2, ?3, 't', 'u', 'a', 'open', 'pending', 'b', 'main', '', '2024-01-01T00:00:00Z', ?4)",
+                rusqlite::params![id, number, repo, category],
+            )
+            .unwrap();
+        }
+
+        // gone/z is no longer configured, so its cached row doesn't count
+        let counts = get_unread_counts(&conn).unwrap();
+        assert_eq!(counts.total, 3);
+        assert_eq!(counts.by_repo.get("gone/z"), None);
+
+        mark_pr_seen(&conn, "a/x#1").unwrap();
+        let counts = get_unread_counts(&conn).unwrap();
+        assert_eq!(counts.total, 2);
+        assert_eq!(counts.by_repo.get("a/x"), Some(&1));
+        assert_eq!(counts.by_category.get("todo"), Some(&1));
+
+        // New activity after the PR was seen makes it unread again
+        conn.execute("UPDATE pr_cache SET updated_at = '2999-01-01T00:00:00Z' WHERE id = 'a/x#1'", [])
+            .unwrap();
+        assert_eq!(get_unread_counts(&conn).unwrap().total, 3);
+    }
+
+    #[test]
+    fn test_apply_done_retention() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        for (id, number, state, updated_at) in [
+            ("o/r#1", 1, "merged", "2020-01-01T00:00:00Z"),
+            ("o/r#2", 2, "closed", "2999-01-01T00:00:00Z"),
+            ("o/r#3", 3, "open", "2020-01-01T00:00:00Z"),
+            ("o/r#4", 4, "closed", "1990-01-01T00:00:00Z"),
+        ] {
+            conn.execute(
+                "INSERT INTO pr_cache (id, number, repo, title, url, author, state, review_status, branch, base_branch, created_at, updated_at)
+                 VALUES (?1, ?2, 'o/r', 't', 'u', 'a', ?3, 'pending', 'b', 'main', '', ?4)",
+                rusqlite::params![id, number, state, updated_at],
+            )
+            .unwrap();
+        }
+
+        // Only old, non-open PRs are archived; truly ancient ones are deleted
+        assert_eq!(apply_done_retention(&conn, Some(7), Some(365 * 30)).unwrap(), (2, 1));
+        let archived: Vec<String> = conn
+            .prepare("SELECT id FROM pr_cache WHERE archived = 1 ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<SqliteResult<_>>()
+            .unwrap();
+        assert_eq!(archived, vec!["o/r#1".to_string()]);
+
+        // Unarchived PRs aren't archived again on the next pass
+        unarchive_pr(&conn, "o/r#1").unwrap();
+        assert_eq!(apply_done_retention(&conn, Some(7), None).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn test_muted_authors() {
+        let patterns = vec!["*[bot]".to_string(), "octocat".to_string(), "team-?".to_string()];
+        assert!(is_author_muted(&patterns, "dependabot[bot]"));
+        assert!(is_author_muted(&patterns, "OctoCat"));
+        assert!(is_author_muted(&patterns, "team-a"));
+        assert!(!is_author_muted(&patterns, "team-ab"));
+        assert!(!is_author_muted(&patterns, "octocat2"));
+        assert!(!is_author_muted(&[], "dependabot[bot]"));
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        set_setting(&conn, "repos", r#"["o/r"]"#).unwrap();
+        for (id, number, author) in [("o/r#1", 1, "dependabot[bot]"), ("o/r#2", 2, "alice")] {
+            conn.execute(
+                "INSERT INTO pr_cache (id, number, repo, title, url, author, state, review_status, branch, base_branch, created_at, updated_at, ci_status)
+                 VALUES (?1, ?2, 'o/r', 't', 'u', ?3, 'open', 'pending', 'b', 'main', '', '', 'failing')",
+                rusqlite::params![id, number, author],
+            )
+            .unwrap();
+        }
+
+        set_setting(&conn, MUTED_AUTHORS_SETTING_KEY, r#"["*[bot]"]"#).unwrap();
+        assert_eq!(refresh_muted_prs(&conn).unwrap(), 1);
+        assert!(is_pr_muted(&conn, "o/r#1"));
+        assert_eq!(get_unread_counts(&conn).unwrap().total, 1);
+        assert_eq!(get_attention_count(&conn, None).unwrap(), 1);
+
+        // Unmuting applies to already-cached PRs
+        set_setting(&conn, MUTED_AUTHORS_SETTING_KEY, "[]").unwrap();
+        assert_eq!(refresh_muted_prs(&conn).unwrap(), 1);
+        assert!(!is_pr_muted(&conn, "o/r#1"));
+        assert_eq!(get_attention_count(&conn, None).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_stacked_prs_need_rebase_after_parent_merge() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        for (id, number, branch, base) in [("o/r#1", 1, "feat-a", "main"), ("o/r#2", 2, "feat-b", "feat-a")] {
+            conn.execute(
+                "INSERT INTO pr_cache (id, number, repo, title, url, author, state, review_status, branch, base_branch, created_at, updated_at)
+                 VALUES (?1, ?2, 'o/r', 't', 'u', 'a', 'open', 'pending', ?3, ?4, '', '')",
+                rusqlite::params![id, number, branch, base],
+            )
+            .unwrap();
+        }
+
+        link_stacked_prs(&conn, "o/r").unwrap();
+        let parent: Option<String> = conn
+            .query_row("SELECT parent_pr_id FROM pr_cache WHERE id = 'o/r#2'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(parent, Some("o/r#1".to_string()));
+
+        update_pr_state(&conn, "o/r#1", "merged", "done").unwrap();
+        let needs_rebase: i32 = conn
+            .query_row("SELECT needs_rebase FROM pr_cache WHERE id = 'o/r#2'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(needs_rebase, 1);
+    }
+
+    #[test]
+    fn test_record_review_request() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO pr_cache (id, number, repo, title, url, author, state, review_status, branch, base_branch, created_at, updated_at)
+             VALUES ('o/r#1', 1, 'o/r', 't', 'u', 'a', 'open', 'pending', 'b', 'main', '', '')",
+            [],
+        )
+        .unwrap();
+        let requested_at = |conn: &Connection| -> Option<String> {
+            conn.query_row("SELECT review_requested_at FROM pr_cache WHERE id = 'o/r#1'", [], |row| row.get(0))
+                .unwrap()
+        };
+
+        // First observation sets the timestamp; later ones preserve it
+        record_review_request(&conn, "o/r#1", true).unwrap();
+        conn.execute("UPDATE pr_cache SET review_requested_at = '2020-01-01T00:00:00Z'", []).unwrap();
+        record_review_request(&conn, "o/r#1", true).unwrap();
+        assert_eq!(requested_at(&conn), Some("2020-01-01T00:00:00Z".to_string()));
+
+        // Waiting since 2020 counts as attention once a threshold is set
+        assert_eq!(get_attention_count(&conn, None).unwrap(), 0);
+        set_setting(&conn, REVIEW_WAITING_THRESHOLD_HOURS_SETTING_KEY, "24").unwrap();
+        assert_eq!(get_attention_count(&conn, None).unwrap(), 1);
+
+        // Request removed, then re-requested: the wait starts over
+        record_review_request(&conn, "o/r#1", false).unwrap();
+        assert_eq!(requested_at(&conn), None);
+        record_review_request(&conn, "o/r#1", true).unwrap();
+        assert_ne!(requested_at(&conn), Some("2020-01-01T00:00:00Z".to_string()));
+        assert!(requested_at(&conn).is_some());
+    }
+
+    #[test]
+    fn test_delete_repo_data() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        for (id, number, repo) in [("a/x#1", 1, "a/x"), ("a/x#2", 2, "a/x"), ("b/y#1", 1, "b/y")] {
+            conn.execute(
+                "INSERT INTO pr_cache (id, number, repo, title, url, author, state, review_status, branch, base_branch, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, 't', 'u', 'a', 'open', 'pending', 'b', 'main', '', '')",
+                rusqlite::params![id, number, repo],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO pr_comments (id, pr_id, thread_id, author, body, created_at, updated_at)
+             VALUES ('c1', 'a/x#1', 'T_1', 'rev', 'fix', '', '')",
+            [],
+        )
+        .unwrap();
+        set_repo_setting(&conn, "a/x", MERGE_METHOD_REPO_SETTING_KEY, "rebase").unwrap();
+        set_repo_setting(&conn, "b/y", MERGE_METHOD_REPO_SETTING_KEY, "squash").unwrap();
+
+        let removed = delete_repo_data(&conn, "a/x").unwrap();
+        assert_eq!((removed.prs, removed.comments, removed.repo_settings), (2, 1, 1));
+
+        let left: Vec<String> = conn
+            .prepare("SELECT id FROM pr_cache")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(left, ["b/y#1"]);
+        assert!(get_repo_setting(&conn, "b/y", MERGE_METHOD_REPO_SETTING_KEY).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_repo_aliases() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        set_repo_setting(&conn, "my-org/backend-service-v2", ALIAS_REPO_SETTING_KEY, "Backend").unwrap();
+        set_repo_setting(&conn, "my-org/web", ALIAS_REPO_SETTING_KEY, "Web").unwrap();
+        set_repo_setting(&conn, "my-org/web", MERGE_METHOD_REPO_SETTING_KEY, "rebase").unwrap();
+        delete_repo_setting(&conn, "my-org/web", ALIAS_REPO_SETTING_KEY).unwrap();
+
+        let aliases: Vec<(String, String)> = get_repo_aliases(&conn).unwrap().into_iter().collect();
+        assert_eq!(aliases, [("my-org/backend-service-v2".to_string(), "Backend".to_string())]);
+        assert!(get_repo_setting(&conn, "my-org/web", MERGE_METHOD_REPO_SETTING_KEY).unwrap().is_some());
     }
 }
@@ -0,0 +1,185 @@
+//! Deep link handling for the clanker:// URL scheme
+//!
+//! Supported links:
+//! - `clanker://pr/owner/repo/123` focuses a PR on the board
+//! - `clanker://monitor/start/owner/repo/123` starts monitoring a PR
+//!
+//! URLs come from outside the app, so they are parsed strictly. Links that
+//! arrive before setup finishes are queued and run once the app is ready.
+
+use crate::api;
+use crate::db::AppState;
+use crate::monitor;
+use crate::notifications;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use tauri::{AppHandle, Manager};
+
+const SCHEME_PREFIX: &str = "clanker://";
+
+/// Actions that can be requested through a deep link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLinkAction {
+    FocusPr { repo: String, pr_number: i32 },
+    StartMonitor { repo: String, pr_number: i32 },
+}
+
+static READY: AtomicBool = AtomicBool::new(false);
+static PENDING: Mutex<Vec<DeepLinkAction>> = Mutex::new(Vec::new());
+
+/// Parse a clanker:// URL into an action
+pub fn parse_deep_link(url: &str) -> Result<DeepLinkAction, String> {
+    let rest = url
+        .strip_prefix(SCHEME_PREFIX)
+        .ok_or_else(|| format!("Unsupported deep link: {}", url))?;
+
+    // Ignore any query string or fragment
+    let path = rest.split(['?', '#']).next().unwrap_or_default();
+    let segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+
+    match segments.as_slice() {
+        ["pr", owner, repo, number] => Ok(DeepLinkAction::FocusPr {
+            repo: parse_repo(owner, repo)?,
+            pr_number: parse_pr_number(number)?,
+        }),
+        ["monitor", "start", owner, repo, number] => Ok(DeepLinkAction::StartMonitor {
+            repo: parse_repo(owner, repo)?,
+            pr_number: parse_pr_number(number)?,
+        }),
+        _ => Err(format!("Unrecognized deep link: {}", url)),
+    }
+}
+
+/// Validate an "owner/repo" string from outside the app (API requests, commands)
+pub(crate) fn parse_repo_slug(slug: &str) -> Result<String, String> {
+    match slug.split_once('/') {
+        Some((owner, repo)) => parse_repo(owner, repo),
+        None => Err(format!("Invalid repository: {} (expected owner/repo)", slug)),
+    }
+}
+
+fn parse_repo(owner: &str, repo: &str) -> Result<String, String> {
+    let valid = |part: &str| {
+        !part.is_empty()
+            && part.len() <= 100
+            && part != "."
+            && part != ".."
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+
+    if !valid(owner) || !valid(repo) {
+        return Err(format!("Invalid repository: {}/{}", owner, repo));
+    }
+    Ok(format!("{}/{}", owner, repo))
+}
+
+fn parse_pr_number(number: &str) -> Result<i32, String> {
+    match number.parse::<i32>() {
+        Ok(n) if n > 0 && number.chars().all(|c| c.is_ascii_digit()) => Ok(n),
+        _ => Err(format!("Invalid PR number: {}", number)),
+    }
+}
+
+/// Handle incoming deep link URLs, queueing them until the app is ready
+pub fn handle_urls(app: &AppHandle, urls: &[String]) {
+    for url in urls {
+        let action = match parse_deep_link(url) {
+            Ok(action) => action,
+            Err(e) => {
+                eprintln!("Ignoring deep link: {}", e);
+                continue;
+            }
+        };
+
+        if READY.load(Ordering::SeqCst) {
+            dispatch(app, action);
+        } else if let Ok(mut pending) = PENDING.lock() {
+            pending.push(action);
+        }
+    }
+}
+
+/// Mark the app as ready and run any deep links received during startup
+pub fn mark_ready(app: &AppHandle) {
+    let pending = match PENDING.lock() {
+        Ok(mut pending) => {
+            READY.store(true, Ordering::SeqCst);
+            std::mem::take(&mut *pending)
+        }
+        Err(_) => return,
+    };
+
+    for action in pending {
+        dispatch(app, action);
+    }
+}
+
+/// Run an action in the background (both paths may call out to gh)
+fn dispatch(app: &AppHandle, action: DeepLinkAction) {
+    let app = app.clone();
+    thread::spawn(move || {
+        let Some(state) = app.try_state::<AppState>() else {
+            eprintln!("Deep link ignored: app state not available");
+            return;
+        };
+
+        match action {
+            DeepLinkAction::FocusPr { repo, pr_number } => {
+                // Make sure the PR is on the board before focusing it
+                if let Err(e) = api::fetch_and_cache_pr(&state, pr_number, &repo) {
+                    eprintln!("Warning: Failed to cache PR: {}", e);
+                }
+                let pr_id = format!("{}#{}", repo, pr_number);
+                let _ = notifications::show_and_focus_pr(app.clone(), pr_id);
+            }
+            DeepLinkAction::StartMonitor { repo, pr_number } => {
+                let pr_id = format!("{}#{}", repo, pr_number);
+                let params = monitor::MonitorStartParams {
+                    pr_number,
+                    repo,
+                    ..Default::default()
+                };
+                match monitor::start_monitor_core(&app, &state, params) {
+                    Ok(_) => {
+                        let _ = notifications::show_and_focus_pr(app.clone(), pr_id);
+                    }
+                    Err(e) => eprintln!("Failed to start monitor from deep link: {}", e),
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_deep_link() {
+        assert_eq!(
+            parse_deep_link("clanker://pr/owner/repo/123"),
+            Ok(DeepLinkAction::FocusPr {
+                repo: "owner/repo".to_string(),
+                pr_number: 123
+            })
+        );
+        assert_eq!(
+            parse_deep_link("clanker://monitor/start/my-org/my.repo/7/?src=slack"),
+            Ok(DeepLinkAction::StartMonitor {
+                repo: "my-org/my.repo".to_string(),
+                pr_number: 7
+            })
+        );
+
+        assert!(parse_deep_link("https://pr/owner/repo/123").is_err());
+        assert!(parse_deep_link("clanker://pr/owner/repo").is_err());
+        assert!(parse_deep_link("clanker://pr/owner/repo/0").is_err());
+        assert!(parse_deep_link("clanker://pr/owner/repo/+5").is_err());
+        assert!(parse_deep_link("clanker://pr/../repo/1").is_err());
+        assert!(parse_deep_link("clanker://pr/own;er/repo/1").is_err());
+        assert!(parse_deep_link("clanker://monitor/stop/owner/repo/1").is_err());
+    }
+}
@@ -0,0 +1,440 @@
+//! Demo mode: GitHub answers from bundled fixtures, monitors run a scripted
+//! stand-in for the real loop, and gh itself is stubbed so merges, label
+//! edits and other writes succeed locally. The session runs on its own
+//! database (`demo.db`), so the whole pipeline runs without a GitHub login or
+//! an AI CLI and never touches real repos, settings or history.
+
+use crate::db::{self, AppState};
+use crate::github::{self, GithubClient};
+use crate::{GitHubPR, PR};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::State;
+
+/// Set to 1 (or 0) to start in (or out of) demo mode regardless of the `demo_mode` setting
+pub const DEMO_ENV: &str = "CLANKER_DEMO";
+
+/// PRs, threads and checks served by `DemoGithub`
+const FIXTURE: &str = include_str!("../fixtures/demo.json");
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Installed demo gh script, which `github::gh_program` runs instead of gh
+static GH_STUB: OnceLock<PathBuf> = OnceLock::new();
+
+/// The fixture-backed client installed as the state's GitHub client
+static GITHUB: OnceLock<Arc<DemoGithub>> = OnceLock::new();
+
+/// Whether this session runs in demo mode
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::SeqCst)
+}
+
+/// The demo gh script while demo mode is active
+pub fn gh_stub() -> Option<PathBuf> {
+    GH_STUB.get().filter(|_| is_active()).cloned()
+}
+
+/// Whether this launch should run in demo mode: `CLANKER_DEMO` if set,
+/// otherwise the `demo_mode` setting (read from the real database)
+pub fn requested(conn: &rusqlite::Connection) -> bool {
+    env_override(std::env::var(DEMO_ENV).ok().as_deref()).unwrap_or_else(|| db::get_demo_mode(conn))
+}
+
+fn env_override(value: Option<&str>) -> Option<bool> {
+    match value.map(str::trim)? {
+        "1" | "true" => Some(true),
+        "0" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Switch this session to demo mode: open the demo database, install the gh
+/// stub, then swap the state's database and GitHub client. Nothing is swapped
+/// unless every step succeeds.
+pub fn activate(state: &mut AppState) -> Result<(), String> {
+    let conn = db::open_connection(&db::get_demo_db_path()?)?;
+    db::init_schema(&conn).map_err(|e| format!("Failed to initialize demo database: {}", e))?;
+    let github = Arc::new(DemoGithub::new()?);
+    let stub = crate::process::install_script("demo-gh.sh", include_str!("../scripts/demo-gh.sh"))?;
+
+    let _ = GH_STUB.set(stub);
+    let github = GITHUB.get_or_init(|| github).clone();
+    state.db = Mutex::new(conn);
+    state.github = github;
+    ACTIVE.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// The real database, for the `demo_mode` setting itself: written in demo
+/// mode, it must reach the database the next launch reads it from
+pub(crate) fn main_db() -> Result<rusqlite::Connection, String> {
+    db::open_connection(&db::get_db_path()?)
+}
+
+/// `GithubClient` answering from the bundled fixture. Merges are kept in
+/// memory, so a merged PR leaves the open list and reports MERGED.
+pub struct DemoGithub {
+    user: String,
+    /// `gh pr list` entries per repo
+    prs: HashMap<String, Vec<Value>>,
+    /// Extra `gh pr view` fields (threads, comments, reviews, checks) per (repo, number)
+    views: HashMap<(String, i32), Value>,
+    /// mergedAt of each PR merged this session
+    merged: Mutex<HashMap<(String, i32), String>>,
+}
+
+impl DemoGithub {
+    pub fn new() -> Result<Self, String> {
+        Self::from_fixture(FIXTURE, Utc::now())
+    }
+
+    /// Parse a fixture, turning its `createdHoursAgo`/`updatedHoursAgo`
+    /// offsets into timestamps relative to `now` so the board never looks stale
+    fn from_fixture(json: &str, now: DateTime<Utc>) -> Result<Self, String> {
+        let fixture: Value =
+            serde_json::from_str(json).map_err(|e| format!("Invalid demo fixture: {}", e))?;
+        let repos = fixture["repos"]
+            .as_object()
+            .ok_or_else(|| "Invalid demo fixture: no repos".to_string())?;
+
+        let mut prs = HashMap::new();
+        let mut views = HashMap::new();
+        for (repo, entries) in repos {
+            let mut list = Vec::new();
+            for entry in entries.as_array().into_iter().flatten() {
+                let mut pr = entry.clone();
+                let number = pr["number"]
+                    .as_i64()
+                    .ok_or_else(|| format!("Invalid demo fixture: PR without number in {}", repo))?
+                    as i32;
+                let hours_ago =
+                    |key: &str| (now - Duration::hours(pr[key].as_i64().unwrap_or(0))).to_rfc3339();
+                let created_at = hours_ago("createdHoursAgo");
+                let updated_at = hours_ago("updatedHoursAgo");
+
+                let Some(fields) = pr.as_object_mut() else {
+                    continue;
+                };
+                fields.remove("createdHoursAgo");
+                fields.remove("updatedHoursAgo");
+                fields.insert("createdAt".to_string(), Value::String(created_at));
+                fields.insert("updatedAt".to_string(), Value::String(updated_at.clone()));
+                if let Some(mut view) = fields.remove("view") {
+                    stamp_comments(&mut view, &updated_at);
+                    views.insert((repo.clone(), number), view);
+                }
+                list.push(pr);
+            }
+            prs.insert(repo.clone(), list);
+        }
+
+        Ok(Self {
+            user: fixture["user"].as_str().unwrap_or("demo-user").to_string(),
+            prs,
+            views,
+            merged: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Repos the fixture has PRs for
+    pub fn repos(&self) -> Vec<String> {
+        let mut repos: Vec<String> = self.prs.keys().cloned().collect();
+        repos.sort();
+        repos
+    }
+
+    fn merged_at(&self, repo: &str, number: i32) -> Option<String> {
+        self.merged.lock().ok()?.get(&(repo.to_string(), number)).cloned()
+    }
+
+    /// What `gh pr view` would print: the list entry, its extras, and any local merge
+    fn view(&self, repo: &str, number: i32) -> Option<Value> {
+        let mut view = self.prs.get(repo)?.iter().find(|pr| pr["number"] == number)?.clone();
+        if let (Some(fields), Some(Value::Object(extra))) =
+            (view.as_object_mut(), self.views.get(&(repo.to_string(), number)))
+        {
+            fields.extend(extra.clone());
+        }
+        if let Some(merged_at) = self.merged_at(repo, number) {
+            view["state"] = Value::String("MERGED".to_string());
+            view["mergedAt"] = Value::String(merged_at);
+        }
+        Some(view)
+    }
+}
+
+/// Give fixture comments without a `createdAt` the PR's last update time
+fn stamp_comments(view: &mut Value, updated_at: &str) {
+    let threads = view["reviewThreads"].as_array_mut().into_iter().flatten();
+    let thread_comments = threads.flat_map(|t| t["comments"].as_array_mut().into_iter().flatten());
+    let mut stamp = |comment: &mut Value| {
+        if comment["createdAt"].as_str().unwrap_or("").is_empty() {
+            comment["createdAt"] = Value::String(updated_at.to_string());
+        }
+    };
+    thread_comments.for_each(&mut stamp);
+    view["comments"].as_array_mut().into_iter().flatten().for_each(&mut stamp);
+}
+
+impl GithubClient for DemoGithub {
+    fn current_user(&self) -> Option<String> {
+        Some(self.user.clone())
+    }
+
+    fn current_repo(&self) -> Option<String> {
+        None
+    }
+
+    fn list_prs(&self, repo: &str, _last_fetch: &Option<String>) -> Result<Vec<GitHubPR>, String> {
+        let open: Vec<Value> = self
+            .prs
+            .get(repo)
+            .into_iter()
+            .flatten()
+            .filter(|pr| {
+                let number = pr["number"].as_i64().unwrap_or_default() as i32;
+                self.merged_at(repo, number).is_none()
+            })
+            .cloned()
+            .collect();
+        serde_json::from_value(Value::Array(open))
+            .map_err(|e| format!("Failed to parse JSON: {}", e))
+    }
+
+    fn view_pr(&self, repo: &str, number: i32, _fields: &str) -> Result<Value, String> {
+        self.view(repo, number).ok_or_else(|| {
+            format!("gh CLI error: Could not resolve to a PullRequest with the number of {}", number)
+        })
+    }
+
+    fn pr_states(&self, repo: &str, numbers: &[i32]) -> HashMap<i32, (String, Option<String>)> {
+        numbers
+            .iter()
+            .filter_map(|num| Some((*num, github::parse_pr_state(&self.view(repo, *num)?)?)))
+            .collect()
+    }
+
+    fn thread_counts(&self, repo: &str, numbers: &[i32]) -> HashMap<i32, (i32, i32)> {
+        numbers
+            .iter()
+            .filter_map(|num| Some((*num, github::view_thread_counts(&self.view(repo, *num)?)?)))
+            .collect()
+    }
+
+    fn review_threads(&self, repo: &str, number: i32) -> Result<Option<Value>, String> {
+        Ok(self
+            .view(repo, number)
+            .and_then(|view| view.get("reviewThreads").cloned())
+            .map(|threads| serde_json::json!({ "reviewThreads": threads })))
+    }
+
+    fn checks(&self, repo: &str, number: i32) -> Result<Value, String> {
+        Ok(self
+            .view(repo, number)
+            .and_then(|view| view.get("checks").cloned())
+            .unwrap_or_else(|| Value::Array(vec![])))
+    }
+
+    fn required_checks(&self, repo: &str, number: i32) -> Result<Value, String> {
+        Ok(self
+            .view(repo, number)
+            .and_then(|view| view.get("requiredChecks").cloned())
+            .unwrap_or_else(|| Value::Array(vec![])))
+    }
+
+    fn merge(&self, repo: &str, number: i32, _method: &str, _delete_branch: bool) -> Result<(), String> {
+        let view = self.view_pr(repo, number, "state")?;
+        if view["state"] != "OPEN" {
+            return Err(format!("Pull request {}#{} is not open", repo, number));
+        }
+        self.merged
+            .lock()
+            .map_err(|_| "Demo merge state poisoned".to_string())?
+            .insert((repo.to_string(), number), Utc::now().to_rfc3339());
+        Ok(())
+    }
+
+    fn compare_status(&self, _repo: &str, _base: &str, _head: &str) -> Option<String> {
+        Some("ahead".to_string())
+    }
+
+    fn rate_limit(&self) -> Result<Value, String> {
+        let reset = (Utc::now() + Duration::hours(1)).timestamp();
+        let bucket = |limit: i64| {
+            serde_json::json!({"limit": limit, "remaining": limit, "used": 0, "reset": reset})
+        };
+        Ok(serde_json::json!({
+            "resources": {"core": bucket(5000), "search": bucket(30), "graphql": bucket(5000)}
+        }))
+    }
+
+    fn user_teams(&self) -> Result<Vec<String>, String> {
+        Ok(vec![])
+    }
+
+    fn auth_status(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    // The rest behave like the demo gh stub: writes succeed without touching
+    // GitHub and reads come back empty
+
+    fn api(&self, _path: &str) -> Result<Value, String> {
+        Ok(serde_json::json!({}))
+    }
+
+    fn api_pages(&self, _path: &str) -> Result<Vec<Value>, String> {
+        Ok(vec![])
+    }
+
+    fn rerun_failed_jobs(&self, _repo: &str, _run_id: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn completed_runs(&self, _repo: &str, _limit: usize) -> Result<Value, String> {
+        Ok(Value::Array(vec![]))
+    }
+
+    fn failed_run_log(&self, _repo: &str, _run_id: &str, _attempt: i64) -> Result<String, String> {
+        Ok(String::new())
+    }
+
+    fn edit_labels(&self, _repo: &str, _number: i32, _add: &[String], _remove: &[String]) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn set_draft(&self, _repo: &str, _number: i32, _draft: bool) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn repo_labels(&self, _repo: &str) -> Result<Vec<String>, String> {
+        Ok(vec![])
+    }
+
+    fn update_branch(&self, _repo: &str, _number: i32) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Add the fixture repos to the repos setting, fetch them into the cache and
+/// record a finished monitor run so history isn't empty
+fn seed_demo_data_internal(state: &AppState, repos: &[String]) -> Result<Vec<PR>, String> {
+    {
+        let conn = state.conn();
+        let mut configured: Vec<String> = db::get_setting(&conn, "repos")
+            .map_err(|e| format!("DB error: {}", e))?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        for repo in repos {
+            if !configured.contains(repo) {
+                configured.push(repo.clone());
+            }
+        }
+        let json = serde_json::to_string(&configured).map_err(|e| e.to_string())?;
+        db::set_setting(&conn, "repos", &json).map_err(|e| format!("DB error: {}", e))?;
+    }
+
+    let outcome = crate::fetch_prs_internal(state, repos, true)?;
+
+    let now = Utc::now();
+    state
+        .conn()
+        .execute(
+            "INSERT OR IGNORE INTO monitors
+                 (id, pr_id, pr_number, repo, status, iteration, max_iterations, interval_minutes,
+                  started_at, last_check_at, ended_at, comments_fixed, exit_reason, log_file)
+             VALUES ('demo-api-server-87', 'demo/api-server#87', 87, 'demo/api-server',
+                     'completed', 2, 10, 15, ?1, ?2, ?2, 1, 'pr_clean', '')",
+            [
+                (now - Duration::hours(3)).to_rfc3339(),
+                (now - Duration::hours(2)).to_rfc3339(),
+            ],
+        )
+        .map_err(|e| format!("Failed to record demo monitor: {}", e))?;
+
+    Ok(outcome.prs)
+}
+
+/// Populate the board with the demo repos' PRs. Only available in demo mode,
+/// so real repos and history are never mixed with fixtures by accident.
+#[tauri::command]
+pub fn seed_demo_data(state: State<'_, AppState>) -> Result<Vec<PR>, String> {
+    if !is_active() {
+        return Err(format!(
+            "Demo data is only available in demo mode (set {}=1 or the {} setting and restart)",
+            DEMO_ENV,
+            db::DEMO_MODE_SETTING_KEY
+        ));
+    }
+    let repos = GITHUB.get().map(|github| github.repos()).unwrap_or_default();
+    seed_demo_data_internal(&state, &repos)
+}
+
+/// Whether this session runs in demo mode
+#[tauri::command]
+pub fn is_demo_mode() -> bool {
+    is_active()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_demo_github_serves_fixture() {
+        let now = Utc::now();
+        let github = DemoGithub::from_fixture(FIXTURE, now).unwrap();
+        assert_eq!(github.repos(), vec!["demo/api-server", "demo/web-app"]);
+
+        let prs = github.list_prs("demo/web-app", &None).unwrap();
+        assert!(prs.len() >= 3);
+        let created = DateTime::parse_from_rfc3339(&prs[0].created_at).unwrap();
+        assert!(created <= now && created > now - Duration::days(7));
+        assert_eq!(github.thread_counts("demo/web-app", &[142]).get(&142), Some(&(3, 2)));
+
+        github.merge("demo/api-server", 87, "squash", true).unwrap();
+        let open = github.list_prs("demo/api-server", &None).unwrap();
+        assert!(open.iter().all(|pr| pr.number != 87));
+        assert_eq!(github.pr_states("demo/api-server", &[87])[&87].0, "merged");
+        assert!(github.merge("demo/api-server", 87, "squash", true).is_err());
+    }
+
+    #[test]
+    fn test_seed_demo_data() {
+        let github = Arc::new(DemoGithub::new().unwrap());
+        let repos = github.repos();
+        let state = AppState::for_tests(github);
+        db::set_setting(&state.conn(), "repos", r#"["o/mine"]"#).unwrap();
+
+        let prs = seed_demo_data_internal(&state, &repos).unwrap();
+        assert!(prs.len() >= 6);
+        assert!(prs.iter().any(|pr| pr.id == "demo/web-app#142" && pr.unresolved_threads == 2));
+
+        // Seeding again keeps one copy of everything
+        seed_demo_data_internal(&state, &repos).unwrap();
+        let conn = state.conn();
+        let configured: Vec<String> =
+            serde_json::from_str(&db::get_setting(&conn, "repos").unwrap().unwrap()).unwrap();
+        assert_eq!(configured, vec!["o/mine", "demo/api-server", "demo/web-app"]);
+        let monitors: i32 = conn
+            .query_row("SELECT COUNT(*) FROM monitors WHERE repo = 'demo/api-server'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(monitors, 1);
+    }
+
+    #[test]
+    fn test_env_override() {
+        assert_eq!(env_override(Some("1")), Some(true));
+        assert_eq!(env_override(Some(" true ")), Some(true));
+        assert_eq!(env_override(Some("0")), Some(false));
+        assert_eq!(env_override(Some("yes please")), None);
+        assert_eq!(env_override(None), None);
+    }
+}
@@ -1,5 +1,23 @@
 //! macOS dock badge functionality
 
+use crate::db;
+use rusqlite::Connection;
+
+/// Resolve the badge count for the configured dock_badge_mode
+pub fn badge_count(conn: &Connection, active_monitor_count: i32) -> Option<i32> {
+    let count = match db::get_dock_badge_mode(conn).as_str() {
+        "off" => 0,
+        "attention" => db::get_attention_count(conn, None).unwrap_or(0),
+        "unread" => db::get_unread_counts(conn).map(|c| c.total).unwrap_or(0),
+        _ => active_monitor_count,
+    };
+    if count > 0 {
+        Some(count)
+    } else {
+        None
+    }
+}
+
 /// Set the dock badge count on macOS
 /// Must dispatch to main thread since AppKit calls are not thread-safe
 #[cfg(target_os = "macos")]
@@ -0,0 +1,280 @@
+//! gh environment preflight
+//!
+//! `check_environment` asks gh whether it is logged in and remembers the
+//! answer on `AppState`. While gh is logged out, monitor starts fail fast with
+//! `GH_AUTH_ERROR` instead of leaving a failed run behind for every PR, or, when
+//! the caller passes `allow_queue`, are parked as 'blocked' monitors. The next
+//! check that finds gh logged in starts them. Blocked monitors have no process,
+//! so they don't count as active for the tray, dock or sleep prevention, and
+//! stopping one just marks it stopped. The check also runs in the background
+//! at startup.
+
+use crate::api;
+use crate::db::{self, AppState};
+use crate::monitor;
+use crate::monitor_queue;
+use chrono::{Duration, Utc};
+use rusqlite::params;
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Runtime, State};
+
+/// Returned when starting a monitor while gh is logged out. The UI matches the
+/// `GhAuth:` prefix to link to the login instructions.
+pub const GH_AUTH_ERROR: &str =
+    "GhAuth: gh is not authenticated; run `gh auth login`, then check the environment again";
+
+/// Result of `check_environment`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentCheck {
+    pub gh_authenticated: bool,
+    /// What gh reported when it isn't logged in
+    pub gh_auth_error: Option<String>,
+    /// Ids of the blocked monitors this check started
+    pub started_monitors: Vec<String>,
+}
+
+/// Whether the last environment check found gh logged out
+pub(crate) fn gh_auth_missing(state: &AppState) -> bool {
+    state.gh_auth_missing.load(Ordering::SeqCst)
+}
+
+/// Remember whether gh is logged in (also set when a monitor exits with auth_expired)
+pub(crate) fn set_gh_auth_missing(state: &AppState, missing: bool) {
+    state.gh_auth_missing.store(missing, Ordering::SeqCst);
+}
+
+/// Ask gh whether it is logged in and remember the answer
+pub(crate) fn refresh_gh_auth(state: &AppState) -> Result<(), String> {
+    let status = state.github.auth_status();
+    set_gh_auth_missing(state, status.is_err());
+    status
+}
+
+/// Check the gh login and start any monitors that were blocked on it
+#[tauri::command]
+pub fn check_environment(app: AppHandle, state: State<'_, AppState>) -> Result<EnvironmentCheck, String> {
+    Ok(check_environment_internal(&app, &state))
+}
+
+pub(crate) fn check_environment_internal<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AppState,
+) -> EnvironmentCheck {
+    let auth = refresh_gh_auth(state);
+
+    let mut started_monitors = Vec::new();
+    if auth.is_ok() {
+        started_monitors = start_blocked_monitors(app, state);
+        if !started_monitors.is_empty() {
+            monitor::emit_state_change(app, state);
+        }
+        // Queued starts wait on gh too
+        monitor_queue::start_queued_monitors(app);
+    }
+
+    EnvironmentCheck {
+        gh_authenticated: auth.is_ok(),
+        gh_auth_error: auth.err(),
+        started_monitors,
+    }
+}
+
+/// A monitor waiting for gh to be logged in
+struct BlockedMonitor {
+    id: String,
+    pr_id: String,
+    pr_number: i32,
+    repo: String,
+    max_iterations: i32,
+    interval_minutes: i32,
+    mode: String,
+}
+
+fn list_blocked(conn: &rusqlite::Connection) -> rusqlite::Result<Vec<BlockedMonitor>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, pr_id, pr_number, repo, max_iterations,
+                COALESCE(effective_interval_minutes, interval_minutes), mode
+         FROM monitors WHERE status = 'blocked'
+         ORDER BY started_at, start_seq",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(BlockedMonitor {
+            id: row.get(0)?,
+            pr_id: row.get(1)?,
+            pr_number: row.get(2)?,
+            repo: row.get(3)?,
+            max_iterations: row.get(4)?,
+            interval_minutes: row.get(5)?,
+            mode: row.get(6)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Start blocked monitors (oldest first) while there are free slots, returning
+/// the ids started. Ones that can't start (e.g. the PR was closed meanwhile)
+/// are marked failed; ones over the concurrency cap stay blocked. A monitor
+/// is marked running before its process spawns, so an exit that beats the
+/// spawn's return still finds it active.
+pub(crate) fn start_blocked_monitors<R: Runtime>(app: &AppHandle<R>, state: &AppState) -> Vec<String> {
+    let blocked = match list_blocked(&state.conn()) {
+        Ok(blocked) => blocked,
+        Err(e) => {
+            eprintln!("Failed to read blocked monitors: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut started = Vec::new();
+    for monitor in blocked {
+        if !monitor_queue::has_capacity(&state.conn()) {
+            break;
+        }
+
+        let now = Utc::now();
+        let next_check = now + Duration::minutes(monitor.interval_minutes as i64);
+        let claimed = state.conn().execute(
+            "UPDATE monitors SET status = 'running', started_at = ?1, next_check_at = ?2
+             WHERE id = ?3 AND status = 'blocked'",
+            params![now.to_rfc3339(), next_check.to_rfc3339(), monitor.id],
+        );
+        // Stopped while we were getting here
+        if !matches!(claimed, Ok(1)) {
+            continue;
+        }
+
+        match start_blocked_monitor(app, state, &monitor) {
+            Ok(pid) => {
+                let conn = state.conn();
+                let _ = conn.execute(
+                    "UPDATE monitors SET pid = ?1 WHERE id = ?2",
+                    params![pid as i32, monitor.id],
+                );
+                let _ = db::move_pr_to_monitoring(&conn, &monitor.pr_id);
+                started.push(monitor.id);
+            }
+            Err(e) => {
+                eprintln!("Failed to start blocked monitor for {}: {}", monitor.pr_id, e);
+                let _ = state.conn().execute(
+                    "UPDATE monitors SET status = 'failed', ended_at = ?1, exit_reason = 'start_failed'
+                     WHERE id = ?2 AND status = 'running'",
+                    params![Utc::now().to_rfc3339(), monitor.id],
+                );
+            }
+        }
+    }
+    started
+}
+
+fn start_blocked_monitor<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AppState,
+    monitor: &BlockedMonitor,
+) -> Result<u32, String> {
+    // The PR could have been merged or closed while gh was logged out
+    api::ensure_pr_monitorable(state, monitor.pr_number, &monitor.repo, false)?;
+
+    let (ai_provider, ai_model, dirty_worktree_policy, skip_ci_fix, working_dir) = {
+        let conn = state.conn();
+        let (ai_provider, ai_model) = db::get_ai_config(&conn);
+        let working_dir = crate::process::resolve_working_dir(&conn, &monitor.repo)?;
+        // The clone may have been configured while the monitor waited
+        conn.execute(
+            "UPDATE monitors SET working_dir = ?1 WHERE id = ?2",
+            rusqlite::params![working_dir.as_ref().map(|dir| dir.display().to_string()), monitor.id],
+        )
+        .map_err(|e| format!("Failed to record working directory: {}", e))?;
+        (
+            ai_provider,
+            ai_model,
+            db::get_monitor_dirty_worktree_policy(&conn),
+            db::get_skip_ci_fix(&conn),
+            working_dir,
+        )
+    };
+
+    state.processes.spawn_monitor(
+        app,
+        &monitor.id,
+        &monitor.pr_id,
+        monitor.pr_number,
+        &monitor.repo,
+        monitor.max_iterations,
+        monitor.interval_minutes,
+        &ai_provider,
+        ai_model.as_deref(),
+        &dirty_worktree_policy,
+        &skip_ci_fix,
+        &monitor.mode,
+        working_dir.as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::mock::MockGithub;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_gh_auth_gates_monitor_starts() {
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_view("o/r", 1, serde_json::json!({"state": "OPEN", "title": "Open"}));
+        let state = AppState::for_tests(github.clone());
+
+        github.logged_out.store(true, Ordering::SeqCst);
+        assert!(refresh_gh_auth(&state).is_err());
+        assert!(gh_auth_missing(&state));
+        assert_eq!(
+            api::ensure_pr_monitorable(&state, 1, "o/r", true).map_err(String::from),
+            Err(GH_AUTH_ERROR.to_string())
+        );
+
+        github.logged_out.store(false, Ordering::SeqCst);
+        assert!(refresh_gh_auth(&state).is_ok());
+        assert!(!gh_auth_missing(&state));
+        assert!(api::ensure_pr_monitorable(&state, 1, "o/r", false).is_ok());
+
+        state
+            .conn()
+            .execute_batch(
+                "INSERT INTO monitors (id, pr_id, pr_number, repo, status, started_at, log_file, mode)
+                 VALUES ('b1', 'o/r#1', 1, 'o/r', 'blocked', '2026-01-01T00:00:00Z', '', 'watch'),
+                        ('r1', 'o/r#2', 2, 'o/r', 'running', '2026-01-01T00:00:00Z', '', 'auto_fix')",
+            )
+            .unwrap();
+        let blocked = list_blocked(&state.conn()).unwrap();
+        assert_eq!(blocked.len(), 1);
+        assert_eq!((blocked[0].id.as_str(), blocked[0].mode.as_str()), ("b1", "watch"));
+        // Blocked monitors have no process, so they aren't active
+        assert_eq!(db::count_active_monitors(&state.conn()).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_start_blocked_monitors_marks_start_failures() {
+        use tauri::Manager;
+
+        // No PR #3 on the mock, so the start fails before anything spawns
+        let app = tauri::test::mock_app();
+        app.manage(AppState::for_tests_default());
+        let state = app.state::<AppState>();
+        state
+            .conn()
+            .execute_batch(
+                "INSERT INTO monitors (id, pr_id, pr_number, repo, status, started_at, log_file)
+                 VALUES ('b3', 'o/r#3', 3, 'o/r', 'blocked', '2026-01-01T00:00:00Z', '')",
+            )
+            .unwrap();
+
+        assert!(start_blocked_monitors(app.handle(), &state).is_empty());
+        let (status, exit_reason): (String, String) = state
+            .conn()
+            .query_row("SELECT status, exit_reason FROM monitors WHERE id = 'b3'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((status.as_str(), exit_reason.as_str()), ("failed", "start_failed"));
+    }
+}
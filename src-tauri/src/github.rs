@@ -0,0 +1,1086 @@
+//! GitHub access behind a trait
+//!
+//! The PR fetch, stale-state check, comment, checks, merge and rate-limit paths talk to
+//! GitHub through `GithubClient` (held in `AppState`) instead of shelling out
+//! inline. `GhCli` is the real implementation on top of the `gh` CLI; tests
+//! swap in `mock::MockGithub`, which serves fixture JSON.
+//!
+//! The `gh_path` setting points every gh invocation at a specific binary;
+//! `override_gh_path` does the same for one thread (the integration tests use
+//! it to substitute a fake one).
+
+use crate::GitHubPR;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::sync::{Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Fields requested from `gh pr list --json`
+pub const PR_LIST_JSON_FIELDS: &str = "number,title,url,state,isDraft,author,headRefName,headRefOid,headRepository,headRepositoryOwner,isCrossRepository,baseRefName,labels,reviewRequests,reviewDecision,latestReviews,statusCheckRollup,mergeable,createdAt,updatedAt";
+
+pub trait GithubClient: Send + Sync {
+    /// Login of the authenticated user (None if unknown)
+    fn current_user(&self) -> Option<String>;
+
+    /// `owner/repo` of the repository in the current working directory
+    fn current_repo(&self) -> Option<String>;
+
+    /// Open PRs involving me, optionally only those updated since `last_fetch`
+    fn list_prs(&self, repo: &str, last_fetch: &Option<String>) -> Result<Vec<GitHubPR>, String>;
+
+    /// `gh pr view --json <fields>` for one PR
+    fn view_pr(&self, repo: &str, number: i32, fields: &str) -> Result<Value, String>;
+
+    /// State ("open" | "merged" | "closed") and merge time of several PRs.
+    /// PRs whose state couldn't be determined are left out.
+    fn pr_states(&self, repo: &str, numbers: &[i32]) -> HashMap<i32, (String, Option<String>)>;
+
+    /// (comments_count, unresolved_threads) for several PRs. PRs whose counts
+    /// couldn't be fetched are left out.
+    fn thread_counts(&self, repo: &str, numbers: &[i32]) -> HashMap<i32, (i32, i32)>;
+
+    /// `{"reviewThreads": [...]}` for a PR, or None when GitHub can't provide them
+    fn review_threads(&self, repo: &str, number: i32) -> Result<Option<Value>, String>;
+
+    /// `gh pr checks --json name,bucket,link,workflow` output for a PR
+    fn checks(&self, repo: &str, number: i32) -> Result<Value, String>;
+
+    /// The status checks on a PR's head commit as `StatusCheck` objects,
+    /// each with GitHub's `isRequired` flag for that PR (the rollup gh lists
+    /// PRs with doesn't carry it)
+    fn required_checks(&self, repo: &str, number: i32) -> Result<Value, String>;
+
+    /// Merge a PR; `method` is "squash" | "merge" | "rebase"
+    fn merge(&self, repo: &str, number: i32, method: &str, delete_branch: bool) -> Result<(), String>;
+
+    /// Compare API status of `head` relative to `base` ("ahead", "diverged", ...)
+    fn compare_status(&self, repo: &str, base: &str, head: &str) -> Option<String>;
+
+    /// `gh api rate_limit` output
+    fn rate_limit(&self) -> Result<Value, String>;
+
+    /// `org/slug` of every team I belong to
+    fn user_teams(&self) -> Result<Vec<String>, String>;
+
+    /// Ok when `gh auth status` finds a usable login, else gh's complaint
+    fn auth_status(&self) -> Result<(), String>;
+
+    /// `gh api <path>` output
+    fn api(&self, path: &str) -> Result<Value, String>;
+
+    /// `gh api --paginate <path>` output, one value per page
+    fn api_pages(&self, path: &str) -> Result<Vec<Value>, String>;
+
+    /// Rerun the failed jobs of a workflow run
+    fn rerun_failed_jobs(&self, repo: &str, run_id: &str) -> Result<(), String>;
+
+    /// The repo's last `limit` completed workflow runs as
+    /// `{headSha, startedAt, updatedAt}` objects
+    fn completed_runs(&self, repo: &str, limit: usize) -> Result<Value, String>;
+
+    /// `gh run view --log-failed` output for one attempt of a run
+    fn failed_run_log(&self, repo: &str, run_id: &str, attempt: i64) -> Result<String, String>;
+
+    /// Add and remove labels on a PR
+    fn edit_labels(&self, repo: &str, number: i32, add: &[String], remove: &[String]) -> Result<(), String>;
+
+    /// Convert a PR to a draft, or mark it ready for review
+    fn set_draft(&self, repo: &str, number: i32, draft: bool) -> Result<(), String>;
+
+    /// Names of a repo's labels
+    fn repo_labels(&self, repo: &str) -> Result<Vec<String>, String>;
+
+    /// Merge the base branch into a PR's branch on GitHub
+    fn update_branch(&self, repo: &str, number: i32) -> Result<(), String>;
+}
+
+/// PRs per batched GraphQL query in `thread_counts`
+const THREAD_COUNT_BATCH: usize = 20;
+
+/// How long a `GhCli` call may run before it is killed
+pub const DEFAULT_GH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// gh binary from the `gh_path` setting (None = `gh` on PATH)
+static GH_PATH: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Apply the `gh_path` setting to subsequent gh invocations
+pub fn set_gh_path(path: Option<PathBuf>) {
+    if let Ok(mut current) = GH_PATH.write() {
+        *current = path;
+    }
+}
+
+thread_local! {
+    /// gh binary for this thread only, set by `override_gh_path`
+    static GH_PATH_OVERRIDE: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Run gh invocations made on the calling thread with `path` until the
+/// returned guard is dropped. Unlike `set_gh_path`, other threads (such as
+/// tests running in parallel) keep using the `gh_path` setting.
+pub fn override_gh_path(path: PathBuf) -> GhPathOverride {
+    let previous = GH_PATH_OVERRIDE.with(|current| current.replace(Some(path)));
+    GhPathOverride { previous }
+}
+
+/// Restores the calling thread's previous gh binary when dropped
+pub struct GhPathOverride {
+    previous: Option<PathBuf>,
+}
+
+impl Drop for GhPathOverride {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        GH_PATH_OVERRIDE.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// The gh binary to run: the demo stub in demo mode, else this thread's
+/// override, the `gh_path` setting, or `gh` from PATH
+pub fn gh_program() -> PathBuf {
+    if let Some(stub) = crate::demo::gh_stub() {
+        return stub;
+    }
+    if let Some(path) = GH_PATH_OVERRIDE.with(|current| current.borrow().clone()) {
+        return path;
+    }
+    GH_PATH
+        .read()
+        .ok()
+        .and_then(|path| path.clone())
+        .unwrap_or_else(|| PathBuf::from("gh"))
+}
+
+thread_local! {
+    /// gh commands built on this thread, for counting what a fetch cycle costs
+    /// without picking up calls other threads make meanwhile
+    static GH_INVOCATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+fn count_gh_invocation() {
+    GH_INVOCATIONS.with(|count| count.set(count.get() + 1));
+}
+
+/// Number of gh invocations made on the calling thread (every one is built by
+/// `gh_command`)
+pub fn gh_invocations() -> u64 {
+    GH_INVOCATIONS.with(Cell::get)
+}
+
+/// A `Command` for gh that honors the `gh_path` setting
+pub fn gh_command() -> Command {
+    count_gh_invocation();
+    Command::new(gh_program())
+}
+
+/// Run a command to completion, killing it if it outlives `timeout`
+pub fn output_with_timeout(mut cmd: Command, timeout: Duration) -> std::io::Result<Output> {
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Drain both pipes while waiting so a chatty process can't block on a full pipe
+    let drain = |pipe: Option<Box<dyn Read + Send>>| {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    };
+    let stdout = drain(child.stdout.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+    let stderr = drain(child.stderr.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("timed out after {}s", timeout.as_secs_f32()),
+            ));
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// Build the `gh pr list` arguments used to fetch PRs for a repo
+pub fn build_fetch_args(repo_path: &str, last_fetch: &Option<String>) -> Vec<String> {
+    // Build search query with optional updated filter
+    let search_query = match last_fetch {
+        Some(ts) => format!("involves:@me updated:>={}", ts),
+        None => "involves:@me".to_string(),
+    };
+
+    vec![
+        "pr".to_string(),
+        "list".to_string(),
+        "--json".to_string(),
+        PR_LIST_JSON_FIELDS.to_string(),
+        "--limit".to_string(),
+        "50".to_string(),
+        "--repo".to_string(),
+        repo_path.to_string(),
+        "--state".to_string(),
+        "open".to_string(),
+        "--search".to_string(),
+        search_query,
+    ]
+}
+
+/// `GithubClient` backed by the `gh` CLI
+pub struct GhCli {
+    /// Fixed gh binary; None follows the `gh_path` setting
+    program: Option<PathBuf>,
+    timeout: Duration,
+    /// Authenticated user's login, looked up once
+    login: Mutex<Option<String>>,
+}
+
+impl Default for GhCli {
+    fn default() -> Self {
+        Self {
+            program: None,
+            timeout: DEFAULT_GH_TIMEOUT,
+            login: Mutex::new(None),
+        }
+    }
+}
+
+impl GhCli {
+    /// Always run this gh binary, regardless of the `gh_path` setting
+    pub fn with_program(program: impl Into<PathBuf>) -> Self {
+        Self {
+            program: Some(program.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn run<I, S>(&self, args: I) -> Result<Output, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<std::ffi::OsStr>,
+    {
+        let mut cmd = match &self.program {
+            Some(program) => {
+                count_gh_invocation();
+                Command::new(program)
+            }
+            None => gh_command(),
+        };
+        cmd.args(args);
+        output_with_timeout(cmd, self.timeout).map_err(|e| format!("Failed to execute gh CLI: {}", e))
+    }
+
+    /// Check a single PR's state with `gh pr view`
+    fn pr_state(&self, repo: &str, number: i32) -> Option<(String, Option<String>)> {
+        let output = self.run(["pr", "view", &number.to_string(), "--repo", repo, "--json", "state,mergedAt"]).ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let json: Value = serde_json::from_slice(&output.stdout).ok()?;
+        parse_pr_state(&json)
+    }
+}
+
+impl GithubClient for GhCli {
+    fn current_user(&self) -> Option<String> {
+        if let Some(login) = self.login.lock().ok()?.clone() {
+            return Some(login);
+        }
+
+        let output = self.run(["api", "user", "-q", ".login"]).ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        let login = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if login.is_empty() {
+            return None;
+        }
+        if let Ok(mut cached) = self.login.lock() {
+            *cached = Some(login.clone());
+        }
+        Some(login)
+    }
+
+    fn current_repo(&self) -> Option<String> {
+        let output = self.run(["repo", "view", "--json", "nameWithOwner", "-q", ".nameWithOwner"]).ok()?;
+
+        if output.status.success() {
+            Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            None
+        }
+    }
+
+    fn list_prs(&self, repo: &str, last_fetch: &Option<String>) -> Result<Vec<GitHubPR>, String> {
+        let mut args = build_fetch_args(repo, last_fetch);
+        let mut output = self.run(&args)?;
+
+        // Very old gh versions don't know headRefOid; retry without it (head_sha stays null)
+        if !output.status.success()
+            && String::from_utf8_lossy(&output.stderr).contains("headRefOid")
+        {
+            for arg in args.iter_mut() {
+                if arg == PR_LIST_JSON_FIELDS {
+                    *arg = PR_LIST_JSON_FIELDS.replace(",headRefOid", "");
+                }
+            }
+            output = self.run(&args)?;
+        }
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("gh CLI error for {}: {}", repo, stderr));
+        }
+
+        serde_json::from_slice(&output.stdout).map_err(|e| format!("Failed to parse JSON: {}", e))
+    }
+
+    fn view_pr(&self, repo: &str, number: i32, fields: &str) -> Result<Value, String> {
+        let output = self.run(["pr", "view", &number.to_string(), "--repo", repo, "--json", fields])?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("gh CLI error: {}", stderr));
+        }
+
+        serde_json::from_slice(&output.stdout).map_err(|e| format!("Failed to parse JSON: {}", e))
+    }
+
+    /// Uses a single batched GraphQL query (one alias per PR), falling back to
+    /// one `gh pr view` per PR if the batch fails
+    fn pr_states(&self, repo: &str, numbers: &[i32]) -> HashMap<i32, (String, Option<String>)> {
+        if numbers.is_empty() {
+            return HashMap::new();
+        }
+
+        let fallback = || {
+            numbers
+                .iter()
+                .filter_map(|num| self.pr_state(repo, *num).map(|state| (*num, state)))
+                .collect()
+        };
+
+        let Some((owner, name)) = repo.split_once('/') else {
+            return fallback();
+        };
+
+        let aliases: Vec<String> = numbers
+            .iter()
+            .map(|num| format!("pr{num}: pullRequest(number: {num}) {{ state mergedAt }}", num = num))
+            .collect();
+        let query = format!(
+            "query {{ repository(owner: \"{owner}\", name: \"{name}\") {{ {aliases} }} }}",
+            owner = owner,
+            name = name,
+            aliases = aliases.join(" ")
+        );
+
+        let json = match self.run(["api", "graphql", "-f", &format!("query={}", query)]) {
+            Ok(out) if out.status.success() => serde_json::from_slice::<Value>(&out.stdout).ok(),
+            _ => None,
+        };
+        let Some(json) = json else {
+            return fallback();
+        };
+
+        let repo_data = &json["data"]["repository"];
+        numbers
+            .iter()
+            .filter_map(|num| parse_pr_state(&repo_data[format!("pr{}", num)]).map(|state| (*num, state)))
+            .collect()
+    }
+
+    /// One GraphQL query per `THREAD_COUNT_BATCH` PRs (one alias per PR).
+    /// Resolution is only known for the first 100 threads of a PR.
+    fn thread_counts(&self, repo: &str, numbers: &[i32]) -> HashMap<i32, (i32, i32)> {
+        let Some((owner, name)) = repo.split_once('/') else {
+            return HashMap::new();
+        };
+
+        let mut counts = HashMap::new();
+        for batch in numbers.chunks(THREAD_COUNT_BATCH) {
+            let aliases: Vec<String> = batch
+                .iter()
+                .map(|num| {
+                    format!(
+                        "pr{num}: pullRequest(number: {num}) {{ comments {{ totalCount }} \
+                         reviewThreads(first: 100) {{ totalCount nodes {{ isResolved }} }} }}",
+                        num = num
+                    )
+                })
+                .collect();
+            let query = format!(
+                "query {{ repository(owner: \"{owner}\", name: \"{name}\") {{ {aliases} }} }}",
+                owner = owner,
+                name = name,
+                aliases = aliases.join(" ")
+            );
+
+            // gh exits non-zero if any alias failed, but still prints the rest
+            let json = self
+                .run(["api", "graphql", "-f", &format!("query={}", query)])
+                .ok()
+                .and_then(|out| serde_json::from_slice::<Value>(&out.stdout).ok());
+            match json {
+                Some(json) => counts.extend(parse_thread_counts(&json["data"]["repository"], batch)),
+                None => eprintln!("Failed to fetch thread counts for {}", repo),
+            }
+        }
+        counts
+    }
+
+    fn review_threads(&self, repo: &str, number: i32) -> Result<Option<Value>, String> {
+        let output = self.run(["pr", "view", &number.to_string(), "--repo", repo, "--json", "reviewThreads"])?;
+
+        // reviewThreads field may not be available in older gh CLI versions
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        serde_json::from_slice(&output.stdout)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse JSON: {}", e))
+    }
+
+    fn checks(&self, repo: &str, number: i32) -> Result<Value, String> {
+        let output = self.run([
+            "pr",
+            "checks",
+            &number.to_string(),
+            "--repo",
+            repo,
+            "--json",
+            "name,bucket,link,workflow",
+        ])?;
+
+        // gh exits non-zero when any check is failing, so only trust stdout
+        serde_json::from_slice(&output.stdout).map_err(|_| {
+            format!(
+                "Failed to list checks: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+        })
+    }
+
+    fn required_checks(&self, repo: &str, number: i32) -> Result<Value, String> {
+        let (owner, name) = repo
+            .split_once('/')
+            .ok_or_else(|| format!("Invalid repo: {}", repo))?;
+        let query = "query($owner: String!, $name: String!, $number: Int!) { \
+             repository(owner: $owner, name: $name) { pullRequest(number: $number) { \
+             commits(last: 1) { nodes { commit { statusCheckRollup { contexts(first: 100) { nodes { \
+             ... on CheckRun { status conclusion isRequired(pullRequestNumber: $number) } \
+             ... on StatusContext { state isRequired(pullRequestNumber: $number) } \
+             } } } } } } } } }";
+        let output = self.run([
+            "api",
+            "graphql",
+            "-f",
+            &format!("query={}", query),
+            "-F",
+            &format!("owner={}", owner),
+            "-F",
+            &format!("name={}", name),
+            "-F",
+            &format!("number={}", number),
+        ])?;
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to fetch required checks: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        let json: Value = serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+        let commits = &json["data"]["repository"]["pullRequest"]["commits"]["nodes"];
+        // No rollup (no checks at all) reads as null
+        Ok(match &commits[0]["commit"]["statusCheckRollup"]["contexts"]["nodes"] {
+            Value::Null => Value::Array(vec![]),
+            nodes => nodes.clone(),
+        })
+    }
+
+    fn merge(&self, repo: &str, number: i32, method: &str, delete_branch: bool) -> Result<(), String> {
+        let number_arg = number.to_string();
+        let method_flag = format!("--{}", method);
+        let mut args = vec!["pr", "merge", number_arg.as_str(), "--repo", repo, method_flag.as_str()];
+        if delete_branch {
+            args.push("--delete-branch");
+        }
+
+        let output = self.run(&args)?;
+        if !output.status.success() {
+            // Pass gh's message through unchanged (e.g. branch protection rejections)
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(())
+    }
+
+    fn compare_status(&self, repo: &str, base: &str, head: &str) -> Option<String> {
+        let output = self.run([
+            "api",
+            &format!("repos/{}/compare/{}...{}", repo, base, head),
+            "--jq",
+            ".status",
+        ])
+        .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    fn rate_limit(&self) -> Result<Value, String> {
+        let output = self.run(["api", "rate_limit"])?;
+        if !output.status.success() {
+            return Err(format!(
+                "gh CLI error: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        serde_json::from_slice(&output.stdout).map_err(|e| format!("Failed to parse JSON: {}", e))
+    }
+
+    fn user_teams(&self) -> Result<Vec<String>, String> {
+        let output = self.run([
+            "api",
+            "user/teams",
+            "--paginate",
+            "--jq",
+            ".[] | .organization.login + \"/\" + .slug",
+        ])?;
+        if !output.status.success() {
+            return Err(format!(
+                "gh CLI error: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect())
+    }
+
+    fn auth_status(&self) -> Result<(), String> {
+        let output = self.run(["auth", "status"])?;
+        if output.status.success() {
+            return Ok(());
+        }
+        let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err(if message.is_empty() {
+            "gh is not logged in".to_string()
+        } else {
+            message
+        })
+    }
+
+    fn api(&self, path: &str) -> Result<Value, String> {
+        let output = self.run(["api", path])?;
+        if !output.status.success() {
+            return Err(format!(
+                "gh api {} failed: {}",
+                path,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        serde_json::from_slice(&output.stdout).map_err(|e| format!("Failed to parse JSON: {}", e))
+    }
+
+    fn api_pages(&self, path: &str) -> Result<Vec<Value>, String> {
+        let output = self.run(["api", "--paginate", path])?;
+        if !output.status.success() {
+            return Err(format!(
+                "gh api {} failed: {}",
+                path,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        // gh prints the pages back to back
+        serde_json::Deserializer::from_slice(&output.stdout)
+            .into_iter::<Value>()
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to parse JSON: {}", e))
+    }
+
+    fn rerun_failed_jobs(&self, repo: &str, run_id: &str) -> Result<(), String> {
+        let output = self.run(["run", "rerun", run_id, "--failed", "--repo", repo])?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(())
+    }
+
+    fn completed_runs(&self, repo: &str, limit: usize) -> Result<Value, String> {
+        let output = self.run([
+            "run",
+            "list",
+            "--repo",
+            repo,
+            "--status",
+            "completed",
+            "--limit",
+            &limit.to_string(),
+            "--json",
+            "headSha,startedAt,updatedAt",
+        ])?;
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to list workflow runs: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("Failed to parse workflow runs: {}", e))
+    }
+
+    fn failed_run_log(&self, repo: &str, run_id: &str, attempt: i64) -> Result<String, String> {
+        let output = self.run([
+            "run",
+            "view",
+            run_id,
+            "--repo",
+            repo,
+            "--log-failed",
+            "--attempt",
+            &attempt.to_string(),
+        ])?;
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to fetch logs for run {}: {}",
+                run_id,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    fn edit_labels(&self, repo: &str, number: i32, add: &[String], remove: &[String]) -> Result<(), String> {
+        let number_arg = number.to_string();
+        let add_arg = add.join(",");
+        let remove_arg = remove.join(",");
+        let mut args = vec!["pr", "edit", number_arg.as_str(), "--repo", repo];
+        if !add.is_empty() {
+            args.extend(["--add-label", add_arg.as_str()]);
+        }
+        if !remove.is_empty() {
+            args.extend(["--remove-label", remove_arg.as_str()]);
+        }
+
+        let output = self.run(&args)?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(())
+    }
+
+    fn set_draft(&self, repo: &str, number: i32, draft: bool) -> Result<(), String> {
+        let number_arg = number.to_string();
+        let mut args = vec!["pr", "ready", number_arg.as_str(), "--repo", repo];
+        if draft {
+            args.push("--undo");
+        }
+
+        let output = self.run(&args)?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(())
+    }
+
+    fn repo_labels(&self, repo: &str) -> Result<Vec<String>, String> {
+        let output = self.run([
+            "label", "list", "--repo", repo, "--limit", "500", "--json", "name", "-q", ".[].name",
+        ])?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect())
+    }
+
+    fn update_branch(&self, repo: &str, number: i32) -> Result<(), String> {
+        let output = self.run([
+            "api",
+            "-X",
+            "PUT",
+            &format!("repos/{}/pulls/{}/update-branch", repo, number),
+        ])?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Read (comments_count, unresolved_threads) per PR from a batched
+/// `thread_counts` query. comments_count covers conversation comments plus
+/// review threads.
+pub fn parse_thread_counts(repo_data: &Value, numbers: &[i32]) -> HashMap<i32, (i32, i32)> {
+    numbers
+        .iter()
+        .filter_map(|num| {
+            let pr = repo_data.get(format!("pr{}", num))?;
+            let comments = pr["comments"]["totalCount"].as_i64()?;
+            let threads = &pr["reviewThreads"];
+            let unresolved = threads["nodes"]
+                .as_array()?
+                .iter()
+                .filter(|thread| thread["isResolved"] == Value::Bool(false))
+                .count() as i64;
+            let total = comments + threads["totalCount"].as_i64()?;
+            Some((*num, (total as i32, unresolved as i32)))
+        })
+        .collect()
+}
+
+/// One rate-limit bucket as reported by the REST `rate_limit` endpoint
+#[derive(Debug, Deserialize)]
+struct RawRateLimitBucket {
+    limit: i64,
+    remaining: i64,
+    #[serde(default)]
+    used: i64,
+    /// Unix epoch seconds
+    reset: i64,
+}
+
+/// Remaining budget of one GitHub rate-limit bucket
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitBucket {
+    pub limit: i64,
+    pub remaining: i64,
+    pub used: i64,
+    /// When the budget refills (RFC3339)
+    pub reset_at: String,
+}
+
+/// The rate-limit buckets the app spends: REST (core), search and GraphQL
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitStatus {
+    pub core: RateLimitBucket,
+    pub search: RateLimitBucket,
+    pub graphql: RateLimitBucket,
+}
+
+/// Parse `gh api rate_limit` output
+pub fn parse_rate_limit(json: &Value) -> Result<RateLimitStatus, String> {
+    let bucket = |name: &str| -> Result<RateLimitBucket, String> {
+        let raw = json
+            .pointer(&format!("/resources/{}", name))
+            .ok_or_else(|| format!("Rate limit response has no {} bucket", name))?;
+        let raw: RawRateLimitBucket = serde_json::from_value(raw.clone())
+            .map_err(|e| format!("Invalid {} rate limit: {}", name, e))?;
+        let reset_at = chrono::DateTime::from_timestamp(raw.reset, 0)
+            .ok_or_else(|| format!("Invalid {} reset time: {}", name, raw.reset))?
+            .to_rfc3339();
+        Ok(RateLimitBucket {
+            limit: raw.limit,
+            remaining: raw.remaining,
+            used: raw.used,
+            reset_at,
+        })
+    };
+
+    Ok(RateLimitStatus {
+        core: bucket("core")?,
+        search: bucket("search")?,
+        graphql: bucket("graphql")?,
+    })
+}
+
+/// Read `{state, mergedAt}` as returned by `gh pr view` and GraphQL
+pub(crate) fn parse_pr_state(json: &Value) -> Option<(String, Option<String>)> {
+    let state = json.get("state")?.as_str()?.to_lowercase();
+    let merged_at = json.get("mergedAt").and_then(|v| v.as_str()).map(|s| s.to_string());
+    Some((state, merged_at))
+}
+
+/// (total, unresolved) threads of a `gh pr view` JSON with `reviewThreads`
+/// and `comments`; top-level comments count toward the total
+pub(crate) fn view_thread_counts(view: &Value) -> Option<(i32, i32)> {
+    let threads = view.get("reviewThreads")?.as_array()?;
+    let comments = view["comments"].as_array().map_or(0, |c| c.len());
+    let unresolved = threads
+        .iter()
+        .filter(|t| t["isResolved"] == Value::Bool(false))
+        .count();
+    Some(((comments + threads.len()) as i32, unresolved as i32))
+}
+
+#[cfg(test)]
+pub mod mock {
+    //! In-memory `GithubClient` serving fixture JSON
+
+    use super::*;
+
+    /// Each fixture holds what the matching `gh` call would print
+    #[derive(Default)]
+    pub struct MockGithub {
+        pub user: Option<String>,
+        /// `gh pr list` output per repo
+        pub prs: Mutex<HashMap<String, Value>>,
+        /// `gh pr view` output per (repo, number); also answers state lookups
+        /// and, via its `reviewThreads`/`checks` fields, thread and check fetches
+        pub views: Mutex<HashMap<(String, i32), Value>>,
+        /// `org/slug` teams the user belongs to
+        pub teams: Vec<String>,
+        /// Makes `auth_status` fail, as on a machine without `gh auth login`
+        pub logged_out: std::sync::atomic::AtomicBool,
+        /// `gh api` responses by path (`api_pages` serves them as one page)
+        pub api: Mutex<HashMap<String, Value>>,
+        /// Run ids passed to `rerun_failed_jobs`
+        pub reruns: Mutex<Vec<String>>,
+    }
+
+    impl MockGithub {
+        pub fn new(user: &str) -> Self {
+            Self {
+                user: Some(user.to_string()),
+                ..Default::default()
+            }
+        }
+
+        pub fn set_prs(&self, repo: &str, prs: Value) {
+            self.prs.lock().unwrap().insert(repo.to_string(), prs);
+        }
+
+        pub fn set_view(&self, repo: &str, number: i32, view: Value) {
+            self.views.lock().unwrap().insert((repo.to_string(), number), view);
+        }
+
+        fn view(&self, repo: &str, number: i32) -> Option<Value> {
+            self.views.lock().unwrap().get(&(repo.to_string(), number)).cloned()
+        }
+
+        pub fn set_api(&self, path: &str, response: Value) {
+            self.api.lock().unwrap().insert(path.to_string(), response);
+        }
+    }
+
+    impl GithubClient for MockGithub {
+        fn current_user(&self) -> Option<String> {
+            self.user.clone()
+        }
+
+        fn current_repo(&self) -> Option<String> {
+            None
+        }
+
+        fn list_prs(&self, repo: &str, _last_fetch: &Option<String>) -> Result<Vec<GitHubPR>, String> {
+            let prs = self
+                .prs
+                .lock()
+                .unwrap()
+                .get(repo)
+                .cloned()
+                .ok_or_else(|| format!("gh CLI error for {}: no fixture", repo))?;
+            serde_json::from_value(prs).map_err(|e| format!("Failed to parse JSON: {}", e))
+        }
+
+        fn view_pr(&self, repo: &str, number: i32, _fields: &str) -> Result<Value, String> {
+            self.view(repo, number)
+                .ok_or_else(|| format!("gh CLI error: no fixture for {}#{}", repo, number))
+        }
+
+        fn pr_states(&self, repo: &str, numbers: &[i32]) -> HashMap<i32, (String, Option<String>)> {
+            numbers
+                .iter()
+                .filter_map(|num| {
+                    let state = parse_pr_state(&self.view(repo, *num)?)?;
+                    Some((*num, state))
+                })
+                .collect()
+        }
+
+        fn thread_counts(&self, repo: &str, numbers: &[i32]) -> HashMap<i32, (i32, i32)> {
+            numbers
+                .iter()
+                .filter_map(|num| Some((*num, view_thread_counts(&self.view(repo, *num)?)?)))
+                .collect()
+        }
+
+        fn review_threads(&self, repo: &str, number: i32) -> Result<Option<Value>, String> {
+            Ok(self
+                .view(repo, number)
+                .and_then(|view| view.get("reviewThreads").cloned())
+                .map(|threads| serde_json::json!({ "reviewThreads": threads })))
+        }
+
+        fn checks(&self, repo: &str, number: i32) -> Result<Value, String> {
+            Ok(self
+                .view(repo, number)
+                .and_then(|view| view.get("checks").cloned())
+                .unwrap_or_else(|| Value::Array(vec![])))
+        }
+
+        fn required_checks(&self, repo: &str, number: i32) -> Result<Value, String> {
+            Ok(self
+                .view(repo, number)
+                .and_then(|view| view.get("requiredChecks").cloned())
+                .unwrap_or_else(|| Value::Array(vec![])))
+        }
+
+        fn merge(&self, _repo: &str, _number: i32, _method: &str, _delete_branch: bool) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn compare_status(&self, _repo: &str, _base: &str, _head: &str) -> Option<String> {
+            Some("ahead".to_string())
+        }
+
+        fn rate_limit(&self) -> Result<Value, String> {
+            let bucket = |limit: i64| {
+                serde_json::json!({"limit": limit, "remaining": limit, "used": 0, "reset": 1704067200})
+            };
+            Ok(serde_json::json!({
+                "resources": {"core": bucket(5000), "search": bucket(30), "graphql": bucket(5000)}
+            }))
+        }
+
+        fn user_teams(&self) -> Result<Vec<String>, String> {
+            Ok(self.teams.clone())
+        }
+
+        fn auth_status(&self) -> Result<(), String> {
+            if self.logged_out.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err("You are not logged into any GitHub hosts".to_string());
+            }
+            Ok(())
+        }
+
+        fn api(&self, path: &str) -> Result<Value, String> {
+            self.api
+                .lock()
+                .unwrap()
+                .get(path)
+                .cloned()
+                .ok_or_else(|| format!("gh api {} failed: no fixture", path))
+        }
+
+        fn api_pages(&self, path: &str) -> Result<Vec<Value>, String> {
+            self.api(path).map(|page| vec![page])
+        }
+
+        fn rerun_failed_jobs(&self, _repo: &str, run_id: &str) -> Result<(), String> {
+            self.reruns.lock().unwrap().push(run_id.to_string());
+            Ok(())
+        }
+
+        fn completed_runs(&self, _repo: &str, _limit: usize) -> Result<Value, String> {
+            Ok(Value::Array(vec![]))
+        }
+
+        fn failed_run_log(&self, _repo: &str, _run_id: &str, _attempt: i64) -> Result<String, String> {
+            Ok(String::new())
+        }
+
+        fn edit_labels(&self, _repo: &str, _number: i32, _add: &[String], _remove: &[String]) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn set_draft(&self, _repo: &str, _number: i32, _draft: bool) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn repo_labels(&self, _repo: &str) -> Result<Vec<String>, String> {
+            Ok(vec![])
+        }
+
+        fn update_branch(&self, _repo: &str, _number: i32) -> Result<(), String> {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rate_limit() {
+        let json = serde_json::json!({
+            "resources": {
+                "core": {"limit": 5000, "remaining": 4990, "used": 10, "reset": 1704067200},
+                "search": {"limit": 30, "remaining": 0, "used": 30, "reset": 1704067260},
+                "graphql": {"limit": 5000, "remaining": 4000, "reset": 1704070800}
+            },
+            "rate": {"limit": 5000, "remaining": 4990, "used": 10, "reset": 1704067200}
+        });
+
+        let status = parse_rate_limit(&json).unwrap();
+        assert_eq!(status.core.remaining, 4990);
+        assert_eq!(status.core.reset_at, "2024-01-01T00:00:00+00:00");
+        assert_eq!(status.search.remaining, 0);
+        assert_eq!(status.graphql.used, 0);
+        assert_eq!(status.graphql.reset_at, "2024-01-01T01:00:00+00:00");
+
+        let missing = serde_json::json!({"resources": {"core": json["resources"]["core"]}});
+        assert!(parse_rate_limit(&missing).unwrap_err().contains("search"));
+    }
+
+    #[test]
+    fn test_gh_invocations_are_per_thread() {
+        let before = gh_invocations();
+        gh_command();
+        // Calls on other threads (monitors, background checks) don't count here
+        thread::spawn(|| {
+            gh_command();
+        })
+        .join()
+        .unwrap();
+        assert_eq!(gh_invocations() - before, 1);
+    }
+
+    #[test]
+    fn test_parse_thread_counts() {
+        let repo_data = serde_json::json!({
+            "pr1": {
+                "comments": {"totalCount": 3},
+                "reviewThreads": {
+                    "totalCount": 3,
+                    "nodes": [{"isResolved": false}, {"isResolved": true}, {"isResolved": false}]
+                }
+            },
+            "pr2": null
+        });
+
+        let counts = parse_thread_counts(&repo_data, &[1, 2, 3]);
+        assert_eq!(counts.get(&1), Some(&(6, 2)));
+        assert_eq!(counts.len(), 1);
+    }
+}
@@ -2,22 +2,45 @@
 #[macro_use]
 extern crate objc;
 
+// db, github and process are public for the subprocess tests in tests/
 mod api;
-mod db;
+mod approvals;
+mod board;
+mod branch_update;
+mod checks;
+mod comment_refresh;
+mod connectivity;
+pub mod db;
+mod demo;
+mod deep_link;
 mod dock;
+mod environment;
+pub mod github;
 mod hotkey;
 mod monitor;
+mod monitor_queue;
+mod monitor_ticks;
 mod notifications;
-mod process;
+mod palette;
+mod pr_edit;
+mod pr_events;
+pub mod process;
+mod rate_usage;
 mod settings;
 mod sleep_prevention;
+mod stacks;
+mod staleness;
+mod timeline;
+mod throttle;
 mod tray;
+mod verify_clone;
+mod wake;
 
 use db::AppState;
 use chrono::Utc;
+use github::GithubClient;
 use serde::{Deserialize, Serialize};
-use std::process::Command;
-use tauri::{Manager, State};
+use tauri::{Emitter, Manager, State};
 
 /// PR data returned from GitHub CLI
 #[derive(Debug, Serialize, Deserialize)]
@@ -30,9 +53,24 @@ pub struct GitHubPR {
     pub is_draft: bool,
     pub author: Author,
     pub head_ref_name: String,
+    #[serde(default)]
+    pub head_ref_oid: Option<String>,
+    #[serde(default)]
+    pub head_repository: Option<HeadRepository>,
+    #[serde(default)]
+    pub head_repository_owner: Option<Author>,
+    #[serde(default)]
+    pub is_cross_repository: bool,
     pub base_ref_name: String,
     pub labels: Vec<Label>,
+    #[serde(default)]
+    pub review_requests: Vec<ReviewRequest>,
     pub review_decision: Option<String>,
+    /// Each reviewer's most recent review
+    #[serde(default)]
+    pub latest_reviews: Vec<LatestReview>,
+    /// None when gh reports no rollup (`null`, e.g. a PR without commits)
+    #[serde(default, deserialize_with = "deserialize_rollup")]
     pub status_check_rollup: Option<Vec<StatusCheck>>,
     pub mergeable: Option<String>,
     pub created_at: String,
@@ -44,16 +82,51 @@ pub struct Author {
     pub login: String,
 }
 
+/// Repository holding a PR's head branch (the fork, for cross-repository PRs)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HeadRepository {
+    pub name: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Label {
     pub name: String,
 }
 
+/// Requested reviewer (users have a login; teams have an `org/slug` slug instead)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReviewRequest {
+    #[serde(default)]
+    pub login: Option<String>,
+    #[serde(default)]
+    pub slug: Option<String>,
+}
+
+/// A reviewer's most recent review (`author` is null for deleted accounts)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LatestReview {
+    #[serde(default)]
+    pub author: Option<Author>,
+    pub state: String,
+}
+
+/// Read a status check rollup, dropping `null` entries instead of failing the whole PR
+fn deserialize_rollup<'de, D>(deserializer: D) -> Result<Option<Vec<StatusCheck>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let rollup: Option<Vec<Option<StatusCheck>>> = Option::deserialize(deserializer)?;
+    Ok(rollup.map(|checks| checks.into_iter().flatten().collect()))
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StatusCheck {
     pub state: Option<String>,
     pub status: Option<String>,
     pub conclusion: Option<String>,
+    /// Only `GithubClient::required_checks` asks GitHub for it; unknown counts as not required
+    #[serde(default, rename = "isRequired")]
+    pub is_required: Option<bool>,
 }
 
 /// Normalized PR data for the frontend
@@ -69,14 +142,43 @@ pub struct PR {
     pub state: String,
     pub is_draft: bool,
     pub ci_status: Option<String>,
+    /// Set while ci_status is a manual override (`set_pr_ci_override`); fetches
+    /// leave it alone until then
+    pub ci_override_until: Option<String>,
     pub ci_url: Option<String>,
     pub review_status: String,
     pub reviewers: Vec<String>,
+    /// Teams asked to review (`org/slug`), kept apart from individual reviewers
+    pub reviewer_teams: Vec<String>,
     pub comments_count: i32,
     pub unresolved_threads: i32,
     pub labels: Vec<String>,
     pub branch: String,
     pub base_branch: String,
+    pub head_sha: Option<String>,
+    /// `owner/name` of the repository the head branch lives in
+    pub head_repo: Option<String>,
+    /// The head branch is in a fork rather than the PR's own repository
+    pub is_fork: bool,
+    /// PR this one is stacked on (its base branch is that PR's head branch)
+    pub parent_pr_id: Option<String>,
+    /// Set when the parent was merged/closed and this PR needs rebasing
+    pub needs_rebase: bool,
+    /// When my review was requested (first seen), if it's still pending
+    pub review_requested_at: Option<String>,
+    /// Hours my requested review has been waiting, computed at read time
+    pub waiting_hours: Option<f64>,
+    /// Author matches the `muted_authors` setting; hidden from the board by default
+    pub muted: bool,
+    /// Moved off the board by the done-column retention job
+    pub archived: bool,
+    /// Whether the fetch saw me as a requested reviewer (None if unknown).
+    /// Requests to one of my teams are added when the PR is cached.
+    #[serde(skip)]
+    pub review_requested_from_me: Option<bool>,
+    /// Logins whose latest review requested changes
+    #[serde(skip)]
+    pub changes_requested_by: Vec<String>,
     pub created_at: String,
     pub updated_at: String,
     pub category: String,
@@ -99,48 +201,54 @@ fn parse_repo_path(input: &str) -> String {
     input.to_string()
 }
 
+/// Quote an argument for display in a copy-pasteable shell command
+fn shell_quote(arg: &str) -> String {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:@,=#".contains(c))
+    {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
 /// Fetch PRs from GitHub without DB access (pure network call)
 /// Used to avoid holding DB lock during network I/O
-fn fetch_prs_from_github(repo_path: &str, last_fetch: &Option<String>) -> Result<Vec<PR>, String> {
-    // Build search query with optional updated filter
-    let search_query = match last_fetch {
-        Some(ts) => format!("involves:@me updated:>={}", ts),
-        None => "involves:@me".to_string(),
-    };
-
-    let args = vec![
-        "pr",
-        "list",
-        "--json",
-        "number,title,url,state,isDraft,author,headRefName,baseRefName,labels,reviewDecision,statusCheckRollup,mergeable,createdAt,updatedAt",
-        "--limit",
-        "50",
-        "--repo",
-        repo_path,
-        "--state",
-        "open",
-        "--search",
-        &search_query,
-    ];
-
-    let output = Command::new("gh")
-        .args(&args)
-        .output()
-        .map_err(|e| format!("Failed to execute gh CLI: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("gh CLI error for {}: {}", repo_path, stderr));
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let github_prs: Vec<GitHubPR> =
-        serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse JSON: {}", e))?;
-
+fn fetch_prs_from_github(
+    github: &dyn GithubClient,
+    repo_path: &str,
+    last_fetch: &Option<String>,
+) -> Result<Vec<PR>, String> {
+    let github_prs = github.list_prs(repo_path, last_fetch)?;
+    let me = github.current_user();
     // Transform GitHub PRs to our normalized format
     let prs: Vec<PR> = github_prs
         .into_iter()
         .map(|gh_pr| {
+            let review_requested_from_me = me.as_deref().map(|me| {
+                gh_pr
+                    .review_requests
+                    .iter()
+                    .any(|r| r.login.as_deref() == Some(me))
+            });
+            let reviewer_teams = gh_pr
+                .review_requests
+                .iter()
+                .filter(|r| r.login.is_none())
+                .filter_map(|r| r.slug.clone())
+                .collect();
+            let changes_requested_by = gh_pr
+                .latest_reviews
+                .iter()
+                .filter(|review| review.state == "CHANGES_REQUESTED")
+                .filter_map(|review| review.author.as_ref().map(|author| author.login.clone()))
+                .collect();
+            let head_repo = head_repo_path(
+                gh_pr.head_repository_owner.as_ref().map(|owner| owner.login.as_str()),
+                gh_pr.head_repository.as_ref().map(|repo| repo.name.as_str()),
+            );
             let ci_status = determine_ci_status(&gh_pr.status_check_rollup);
             let review_status = determine_review_status(&gh_pr.review_decision, &gh_pr.mergeable);
             let category = determine_category(&gh_pr.state, false);
@@ -155,14 +263,27 @@ fn fetch_prs_from_github(repo_path: &str, last_fetch: &Option<String>) -> Result
                 state: gh_pr.state.to_lowercase(),
                 is_draft: gh_pr.is_draft,
                 ci_status,
+                ci_override_until: None,
                 ci_url: None,
                 review_status,
                 reviewers: vec![],
+                reviewer_teams,
                 comments_count: 0,
                 unresolved_threads: 0,
                 labels: gh_pr.labels.into_iter().map(|l| l.name).collect(),
                 branch: gh_pr.head_ref_name,
                 base_branch: gh_pr.base_ref_name,
+                head_sha: gh_pr.head_ref_oid,
+                head_repo,
+                is_fork: gh_pr.is_cross_repository,
+                parent_pr_id: None,
+                needs_rebase: false,
+                review_requested_at: None,
+                waiting_hours: None,
+                muted: false,
+                archived: false,
+                review_requested_from_me,
+                changes_requested_by,
                 created_at: gh_pr.created_at,
                 updated_at: gh_pr.updated_at,
                 category,
@@ -173,30 +294,84 @@ fn fetch_prs_from_github(repo_path: &str, last_fetch: &Option<String>) -> Result
     Ok(prs)
 }
 
-/// Cache a PR in the database
-fn cache_pr(conn: &rusqlite::Connection, pr: &PR) -> rusqlite::Result<()> {
+/// Fill in comment and unresolved-thread counts. PRs unchanged since they were
+/// cached keep their cached counts; the rest are counted with batched GraphQL
+/// queries (no DB lock held while those run).
+fn fill_thread_counts(state: &AppState, repo_path: &str, prs: &mut [PR]) {
+    let cached = db::get_cached_thread_counts(&state.conn(), repo_path).unwrap_or_default();
+
+    let mut to_count = Vec::new();
+    for pr in prs.iter_mut() {
+        match cached.get(&pr.id) {
+            Some((updated_at, comments_count, unresolved_threads)) => {
+                pr.comments_count = *comments_count;
+                pr.unresolved_threads = *unresolved_threads;
+                if *updated_at != pr.updated_at {
+                    to_count.push(pr.number);
+                }
+            }
+            None => to_count.push(pr.number),
+        }
+    }
+    if to_count.is_empty() {
+        return;
+    }
+
+    let counts = state.github.thread_counts(repo_path, &to_count);
+    for pr in prs.iter_mut() {
+        if let Some((comments_count, unresolved_threads)) = counts.get(&pr.number) {
+            pr.comments_count = *comments_count;
+            pr.unresolved_threads = *unresolved_threads;
+        }
+    }
+}
+
+/// Cache a PR in the database.
+/// Returns the previous head SHA if the PR's head commit changed since the last fetch.
+fn cache_pr(conn: &rusqlite::Connection, pr: &PR) -> rusqlite::Result<Option<String>> {
+    let previous_sha = match &pr.head_sha {
+        Some(sha) => db::record_head_sha(conn, &pr.id, sha)?,
+        None => None,
+    };
+
     conn.execute(
         r#"
         INSERT INTO pr_cache (
             id, number, repo, title, url, author, state, is_draft,
             ci_status, ci_url, review_status, reviewers, comments_count,
             unresolved_threads, labels, branch, base_branch, created_at,
-            updated_at, column_assignment, cached_at
+            updated_at, column_assignment, head_sha, reviewer_teams, head_repo, is_fork,
+            changes_requested_by, cached_at
         ) VALUES (
             ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
-            ?14, ?15, ?16, ?17, ?18, ?19, ?20, datetime('now')
+            ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, datetime('now')
         )
         ON CONFLICT(id) DO UPDATE SET
             title = excluded.title,
             state = excluded.state,
             is_draft = excluded.is_draft,
-            ci_status = excluded.ci_status,
+            -- A manual override (db::set_ci_override) holds until it expires
+            ci_status = CASE WHEN datetime(pr_cache.ci_override_until) > datetime('now')
+                             THEN pr_cache.ci_status ELSE excluded.ci_status END,
+            ci_status_fetched = CASE WHEN datetime(pr_cache.ci_override_until) > datetime('now')
+                                     THEN excluded.ci_status END,
+            ci_override_until = CASE WHEN datetime(pr_cache.ci_override_until) > datetime('now')
+                                     THEN pr_cache.ci_override_until END,
             ci_url = excluded.ci_url,
             review_status = excluded.review_status,
             reviewers = excluded.reviewers,
+            reviewer_teams = excluded.reviewer_teams,
             comments_count = excluded.comments_count,
+            unresolved_threads = excluded.unresolved_threads,
             labels = excluded.labels,
             updated_at = excluded.updated_at,
+            head_sha = COALESCE(excluded.head_sha, pr_cache.head_sha),
+            head_repo = excluded.head_repo,
+            is_fork = excluded.is_fork,
+            changes_requested_by = excluded.changes_requested_by,
+            -- A reopened PR comes back to the board and can be archived again later
+            archived = CASE WHEN excluded.state = 'open' THEN 0 ELSE pr_cache.archived END,
+            archived_at = CASE WHEN excluded.state = 'open' THEN NULL ELSE pr_cache.archived_at END,
             cached_at = datetime('now')
         "#,
         rusqlite::params![
@@ -220,55 +395,91 @@ fn cache_pr(conn: &rusqlite::Connection, pr: &PR) -> rusqlite::Result<()> {
             pr.created_at,
             pr.updated_at,
             pr.category,
+            pr.head_sha,
+            serde_json::to_string(&pr.reviewer_teams).unwrap_or_else(|_| "[]".to_string()),
+            pr.head_repo,
+            pr.is_fork,
+            serde_json::to_string(&pr.changes_requested_by).unwrap_or_else(|_| "[]".to_string()),
         ],
     )?;
-    Ok(())
+
+    let my_teams = db::get_my_teams(conn);
+    let team_requested = pr
+        .reviewer_teams
+        .iter()
+        .any(|team| my_teams.contains(&team.to_lowercase()));
+    let requested = match pr.review_requested_from_me {
+        Some(me) => Some(me || team_requested),
+        None => Some(true).filter(|_| team_requested),
+    };
+    if let Some(requested) = requested {
+        db::record_review_request(conn, &pr.id, requested)?;
+    }
+
+    Ok(previous_sha)
+}
+
+/// `owner/name` from gh's headRepositoryOwner and headRepository (None if either is missing)
+pub(crate) fn head_repo_path(owner: Option<&str>, name: Option<&str>) -> Option<String> {
+    match (owner, name) {
+        (Some(owner), Some(name)) if !owner.is_empty() && !name.is_empty() => {
+            Some(format!("{}/{}", owner, name))
+        }
+        _ => None,
+    }
+}
+
+/// Hours elapsed since an RFC 3339 timestamp
+fn hours_since(timestamp: &str) -> Option<f64> {
+    let at = chrono::DateTime::parse_from_rfc3339(timestamp).ok()?;
+    let minutes = Utc::now().signed_duration_since(at).num_minutes();
+    Some((minutes.max(0) as f64 / 60.0 * 10.0).round() / 10.0)
 }
 
-/// Get cached PRs for a specific repo
-fn get_cached_prs_for_repo(conn: &rusqlite::Connection, repo: &str) -> Result<Vec<PR>, String> {
+/// Whether a head change from `old_sha` to `new_sha` rewrote history.
+/// A plain push leaves the new head "ahead" of the old one; anything else
+/// (diverged, behind, or the old commit no longer existing) counts as a force-push.
+fn is_force_push(github: &dyn GithubClient, repo: &str, old_sha: &str, new_sha: &str) -> bool {
+    github
+        .compare_status(repo, old_sha, new_sha)
+        .is_none_or(|status| status != "ahead")
+}
+
+/// Get cached PRs for a specific repo; PRs from muted authors are skipped
+/// unless `include_muted` is set
+fn get_cached_prs_for_repo(
+    conn: &rusqlite::Connection,
+    repo: &str,
+    include_muted: bool,
+) -> Result<Vec<PR>, String> {
+    query_cached_prs(
+        conn,
+        "repo = ?1 AND archived = 0 AND (?2 OR muted = 0) ORDER BY updated_at DESC",
+        rusqlite::params![repo, include_muted],
+    )
+}
+
+/// Columns read by `cached_pr_from_row`, in order
+const CACHED_PR_COLUMNS: &str = "id, number, repo, title, url, author, state, is_draft,
+    ci_status, ci_url, review_status, reviewers, comments_count,
+    unresolved_threads, labels, branch, base_branch, created_at,
+    updated_at, column_assignment, head_sha, parent_pr_id,
+    needs_rebase, review_requested_at, muted, archived, reviewer_teams, head_repo, is_fork,
+    CASE WHEN datetime(ci_override_until) > datetime('now') THEN ci_override_until END,
+    changes_requested_by";
+
+/// Read cached PRs matching a WHERE clause (which may end in ORDER BY/LIMIT)
+fn query_cached_prs(
+    conn: &rusqlite::Connection,
+    filter: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<PR>, String> {
     let mut stmt = conn
-        .prepare(
-            r#"
-            SELECT id, number, repo, title, url, author, state, is_draft,
-                   ci_status, ci_url, review_status, reviewers, comments_count,
-                   unresolved_threads, labels, branch, base_branch, created_at,
-                   updated_at, column_assignment
-            FROM pr_cache
-            WHERE repo = ?1
-            ORDER BY updated_at DESC
-            "#,
-        )
+        .prepare(&format!("SELECT {} FROM pr_cache WHERE {}", CACHED_PR_COLUMNS, filter))
         .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
     let prs = stmt
-        .query_map([repo], |row| {
-            let reviewers_json: String = row.get(11)?;
-            let labels_json: String = row.get(14)?;
-
-            Ok(PR {
-                id: row.get(0)?,
-                number: row.get(1)?,
-                repo: row.get(2)?,
-                title: row.get(3)?,
-                url: row.get(4)?,
-                author: row.get(5)?,
-                state: row.get(6)?,
-                is_draft: row.get::<_, i32>(7)? != 0,
-                ci_status: row.get(8)?,
-                ci_url: row.get(9)?,
-                review_status: row.get(10)?,
-                reviewers: serde_json::from_str(&reviewers_json).unwrap_or_default(),
-                comments_count: row.get(12)?,
-                unresolved_threads: row.get(13)?,
-                labels: serde_json::from_str(&labels_json).unwrap_or_default(),
-                branch: row.get(15)?,
-                base_branch: row.get(16)?,
-                created_at: row.get(17)?,
-                updated_at: row.get(18)?,
-                category: row.get(19)?,
-            })
-        })
+        .query_map(params, cached_pr_from_row)
         .map_err(|e| format!("Query failed: {}", e))?
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| format!("Failed to read rows: {}", e))?;
@@ -276,19 +487,71 @@ fn get_cached_prs_for_repo(conn: &rusqlite::Connection, repo: &str) -> Result<Ve
     Ok(prs)
 }
 
+fn cached_pr_from_row(row: &rusqlite::Row) -> rusqlite::Result<PR> {
+    let reviewers_json: String = row.get(11)?;
+    let labels_json: String = row.get(14)?;
+    let review_requested_at: Option<String> = row.get(23)?;
+    let reviewer_teams_json: String = row.get(26)?;
+    let changes_requested_by_json: String = row.get(30)?;
+
+    Ok(PR {
+        id: row.get(0)?,
+        number: row.get(1)?,
+        repo: row.get(2)?,
+        title: row.get(3)?,
+        url: row.get(4)?,
+        author: row.get(5)?,
+        state: row.get(6)?,
+        is_draft: row.get::<_, i32>(7)? != 0,
+        ci_status: row.get(8)?,
+        ci_override_until: row.get(29)?,
+        ci_url: row.get(9)?,
+        review_status: row.get(10)?,
+        reviewers: serde_json::from_str(&reviewers_json).unwrap_or_default(),
+        reviewer_teams: serde_json::from_str(&reviewer_teams_json).unwrap_or_default(),
+        comments_count: row.get(12)?,
+        unresolved_threads: row.get(13)?,
+        labels: serde_json::from_str(&labels_json).unwrap_or_default(),
+        branch: row.get(15)?,
+        base_branch: row.get(16)?,
+        head_sha: row.get(20)?,
+        head_repo: row.get(27)?,
+        is_fork: row.get(28)?,
+        parent_pr_id: row.get(21)?,
+        needs_rebase: row.get::<_, i32>(22)? != 0,
+        waiting_hours: review_requested_at.as_deref().and_then(hours_since),
+        review_requested_at,
+        muted: row.get::<_, i32>(24)? != 0,
+        archived: row.get::<_, i32>(25)? != 0,
+        review_requested_from_me: None,
+        changes_requested_by: serde_json::from_str(&changes_requested_by_json).unwrap_or_default(),
+        created_at: row.get(17)?,
+        updated_at: row.get(18)?,
+        category: row.get(19)?,
+    })
+}
+
+/// What `fetch_prs` returns
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FetchPrsResult {
+    prs: Vec<PR>,
+    /// GitHub was unreachable, so `prs` is the cached data
+    offline: bool,
+}
+
 /// Fetch PRs from GitHub using gh CLI with incremental caching
 /// Supports single repo (repo param) or multiple repos (repos param)
 /// Set force_refresh=true to bypass cache and fetch all PRs
 #[tauri::command]
 fn fetch_prs(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     repo: Option<String>,
     repos: Option<Vec<String>>,
     force_refresh: Option<bool>,
-) -> Result<Vec<PR>, String> {
-    let force = force_refresh.unwrap_or(false);
-
-    // Determine which repos to fetch from (brief lock)
+) -> Result<FetchPrsResult, String> {
+    // Determine which repos to fetch from
     let repos_to_fetch: Vec<String> = {
         match (repos, repo) {
             // Multiple repos specified
@@ -297,7 +560,7 @@ fn fetch_prs(
             (_, Some(r)) if !r.is_empty() => vec![r],
             // No repos - try to get current repo from working directory
             _ => {
-                if let Some(current) = get_current_repo() {
+                if let Some(current) = state.github.current_repo() {
                     vec![current]
                 } else {
                     return Err("No repository specified and not in a git repository".to_string());
@@ -306,10 +569,95 @@ fn fetch_prs(
         }
     };
 
+    // Offline, or (unless forced) down to the rate-limit reserve: serve the cache
+    // rather than spawning gh calls that can only time out or eat the budget.
+    // Close to the reserve, a background refresh waits its turn first.
+    let force_refresh = force_refresh.unwrap_or(false);
+    let offline = !connectivity::is_online();
+    let throttle = if offline || force_refresh {
+        throttle::Throttle::Proceed
+    } else {
+        throttle::check(&state, "PR refresh")
+    };
+    if let throttle::Throttle::Delay(delay) = throttle {
+        std::thread::sleep(delay);
+    }
+    if offline || throttle == throttle::Throttle::Defer {
+        let conn = state.conn();
+        let mut cached = Vec::new();
+        for repo in &repos_to_fetch {
+            cached.extend(get_cached_prs_for_repo(&conn, &parse_repo_path(repo), false)?);
+        }
+        return Ok(FetchPrsResult { prs: cached, offline });
+    }
+
+    // Counted on this thread, so calls other threads make meanwhile aren't included
+    let gh_calls_before = github::gh_invocations();
+    let outcome = fetch_prs_internal(&state, &repos_to_fetch, force_refresh)?;
+
+    pr_events::emit_changes(&app, &outcome.changes);
+    approvals::handle_changes(&app, &outcome.changes);
+    notifications::notify_review_requests(&app, &outcome.changes);
+    {
+        // PR states changed, so an attention/unread badge may need updating
+        let conn = state.conn();
+        emit_unread_changed(&app, &conn);
+    }
+
+    // Phase 4: React to pushes a monitor didn't make, and to force-pushes
+    // (network calls, no lock held)
+    for (pr_id, pr_number, repo, previous_sha, head_sha) in outcome.head_changes {
+        monitor::check_external_push(&app, &state, &pr_id, &head_sha);
+
+        if !is_force_push(state.github.as_ref(), &repo, &previous_sha, &head_sha) {
+            continue;
+        }
+
+        let refetch_comments = {
+            let conn = state.conn();
+            if let Err(e) = db::mark_comments_stale(&conn, &pr_id) {
+                eprintln!("Failed to mark comments stale for {}: {}", pr_id, e);
+            }
+            db::get_refetch_comments_on_force_push(&conn)
+        };
+
+        let _ = app.emit(
+            "pr:force-pushed",
+            serde_json::json!({
+                "prId": pr_id,
+                "previousSha": previous_sha,
+                "headSha": head_sha,
+            }),
+        );
+
+        if refetch_comments {
+            if let Err(e) = monitor::fetch_pr_comments(app.clone(), app.state::<AppState>(), pr_number, repo) {
+                eprintln!("Failed to refetch comments for {}: {}", pr_id, e);
+            }
+        }
+    }
+
+    rate_usage::record_cycle(&app, github::gh_invocations() - gh_calls_before);
+
+    Ok(FetchPrsResult { prs: outcome.prs, offline: false })
+}
+
+/// What a `fetch_prs_internal` run found, for the command to report
+struct FetchOutcome {
+    /// Cached PRs of the fetched repos, merged/closed ones included
+    prs: Vec<PR>,
+    changes: pr_events::PrChanges,
+    /// (pr_id, number, repo, previous_sha, head_sha) of PRs whose head moved
+    head_changes: Vec<(String, i32, String, String, String)>,
+}
+
+/// Fetch and cache PRs for the given repos: everything `fetch_prs` does that
+/// doesn't need an AppHandle (events and force-push handling happen in the command)
+fn fetch_prs_internal(state: &AppState, repos: &[String], force: bool) -> Result<FetchOutcome, String> {
     // Phase 1: Get last_fetch timestamps (brief lock, release before network)
     let fetch_metadata: Vec<(String, Option<String>)> = {
-        let conn = state.db.lock().map_err(|e| format!("DB lock error: {}", e))?;
-        repos_to_fetch
+        let conn = state.conn();
+        repos
             .iter()
             .map(|r| {
                 let repo_path = parse_repo_path(r);
@@ -324,31 +672,55 @@ fn fetch_prs(
     }; // Lock released here
 
     // Phase 2: Fetch from GitHub (NO lock held during network calls)
-    let mut fetched_data: Vec<(String, Option<String>, Vec<PR>)> = Vec::new();
+    let mut fetched_data: Vec<(String, Option<String>, Vec<PR>, i64)> = Vec::new();
+    let mut failed: Vec<(String, String)> = Vec::new();
     for (repo_path, last_fetch) in fetch_metadata {
-        match fetch_prs_from_github(&repo_path, &last_fetch) {
-            Ok(prs) => fetched_data.push((repo_path, last_fetch, prs)),
+        let started = std::time::Instant::now();
+        match fetch_prs_from_github(state.github.as_ref(), &repo_path, &last_fetch) {
+            Ok(mut prs) => {
+                fill_thread_counts(state, &repo_path, &mut prs);
+                let duration_ms = started.elapsed().as_millis() as i64;
+                fetched_data.push((repo_path, last_fetch, prs, duration_ms))
+            }
             Err(e) => {
                 eprintln!("Failed to fetch PRs from {}: {}", repo_path, e);
+                failed.push((repo_path, e));
             }
         }
     }
 
     // Phase 3: Save to database and collect results (re-acquire lock)
-    let conn = state.db.lock().map_err(|e| format!("DB lock error: {}", e))?;
+    let conn = state.conn();
+    let now = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    for (repo_path, error) in &failed {
+        if let Err(e) = db::record_fetch_error(&conn, repo_path, error, &now) {
+            eprintln!("Failed to record fetch error: {}", e);
+        }
+    }
     let mut all_prs: Vec<PR> = Vec::new();
+    let mut head_changes: Vec<(String, i32, String, String, String)> = Vec::new();
+    let fetched_repos: Vec<String> = fetched_data.iter().map(|(repo, ..)| repo.clone()).collect();
+    let before = pr_events::snapshot_repos(&conn, &fetched_repos);
 
-    for (repo_path, last_fetch, prs) in fetched_data {
+    for (repo_path, last_fetch, prs, duration_ms) in fetched_data {
         // Cache PRs in database
         for pr in &prs {
-            if let Err(e) = cache_pr(&conn, pr) {
-                eprintln!("Failed to cache PR: {}", e);
+            match cache_pr(&conn, pr) {
+                Ok(Some(previous_sha)) => head_changes.push((
+                    pr.id.clone(),
+                    pr.number,
+                    pr.repo.clone(),
+                    previous_sha,
+                    pr.head_sha.clone().unwrap_or_default(),
+                )),
+                Ok(None) => {}
+                Err(e) => eprintln!("Failed to cache PR: {}", e),
             }
         }
 
         // Update last fetch timestamp
         let now = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
-        if let Err(e) = db::set_last_fetch(&conn, &repo_path, &now, prs.len() as i32) {
+        if let Err(e) = db::set_last_fetch(&conn, &repo_path, &now, prs.len() as i32, duration_ms) {
             eprintln!("Failed to update fetch metadata: {}", e);
         }
 
@@ -360,13 +732,20 @@ fn fetch_prs(
             match db::get_stale_pr_ids(&conn, &repo_path, &active_ids) {
                 Ok(stale_prs) if !stale_prs.is_empty() => {
                     println!("Found {} potentially merged/closed PRs, batch-checking status...", stale_prs.len());
-                    let results = batch_check_pr_states(&repo_path, &stale_prs);
-                    for (pr_id, pr_number, state, _) in results {
-                        let category = determine_category(&state, false);
-                        if let Err(e) = db::update_pr_state(&conn, &pr_id, &state, &category) {
+                    let numbers: Vec<i32> = stale_prs.iter().map(|(_, number)| *number).collect();
+                    let states = state.github.pr_states(&repo_path, &numbers);
+                    for (pr_id, pr_number) in stale_prs {
+                        let Some((pr_state, merged_at)) = states.get(&pr_number) else {
+                            continue;
+                        };
+                        let category = determine_category(pr_state, false);
+                        if let Err(e) = db::update_pr_state(&conn, &pr_id, pr_state, &category) {
                             eprintln!("Failed to update PR state: {}", e);
                         } else {
-                            println!("Updated PR #{} to state: {} (category: {})", pr_number, state, category);
+                            if let Some(merged_at) = merged_at {
+                                db::set_pr_merged_at(&conn, &pr_id, merged_at).ok();
+                            }
+                            println!("Updated PR #{} to state: {} (category: {})", pr_number, pr_state, category);
                         }
                     }
                 }
@@ -375,31 +754,140 @@ fn fetch_prs(
             }
         }
 
+        dismiss_expired_merged_prs(&conn, &repo_path);
+
+        if let Err(e) = db::refresh_muted_prs(&conn) {
+            eprintln!("Failed to apply muted authors: {}", e);
+        }
+
+        if let Err(e) = db::link_stacked_prs(&conn, &repo_path) {
+            eprintln!("Failed to link stacked PRs for {}: {}", repo_path, e);
+        }
+
         // Always return from cache so merged/closed PRs are included
-        match get_cached_prs_for_repo(&conn, &repo_path) {
+        match get_cached_prs_for_repo(&conn, &repo_path, false) {
             Ok(cached) => all_prs.extend(cached),
             Err(e) => eprintln!("Failed to get cached PRs: {}", e),
         }
     }
 
-    Ok(all_prs)
+    if let Err(e) = db::apply_done_retention(
+        &conn,
+        db::get_done_archive_after_days(&conn),
+        db::get_done_delete_after_days(&conn),
+    ) {
+        eprintln!("Failed to apply done-column retention: {}", e);
+    }
+
+    let after = pr_events::snapshot_repos(&conn, &fetched_repos);
+    Ok(FetchOutcome {
+        prs: all_prs,
+        changes: pr_events::diff(&before, &after),
+        head_changes,
+    })
+}
+
+/// PRs from a one-off look at a repo (see `ad_hoc_fetch`)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AdHocFetchResult {
+    repo: String,
+    prs: Vec<PR>,
+    /// Whether the repo is also on the board; the PRs returned here are never cached either way
+    tracked: bool,
+    fetched_at: String,
+}
+
+/// Fetch a repo's open PRs for a quick look without adding the repo to the
+/// board: nothing is written to the repos setting or the PR cache
+#[tauri::command]
+fn ad_hoc_fetch(state: State<'_, AppState>, repo: String) -> Result<AdHocFetchResult, String> {
+    let repo_path = parse_repo_path(repo.trim());
+    if repo_path.split('/').filter(|part| !part.is_empty()).count() != 2 {
+        return Err(format!("Invalid repository: {}", repo));
+    }
+
+    let tracked = settings::get_repos(state.clone())?
+        .iter()
+        .any(|configured| parse_repo_path(configured) == repo_path);
+
+    let mut prs = fetch_prs_from_github(state.github.as_ref(), &repo_path, &None)?;
+    fill_thread_counts(&state, &repo_path, &mut prs);
+    prs.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+
+    Ok(AdHocFetchResult {
+        repo: repo_path,
+        prs,
+        tracked,
+        fetched_at: Utc::now().to_rfc3339(),
+    })
+}
+
+/// Preview the gh command `fetch_prs` would run for a repo, without executing it
+#[tauri::command]
+fn preview_fetch_command(
+    state: State<'_, AppState>,
+    repo: String,
+    force_refresh: Option<bool>,
+) -> Result<String, String> {
+    let repo_path = parse_repo_path(&repo);
+
+    let last_fetch = if force_refresh.unwrap_or(false) {
+        None
+    } else {
+        let conn = state.conn();
+        db::get_last_fetch(&conn, &repo_path).ok().flatten()
+    };
+
+    let args = github::build_fetch_args(&repo_path, &last_fetch);
+    let quoted: Vec<String> = args.iter().map(|a| shell_quote(a)).collect();
+
+    Ok(format!("gh {}", quoted.join(" ")))
 }
 
-/// Get cached PRs without making network requests
+/// Get cached PRs without making network requests.
+/// `sort` is "updated" (default, most recently updated first per repo) or
+/// "waiting" (longest-waiting review requests first).
+/// PRs from muted authors are left out unless `include_muted` is true.
 #[tauri::command]
 fn get_cached_prs(
     state: State<'_, AppState>,
     repo: Option<String>,
     repos: Option<Vec<String>>,
+    sort: Option<String>,
+    include_muted: Option<bool>,
 ) -> Result<Vec<PR>, String> {
-    let conn = state.db.lock().map_err(|e| format!("DB lock error: {}", e))?;
+    let conn = state.conn();
+    let mut all_prs = cached_board_prs(&conn, repo, repos, include_muted.unwrap_or(false))?;
+
+    match sort.as_deref() {
+        None | Some("updated") => {}
+        Some("waiting") => all_prs.sort_by(|a, b| {
+            b.waiting_hours
+                .unwrap_or(-1.0)
+                .total_cmp(&a.waiting_hours.unwrap_or(-1.0))
+        }),
+        Some(other) => return Err(format!("Invalid sort: {}", other)),
+    }
+
+    Ok(all_prs)
+}
 
+/// Cached PRs of the given repos, or of every configured repo when none are given
+fn cached_board_prs(
+    conn: &rusqlite::Connection,
+    repo: Option<String>,
+    repos: Option<Vec<String>>,
+    include_muted: bool,
+) -> Result<Vec<PR>, String> {
     // Determine which repos to get from
     let repos_to_fetch: Vec<String> = match (repos, repo) {
         (Some(r), _) if !r.is_empty() => r,
         (_, Some(r)) if !r.is_empty() => vec![r],
         _ => {
-            // Get all repos from cache
+            // Get all configured repos from cache (removed repos may still have rows)
+            let configured =
+                db::get_configured_repos(conn).map_err(|e| format!("DB error: {}", e))?;
             let mut stmt = conn
                 .prepare("SELECT DISTINCT repo FROM pr_cache")
                 .map_err(|e| format!("DB error: {}", e))?;
@@ -407,6 +895,7 @@ fn get_cached_prs(
                 .query_map([], |row| row.get(0))
                 .map_err(|e| format!("Query error: {}", e))?
                 .filter_map(|r| r.ok())
+                .filter(|repo| configured.contains(repo))
                 .collect();
             repos
         }
@@ -415,7 +904,8 @@ fn get_cached_prs(
     let mut all_prs: Vec<PR> = Vec::new();
     for repo in repos_to_fetch {
         let repo_path = parse_repo_path(&repo);
-        match get_cached_prs_for_repo(&conn, &repo_path) {
+        dismiss_expired_merged_prs(conn, &repo_path);
+        match get_cached_prs_for_repo(conn, &repo_path, include_muted) {
             Ok(prs) => all_prs.extend(prs),
             Err(e) => eprintln!("Failed to get cached PRs for {}: {}", repo, e),
         }
@@ -424,172 +914,498 @@ fn get_cached_prs(
     Ok(all_prs)
 }
 
+/// Get cached PRs hidden from the board because their author is muted
+#[tauri::command]
+fn get_muted_prs(state: State<'_, AppState>, repo: String) -> Result<Vec<PR>, String> {
+    let conn = state.conn();
+    let prs = get_cached_prs_for_repo(&conn, &parse_repo_path(&repo), true)?;
+    Ok(prs.into_iter().filter(|pr| pr.muted).collect())
+}
+
+/// Get archived PRs for a repo, most recently updated first
+#[tauri::command]
+fn get_archived_prs(
+    state: State<'_, AppState>,
+    repo: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<PR>, String> {
+    let conn = state.conn();
+    query_cached_prs(
+        &conn,
+        "repo = ?1 AND archived = 1 ORDER BY updated_at DESC LIMIT ?2 OFFSET ?3",
+        rusqlite::params![
+            parse_repo_path(&repo),
+            limit.unwrap_or(50).clamp(1, 500),
+            offset.unwrap_or(0).max(0)
+        ],
+    )
+}
+
+/// Result of `get_changes_since`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChangesSince {
+    count: usize,
+    /// How many of `prs` were opened after the timestamp (the rest were updated)
+    opened: usize,
+    /// Most recently updated first
+    prs: Vec<PR>,
+}
+
+/// Cached board PRs GitHub reports as updated after `since` (RFC 3339), for a
+/// "what's new since I last checked" view
+fn changes_since(conn: &rusqlite::Connection, since: &str) -> Result<ChangesSince, String> {
+    let since = chrono::DateTime::parse_from_rfc3339(since)
+        .map_err(|e| format!("Invalid timestamp '{}': {}", since, e))?
+        .with_timezone(&chrono::Utc);
+    let prs = query_cached_prs(
+        conn,
+        "archived = 0 AND muted = 0 AND datetime(updated_at) > datetime(?1)
+         ORDER BY updated_at DESC",
+        [since.to_rfc3339()],
+    )?;
+    let opened = prs
+        .iter()
+        .filter(|pr| {
+            chrono::DateTime::parse_from_rfc3339(&pr.created_at).is_ok_and(|created| created > since)
+        })
+        .count();
+    Ok(ChangesSince {
+        count: prs.len(),
+        opened,
+        prs,
+    })
+}
+
+/// PRs that changed since a given time
+#[tauri::command]
+fn get_changes_since(state: State<'_, AppState>, timestamp: String) -> Result<ChangesSince, String> {
+    changes_since(&state.conn(), &timestamp)
+}
+
+/// Open PRs waiting on my review that I can act on now: I'm a requested
+/// reviewer (directly or through a team), CI is passing, I didn't open it and
+/// my latest review isn't a change request. Conflict-free PRs come first, then
+/// the ones that have waited longest.
+fn actionable_reviews(state: &AppState, repo: Option<&str>) -> Result<Vec<PR>, String> {
+    let me = state
+        .github
+        .current_user()
+        .ok_or("Could not determine your GitHub login; check `gh auth status`")?;
+    let candidates = query_cached_prs(
+        &state.conn(),
+        "review_requested_at IS NOT NULL AND state = 'open' AND is_draft = 0
+         AND ci_status = 'passing' AND archived = 0 AND muted = 0
+         AND lower(author) != lower(?1) AND (?2 IS NULL OR repo = ?2)",
+        rusqlite::params![me, repo],
+    )?;
+
+    let mut prs: Vec<PR> = candidates
+        .into_iter()
+        .filter(|pr| {
+            !pr.changes_requested_by
+                .iter()
+                .any(|login| login.eq_ignore_ascii_case(&me))
+        })
+        .collect();
+    prs.sort_by(|a, b| {
+        (a.review_status == "conflicts")
+            .cmp(&(b.review_status == "conflicts"))
+            .then(a.review_requested_at.cmp(&b.review_requested_at))
+    });
+    Ok(prs)
+}
+
+/// PRs waiting on my review that are ready for it, optionally for one repo
+#[tauri::command]
+fn get_actionable_reviews(state: State<'_, AppState>, repo: Option<String>) -> Result<Vec<PR>, String> {
+    let repo = repo.map(|r| parse_repo_path(&r));
+    actionable_reviews(&state, repo.as_deref())
+}
+
+/// Move an archived PR back onto the board. The retention job won't archive it again.
+#[tauri::command]
+fn unarchive_pr(app: tauri::AppHandle, state: State<'_, AppState>, pr_id: String) -> Result<(), String> {
+    let conn = state.conn();
+    let before = pr_events::snapshot_pr(&conn, &pr_id);
+    let updated = db::unarchive_pr(&conn, &pr_id)
+        .map_err(|e| format!("Failed to unarchive PR: {}", e))?;
+    if updated == 0 {
+        return Err(format!("PR not found in cache: {}", pr_id));
+    }
+    pr_events::emit_pr_diff(&app, &conn, &pr_id, &before);
+    emit_unread_changed(&app, &conn);
+    Ok(())
+}
+
+/// Open the app data directory (database, monitor logs, scripts) in the file manager.
+/// Returns its path.
+#[tauri::command]
+fn open_data_dir(app: tauri::AppHandle) -> Result<String, String> {
+    use tauri_plugin_opener::OpenerExt;
+
+    let dir = db::app_data_dir()?.to_string_lossy().to_string();
+    app.opener()
+        .open_path(dir.clone(), None::<&str>)
+        .map_err(|e| format!("Failed to open data directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Outcome of changing the data directory
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DataDirChange {
+    /// Directory the app will use from the next launch
+    data_dir: String,
+    /// The DB is opened at startup, so a new location only applies after a restart
+    restart_required: bool,
+}
+
+/// Set a custom data directory (None or empty restores the default). The
+/// directory must be writable; existing data is not moved. CLANKER_DATA_DIR,
+/// when set, still takes precedence.
+#[tauri::command]
+fn set_data_dir(path: Option<String>) -> Result<DataDirChange, String> {
+    let current = db::app_data_dir()?;
+
+    let dir = path
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .map(std::path::PathBuf::from);
+    if let Some(dir) = &dir {
+        if !dir.is_absolute() {
+            return Err(format!("Data directory must be an absolute path: {}", dir.display()));
+        }
+        db::ensure_dir_writable(dir)?;
+    }
+    db::set_configured_data_dir(dir)?;
+
+    let next = db::app_data_dir()?;
+    Ok(DataDirChange {
+        data_dir: next.to_string_lossy().to_string(),
+        restart_required: next != current,
+    })
+}
+
+/// Get unread PR counts (activity since last viewed), per repo and per category
+#[tauri::command]
+fn get_unread_counts(state: State<'_, AppState>) -> Result<db::UnreadCounts, String> {
+    let conn = state.conn();
+    db::get_unread_counts(&conn).map_err(|e| format!("Failed to count unread PRs: {}", e))
+}
+
+/// Mark a PR as seen (clears its unread state until new activity)
+#[tauri::command]
+fn mark_pr_seen(app: tauri::AppHandle, state: State<'_, AppState>, pr_id: String) -> Result<(), String> {
+    let conn = state.conn();
+    db::mark_pr_seen(&conn, &pr_id).map_err(|e| format!("Failed to mark PR seen: {}", e))?;
+    emit_unread_changed(&app, &conn);
+    Ok(())
+}
+
+/// Emit `unread:changed` and refresh the tray title and dock badge
+fn emit_unread_changed(app: &tauri::AppHandle, conn: &rusqlite::Connection) {
+    let counts = db::get_unread_counts(conn).unwrap_or_default();
+    tray::update_tray_unread(app, counts.total);
+    dock::set_dock_badge(dock::badge_count(
+        conn,
+        db::count_active_monitors(conn).unwrap_or(0),
+    ));
+    let _ = app.emit("unread:changed", counts);
+}
+
+/// Count PRs needing attention (changes requested, failing CI, or unresolved threads)
+#[tauri::command]
+fn get_attention_count(state: State<'_, AppState>, repo: Option<String>) -> Result<i32, String> {
+    let conn = state.conn();
+    let repo_path = repo.map(|r| parse_repo_path(&r));
+    db::get_attention_count(&conn, repo_path.as_deref())
+        .map_err(|e| format!("Failed to count PRs needing attention: {}", e))
+}
+
+/// Get per-repo fetch metadata (last sync time, PR count, duration)
+#[tauri::command]
+fn get_fetch_metadata(
+    state: State<'_, AppState>,
+    repo: Option<String>,
+) -> Result<Vec<db::FetchMetadata>, String> {
+    let conn = state.conn();
+    let repo_path = repo.as_deref().map(parse_repo_path);
+    db::get_fetch_metadata(&conn, repo_path.as_deref())
+        .map_err(|e| format!("Failed to get fetch metadata: {}", e))
+}
+
 /// Clear the PR cache
 #[tauri::command]
 fn clear_pr_cache(state: State<'_, AppState>, repo: Option<String>) -> Result<(), String> {
-    let conn = state.db.lock().map_err(|e| format!("DB lock error: {}", e))?;
+    let conn = state.conn();
     db::clear_pr_cache(&conn, repo.as_deref()).map_err(|e| format!("Failed to clear cache: {}", e))
 }
 
 /// Dismiss a PR (remove from the dashboard)
 #[tauri::command]
 fn dismiss_pr(state: State<'_, AppState>, pr_id: String) -> Result<(), String> {
-    let conn = state.db.lock().map_err(|e| format!("DB lock error: {}", e))?;
+    let conn = state.conn();
     db::dismiss_pr(&conn, &pr_id).map_err(|e| format!("Failed to dismiss PR: {}", e))?;
     Ok(())
 }
 
-fn get_current_repo() -> Option<String> {
-    let output = Command::new("gh")
-        .args(["repo", "view", "--json", "nameWithOwner", "-q", ".nameWithOwner"])
-        .output()
-        .ok()?;
+/// Dismiss all merged/closed PRs (optionally for one repo). Returns how many were removed.
+#[tauri::command]
+fn clear_done_prs(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    repo: Option<String>,
+) -> Result<usize, String> {
+    let conn = state.conn();
+    let repo_path = repo.map(|r| parse_repo_path(&r));
+    let snapshot = |conn: &rusqlite::Connection| match &repo_path {
+        Some(repo) => pr_events::snapshot_repos(conn, std::slice::from_ref(repo)),
+        None => pr_events::snapshot_all(conn),
+    };
 
-    if output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        Some(stdout.trim().to_string())
-    } else {
-        None
+    let before = snapshot(&conn);
+    let cleared = db::clear_done_prs(&conn, repo_path.as_deref())
+        .map_err(|e| format!("Failed to clear done PRs: {}", e))?;
+    pr_events::emit_changes(&app, &pr_events::diff(&before, &snapshot(&conn)));
+    Ok(cleared)
+}
+
+/// Apply the auto_dismiss_merged_after_minutes setting to a repo's cached PRs
+fn dismiss_expired_merged_prs(conn: &rusqlite::Connection, repo_path: &str) {
+    if let Some(minutes) = db::get_auto_dismiss_merged_after_minutes(conn) {
+        if let Err(e) = db::dismiss_expired_merged_prs(conn, repo_path, minutes) {
+            eprintln!("Failed to auto-dismiss merged PRs for {}: {}", repo_path, e);
+        }
     }
 }
 
-fn determine_ci_status(status_checks: &Option<Vec<StatusCheck>>) -> Option<String> {
-    let checks = status_checks.as_ref()?;
-    if checks.is_empty() {
-        return None;
+/// Remaining GitHub API budget (REST core, search, GraphQL) and reset times
+#[tauri::command]
+fn get_rate_limit(state: State<'_, AppState>) -> Result<github::RateLimitStatus, String> {
+    let status = github::parse_rate_limit(&state.github.rate_limit()?)?;
+    throttle::record(status.clone());
+    Ok(status)
+}
+
+/// Whether CI allows merging: neither the cached status nor a fresh look at
+/// the required checks may be failing or waiting on an action. The cached
+/// rollup can't tell required checks apart, so a cancelled required check
+/// only shows up in the fresh look.
+fn check_merge_ci(state: &AppState, repo: &str, number: i32, ci_status: Option<&str>) -> Result<(), String> {
+    let blocked = |status: Option<&str>| match status {
+        Some("failing") => Err("PR has failing CI checks".to_string()),
+        Some("action_required") => Err("PR has CI checks waiting for approval".to_string()),
+        _ => Ok(()),
+    };
+    blocked(ci_status)?;
+
+    let checks: Option<Vec<StatusCheck>> = state
+        .github
+        .required_checks(repo, number)
+        .and_then(|json| {
+            serde_json::from_value::<Vec<Option<StatusCheck>>>(json)
+                .map_err(|e| format!("Failed to parse checks: {}", e))
+        })
+        .map(|checks| Some(checks.into_iter().flatten().collect()))
+        .map_err(|e| format!("Couldn't confirm the required checks: {}", e))?;
+    blocked(determine_ci_status(&checks).as_deref())
+}
+
+/// Merge a PR via gh. Requires an approved review and non-failing CI unless `force` is set.
+/// `method` is "squash" | "merge" | "rebase"; defaults to the repo's merge_method setting.
+#[tauri::command]
+fn merge_pr(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    pr_id: String,
+    method: Option<String>,
+    delete_branch: bool,
+    force: Option<bool>,
+) -> Result<(), String> {
+    let (repo, number, review_status, ci_status, default_method) = {
+        let conn = state.conn();
+        let (repo, number, review_status, ci_status): (String, i32, String, Option<String>) = conn
+            .query_row(
+                &format!(
+                    "SELECT repo, number, review_status, {} FROM pr_cache WHERE id = ?1",
+                    db::FETCHED_CI_STATUS_SQL
+                ),
+                [&pr_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .map_err(|e| format!("PR not found in cache: {} ({})", pr_id, e))?;
+        let default_method = db::get_repo_merge_method(&conn, &repo);
+        (repo, number, review_status, ci_status, default_method)
+    };
+
+    if !force.unwrap_or(false) {
+        if review_status != "approved" {
+            return Err(format!("PR is not approved (review status: {})", review_status));
+        }
+        check_merge_ci(&state, &repo, number, ci_status.as_deref())?;
     }
 
-    // Check if any are failing (conclusion can be uppercase or lowercase)
-    let has_failure = checks.iter().any(|c| {
-        matches!(
-            c.conclusion.as_deref().map(|s| s.to_uppercase()).as_deref(),
-            Some("FAILURE")
-        )
-    });
-    if has_failure {
-        return Some("failing".to_string());
+    let method = method.unwrap_or(default_method);
+    if !matches!(method.as_str(), "squash" | "merge" | "rebase") {
+        return Err(format!("Invalid merge method: {}", method));
     }
 
-    // Check if any are pending/in-progress
-    // - status: QUEUED, IN_PROGRESS (GitHub CI)
-    // - state: PENDING (status checks)
-    // - conclusion is empty string when still running
-    let has_pending = checks.iter().any(|c| {
-        let status_upper = c.status.as_deref().map(|s| s.to_uppercase());
-        let state_upper = c.state.as_deref().map(|s| s.to_uppercase());
-        let conclusion = c.conclusion.as_deref();
-
-        matches!(status_upper.as_deref(), Some("QUEUED") | Some("IN_PROGRESS"))
-            || matches!(state_upper.as_deref(), Some("PENDING"))
-            || conclusion == Some("") // Empty conclusion means still running
-    });
-    if has_pending {
-        return Some("pending".to_string());
+    state.github.merge(&repo, number, &method, delete_branch)?;
+
+    {
+        let conn = state.conn();
+        let before = pr_events::snapshot_pr(&conn, &pr_id);
+        let category = determine_category("merged", false);
+        db::update_pr_state(&conn, &pr_id, "merged", &category)
+            .map_err(|e| format!("Failed to update PR state: {}", e))?;
+        db::set_pr_merged_at(&conn, &pr_id, &Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string())
+            .map_err(|e| format!("Failed to record merge time: {}", e))?;
+        pr_events::emit_pr_diff(&app, &conn, &pr_id, &before);
     }
 
-    // All passing
-    Some("passing".to_string())
+    monitor::auto_stop_monitor_for_pr(&app, &state, &pr_id, "pr_merged")?;
+
+    Ok(())
 }
 
-fn determine_review_status(review_decision: &Option<String>, mergeable: &Option<String>) -> String {
-    // Check for merge conflicts first - they take priority
-    if mergeable.as_deref() == Some("CONFLICTING") {
-        return "conflicts".to_string();
+/// Get a PR's head commit SHA, fetching and caching it if not yet known
+#[tauri::command]
+fn get_pr_head_sha(state: State<'_, AppState>, pr_id: String) -> Result<Option<String>, String> {
+    head_sha_for_pr(&state, &pr_id)
+}
+
+/// Shared by `get_pr_head_sha` and `checks::get_ci_summary`
+pub(crate) fn head_sha_for_pr(state: &AppState, pr_id: &str) -> Result<Option<String>, String> {
+    let (repo, number, cached_sha): (String, i32, Option<String>) = {
+        let conn = state.conn();
+        conn.query_row(
+            "SELECT repo, number, head_sha FROM pr_cache WHERE id = ?1",
+            [pr_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| format!("PR not found in cache: {} ({})", pr_id, e))?
+    };
+
+    if cached_sha.is_some() {
+        return Ok(cached_sha);
     }
 
-    match review_decision.as_deref() {
-        Some("APPROVED") => "approved".to_string(),
-        Some("CHANGES_REQUESTED") => "changes_requested".to_string(),
-        Some("REVIEW_REQUIRED") => "pending".to_string(),
-        _ => "pending".to_string(),
+    // Older gh versions don't support headRefOid; leave it unknown
+    let Ok(pr) = state.github.view_pr(&repo, number, "headRefOid") else {
+        return Ok(None);
+    };
+    let sha = pr["headRefOid"].as_str().unwrap_or("").trim().to_string();
+    if sha.is_empty() {
+        return Ok(None);
     }
+
+    let conn = state.conn();
+    conn.execute(
+        "UPDATE pr_cache SET head_sha = ?1 WHERE id = ?2",
+        [sha.as_str(), pr_id],
+    )
+    .map_err(|e| format!("Failed to cache head SHA: {}", e))?;
+
+    Ok(Some(sha))
 }
 
-/// Check the state of a single PR via GitHub API
-/// Returns (state, merged_at) where state is "open", "merged", or "closed"
-fn check_pr_state(repo: &str, pr_number: i32) -> Option<(String, Option<String>)> {
-    let output = Command::new("gh")
-        .args([
-            "pr", "view",
-            &pr_number.to_string(),
-            "--repo", repo,
-            "--json", "state,mergedAt",
-        ])
-        .output()
-        .ok()?;
+/// How one check counts towards the PR's CI status
+#[derive(Debug, PartialEq)]
+pub(crate) enum CheckOutcome {
+    Failing,
+    ActionRequired,
+    Pending,
+    Passing,
+    /// Skipped, neutral, stale, or cancelled when not required
+    Ignored,
+    /// Nothing recognizable reported (e.g. every field null); not evidence of passing
+    Unknown,
+}
 
-    if !output.status.success() {
-        return None;
+/// Classify one check; also used for the REST check runs in `checks::get_ci_summary`
+pub(crate) fn check_outcome(check: &StatusCheck) -> CheckOutcome {
+    // Values can be uppercase or lowercase depending on the source
+    let upper = |s: &Option<String>| s.as_deref().map(|s| s.to_uppercase());
+    let conclusion = upper(&check.conclusion);
+    let state = upper(&check.state);
+    let status = upper(&check.status);
+
+    match conclusion.as_deref() {
+        Some("FAILURE") | Some("TIMED_OUT") | Some("STARTUP_FAILURE") => {
+            return CheckOutcome::Failing
+        }
+        Some("CANCELLED") if check.is_required == Some(true) => return CheckOutcome::Failing,
+        Some("CANCELLED") | Some("SKIPPED") | Some("NEUTRAL") | Some("STALE") => {
+            return CheckOutcome::Ignored
+        }
+        Some("ACTION_REQUIRED") => return CheckOutcome::ActionRequired,
+        _ => {}
+    }
+    // Commit statuses (no conclusion) report through state
+    if matches!(state.as_deref(), Some("FAILURE") | Some("ERROR")) {
+        return CheckOutcome::Failing;
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let json: serde_json::Value = serde_json::from_str(&stdout).ok()?;
+    // Still running:
+    // - status: QUEUED, IN_PROGRESS, WAITING, REQUESTED, PENDING (GitHub CI)
+    // - state: PENDING, EXPECTED (status checks)
+    // - conclusion is empty string when still running
+    if matches!(
+        status.as_deref(),
+        Some("QUEUED") | Some("IN_PROGRESS") | Some("WAITING") | Some("REQUESTED") | Some("PENDING")
+    ) || matches!(state.as_deref(), Some("PENDING") | Some("EXPECTED"))
+        || conclusion.as_deref() == Some("")
+    {
+        return CheckOutcome::Pending;
+    }
+    // Only an explicit success counts as passing
+    if conclusion.as_deref() == Some("SUCCESS") || state.as_deref() == Some("SUCCESS") {
+        return CheckOutcome::Passing;
+    }
+    CheckOutcome::Unknown
+}
 
-    let state = json.get("state")?.as_str()?.to_lowercase();
-    let merged_at = json.get("mergedAt").and_then(|v| v.as_str()).map(|s| s.to_string());
+/// Values `determine_ci_status` produces (None aside)
+pub(crate) const CI_STATUSES: &[&str] = &["passing", "failing", "pending", "action_required"];
+
+/// Overall CI status of a PR's status check rollup: "failing",
+/// "action_required", "pending" or "passing", or None (unknown) without a
+/// rollup or checks that count. Checks with no usable data never make it "passing".
+pub(crate) fn determine_ci_status(status_checks: &Option<Vec<StatusCheck>>) -> Option<String> {
+    let outcomes: Vec<CheckOutcome> = status_checks
+        .as_ref()?
+        .iter()
+        .map(check_outcome)
+        .filter(|outcome| !matches!(outcome, CheckOutcome::Ignored | CheckOutcome::Unknown))
+        .collect();
 
-    Some((state, merged_at))
+    let status = if outcomes.is_empty() {
+        return None;
+    } else if outcomes.contains(&CheckOutcome::Failing) {
+        "failing"
+    } else if outcomes.contains(&CheckOutcome::ActionRequired) {
+        "action_required"
+    } else if outcomes.contains(&CheckOutcome::Pending) {
+        "pending"
+    } else {
+        "passing"
+    };
+    Some(status.to_string())
 }
 
-/// Check the state of multiple PRs in a single GraphQL query.
-/// Returns a map of pr_number -> (state, merged_at).
-/// Falls back to individual queries if the batch fails.
-fn batch_check_pr_states(repo: &str, pr_numbers: &[(String, i32)]) -> Vec<(String, i32, String, Option<String>)> {
-    if pr_numbers.is_empty() {
-        return vec![];
-    }
-
-    // Build a batched GraphQL query: one alias per PR
-    let parts: Vec<&str> = repo.splitn(2, '/').collect();
-    if parts.len() != 2 {
-        // Fallback to individual queries
-        return pr_numbers.iter().filter_map(|(pr_id, num)| {
-            check_pr_state(repo, *num).map(|(state, merged_at)| (pr_id.clone(), *num, state, merged_at))
-        }).collect();
+fn determine_review_status(review_decision: &Option<String>, mergeable: &Option<String>) -> String {
+    // Check for merge conflicts first - they take priority
+    if mergeable.as_deref() == Some("CONFLICTING") {
+        return "conflicts".to_string();
     }
-    let (owner, name) = (parts[0], parts[1]);
 
-    let aliases: Vec<String> = pr_numbers.iter().map(|(_, num)| {
-        format!(
-            "pr{num}: pullRequest(number: {num}) {{ state mergedAt }}",
-            num = num
-        )
-    }).collect();
-
-    let query = format!(
-        "query {{ repository(owner: \"{owner}\", name: \"{name}\") {{ {aliases} }} }}",
-        owner = owner,
-        name = name,
-        aliases = aliases.join(" ")
-    );
-
-    let output = Command::new("gh")
-        .args(["api", "graphql", "-f", &format!("query={}", query)])
-        .output();
-
-    match output {
-        Ok(out) if out.status.success() => {
-            let stdout = String::from_utf8_lossy(&out.stdout);
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&stdout) {
-                let repo_data = &json["data"]["repository"];
-                return pr_numbers.iter().filter_map(|(pr_id, num)| {
-                    let pr_data = &repo_data[format!("pr{}", num)];
-                    let state = pr_data["state"].as_str()?.to_lowercase();
-                    let merged_at = pr_data["mergedAt"].as_str().map(|s| s.to_string());
-                    Some((pr_id.clone(), *num, state, merged_at))
-                }).collect();
-            }
-            // JSON parse failed, fallback
-            pr_numbers.iter().filter_map(|(pr_id, num)| {
-                check_pr_state(repo, *num).map(|(state, merged_at)| (pr_id.clone(), *num, state, merged_at))
-            }).collect()
-        }
-        _ => {
-            // gh command failed, fallback to individual queries
-            pr_numbers.iter().filter_map(|(pr_id, num)| {
-                check_pr_state(repo, *num).map(|(state, merged_at)| (pr_id.clone(), *num, state, merged_at))
-            }).collect()
-        }
+    match review_decision.as_deref() {
+        Some("APPROVED") => "approved".to_string(),
+        Some("CHANGES_REQUESTED") => "changes_requested".to_string(),
+        Some("REVIEW_REQUIRED") => "pending".to_string(),
+        _ => "pending".to_string(),
     }
 }
 
@@ -604,10 +1420,76 @@ fn determine_category(state: &str, is_monitoring: bool) -> String {
     }
 }
 
+/// Re-apply `determine_category` to cached PRs (optionally one repo) using
+/// their cached state and whether they have an active monitor.
+/// Returns how many PRs changed column.
+fn recompute_cached_categories(
+    conn: &rusqlite::Connection,
+    repo: Option<&str>,
+) -> rusqlite::Result<usize> {
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.state, p.column_assignment,
+                EXISTS(SELECT 1 FROM monitors m
+                       WHERE m.pr_id = p.id AND m.status IN ('running', 'sleeping'))
+         FROM pr_cache p
+         WHERE ?1 IS NULL OR p.repo = ?1",
+    )?;
+    let rows = stmt
+        .query_map([repo], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, bool>(3)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut changed = 0;
+    for (pr_id, pr_state, column, monitoring) in rows {
+        let category = determine_category(&pr_state, monitoring);
+        if category != column {
+            conn.execute(
+                "UPDATE pr_cache SET column_assignment = ?1 WHERE id = ?2",
+                [&category, &pr_id],
+            )?;
+            changed += 1;
+        }
+    }
+    Ok(changed)
+}
+
+/// Recompute board columns for cached PRs without a network refresh (e.g. after
+/// the categorization rules changed). Emits `pr:updated` for moved cards and
+/// returns how many moved.
+#[tauri::command]
+fn recompute_categories(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    repo: Option<String>,
+) -> Result<usize, String> {
+    let repo = repo.map(|r| parse_repo_path(&r));
+    let conn = state.conn();
+    let before = match &repo {
+        Some(repo) => pr_events::snapshot_repos(&conn, std::slice::from_ref(repo)),
+        None => pr_events::snapshot_all(&conn),
+    };
+
+    let changed = recompute_cached_categories(&conn, repo.as_deref())
+        .map_err(|e| format!("Failed to recompute categories: {}", e))?;
+
+    let after = match &repo {
+        Some(repo) => pr_events::snapshot_repos(&conn, std::slice::from_ref(repo)),
+        None => pr_events::snapshot_all(&conn),
+    };
+    pr_events::emit_changes(&app, &pr_events::diff(&before, &after));
+    Ok(changed)
+}
+
 /// Update sleep prevention state based on current monitors and setting
 #[tauri::command]
-fn sync_sleep_prevention(state: State<'_, AppState>) -> Result<bool, String> {
-    let conn = state.db.lock().map_err(|e| format!("DB lock error: {}", e))?;
+fn sync_sleep_prevention(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<bool, String> {
+    let conn = state.conn();
 
     // Check if feature is enabled
     let enabled = db::get_setting_value(&conn, "sleep_prevention_enabled")
@@ -623,20 +1505,68 @@ fn sync_sleep_prevention(state: State<'_, AppState>) -> Result<bool, String> {
         )
         .map_err(|e| format!("Failed to count monitors: {}", e))?;
 
-    sleep_prevention::update_sleep_state(count, enabled);
+    sleep_prevention::update_sleep_state(&app, count, enabled);
 
     Ok(sleep_prevention::is_sleep_prevented())
 }
 
+/// Get the number of active monitors, so a freshly opened window can sync
+/// without waiting for the next `monitor:state-changed` event
+#[tauri::command]
+fn get_active_monitor_count(state: State<'_, AppState>) -> Result<i32, String> {
+    monitor::get_active_monitor_count(&state)
+}
+
 /// Get current sleep prevention status
 #[tauri::command]
-fn get_sleep_prevention_status() -> bool {
-    sleep_prevention::is_sleep_prevented()
+fn get_sleep_prevention_status() -> sleep_prevention::SleepPreventionStatus {
+    sleep_prevention::SleepPreventionStatus {
+        prevented: sleep_prevention::is_sleep_prevented(),
+        paused: sleep_prevention::is_paused(),
+    }
+}
+
+/// Temporarily let the system sleep (or undo that) without changing the
+/// sleep_prevention_enabled setting. The pause lasts until the app restarts.
+#[tauri::command]
+fn sleep_prevention_pause(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paused: bool,
+) -> Result<sleep_prevention::SleepPreventionStatus, String> {
+    sleep_prevention::set_paused(paused);
+    sync_sleep_prevention(app, state)?;
+    Ok(get_sleep_prevention_status())
+}
+
+/// Get detailed sleep prevention state for troubleshooting
+#[tauri::command]
+fn get_sleep_prevention_details(
+    state: State<'_, AppState>,
+) -> Result<sleep_prevention::SleepPreventionDetails, String> {
+    let conn = state.conn();
+
+    let enabled = db::get_setting_value(&conn, "sleep_prevention_enabled")
+        .map(|v| v == "true")
+        .unwrap_or(false);
+
+    Ok(sleep_prevention::get_details(enabled))
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        // Must be registered first: a second launch hands off to the running
+        // instance and exits before setup, so it never touches the DB, the API
+        // port, or the exit cleanup (kill_all/allow_sleep)
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.unminimize();
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_window_state::Builder::new().build())
         .plugin(tauri_plugin_notification::init())
@@ -647,41 +1577,85 @@ pub fn run() {
         .setup(|app| {
             // Initialize database
             let db_path = db::get_db_path().expect("Failed to get database path");
-            let state = AppState::new(db_path).expect("Failed to initialize database");
+            let mut state = AppState::new(db_path).expect("Failed to initialize database");
+            db::init_schema(&state.conn()).expect("Failed to initialize database schema");
+
+            // Demo mode swaps in its own database, fixture GitHub data and
+            // scripted monitors. If that fails, don't start rather than run
+            // the demo half-switched against real repos.
+            let demo_requested = demo::requested(&state.conn());
+            if demo_requested {
+                if let Err(e) = demo::activate(&mut state) {
+                    panic!("Failed to start demo mode ({}=0 starts normally): {}", demo::DEMO_ENV, e);
+                }
+            }
 
-            // Initialize schema and clean up old data
-            {
-                let conn = state.db.lock().unwrap();
-                db::init_schema(&conn).expect("Failed to initialize database schema");
+            // Clean up old data
+            let (active_count, sleep_enabled, badge) = {
+                let conn = state.conn();
+                github::set_gh_path(db::get_gh_path(&conn));
 
                 // Clean up old completed/failed monitors (keep last 50)
                 if let Err(e) = db::cleanup_old_monitors(&conn) {
                     eprintln!("Failed to clean up old monitors: {}", e);
                 }
 
-                // Mark any "running" monitors from a previous crash as failed
-                let _ = conn.execute(
-                    "UPDATE monitors SET status = 'failed', exit_reason = 'app_restart', ended_at = datetime('now') WHERE status IN ('running', 'sleeping')",
-                    [],
-                );
-            }
+                // Mark monitors whose process died (e.g. after a crash) as failed
+                // before anything reads the monitor state
+                if let Err(e) = db::reconcile_dead_monitors(&conn) {
+                    eprintln!("Failed to reconcile dead monitors: {}", e);
+                }
+
+                let sleep_enabled = db::get_setting_value(&conn, "sleep_prevention_enabled")
+                    .map(|v| v == "true")
+                    .unwrap_or(false);
+                let active_count = db::count_active_monitors(&conn).unwrap_or(0);
+                (active_count, sleep_enabled, dock::badge_count(&conn, active_count))
+            };
 
             // Store state for use in commands
             app.manage(state);
 
+            // Monitors a detached quit left running can be stopped again, and their exit is recorded
+            process::adopt_detached_monitors(app.handle());
+
             // Create system tray
             tray::create_tray(app.handle())?;
 
+            // Initial tray/dock/sleep state from the reconciled monitors
+            tray::update_tray_status(active_count);
+            dock::set_dock_badge(badge);
+            sleep_prevention::update_sleep_state(app.handle(), active_count, sleep_enabled);
+
+            // Learn whether gh is logged in, then start monitors blocked on it
+            // and resume starts queued before the last quit, up to the cap
+            let check_handle = app.handle().clone();
+            std::thread::spawn(move || {
+                if let Some(state) = check_handle.try_state::<AppState>() {
+                    environment::check_environment_internal(&check_handle, &state);
+                }
+            });
+
             // Register global hotkey (Cmd+Shift+P to toggle window)
             if let Err(e) = hotkey::register_global_hotkey(app.handle()) {
                 eprintln!("Failed to register global hotkey: {}", e);
             }
 
-            // Minimize to tray on close (hide instead of quit)
+            // Minimize to tray on close, unless close_behavior is "quit"
             let app_handle = app.handle().clone();
             if let Some(window) = app.get_webview_window("main") {
                 window.on_window_event(move |event| {
                     if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                        let close_behavior = app_handle
+                            .try_state::<AppState>()
+                            .map(|state| db::get_close_behavior(&state.conn()))
+                            .unwrap_or_else(|| "hide".to_string());
+                        if close_behavior == "quit" {
+                            // Monitor cleanup runs on RunEvent::Exit
+                            app_handle.exit(0);
+                            return;
+                        }
+
                         // Prevent the window from closing, hide it instead
                         api.prevent_close();
                         if let Some(win) = app_handle.get_webview_window("main") {
@@ -692,7 +1666,45 @@ pub fn run() {
             }
 
             // Start HTTP API server for external integrations (e.g., Claude Code)
-            api::start_api_server(app.handle().clone());
+            let api_enabled = {
+                let state = app.state::<AppState>();
+                let conn = state.conn();
+                db::get_api_enabled(&conn)
+            };
+            if api_enabled {
+                if let Err(e) = api::spawn_api_server(app.handle().clone()) {
+                    eprintln!("{}", e);
+                }
+            }
+
+            // Handle clanker:// deep links (queued until setup completes)
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+
+                #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+                if let Err(e) = app.deep_link().register_all() {
+                    eprintln!("Failed to register deep link scheme: {}", e);
+                }
+
+                let handle = app.handle().clone();
+                app.deep_link().on_open_url(move |event| {
+                    let urls: Vec<String> = event.urls().iter().map(|u| u.to_string()).collect();
+                    deep_link::handle_urls(&handle, &urls);
+                });
+
+                // URLs the app was launched with
+                if let Ok(Some(urls)) = app.deep_link().get_current() {
+                    let urls: Vec<String> = urls.iter().map(|u| u.to_string()).collect();
+                    deep_link::handle_urls(app.handle(), &urls);
+                }
+            }
+            deep_link::mark_ready(app.handle());
+
+            // Refresh PRs and nudge monitors when the system wakes
+            wake::start_wake_listener(app.handle().clone());
+            staleness::start_staleness_watcher(app.handle().clone());
+            comment_refresh::start_comment_refresher(app.handle().clone());
+            connectivity::start_connectivity_watcher(app.handle().clone());
 
             println!("Clanker Spanker initialized successfully");
 
@@ -700,43 +1712,124 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             fetch_prs,
+            preview_fetch_command,
+            ad_hoc_fetch,
+            palette::palette_search,
             get_cached_prs,
+            get_fetch_metadata,
+            staleness::get_fetch_status,
             clear_pr_cache,
             dismiss_pr,
+            clear_done_prs,
+            get_attention_count,
+            get_muted_prs,
+            get_archived_prs,
+            get_changes_since,
+            get_actionable_reviews,
+            demo::seed_demo_data,
+            demo::is_demo_mode,
+            unarchive_pr,
+            get_unread_counts,
+            mark_pr_seen,
+            merge_pr,
+            recompute_categories,
+            approvals::get_audit_log,
+            get_rate_limit,
+            rate_usage::get_rate_limit_history,
+            connectivity::get_connectivity_status,
+            get_pr_head_sha,
+            checks::rerun_failed_checks,
+            checks::get_ci_failure_logs,
+            checks::get_ci_summary,
+            checks::set_pr_ci_override,
+            checks::clear_pr_ci_override,
+            branch_update::update_pr_branch,
+            pr_edit::set_pr_labels,
+            pr_edit::set_pr_draft,
+            pr_edit::get_repo_labels,
             settings::get_repos,
+            settings::get_repo_summaries,
+            settings::set_repo_alias,
+            settings::get_repo_aliases,
             settings::add_repo,
             settings::remove_repo,
             settings::get_selected_repo,
             settings::set_selected_repo,
             settings::get_setting,
             settings::set_setting,
+            settings::discover_my_teams,
+            settings::get_repo_setting,
+            settings::set_repo_setting,
+            settings::export_settings,
+            board::get_board_snapshot,
+            settings::import_settings,
             settings::get_effective_ai_model,
             monitor::start_monitor,
             monitor::stop_monitor,
+            monitor::stop_monitor_for_pr,
             monitor::get_monitors,
             monitor::get_monitor,
+            monitor::export_monitors,
+            monitor_ticks::get_monitor_ticks,
+            monitor::update_monitor_config,
+            monitor::get_overdue_monitors,
             monitor::get_monitor_for_pr,
             monitor::get_recent_monitor_for_pr,
             monitor::read_monitor_log,
+            monitor::get_monitor_logs,
+            monitor::resume_monitor,
+            monitor::send_monitor_input,
+            monitor::list_app_child_processes,
+            monitor::is_monitor_alive,
+            monitor::kill_orphans,
+            monitor::set_monitors_paused,
+            monitor::pause_all_monitors,
+            monitor::check_monitor_script,
+            environment::check_environment,
+            monitor::resume_all_monitors,
             monitor::fetch_pr_comments,
+            monitor::fetch_all_pr_comments,
+            monitor::cancel_comment_fetch,
+            monitor_queue::enqueue_monitor,
+            monitor_queue::cancel_queued_monitor,
+            monitor_queue::get_queued_monitors,
             monitor::get_pr_comments,
+            monitor::acknowledge_comment,
+            monitor::acknowledge_all_comments,
+            timeline::get_pr_timeline,
+            stacks::get_pr_stacks,
+            checks::suggest_monitor_interval,
+            verify_clone::verify_clone,
             notifications::notify_pr_clean,
             notifications::notify_comment_found,
             notifications::notify_monitor_complete,
             notifications::notify_monitor_failed,
+            notifications::notify_pr_approved,
+            notifications::notify_review_requested,
+            notifications::is_window_focused,
             notifications::show_and_focus_pr,
+            api::start_api_server,
+            api::stop_api_server,
+            api::get_api_server_status,
+            api::get_api_address,
+            open_data_dir,
+            set_data_dir,
             sync_sleep_prevention,
-            get_sleep_prevention_status
+            get_active_monitor_count,
+            get_sleep_prevention_status,
+            sleep_prevention_pause,
+            get_sleep_prevention_details
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|app_handle, event| {
             if let tauri::RunEvent::Exit = event {
-                // Cleanup: Kill all running monitor processes
+                // Cleanup: Kill, wait for, or detach running monitor processes
                 if let Some(state) = app_handle.try_state::<AppState>() {
-                    println!("Cleaning up monitor processes...");
-                    state.processes.kill_all();
+                    monitor::shutdown_monitors(app_handle, &state);
                 }
+                // Cleanup: End the wake listener's helper process (gdbus on Linux)
+                wake::stop_wake_listener();
                 // Cleanup: Release sleep prevention assertion
                 if let Err(e) = sleep_prevention::allow_sleep() {
                     eprintln!("Warning: Failed to release sleep assertion on exit: {}", e);
@@ -744,3 +1837,443 @@ pub fn run() {
             }
         });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use github::mock::MockGithub;
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    /// A `gh pr list` entry as GitHub returns it
+    fn gh_pr(number: i32, extra: Value) -> Value {
+        let mut pr = json!({
+            "number": number,
+            "title": format!("PR {}", number),
+            "url": format!("https://github.com/o/r/pull/{}", number),
+            "state": "OPEN",
+            "isDraft": false,
+            "author": {"login": "alice"},
+            "headRefName": format!("branch-{}", number),
+            "headRefOid": format!("sha{}", number),
+            "baseRefName": "main",
+            "labels": [],
+            "reviewRequests": [],
+            "createdAt": "2024-01-01T00:00:00Z",
+            "updatedAt": "2024-01-02T00:00:00Z",
+        });
+        pr.as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        pr
+    }
+
+    fn checks(json: Value) -> Option<Vec<StatusCheck>> {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_determine_ci_status() {
+        assert_eq!(determine_ci_status(&None), None);
+        assert_eq!(determine_ci_status(&checks(json!([]))), None);
+        assert_eq!(
+            determine_ci_status(&checks(json!([{"conclusion": "SUCCESS"}, {"conclusion": "failure"}]))),
+            Some("failing".to_string())
+        );
+        assert_eq!(
+            determine_ci_status(&checks(json!([{"conclusion": "SUCCESS"}, {"status": "IN_PROGRESS", "conclusion": ""}]))),
+            Some("pending".to_string())
+        );
+        assert_eq!(
+            determine_ci_status(&checks(json!([{"state": "PENDING"}]))),
+            Some("pending".to_string())
+        );
+        assert_eq!(
+            determine_ci_status(&checks(json!([{"status": "COMPLETED", "conclusion": "SUCCESS"}]))),
+            Some("passing".to_string())
+        );
+
+        let cases: &[(Value, Option<&str>)] = &[
+            (json!({"conclusion": "TIMED_OUT"}), Some("failing")),
+            (json!({"conclusion": "startup_failure"}), Some("failing")),
+            (json!({"conclusion": "CANCELLED", "isRequired": true}), Some("failing")),
+            (json!({"conclusion": "CANCELLED"}), Some("passing")),
+            (json!({"conclusion": "CANCELLED", "isRequired": false}), Some("passing")),
+            (json!({"conclusion": "SKIPPED", "isRequired": true}), Some("passing")),
+            (json!({"conclusion": "NEUTRAL"}), Some("passing")),
+            (json!({"conclusion": "ACTION_REQUIRED"}), Some("action_required")),
+            (json!({"state": "ERROR"}), Some("failing")),
+            (json!({"state": "FAILURE"}), Some("failing")),
+            (json!({"state": "SUCCESS"}), Some("passing")),
+            (json!({"status": "QUEUED"}), Some("pending")),
+        ];
+        for (check, expected) in cases {
+            // Alongside one passing check, so ignored checks leave it "passing"
+            let rollup = checks(json!([{"status": "COMPLETED", "conclusion": "SUCCESS"}, check]));
+            assert_eq!(determine_ci_status(&rollup).as_deref(), *expected, "{}", check);
+        }
+
+        // Failures outrank action required, which outranks pending
+        let rollup = checks(json!([{"conclusion": "ACTION_REQUIRED"}, {"status": "IN_PROGRESS"}]));
+        assert_eq!(determine_ci_status(&rollup).as_deref(), Some("action_required"));
+        let rollup = checks(json!([{"conclusion": "ACTION_REQUIRED"}, {"conclusion": "TIMED_OUT"}]));
+        assert_eq!(determine_ci_status(&rollup).as_deref(), Some("failing"));
+        // Only ignored checks: nothing to report
+        let rollup = checks(json!([{"conclusion": "SKIPPED"}, {"conclusion": "NEUTRAL"}]));
+        assert_eq!(determine_ci_status(&rollup), None);
+    }
+
+    #[test]
+    fn test_check_merge_ci() {
+        use crate::github::mock::MockGithub;
+        use std::sync::Arc;
+
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_view(
+            "o/r",
+            1,
+            json!({"requiredChecks": [
+                {"conclusion": "CANCELLED", "isRequired": false},
+                {"conclusion": "SUCCESS", "isRequired": true},
+            ]}),
+        );
+        github.set_view(
+            "o/r",
+            2,
+            json!({"requiredChecks": [{"conclusion": "CANCELLED", "isRequired": true}]}),
+        );
+        let state = AppState::for_tests(github);
+
+        assert_eq!(check_merge_ci(&state, "o/r", 1, Some("passing")), Ok(()));
+        assert_eq!(
+            check_merge_ci(&state, "o/r", 1, Some("action_required")),
+            Err("PR has CI checks waiting for approval".to_string())
+        );
+        // The cached rollup read the cancelled required check as passing
+        assert_eq!(
+            check_merge_ci(&state, "o/r", 2, Some("passing")),
+            Err("PR has failing CI checks".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ci_status_without_check_data() {
+        // Rollups as gh can return them, parsed the way a fetched PR is
+        let cases: &[(Value, Option<&str>)] = &[
+            (Value::Null, None),
+            (json!([]), None),
+            (json!([null]), None),
+            (json!([{}]), None),
+            (json!([{"state": null, "status": null, "conclusion": null, "isRequired": null}]), None),
+            (json!([{"__typename": "CheckRun", "name": "build", "status": "COMPLETED"}]), None),
+            (json!([{"status": "COMPLETED", "conclusion": null}, {}]), None),
+            (json!([{}, {"conclusion": "SUCCESS"}]), Some("passing")),
+            (json!([null, {"__typename": "StatusContext", "state": "SUCCESS"}]), Some("passing")),
+            (json!([{"conclusion": null}, {"state": "EXPECTED"}]), Some("pending")),
+            (json!([{"status": "WAITING"}, {"conclusion": "SUCCESS"}]), Some("pending")),
+            (json!([{}, {"conclusion": "SUCCESS"}, {"state": "ERROR"}]), Some("failing")),
+        ];
+        for (rollup, expected) in cases {
+            let mut pr = gh_pr(1, json!({}));
+            pr["statusCheckRollup"] = rollup.clone();
+            let pr: GitHubPR = serde_json::from_value(pr).unwrap();
+            assert_eq!(determine_ci_status(&pr.status_check_rollup).as_deref(), *expected, "{}", rollup);
+        }
+        // A missing rollup field reads the same as null
+        let mut pr = gh_pr(1, json!({}));
+        pr.as_object_mut().unwrap().remove("statusCheckRollup");
+        let pr: GitHubPR = serde_json::from_value(pr).unwrap();
+        assert!(pr.status_check_rollup.is_none());
+    }
+
+    #[test]
+    fn test_determine_category() {
+        assert_eq!(determine_category("OPEN", false), "todo");
+        assert_eq!(determine_category("merged", false), "done");
+        assert_eq!(determine_category("closed", false), "done");
+        assert_eq!(determine_category("open", true), "monitoring");
+    }
+
+    #[test]
+    fn test_fetch_prs_normalizes_github_data() {
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_prs(
+            "o/r",
+            json!([
+                gh_pr(1, json!({
+                    "statusCheckRollup": [{"conclusion": "FAILURE"}],
+                    "isCrossRepository": true,
+                    "headRepository": {"name": "r"},
+                    "headRepositoryOwner": {"login": "alice"},
+                    "reviewDecision": "CHANGES_REQUESTED",
+                    "labels": [{"name": "bug"}],
+                    "reviewRequests": [{"login": "me"}],
+                })),
+                gh_pr(2, json!({
+                    "statusCheckRollup": [{"status": "COMPLETED", "conclusion": "SUCCESS"}],
+                    "reviewDecision": "APPROVED",
+                    "mergeable": "CONFLICTING",
+                })),
+            ]),
+        );
+        let state = AppState::for_tests(github);
+
+        let outcome = fetch_prs_internal(&state, &["https://github.com/o/r".to_string()], false).unwrap();
+        let mut prs = outcome.prs;
+        prs.sort_by_key(|pr| pr.number);
+
+        assert_eq!(prs.len(), 2);
+        assert_eq!(prs[0].id, "o/r#1");
+        assert_eq!(prs[0].ci_status.as_deref(), Some("failing"));
+        assert_eq!(prs[0].review_status, "changes_requested");
+        assert_eq!(prs[0].labels, vec!["bug".to_string()]);
+        assert_eq!(prs[0].category, "todo");
+        assert!(prs[0].review_requested_at.is_some());
+        assert_eq!(prs[0].head_repo.as_deref(), Some("alice/r"));
+        assert!(prs[0].is_fork);
+        assert_eq!(prs[1].head_repo, None);
+        assert!(!prs[1].is_fork);
+        assert_eq!(prs[1].ci_status.as_deref(), Some("passing"));
+        // Conflicts take priority over the review decision
+        assert_eq!(prs[1].review_status, "conflicts");
+        assert!(prs[1].review_requested_at.is_none());
+
+        assert_eq!(outcome.changes.added.len(), 2);
+        assert!(outcome.head_changes.is_empty());
+    }
+
+    #[test]
+    fn test_fetch_prs_counts_threads_of_updated_prs() {
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_prs("o/r", json!([gh_pr(1, json!({}))]));
+        let threads = |resolved: &[bool]| {
+            let threads: Vec<Value> = resolved.iter().map(|r| json!({"isResolved": r})).collect();
+            json!({"reviewThreads": threads, "comments": [{}]})
+        };
+        github.set_view("o/r", 1, threads(&[false, true]));
+        let state = AppState::for_tests(github.clone());
+
+        let counts = |state: &AppState| {
+            let prs = fetch_prs_internal(state, &["o/r".to_string()], false).unwrap().prs;
+            (prs[0].comments_count, prs[0].unresolved_threads)
+        };
+        assert_eq!(counts(&state), (3, 1));
+
+        // Not re-counted while updatedAt is unchanged
+        github.set_view("o/r", 1, threads(&[false, false]));
+        assert_eq!(counts(&state), (3, 1));
+
+        github.set_prs("o/r", json!([gh_pr(1, json!({"updatedAt": "2024-01-03T00:00:00Z"}))]));
+        assert_eq!(counts(&state), (3, 2));
+    }
+
+    #[test]
+    fn test_team_review_requests() {
+        let github = Arc::new(MockGithub::new("me"));
+        let team = |slug: &str| json!({"__typename": "Team", "name": "x", "slug": slug});
+        github.set_prs(
+            "o/r",
+            json!([
+                gh_pr(1, json!({"reviewRequests": [team("org/backend"), {"login": "alice"}]})),
+                gh_pr(2, json!({"reviewRequests": [team("org/frontend")]})),
+            ]),
+        );
+        let state = AppState::for_tests(github);
+        db::set_setting(&state.conn(), db::MY_TEAMS_SETTING_KEY, r#"["@Org/Backend"]"#).unwrap();
+
+        let mut prs = fetch_prs_internal(&state, &["o/r".to_string()], true).unwrap().prs;
+        prs.sort_by_key(|pr| pr.number);
+        assert_eq!(prs[0].reviewer_teams, ["org/backend"]);
+        assert!(prs[0].reviewers.is_empty());
+        // A request to my team counts as a request to me; other teams don't
+        assert!(prs[0].review_requested_at.is_some());
+        assert_eq!(prs[1].reviewer_teams, ["org/frontend"]);
+        assert!(prs[1].review_requested_at.is_none());
+    }
+
+    #[test]
+    fn test_recompute_cached_categories() {
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_prs("o/r", json!([gh_pr(1, json!({})), gh_pr(2, json!({})), gh_pr(3, json!({}))]));
+        let state = AppState::for_tests(github);
+        fetch_prs_internal(&state, &["o/r".to_string()], true).unwrap();
+
+        let conn = state.conn();
+        conn.execute("UPDATE pr_cache SET column_assignment = 'done' WHERE id = 'o/r#1'", [])
+            .unwrap();
+        conn.execute("UPDATE pr_cache SET state = 'merged' WHERE id = 'o/r#2'", [])
+            .unwrap();
+        conn.execute(
+            "INSERT INTO monitors (id, pr_id, pr_number, repo, status, started_at, log_file)
+             VALUES ('m1', 'o/r#3', 3, 'o/r', 'sleeping', '', '')",
+            [],
+        )
+        .unwrap();
+
+        assert_eq!(recompute_cached_categories(&conn, Some("other/repo")).unwrap(), 0);
+        assert_eq!(recompute_cached_categories(&conn, Some("o/r")).unwrap(), 3);
+        let columns: Vec<String> = conn
+            .prepare("SELECT column_assignment FROM pr_cache ORDER BY number")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(columns, vec!["todo", "done", "monitoring"]);
+        assert_eq!(recompute_cached_categories(&conn, None).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_fetch_prs_moves_stale_prs_to_done() {
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_prs("o/r", json!([gh_pr(1, json!({})), gh_pr(2, json!({}))]));
+        let state = AppState::for_tests(github.clone());
+        fetch_prs_internal(&state, &["o/r".to_string()], true).unwrap();
+
+        // #2 dropped out of the open list and has since been merged
+        github.set_prs("o/r", json!([gh_pr(1, json!({"headRefOid": "sha1b"}))]));
+        github.set_view("o/r", 2, json!({"state": "MERGED", "mergedAt": "2024-01-03T00:00:00Z"}));
+        let outcome = fetch_prs_internal(&state, &["o/r".to_string()], true).unwrap();
+
+        let merged = outcome.prs.iter().find(|pr| pr.number == 2).unwrap();
+        assert_eq!(merged.state, "merged");
+        assert_eq!(merged.category, "done");
+        assert_eq!(outcome.changes.updated.len(), 2);
+        assert_eq!(
+            outcome.head_changes,
+            vec![("o/r#1".to_string(), 1, "o/r".to_string(), "sha1".to_string(), "sha1b".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_fetch_prs_unarchives_reopened_prs() {
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_prs("o/r", json!([gh_pr(1, json!({}))]));
+        let state = AppState::for_tests(github.clone());
+        fetch_prs_internal(&state, &["o/r".to_string()], true).unwrap();
+
+        // Closed, then archived by the retention job
+        state
+            .conn()
+            .execute(
+                "UPDATE pr_cache SET state = 'closed', archived = 1, archived_at = '2024-02-01T00:00:00Z'",
+                [],
+            )
+            .unwrap();
+        let outcome = fetch_prs_internal(&state, &["o/r".to_string()], true).unwrap();
+
+        assert!(!outcome.prs[0].archived);
+        let archived_at: Option<String> = state
+            .conn()
+            .query_row("SELECT archived_at FROM pr_cache WHERE id = 'o/r#1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(archived_at, None);
+    }
+
+    #[test]
+    fn test_actionable_reviews() {
+        let github = Arc::new(MockGithub::new("me"));
+        let green = json!([{"conclusion": "SUCCESS"}]);
+        let requested = |extra: Value| {
+            let mut fields = json!({"reviewRequests": [{"login": "me"}], "statusCheckRollup": green});
+            fields.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+            fields
+        };
+        let changes_requested_by = |login: &str| {
+            json!({"latestReviews": [{"author": {"login": login}, "state": "CHANGES_REQUESTED"}]})
+        };
+        github.set_prs(
+            "o/r",
+            json!([
+                gh_pr(1, requested(json!({"mergeable": "CONFLICTING"}))),
+                gh_pr(2, requested(changes_requested_by("bob"))),
+                gh_pr(3, requested(json!({"statusCheckRollup": [{"conclusion": "FAILURE"}]}))),
+                gh_pr(4, requested(changes_requested_by("Me"))),
+                gh_pr(5, requested(json!({"author": {"login": "Me"}}))),
+                gh_pr(6, requested(json!({"isDraft": true}))),
+                gh_pr(7, json!({"statusCheckRollup": green})),
+            ]),
+        );
+        let state = AppState::for_tests(github);
+        fetch_prs_internal(&state, &["o/r".to_string()], true).unwrap();
+
+        let numbers = |repo: Option<&str>| -> Vec<i32> {
+            actionable_reviews(&state, repo).unwrap().iter().map(|pr| pr.number).collect()
+        };
+        // #1 has conflicts, so it comes after #2 (whose change request isn't mine)
+        assert_eq!(numbers(None), [2, 1]);
+        assert_eq!(numbers(Some("o/r")), [2, 1]);
+        assert!(numbers(Some("o/other")).is_empty());
+    }
+
+    #[test]
+    fn test_changes_since() {
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_prs(
+            "o/r",
+            json!([
+                gh_pr(1, json!({"createdAt": "2024-01-01T00:00:00Z", "updatedAt": "2024-01-01T08:00:00Z"})),
+                gh_pr(2, json!({"createdAt": "2024-01-01T00:00:00Z", "updatedAt": "2024-01-01T10:00:00Z"})),
+                gh_pr(3, json!({"createdAt": "2024-01-01T09:30:00Z", "updatedAt": "2024-01-01T09:30:00Z"})),
+            ]),
+        );
+        let state = AppState::for_tests(github);
+        fetch_prs_internal(&state, &["o/r".to_string()], true).unwrap();
+
+        let conn = state.conn();
+        let changes = changes_since(&conn, "2024-01-01T10:00:00+01:00").unwrap();
+        let numbers: Vec<i32> = changes.prs.iter().map(|pr| pr.number).collect();
+        assert_eq!((changes.count, changes.opened), (2, 1));
+        assert_eq!(numbers, [2, 3]);
+        assert_eq!(changes_since(&conn, "2024-01-02T00:00:00Z").unwrap().count, 0);
+        assert!(changes_since(&conn, "9am").is_err());
+    }
+
+    #[test]
+    fn test_same_named_repos_stay_apart() {
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_prs("acme/api", json!([gh_pr(1, json!({})), gh_pr(2, json!({}))]));
+        github.set_prs("globex/api", json!([gh_pr(1, json!({"title": "Globex fix"}))]));
+        let state = AppState::for_tests(github.clone());
+        let repos = ["acme/api".to_string(), "https://github.com/globex/api".to_string()];
+        fetch_prs_internal(&state, &repos, true).unwrap();
+
+        let conn = state.conn();
+        db::set_setting(&conn, "repos", &serde_json::to_string(&repos).unwrap()).unwrap();
+        let ids = |prs: Vec<PR>| -> Vec<String> {
+            let mut ids: Vec<String> = prs.into_iter().map(|pr| pr.id).collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(
+            ids(cached_board_prs(&conn, None, None, false).unwrap()),
+            ["acme/api#1", "acme/api#2", "globex/api#1"]
+        );
+        let globex = cached_board_prs(&conn, Some("globex/api".to_string()), None, false).unwrap();
+        assert_eq!(globex.len(), 1);
+        assert_eq!(globex[0].title, "Globex fix");
+        assert_eq!(
+            ids(cached_board_prs(&conn, None, Some(vec!["acme/api".to_string()]), false).unwrap()),
+            ["acme/api#1", "acme/api#2"]
+        );
+        // A bare name matches neither repo
+        assert!(cached_board_prs(&conn, Some("api".to_string()), None, false).unwrap().is_empty());
+
+        conn.execute_batch(
+            "INSERT INTO monitors (id, pr_id, pr_number, repo, status, started_at, log_file)
+             VALUES ('m1', 'acme/api#1', 1, 'acme/api', 'running', '2026-01-01T00:00:00Z', ''),
+                    ('m2', 'globex/api#1', 1, 'globex/api', 'running', '2026-01-01T00:00:00Z', '')",
+        )
+        .unwrap();
+        let monitors = |repo: &str| -> Vec<String> {
+            monitor::list_monitors(&conn, None, Some(repo.to_string()), None)
+                .unwrap()
+                .into_iter()
+                .map(|m| m.id)
+                .collect()
+        };
+        assert_eq!(monitors("globex/api"), ["m2"]);
+        assert_eq!(monitors("https://github.com/acme/api"), ["m1"]);
+        assert!(monitors("api").is_empty());
+    }
+}
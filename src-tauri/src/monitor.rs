@@ -1,26 +1,48 @@
 use crate::db::{self, AppState};
 use crate::dock;
+use crate::environment;
+use crate::monitor_queue;
+use crate::pr_events;
 use crate::sleep_prevention;
+use crate::throttle::{self, Throttle};
 use crate::tray;
 use chrono::{DateTime, Duration, Utc};
 use rusqlite::params;
 use serde::{Deserialize, Serialize};
-use tauri::{Emitter, State};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{Emitter, Manager, State};
 use uuid::Uuid;
 
+/// Minutes between monitor checks when no interval is given
+pub const DEFAULT_INTERVAL_MINUTES: i32 = 15;
+
+/// Per-monitor notification levels: "default" follows the global notification
+/// settings; the rest override them, from noisiest to quietest
+pub const NOTIFICATION_LEVELS: &[&str] =
+    &["default", "all", "important", "completion_only", "silent"];
+
+/// Notification level of monitors started without one
+pub const DEFAULT_NOTIFICATION_LEVEL: &str = "default";
+
+/// Monitor modes: "auto_fix" lets the script commit fixes, "watch" only polls and reports
+pub const MONITOR_MODES: &[&str] = &["auto_fix", "watch"];
+
 /// Event payload for monitor state changes
-#[derive(Clone, Serialize)]
+#[derive(Clone, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MonitorStatePayload {
     pub active_count: i32,
+    /// Set when a single monitor progressed (an iteration marker)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monitor_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_completion_at: Option<String>,
 }
 
 /// Get count of active monitors (running or sleeping)
 pub fn get_active_monitor_count(state: &AppState) -> Result<i32, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    let conn = state.conn();
 
     let count: i32 = conn
         .query_row(
@@ -33,28 +55,38 @@ pub fn get_active_monitor_count(state: &AppState) -> Result<i32, String> {
     Ok(count)
 }
 
+/// Whether a PR has a running or sleeping monitor
+pub(crate) fn has_active_monitor(conn: &rusqlite::Connection, pr_id: &str) -> bool {
+    conn.query_row(
+        "SELECT 1 FROM monitors WHERE pr_id = ?1 AND status IN ('running', 'sleeping')",
+        [pr_id],
+        |_| Ok(()),
+    )
+    .is_ok()
+}
+
 /// Emit monitor state changed event and update tray/dock/sleep prevention
-fn emit_state_change<R: tauri::Runtime>(app: &tauri::AppHandle<R>, state: &AppState) {
+pub(crate) fn emit_state_change<R: tauri::Runtime>(app: &tauri::AppHandle<R>, state: &AppState) {
     if let Ok(count) = get_active_monitor_count(state) {
         // Update tray tooltip
         tray::update_tray_status(count);
 
-        // Update dock badge (macOS only)
-        dock::set_dock_badge(if count > 0 { Some(count) } else { None });
+        // Update dock badge (macOS only) and sleep prevention based on settings
+        let conn = state.conn();
+        dock::set_dock_badge(dock::badge_count(&conn, count));
+        crate::monitor_ticks::refresh(&conn);
 
-        // Update sleep prevention based on setting
-        if let Ok(conn) = state.db.lock() {
-            let sleep_enabled = db::get_setting_value(&conn, "sleep_prevention_enabled")
-                .map(|v| v == "true")
-                .unwrap_or(false);
-            sleep_prevention::update_sleep_state(count, sleep_enabled);
-        }
+        let sleep_enabled = db::get_setting_value(&conn, "sleep_prevention_enabled")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        sleep_prevention::update_sleep_state(app, count, sleep_enabled);
 
         // Emit event for frontend
         let _ = app.emit(
             "monitor:state-changed",
             MonitorStatePayload {
                 active_count: count,
+                ..Default::default()
             },
         );
     }
@@ -114,10 +146,312 @@ pub struct Monitor {
     pub comments_fixed: i32,
     pub exit_reason: Option<String>,
     pub log_file: String,
+    pub tags: Vec<String>,
+    pub notification_level: String,
+    /// PR head commit the latest iteration ran against
+    pub head_sha: Option<String>,
+    /// Suspended by the pause-all switch
+    pub paused: bool,
+    /// "auto_fix" or "watch" (see MONITOR_MODES)
+    pub mode: String,
+    /// Local clone the monitor ran in, when one was configured for the repo
+    pub working_dir: Option<String>,
+    /// When the last iteration should finish, if the monitor is active
+    pub estimated_completion_at: Option<String>,
+    /// Something to tell the user about a monitor just started (e.g. a fork PR)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+/// Parse the JSON tags column, treating malformed values as no tags
+pub(crate) fn parse_tags(json: &str) -> Vec<String> {
+    serde_json::from_str(json).unwrap_or_default()
+}
+
+fn parse_time(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// The monitor columns a completion estimate is based on
+struct IterationTiming {
+    status: String,
+    iteration: i32,
+    max_iterations: i32,
+    interval_minutes: i32,
+    started_at: String,
+    /// When the current iteration started (its @@ITERATION@@ marker)
+    last_check_at: Option<String>,
+    /// Time spent paused by earlier pauses
+    paused_seconds: i64,
+    /// Set while paused
+    paused_at: Option<String>,
+}
+
+/// Estimate when an active monitor finishes its last iteration.
+///
+/// A cycle is mostly the sleep between checks, so the average cycle so far
+/// (minus paused time) is used, never less than the interval. The last
+/// iteration does not sleep, so it only counts the work part of a cycle.
+fn estimate_completion(timing: &IterationTiming, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if !matches!(timing.status.as_str(), "running" | "sleeping") {
+        return None;
+    }
+    let started_at = parse_time(&timing.started_at)?;
+    let anchor = timing
+        .last_check_at
+        .as_deref()
+        .and_then(parse_time)
+        .unwrap_or(started_at);
+
+    let interval = i64::from(timing.interval_minutes.max(0)) * 60;
+    let current = i64::from(timing.iteration.max(1));
+    let cycle = if current >= 2 {
+        let elapsed = (anchor - started_at).num_seconds() - timing.paused_seconds;
+        (elapsed / (current - 1)).max(interval)
+    } else {
+        interval
+    };
+    let remaining = (i64::from(timing.max_iterations) - current).max(0);
+    let mut estimate =
+        anchor + Duration::seconds(cycle * remaining + (cycle - interval).max(0));
+
+    // A paused monitor makes no progress, so its estimate moves with the clock
+    if let Some(paused_at) = timing.paused_at.as_deref().and_then(parse_time) {
+        estimate += now - paused_at;
+    }
+    Some(estimate.max(now))
+}
+
+/// Estimated completion time (RFC 3339) of a monitor, None unless it is active
+pub(crate) fn estimated_completion_at(
+    conn: &rusqlite::Connection,
+    monitor_id: &str,
+) -> Option<String> {
+    let timing = conn
+        .query_row(
+            "SELECT status, iteration, max_iterations,
+                    COALESCE(effective_interval_minutes, interval_minutes), started_at,
+                    last_check_at, paused_seconds, paused_at
+             FROM monitors WHERE id = ?1",
+            [monitor_id],
+            |row| {
+                Ok(IterationTiming {
+                    status: row.get(0)?,
+                    iteration: row.get(1)?,
+                    max_iterations: row.get(2)?,
+                    interval_minutes: row.get(3)?,
+                    started_at: row.get(4)?,
+                    last_check_at: row.get(5)?,
+                    paused_seconds: row.get(6)?,
+                    paused_at: row.get(7)?,
+                })
+            },
+        )
+        .ok()?;
+    estimate_completion(&timing, Utc::now()).map(|t| t.to_rfc3339())
+}
+
+/// `interval` shifted by up to `jitter_percent` either way, at least a minute.
+/// The offset is derived from the monitor's (random v4) ID, so it is fixed for
+/// the monitor's lifetime.
+pub(crate) fn jittered_interval(interval: i32, jitter_percent: i64, monitor_id: &str) -> i32 {
+    // The high half: the low half starts with the fixed variant bits
+    let seed = Uuid::parse_str(monitor_id).map_or(0, |id| (id.as_u128() >> 64) as u64);
+    // Uniform in [-1, 1]
+    let unit = seed as f64 / u64::MAX as f64 * 2.0 - 1.0;
+    let offset = (f64::from(interval) * jitter_percent as f64 / 100.0 * unit).round() as i32;
+    (interval + offset).max(1)
+}
+
+/// Validate and dedupe user-supplied tags (trimmed, non-empty, order preserved)
+pub(crate) fn normalize_tags(tags: Option<Vec<String>>) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags.unwrap_or_default() {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return Err("Tags must be non-empty strings".to_string());
+        }
+        if !normalized.iter().any(|t| t == tag) {
+            normalized.push(tag.to_string());
+        }
+    }
+    Ok(normalized)
+}
+
+/// Largest accepted max_iterations for a monitor
+pub const MAX_ITERATIONS_LIMIT: i32 = 100;
+
+/// Longest accepted interval between checks (a day)
+pub const MAX_INTERVAL_MINUTES: i32 = 24 * 60;
+
+/// One invalid argument of a monitor start request
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    /// Request field name (camelCase, as in the API)
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(field: &str, message: String) -> Self {
+        Self {
+            field: field.to_string(),
+            message,
+        }
+    }
+}
+
+/// Check the arguments of a monitor start before anything reaches gh or the DB.
+/// `repo` must already be in owner/repo form.
+pub(crate) fn validate_start_request(
+    repo: &str,
+    pr_number: i32,
+    max_iterations: Option<i32>,
+    interval_minutes: Option<i32>,
+    notification_level: Option<&str>,
+    mode: Option<&str>,
+) -> Result<(), Vec<FieldError>> {
+    let mut errors = Vec::new();
+
+    if let Err(e) = crate::deep_link::parse_repo_slug(repo) {
+        errors.push(FieldError::new("repo", e));
+    }
+    if pr_number <= 0 {
+        errors.push(FieldError::new("prNumber", format!("Must be positive, got {}", pr_number)));
+    }
+    if let Some(n) = max_iterations.filter(|n| !(1..=MAX_ITERATIONS_LIMIT).contains(n)) {
+        errors.push(FieldError::new(
+            "maxIterations",
+            format!("Must be between 1 and {}, got {}", MAX_ITERATIONS_LIMIT, n),
+        ));
+    }
+    if let Some(n) = interval_minutes.filter(|n| !(1..=MAX_INTERVAL_MINUTES).contains(n)) {
+        errors.push(FieldError::new(
+            "intervalMinutes",
+            format!("Must be between 1 and {}, got {}", MAX_INTERVAL_MINUTES, n),
+        ));
+    }
+    if let Err(e) = normalize_notification_level(notification_level.map(str::to_string)) {
+        errors.push(FieldError::new("notificationLevel", e));
+    }
+    if let Err(e) = normalize_monitor_mode(mode.map(str::to_string)) {
+        errors.push(FieldError::new("mode", e));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Join field errors into one message for callers that only take a string
+pub(crate) fn describe_field_errors(errors: &[FieldError]) -> String {
+    let details: Vec<String> = errors
+        .iter()
+        .map(|e| format!("{}: {}", e.field, e.message))
+        .collect();
+    format!("Invalid monitor request ({})", details.join("; "))
+}
+
+/// Validate a requested notification level, defaulting to "default"
+pub(crate) fn normalize_notification_level(level: Option<String>) -> Result<String, String> {
+    let level = level.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+    match level {
+        None => Ok(DEFAULT_NOTIFICATION_LEVEL.to_string()),
+        Some(level) if NOTIFICATION_LEVELS.contains(&level.as_str()) => Ok(level),
+        Some(level) => Err(format!(
+            "Invalid notification level: {} (expected one of {})",
+            level,
+            NOTIFICATION_LEVELS.join(", ")
+        )),
+    }
+}
+
+/// Validate a requested monitor mode, defaulting to "auto_fix"
+pub(crate) fn normalize_monitor_mode(mode: Option<String>) -> Result<String, String> {
+    let mode = mode.map(|m| m.trim().to_string()).filter(|m| !m.is_empty());
+    match mode {
+        None => Ok("auto_fix".to_string()),
+        Some(mode) if MONITOR_MODES.contains(&mode.as_str()) => Ok(mode),
+        Some(mode) => Err(format!(
+            "Invalid monitor mode: {} (expected one of {})",
+            mode,
+            MONITOR_MODES.join(", ")
+        )),
+    }
+}
+
+/// Whether a monitor at `level` lets a notification of `notification_type` through.
+/// "default" and "all" let everything through ("default" then defers to the global
+/// settings); "important" drops routine comment_found alerts; "completion_only" keeps
+/// just the monitor_complete / monitor_failed alerts sent when the monitor ends.
+pub(crate) fn notification_level_allows(level: &str, notification_type: &str) -> bool {
+    match level {
+        "silent" => false,
+        "completion_only" => matches!(notification_type, "monitor_complete" | "monitor_failed"),
+        "important" => notification_type != "comment_found",
+        _ => true,
+    }
+}
+
+/// Everything a monitor start can ask for. Shared by the `start_monitor`
+/// command, the HTTP API, the monitor queue and deep links.
+#[derive(Debug, Default)]
+pub(crate) struct MonitorStartParams {
+    pub pr_number: i32,
+    /// owner/repo
+    pub repo: String,
+    pub max_iterations: Option<i32>,
+    pub interval_minutes: Option<i32>,
+    pub tags: Option<Vec<String>>,
+    pub notification_level: Option<String>,
+    pub mode: Option<String>,
+    /// Park the monitor as 'blocked' while gh is logged out instead of failing
+    pub allow_queue: bool,
+    /// Start even if the PR is closed, merged or can't be fetched
+    pub force: bool,
+}
+
+/// Why a monitor didn't start
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum MonitorStartError {
+    /// Bad arguments; nothing reached gh or the DB
+    #[error("{}", describe_field_errors(.0))]
+    Invalid(Vec<FieldError>),
+    /// The PR already has a running, sleeping or blocked monitor
+    #[error("Monitor already running for PR: {0}")]
+    AlreadyRunning(String),
+    /// The PR can't be monitored (closed, muted author, bad local clone, ...)
+    #[error("{0}")]
+    Refused(String),
+    /// Not now, but maybe later: gh logged out or unreachable, monitors paused
+    /// or at their limit
+    #[error("{0}")]
+    Unavailable(String),
+    /// Creating the monitor row or spawning its process failed
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl MonitorStartError {
+    /// Whether trying the same start again later can't succeed
+    pub(crate) fn is_permanent(&self) -> bool {
+        matches!(self, Self::Invalid(_) | Self::AlreadyRunning(_) | Self::Refused(_))
+    }
+}
+
+impl From<MonitorStartError> for String {
+    fn from(error: MonitorStartError) -> Self {
+        error.to_string()
+    }
 }
 
 /// Start monitoring a PR
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn start_monitor(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
@@ -126,32 +460,100 @@ pub fn start_monitor(
     repo: String,
     max_iterations: Option<i32>,
     interval_minutes: Option<i32>,
+    tags: Option<Vec<String>>,
+    notification_level: Option<String>,
+    mode: Option<String>,
+    allow_queue: Option<bool>,
+    force: Option<bool>,
 ) -> Result<Monitor, String> {
+    if pr_id != format!("{}#{}", repo, pr_number) {
+        return Err(format!("PR id {} does not match {}#{}", pr_id, repo, pr_number));
+    }
+
+    let params = MonitorStartParams {
+        pr_number,
+        repo,
+        max_iterations,
+        interval_minutes,
+        tags,
+        notification_level,
+        mode,
+        allow_queue: allow_queue.unwrap_or(false),
+        force: force.unwrap_or(false),
+    };
+    Ok(start_monitor_core(&app, &state, params)?)
+}
+
+/// Validate, cache the PR, insert the monitor row and spawn its process.
+/// Emits `monitor:state-changed` plus the PR's `pr:*` diff (its card moves to
+/// the monitoring column). If the process can't be spawned the row is removed
+/// again, so no 'running' monitor is left without a process.
+pub(crate) fn start_monitor_core<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    state: &AppState,
+    params: MonitorStartParams,
+) -> Result<Monitor, MonitorStartError> {
+    use MonitorStartError::{AlreadyRunning, Failed, Invalid, Refused, Unavailable};
+
+    validate_start_request(
+        &params.repo,
+        params.pr_number,
+        params.max_iterations,
+        params.interval_minutes,
+        params.notification_level.as_deref(),
+        params.mode.as_deref(),
+    )
+    .map_err(Invalid)?;
+    let tags = normalize_tags(params.tags).map_err(|e| Invalid(vec![FieldError::new("tags", e)]))?;
+    let notification_level = normalize_notification_level(params.notification_level)
+        .map_err(|e| Invalid(vec![FieldError::new("notificationLevel", e)]))?;
+    let mode = normalize_monitor_mode(params.mode).map_err(|e| Invalid(vec![FieldError::new("mode", e)]))?;
+    let MonitorStartParams { pr_number, repo, .. } = params;
+    let pr_id = format!("{}#{}", repo, pr_number);
+
+    // With gh logged out, allow_queue parks the monitor as 'blocked' until
+    // check_environment sees a login; otherwise the start fails with GH_AUTH_ERROR
+    let blocked = params.allow_queue && environment::gh_auth_missing(state);
+
+    let before = pr_events::snapshot_pr(&state.conn(), &pr_id);
+
+    // Make sure the PR exists and is open
+    let warning = if blocked {
+        None
+    } else {
+        crate::api::ensure_pr_monitorable(state, pr_number, &repo, params.force)?
+    };
+
+    let tags_json = serde_json::to_string(&tags).unwrap_or_else(|_| "[]".to_string());
     let id = Uuid::new_v4().to_string();
-    let max_iter = max_iterations.unwrap_or(10);
-    let interval = interval_minutes.unwrap_or(15); // Default to 15 minutes
-    let (ai_provider, ai_model, dirty_worktree_policy, skip_ci_fix) = {
-        let conn = state
-            .db
-            .lock()
-            .map_err(|e| format!("Failed to lock database: {}", e))?;
+    let max_iter = params.max_iterations.unwrap_or(10);
+    let interval = params.interval_minutes.unwrap_or(DEFAULT_INTERVAL_MINUTES);
+    // What the script actually sleeps between checks
+    let jitter = db::get_monitor_interval_jitter_percent(&state.conn());
+    let effective_interval = jittered_interval(interval, jitter, &id);
+    let (ai_provider, ai_model, dirty_worktree_policy, skip_ci_fix, working_dir) = {
+        let conn = state.conn();
         let (ai_provider, ai_model) = db::get_ai_config(&conn);
         let dirty_worktree_policy = db::get_monitor_dirty_worktree_policy(&conn);
         let skip_ci_fix = db::get_skip_ci_fix(&conn);
-        (ai_provider, ai_model, dirty_worktree_policy, skip_ci_fix)
+        if db::is_pr_muted(&conn, &pr_id) {
+            return Err(Refused(format!("Cannot monitor {}: its author is muted", pr_id)));
+        }
+        if !blocked {
+            monitor_queue::check_capacity(&conn).map_err(Unavailable)?;
+        }
+        let working_dir = crate::process::resolve_working_dir(&conn, &repo).map_err(Refused)?;
+        (ai_provider, ai_model, dirty_worktree_policy, skip_ci_fix, working_dir)
     };
     let now: DateTime<Utc> = Utc::now();
     let started_at = now.to_rfc3339();
-    let next_check = (now + Duration::minutes(interval as i64)).to_rfc3339();
+    let next_check = (now + Duration::minutes(effective_interval as i64)).to_rfc3339();
 
     // Create log file path
-    let log_dir = dirs::data_local_dir()
-        .ok_or_else(|| "Failed to get local data directory".to_string())?
-        .join("com.clanker-spanker.app")
-        .join("logs");
+    let log_dir = state.data_dir().map_err(Failed)?.join("logs");
 
     std::fs::create_dir_all(&log_dir)
-        .map_err(|e| format!("Failed to create log directory: {}", e))?;
+        .map_err(|e| Failed(format!("Failed to create log directory: {}", e)))?;
 
     let log_file = log_dir
         .join(format!("monitor-{}-{}.log", pr_number, &id))
@@ -160,98 +562,100 @@ pub fn start_monitor(
 
     // Database operations in a block to release lock early
     {
-        let conn = state
-            .db
-            .lock()
-            .map_err(|e| format!("Failed to lock database: {}", e))?;
+        let conn = state.conn();
 
         // Check if there's already an active monitor for this PR
         let existing: Option<String> = conn
             .query_row(
-                "SELECT id FROM monitors WHERE pr_id = ?1 AND status IN ('running', 'sleeping')",
+                "SELECT id FROM monitors WHERE pr_id = ?1 AND status IN ('running', 'sleeping', 'blocked')",
                 [&pr_id],
                 |row| row.get(0),
             )
             .ok();
 
         if existing.is_some() {
-            return Err(format!("Monitor already running for PR: {}", pr_id));
+            return Err(AlreadyRunning(pr_id));
         }
 
         conn.execute(
             r#"
             INSERT INTO monitors (
                 id, pr_id, pr_number, repo, status, iteration, max_iterations,
-                interval_minutes, started_at, next_check_at, log_file
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                interval_minutes, started_at, next_check_at, log_file, tags, notification_level,
+                effective_interval_minutes, mode, working_dir
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
             "#,
             params![
                 id,
                 pr_id,
                 pr_number,
                 repo,
-                "running",
+                if blocked { "blocked" } else { "running" },
                 0,
                 max_iter,
                 interval,
                 started_at,
-                next_check,
-                log_file
+                (!blocked).then_some(&next_check),
+                log_file,
+                tags_json,
+                notification_level,
+                effective_interval,
+                mode,
+                working_dir.as_ref().map(|dir| dir.display().to_string())
             ],
         )
-        .map_err(|e| format!("Failed to create monitor: {}", e))?;
+        .map_err(|e| Failed(format!("Failed to create monitor: {}", e)))?;
     }
 
-    // Spawn the monitor process
-    let pid = state.processes.spawn_monitor(
-        &app,
-        &id,
-        &pr_id,
-        pr_number,
-        &repo,
-        max_iter,
-        interval,
-        &ai_provider,
-        ai_model.as_deref(),
-        &dirty_worktree_policy,
-        &skip_ci_fix,
-    )?;
-
-    // Update the PID in the database
-    {
-        let conn = state
-            .db
-            .lock()
-            .map_err(|e| format!("Failed to lock database: {}", e))?;
+    if !blocked {
+        // Spawn the monitor process
+        let spawned = state.processes.spawn_monitor(
+            app,
+            &id,
+            &pr_id,
+            pr_number,
+            &repo,
+            max_iter,
+            effective_interval,
+            &ai_provider,
+            ai_model.as_deref(),
+            &dirty_worktree_policy,
+            &skip_ci_fix,
+            &mode,
+            working_dir.as_deref(),
+        );
+        let pid = match spawned {
+            Ok(pid) => pid,
+            Err(e) => {
+                // Roll back: a 'running' row without a process would never finish
+                let _ = state.conn().execute("DELETE FROM monitors WHERE id = ?1", [&id]);
+                return Err(Failed(e));
+            }
+        };
+
+        // Update the PID in the database
+        let conn = state.conn();
 
         conn.execute(
             "UPDATE monitors SET pid = ?1 WHERE id = ?2",
             params![pid as i32, id],
         )
-        .map_err(|e| format!("Failed to update monitor PID: {}", e))?;
+        .map_err(|e| Failed(format!("Failed to update monitor PID: {}", e)))?;
+        db::move_pr_to_monitoring(&conn, &pr_id)
+            .map_err(|e| Failed(format!("Failed to move PR to monitoring: {}", e)))?;
+
+        // The monitor is running, so any queued start for this PR is done
+        monitor_queue::remove_queued(&conn, &pr_id)
+            .map_err(|e| Failed(format!("Failed to clear queued monitor: {}", e)))?;
     }
 
-    // Emit state change event and update tray
-    emit_state_change(&app, &state);
+    // Emit state change event and update tray, dock and sleep prevention
+    emit_state_change(app, state);
+    pr_events::emit_pr_diff(app, &state.conn(), &pr_id, &before);
 
-    Ok(Monitor {
-        id,
-        pr_id,
-        pr_number,
-        repo,
-        pid: Some(pid as i32),
-        status: "running".to_string(),
-        iteration: 0,
-        max_iterations: max_iter,
-        interval_minutes: interval,
-        started_at,
-        last_check_at: None,
-        next_check_at: Some(next_check),
-        ended_at: None,
-        comments_fixed: 0,
-        exit_reason: None,
-        log_file,
-    })
+    let mut monitor = monitor_by_id(&state.conn(), &id).map_err(Failed)?;
+    monitor.warning = warning;
+    Ok(monitor)
 }
 
 /// Stop a running monitor
@@ -261,57 +665,164 @@ pub fn stop_monitor(
     state: State<'_, AppState>,
     monitor_id: String,
 ) -> Result<Monitor, String> {
+    stop_monitor_with_reason(&app, &state, &monitor_id, "user_stopped")?;
+
+    get_monitor(state, monitor_id)
+}
+
+/// Kill a monitor's process and mark it stopped with the given exit reason
+pub(crate) fn stop_monitor_with_reason<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    state: &AppState,
+    monitor_id: &str,
+    exit_reason: &str,
+) -> Result<(), String> {
     // Kill the process first
-    if let Err(e) = state.processes.kill(&monitor_id) {
+    if let Err(e) = state.processes.kill(monitor_id) {
         eprintln!("Warning: Failed to kill monitor process: {}", e);
     }
 
     // Update database
     {
-        let conn = state
-            .db
-            .lock()
-            .map_err(|e| format!("Failed to lock database: {}", e))?;
+        let conn = state.conn();
 
         let now = Utc::now().to_rfc3339();
 
         conn.execute(
             r#"
             UPDATE monitors
-            SET status = 'stopped', ended_at = ?1, exit_reason = 'user_stopped'
-            WHERE id = ?2 AND status IN ('running', 'sleeping')
+            SET status = 'stopped', ended_at = ?1, exit_reason = ?2
+            WHERE id = ?3 AND status IN ('running', 'sleeping', 'blocked')
             "#,
-            params![now, monitor_id],
+            params![now, exit_reason, monitor_id],
         )
         .map_err(|e| format!("Failed to stop monitor: {}", e))?;
     }
 
     // Emit state change event and update tray
-    emit_state_change(&app, &state);
+    emit_state_change(app, state);
 
-    get_monitor(state, monitor_id)
+    // A slot freed up
+    monitor_queue::start_queued_monitors(app);
+
+    Ok(())
+}
+
+/// Stop the active monitor for a PR that no longer needs monitoring (e.g. merged).
+/// Returns the stopped monitor's ID, or None if the PR had no active monitor.
+pub fn auto_stop_monitor_for_pr<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    state: &AppState,
+    pr_id: &str,
+    exit_reason: &str,
+) -> Result<Option<String>, String> {
+    let monitor_id: Option<String> = {
+        let conn = state.conn();
+
+        match conn.query_row(
+            "SELECT id FROM monitors WHERE pr_id = ?1 AND status IN ('running', 'sleeping', 'blocked')",
+            [pr_id],
+            |row| row.get(0),
+        ) {
+            Ok(id) => Some(id),
+            Err(rusqlite::Error::QueryReturnedNoRows) => None,
+            Err(e) => return Err(format!("Database error: {}", e)),
+        }
+    };
+
+    if let Some(id) = &monitor_id {
+        stop_monitor_with_reason(app, state, id, exit_reason)?;
+    }
+
+    Ok(monitor_id)
+}
+
+/// Stop the active monitor for a PR (for callers that only know the PR ID)
+#[tauri::command]
+pub fn stop_monitor_for_pr(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    pr_id: String,
+) -> Result<Monitor, String> {
+    let monitor_id: String = {
+        let conn = state.conn();
+
+        match conn.query_row(
+            "SELECT id FROM monitors WHERE pr_id = ?1 AND status IN ('running', 'sleeping', 'blocked')",
+            [&pr_id],
+            |row| row.get(0),
+        ) {
+            Ok(id) => id,
+            Err(rusqlite::Error::QueryReturnedNoRows) => {
+                return Err(format!("No active monitor for PR: {}", pr_id))
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        }
+    };
+
+    stop_monitor(app, state, monitor_id)
 }
 
-/// Get all monitors, optionally filtered by status or repo
+/// Get all monitors, optionally filtered by status, repo, or tags (must have all)
 #[tauri::command]
 pub fn get_monitors(
     state: State<'_, AppState>,
     status: Option<String>,
     repo: Option<String>,
+    tags: Option<Vec<String>>,
 ) -> Result<Vec<Monitor>, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    list_monitors(&state.conn(), status, repo, tags)
+}
+
+/// Columns `monitor_from_row` reads, in order
+const MONITOR_COLUMNS: &str = "id, pr_id, pr_number, repo, pid, status, iteration, max_iterations, \
+     interval_minutes, started_at, last_check_at, next_check_at, ended_at, comments_fixed, \
+     exit_reason, log_file, tags, notification_level, head_sha, paused, mode, working_dir";
+
+/// Map a row selected with `MONITOR_COLUMNS` (estimated completion and warning left unset)
+fn monitor_from_row(row: &rusqlite::Row) -> rusqlite::Result<Monitor> {
+    Ok(Monitor {
+        id: row.get(0)?,
+        pr_id: row.get(1)?,
+        pr_number: row.get(2)?,
+        repo: row.get(3)?,
+        pid: row.get(4)?,
+        status: row.get(5)?,
+        iteration: row.get(6)?,
+        max_iterations: row.get(7)?,
+        interval_minutes: row.get(8)?,
+        started_at: row.get(9)?,
+        last_check_at: row.get(10)?,
+        next_check_at: row.get(11)?,
+        ended_at: row.get(12)?,
+        comments_fixed: row.get(13)?,
+        exit_reason: row.get(14)?,
+        log_file: row.get(15)?,
+        tags: parse_tags(&row.get::<_, String>(16)?),
+        notification_level: row.get(17)?,
+        head_sha: row.get(18)?,
+        paused: row.get(19)?,
+        mode: row.get(20)?,
+        working_dir: row.get(21)?,
+        estimated_completion_at: None,
+        warning: None,
+    })
+}
 
-    let mut query = String::from(
+/// Monitors matching the `get_monitors` filters, newest first
+pub(crate) fn list_monitors(
+    conn: &rusqlite::Connection,
+    status: Option<String>,
+    repo: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<Vec<Monitor>, String> {
+    let mut query = format!(
         r#"
-        SELECT id, pr_id, pr_number, repo, pid, status, iteration, max_iterations,
-               interval_minutes, started_at, last_check_at, next_check_at, ended_at,
-               comments_fixed, exit_reason, log_file
+        SELECT {}
         FROM monitors
         WHERE 1=1
         "#,
+        MONITOR_COLUMNS
     );
 
     let mut params: Vec<String> = vec![];
@@ -330,12 +841,21 @@ pub fn get_monitors(
         }
     }
 
+    // Always the full owner/repo: repos can share a name across owners
     if let Some(r) = &repo {
         query.push_str(&format!(" AND repo = ?{}", params.len() + 1));
-        params.push(r.clone());
+        params.push(crate::parse_repo_path(r));
+    }
+
+    for tag in tags.unwrap_or_default() {
+        query.push_str(&format!(
+            " AND EXISTS (SELECT 1 FROM json_each(monitors.tags) WHERE value = ?{})",
+            params.len() + 1
+        ));
+        params.push(tag.trim().to_string());
     }
 
-    query.push_str(" ORDER BY started_at DESC");
+    query.push_str(" ORDER BY started_at DESC, start_seq DESC");
 
     let mut stmt = conn
         .prepare(&query)
@@ -344,215 +864,798 @@ pub fn get_monitors(
     let params_refs: Vec<&dyn rusqlite::ToSql> =
         params.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
 
-    let monitors = stmt
-        .query_map(params_refs.as_slice(), |row| {
-            Ok(Monitor {
-                id: row.get(0)?,
-                pr_id: row.get(1)?,
-                pr_number: row.get(2)?,
-                repo: row.get(3)?,
-                pid: row.get(4)?,
-                status: row.get(5)?,
-                iteration: row.get(6)?,
-                max_iterations: row.get(7)?,
-                interval_minutes: row.get(8)?,
-                started_at: row.get(9)?,
-                last_check_at: row.get(10)?,
-                next_check_at: row.get(11)?,
-                ended_at: row.get(12)?,
-                comments_fixed: row.get(13)?,
-                exit_reason: row.get(14)?,
-                log_file: row.get(15)?,
-            })
-        })
+    let mut monitors = stmt
+        .query_map(params_refs.as_slice(), monitor_from_row)
         .map_err(|e| format!("Failed to query monitors: {}", e))?
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| format!("Failed to read monitor: {}", e))?;
 
+    for monitor in &mut monitors {
+        monitor.estimated_completion_at = estimated_completion_at(conn, &monitor.id);
+    }
+
     Ok(monitors)
 }
 
+/// Columns `export_monitors` can write, in their default order (Monitor's JSON field names)
+pub const MONITOR_EXPORT_COLUMNS: &[&str] = &[
+    "id",
+    "prId",
+    "prNumber",
+    "repo",
+    "status",
+    "mode",
+    "iteration",
+    "maxIterations",
+    "intervalMinutes",
+    "startedAt",
+    "lastCheckAt",
+    "nextCheckAt",
+    "estimatedCompletionAt",
+    "endedAt",
+    "commentsFixed",
+    "exitReason",
+    "tags",
+    "notificationLevel",
+    "headSha",
+    "paused",
+    "workingDir",
+    "logFile",
+];
+
+/// Export monitors matching the `get_monitors` filters as CSV. `columns` picks
+/// and orders the columns (see MONITOR_EXPORT_COLUMNS); all of them by default.
+#[tauri::command]
+pub fn export_monitors(
+    state: State<'_, AppState>,
+    status: Option<String>,
+    repo: Option<String>,
+    tags: Option<Vec<String>>,
+    columns: Option<Vec<String>>,
+) -> Result<String, String> {
+    let columns = export_columns(columns)?;
+    let monitors = list_monitors(&state.conn(), status, repo, tags)?;
+    Ok(monitors_csv(&monitors, &columns))
+}
+
+/// Validate requested export columns, defaulting to all of them
+fn export_columns(columns: Option<Vec<String>>) -> Result<Vec<String>, String> {
+    let Some(columns) = columns.filter(|c| !c.is_empty()) else {
+        return Ok(MONITOR_EXPORT_COLUMNS.iter().map(|c| c.to_string()).collect());
+    };
+    let unknown: Vec<&str> = columns
+        .iter()
+        .map(String::as_str)
+        .filter(|c| !MONITOR_EXPORT_COLUMNS.contains(c))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(format!(
+            "Unknown export column(s): {} (valid columns: {})",
+            unknown.join(", "),
+            MONITOR_EXPORT_COLUMNS.join(", ")
+        ));
+    }
+    Ok(columns)
+}
+
+fn monitors_csv(monitors: &[Monitor], columns: &[String]) -> String {
+    let mut csv = columns.iter().map(|c| csv_field(c)).collect::<Vec<_>>().join(",");
+    csv.push('\n');
+    for monitor in monitors {
+        let row = serde_json::to_value(monitor).unwrap_or_default();
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|column| {
+                let cell = match &row[column.as_str()] {
+                    serde_json::Value::Null => String::new(),
+                    serde_json::Value::String(s) => s.clone(),
+                    // tags
+                    serde_json::Value::Array(items) => items
+                        .iter()
+                        .filter_map(|item| item.as_str())
+                        .collect::<Vec<_>>()
+                        .join(";"),
+                    other => other.to_string(),
+                };
+                csv_field(&cell)
+            })
+            .collect();
+        csv.push_str(&cells.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
 /// Get a single monitor by ID
 #[tauri::command]
 pub fn get_monitor(state: State<'_, AppState>, monitor_id: String) -> Result<Monitor, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    monitor_by_id(&state.conn(), &monitor_id)
+}
 
+pub(crate) fn monitor_by_id(conn: &rusqlite::Connection, monitor_id: &str) -> Result<Monitor, String> {
     conn.query_row(
-        r#"
-        SELECT id, pr_id, pr_number, repo, pid, status, iteration, max_iterations,
-               interval_minutes, started_at, last_check_at, next_check_at, ended_at,
-               comments_fixed, exit_reason, log_file
-        FROM monitors WHERE id = ?1
-        "#,
-        [&monitor_id],
-        |row| {
-            Ok(Monitor {
-                id: row.get(0)?,
-                pr_id: row.get(1)?,
-                pr_number: row.get(2)?,
-                repo: row.get(3)?,
-                pid: row.get(4)?,
-                status: row.get(5)?,
-                iteration: row.get(6)?,
-                max_iterations: row.get(7)?,
-                interval_minutes: row.get(8)?,
-                started_at: row.get(9)?,
-                last_check_at: row.get(10)?,
-                next_check_at: row.get(11)?,
-                ended_at: row.get(12)?,
-                comments_fixed: row.get(13)?,
-                exit_reason: row.get(14)?,
-                log_file: row.get(15)?,
-            })
-        },
+        &format!("SELECT {} FROM monitors WHERE id = ?1", MONITOR_COLUMNS),
+        [monitor_id],
+        monitor_from_row,
     )
+    .map(|mut monitor| {
+        monitor.estimated_completion_at = estimated_completion_at(conn, &monitor.id);
+        monitor
+    })
     .map_err(|e| format!("Monitor not found: {}", e))
 }
 
+/// Update settings of an existing monitor. Fields left as None are unchanged.
+#[tauri::command]
+pub fn update_monitor_config(
+    state: State<'_, AppState>,
+    monitor_id: String,
+    notification_level: Option<String>,
+) -> Result<Monitor, String> {
+    if notification_level.is_some() {
+        let level = normalize_notification_level(notification_level)?;
+        let updated = state
+            .conn()
+            .execute(
+                "UPDATE monitors SET notification_level = ?1 WHERE id = ?2",
+                params![level, monitor_id],
+            )
+            .map_err(|e| format!("Failed to update monitor: {}", e))?;
+        if updated == 0 {
+            return Err(format!("Monitor not found: {}", monitor_id));
+        }
+    }
+
+    get_monitor(state, monitor_id)
+}
+
+/// Get active monitors whose next check is more than `grace_minutes` overdue
+#[tauri::command]
+pub fn get_overdue_monitors(
+    state: State<'_, AppState>,
+    grace_minutes: Option<i64>,
+) -> Result<Vec<Monitor>, String> {
+    let cutoff = Utc::now() - Duration::minutes(grace_minutes.unwrap_or(10).max(0));
+
+    let monitors = get_monitors(state, Some("active".to_string()), None, None)?;
+    Ok(monitors
+        .into_iter()
+        .filter(|m| {
+            // A paused monitor's next check can't be on time
+            !m.paused
+                && m
+                    .next_check_at
+                    .as_deref()
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .is_some_and(|next| next.with_timezone(&Utc) < cutoff)
+        })
+        .collect())
+}
+
 /// Get active monitor for a specific PR (if any)
 #[tauri::command]
 pub fn get_monitor_for_pr(
     state: State<'_, AppState>,
     pr_id: String,
 ) -> Result<Option<Monitor>, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    let conn = state.conn();
 
     let result = conn.query_row(
-        r#"
-        SELECT id, pr_id, pr_number, repo, pid, status, iteration, max_iterations,
-               interval_minutes, started_at, last_check_at, next_check_at, ended_at,
-               comments_fixed, exit_reason, log_file
-        FROM monitors
-        WHERE pr_id = ?1 AND status IN ('running', 'sleeping')
-        ORDER BY started_at DESC
-        LIMIT 1
-        "#,
+        &format!(
+            r#"
+            SELECT {}
+            FROM monitors
+            WHERE pr_id = ?1 AND status IN ('running', 'sleeping')
+            ORDER BY started_at DESC, start_seq DESC
+            LIMIT 1
+            "#,
+            MONITOR_COLUMNS
+        ),
         [&pr_id],
-        |row| {
-            Ok(Monitor {
-                id: row.get(0)?,
-                pr_id: row.get(1)?,
-                pr_number: row.get(2)?,
-                repo: row.get(3)?,
-                pid: row.get(4)?,
-                status: row.get(5)?,
-                iteration: row.get(6)?,
-                max_iterations: row.get(7)?,
-                interval_minutes: row.get(8)?,
-                started_at: row.get(9)?,
-                last_check_at: row.get(10)?,
-                next_check_at: row.get(11)?,
-                ended_at: row.get(12)?,
-                comments_fixed: row.get(13)?,
-                exit_reason: row.get(14)?,
-                log_file: row.get(15)?,
-            })
-        },
+        monitor_from_row,
     );
 
     match result {
-        Ok(monitor) => Ok(Some(monitor)),
+        Ok(mut monitor) => {
+            monitor.estimated_completion_at = estimated_completion_at(&conn, &monitor.id);
+            Ok(Some(monitor))
+        }
         Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
         Err(e) => Err(format!("Database error: {}", e)),
     }
 }
 
-/// Get the most recent monitor for a PR (including completed/failed)
-#[tauri::command]
-pub fn get_recent_monitor_for_pr(
-    state: State<'_, AppState>,
-    pr_id: String,
-) -> Result<Option<Monitor>, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+/// The most recent monitor run for a PR, with a summary of its other runs
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentMonitor {
+    #[serde(flatten)]
+    pub monitor: Monitor,
+    /// Whether any run for the PR is still running or sleeping
+    pub has_active: bool,
+    /// When the PR's latest completed run ended
+    pub last_completed_at: Option<String>,
+}
 
-    let result = conn.query_row(
+/// Most recent monitor for a PR in any of `statuses` (any status if None).
+/// Runs started in the same second are ordered by insertion.
+pub(crate) fn recent_monitor_for_pr(
+    conn: &rusqlite::Connection,
+    pr_id: &str,
+    statuses: Option<&[String]>,
+) -> Result<Option<RecentMonitor>, String> {
+    let mut query = format!(
         r#"
-        SELECT id, pr_id, pr_number, repo, pid, status, iteration, max_iterations,
-               interval_minutes, started_at, last_check_at, next_check_at, ended_at,
-               comments_fixed, exit_reason, log_file
+        SELECT {}
         FROM monitors
         WHERE pr_id = ?1
-        ORDER BY started_at DESC
-        LIMIT 1
         "#,
-        [&pr_id],
-        |row| {
-            Ok(Monitor {
-                id: row.get(0)?,
-                pr_id: row.get(1)?,
-                pr_number: row.get(2)?,
-                repo: row.get(3)?,
-                pid: row.get(4)?,
-                status: row.get(5)?,
-                iteration: row.get(6)?,
-                max_iterations: row.get(7)?,
-                interval_minutes: row.get(8)?,
-                started_at: row.get(9)?,
-                last_check_at: row.get(10)?,
-                next_check_at: row.get(11)?,
-                ended_at: row.get(12)?,
-                comments_fixed: row.get(13)?,
-                exit_reason: row.get(14)?,
-                log_file: row.get(15)?,
-            })
-        },
+        MONITOR_COLUMNS
     );
-
-    match result {
-        Ok(monitor) => Ok(Some(monitor)),
-        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-        Err(e) => Err(format!("Database error: {}", e)),
+    let mut params: Vec<&dyn rusqlite::ToSql> = vec![&pr_id];
+    if let Some(statuses) = statuses.filter(|s| !s.is_empty()) {
+        let placeholders: Vec<String> =
+            (0..statuses.len()).map(|i| format!("?{}", i + 2)).collect();
+        query.push_str(&format!(" AND status IN ({})", placeholders.join(", ")));
+        params.extend(statuses.iter().map(|s| s as &dyn rusqlite::ToSql));
     }
-}
+    query.push_str(" ORDER BY started_at DESC, start_seq DESC LIMIT 1");
 
-/// Read the log file content for a monitor
-#[tauri::command]
-pub fn read_monitor_log(
-    state: State<'_, AppState>,
-    monitor_id: String,
-) -> Result<String, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    let result = conn.query_row(&query, params.as_slice(), monitor_from_row);
+    let mut monitor = match result {
+        Ok(monitor) => monitor,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+        Err(e) => return Err(format!("Database error: {}", e)),
+    };
+    monitor.estimated_completion_at = estimated_completion_at(conn, &monitor.id);
 
-    // Get the log file path
-    let log_file: String = conn
+    let (has_active, last_completed_at) = conn
         .query_row(
-            "SELECT log_file FROM monitors WHERE id = ?1",
-            [&monitor_id],
-            |row| row.get(0),
+            "SELECT EXISTS (SELECT 1 FROM monitors
+                            WHERE pr_id = ?1 AND status IN ('running', 'sleeping')),
+                    (SELECT MAX(ended_at) FROM monitors
+                     WHERE pr_id = ?1 AND status = 'completed')",
+            [pr_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
         )
-        .map_err(|e| format!("Monitor not found: {}", e))?;
+        .map_err(|e| format!("Database error: {}", e))?;
 
-    // Read the log file
-    std::fs::read_to_string(&log_file)
-        .map_err(|e| format!("Failed to read log file: {}", e))
+    Ok(Some(RecentMonitor {
+        monitor,
+        has_active,
+        last_completed_at,
+    }))
 }
 
-/// Update monitor iteration (internal use)
-pub fn update_monitor_iteration(
-    state: &State<'_, AppState>,
-    monitor_id: &str,
-    iteration: i32,
-    comments_fixed: i32,
-) -> Result<(), String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
-
-    let now = Utc::now().to_rfc3339();
-
+/// Get the most recent monitor for a PR (including completed/failed),
+/// optionally only among runs with one of `statuses`
+#[tauri::command]
+pub fn get_recent_monitor_for_pr(
+    state: State<'_, AppState>,
+    pr_id: String,
+    statuses: Option<Vec<String>>,
+) -> Result<Option<RecentMonitor>, String> {
+    recent_monitor_for_pr(&state.conn(), &pr_id, statuses.as_deref())
+}
+
+/// A structured per-iteration log entry from monitor_logs
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorLogEntry {
+    pub iteration: i32,
+    pub timestamp: String,
+    pub action: String,
+    pub message: Option<String>,
+    pub head_sha: Option<String>,
+}
+
+/// Get a monitor's structured log entries, oldest first
+#[tauri::command]
+pub fn get_monitor_logs(
+    state: State<'_, AppState>,
+    monitor_id: String,
+) -> Result<Vec<MonitorLogEntry>, String> {
+    let conn = state.conn();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT iteration, timestamp, action, message, head_sha FROM monitor_logs
+             WHERE monitor_id = ?1 ORDER BY id",
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+    let entries = stmt
+        .query_map([&monitor_id], |row| {
+            Ok(MonitorLogEntry {
+                iteration: row.get(0)?,
+                timestamp: row.get(1)?,
+                action: row.get(2)?,
+                message: row.get(3)?,
+                head_sha: row.get(4)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query monitor logs: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read monitor log: {}", e))?;
+
+    Ok(entries)
+}
+
+/// Lines of log returned by default, and at most, for a tail
+pub const DEFAULT_LOG_TAIL_LINES: usize = 100;
+pub const MAX_LOG_TAIL_LINES: usize = 1000;
+
+/// Only this much of the end of a log file is read for a tail
+const LOG_TAIL_BYTES: u64 = 1024 * 1024;
+
+/// The last `lines` lines of a log file
+pub(crate) fn tail_log(path: &std::path::Path, lines: usize) -> std::io::Result<Vec<String>> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(path)?;
+    let start = file.metadata()?.len().saturating_sub(LOG_TAIL_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+
+    let text = String::from_utf8_lossy(&bytes);
+    let mut all: Vec<&str> = text.lines().collect();
+    // Starting mid-file, the first line is likely cut off
+    if start > 0 && !all.is_empty() {
+        all.remove(0);
+    }
+    let skip = all.len().saturating_sub(lines);
+    Ok(all[skip..].iter().map(|line| line.to_string()).collect())
+}
+
+/// Preflight for the settings UI: install the monitor script as a monitor
+/// start would and check that it exists, is executable and parses
+#[tauri::command]
+pub fn check_monitor_script(app: tauri::AppHandle) -> crate::process::ScriptCheck {
+    crate::process::check_monitor_script(&app)
+}
+
+/// Read the log file content for a monitor
+#[tauri::command]
+pub fn read_monitor_log(
+    state: State<'_, AppState>,
+    monitor_id: String,
+) -> Result<String, String> {
+    let conn = state.conn();
+
+    // Get the log file path
+    let log_file: String = conn
+        .query_row(
+            "SELECT log_file FROM monitors WHERE id = ?1",
+            [&monitor_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Monitor not found: {}", e))?;
+
+    // Read the log file
+    std::fs::read_to_string(&log_file)
+        .map_err(|e| format!("Failed to read log file: {}", e))
+}
+
+/// Pause or resume all automation. Pausing suspends every active monitor's
+/// process group and blocks new starts (start_monitor errors, enqueue_monitor
+/// queues) until resumed. Resuming only continues monitors the switch paused.
+/// The flag is persisted. Returns the IDs of the monitors that changed.
+#[tauri::command]
+pub fn set_monitors_paused(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paused: bool,
+) -> Result<Vec<String>, String> {
+    apply_monitors_paused(&app, &state, paused)
+}
+
+pub(crate) fn apply_monitors_paused<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    state: &AppState,
+    paused: bool,
+) -> Result<Vec<String>, String> {
+    {
+        let conn = state.conn();
+        let value = if paused { "true" } else { "false" };
+        db::set_setting(&conn, db::MONITORS_PAUSED_SETTING_KEY, value)
+            .map_err(|e| format!("Failed to save pause setting: {}", e))?;
+    }
+    let changed = set_active_monitors_paused(state, paused)?;
+
+    tray::set_pause_checked(app, paused);
+    let _ = app.emit("monitor:paused-changed", serde_json::json!({ "paused": paused }));
+    emit_state_change(app, state);
+
+    if !paused {
+        monitor_queue::start_queued_monitors(app);
+    }
+
+    Ok(changed)
+}
+
+/// Suspend (or continue) the process group of every active monitor not
+/// already in that state and record it on the row. Returns the changed IDs.
+fn set_active_monitors_paused(state: &AppState, paused: bool) -> Result<Vec<String>, String> {
+    let ids: Vec<String> = {
+        let conn = state.conn();
+        let mut stmt = conn
+            .prepare("SELECT id FROM monitors WHERE status IN ('running', 'sleeping') AND paused = ?1")
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        let ids = stmt
+            .query_map([!paused], |row| row.get(0))
+            .map_err(|e| format!("Failed to query monitors: {}", e))?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|e| format!("Failed to read monitor: {}", e))?;
+        ids
+    };
+
+    let mut changed = Vec::new();
+    for id in ids {
+        match state.processes.set_group_stopped(&id, paused) {
+            Ok(()) => changed.push(id),
+            // A monitor whose process is gone has nothing left to resume
+            Err(_) if !paused => changed.push(id),
+            Err(e) => eprintln!("Failed to pause monitor {}: {}", id, e),
+        }
+    }
+
+    {
+        let conn = state.conn();
+        let now = Utc::now();
+        for id in &changed {
+            record_paused(&conn, id, paused, now)
+                .map_err(|e| format!("Failed to update monitor: {}", e))?;
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Record a monitor's pause state on its row
+fn record_paused(
+    conn: &rusqlite::Connection,
+    id: &str,
+    paused: bool,
+    now: DateTime<Utc>,
+) -> rusqlite::Result<usize> {
+    if paused {
+        return conn.execute(
+            "UPDATE monitors SET paused = 1, paused_at = ?1 WHERE id = ?2",
+            params![now.to_rfc3339(), id],
+        );
+    }
+
+    // Bank the paused stretch so completion estimates can leave it out, and
+    // push the next check back by it: the stopped sleep resumes where it was
+    let (paused_at, next_check_at): (Option<String>, Option<String>) = conn
+        .query_row(
+            "SELECT paused_at, next_check_at FROM monitors WHERE id = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap_or((None, None));
+    let seconds = paused_at
+        .as_deref()
+        .and_then(parse_time)
+        .map_or(0, |at| (now - at).num_seconds().max(0));
+    let next_check_at = next_check_at
+        .as_deref()
+        .and_then(parse_time)
+        .map(|at| (at + Duration::seconds(seconds)).to_rfc3339());
+    conn.execute(
+        "UPDATE monitors SET paused = 0, paused_at = NULL,
+                paused_seconds = paused_seconds + ?1, next_check_at = ?2
+         WHERE id = ?3",
+        params![seconds, next_check_at, id],
+    )
+}
+
+/// Resume one paused monitor, e.g. one paused by an external push
+#[tauri::command]
+pub fn resume_monitor(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    monitor_id: String,
+) -> Result<Monitor, String> {
+    if db::get_monitors_paused(&state.conn()) {
+        return Err(
+            "Automation is paused; turn the pause switch off to resume monitors".to_string(),
+        );
+    }
+    if !monitor_by_id(&state.conn(), &monitor_id)?.paused {
+        return Err(format!("Monitor {} is not paused", monitor_id));
+    }
+
+    // A monitor whose process is gone has nothing left to resume
+    if let Err(e) = state.processes.set_group_stopped(&monitor_id, false) {
+        eprintln!("Failed to resume monitor {}: {}", monitor_id, e);
+    }
+    {
+        let conn = state.conn();
+        record_paused(&conn, &monitor_id, false, Utc::now())
+            .map_err(|e| format!("Failed to update monitor: {}", e))?;
+    }
+    emit_state_change(&app, &state);
+    monitor_by_id(&state.conn(), &monitor_id)
+}
+
+/// A push to a monitored PR that its monitor didn't make
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalPush {
+    pub monitor_id: String,
+    pub pr_id: String,
+    pub pr_number: i32,
+    pub previous_sha: String,
+    pub head_sha: String,
+    /// Whether the monitor was paused (see `external_push_action`)
+    pub paused: bool,
+    /// The monitor's mode; watch-mode monitors never push, so they aren't paused
+    #[serde(skip)]
+    mode: String,
+}
+
+/// Compare a PR head seen by a fetch or a `@@HEAD` marker with the last head
+/// the PR's active monitor checked or pushed (`@@PUSHED`) or is pushing
+/// (`@@PUSHING`). A different head means someone else pushed mid-run: it is
+/// logged to the monitor's events and returned. Monitors that haven't reported
+/// a head yet have nothing to compare.
+fn detect_external_push(
+    conn: &rusqlite::Connection,
+    pr_id: &str,
+    head_sha: &str,
+) -> Option<ExternalPush> {
+    type Row = (String, i32, Option<String>, Option<String>, bool, String);
+    let (monitor_id, pr_number, known, pushing, paused, mode): Row = conn
+        .query_row(
+            "SELECT id, pr_number, head_sha, pushing_sha, paused, mode FROM monitors
+             WHERE pr_id = ?1 AND status IN ('running', 'sleeping')
+             ORDER BY started_at DESC, start_seq DESC LIMIT 1",
+            [pr_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
+        )
+        .ok()?;
+    if pushing.as_deref() == Some(head_sha) {
+        return None;
+    }
+    let previous_sha = known.filter(|known| known != head_sha)?;
+
+    if let Err(e) = db::record_external_push(conn, &monitor_id, &previous_sha, head_sha) {
+        eprintln!("Failed to record external push on {}: {}", pr_id, e);
+    }
+    Some(ExternalPush {
+        monitor_id,
+        pr_id: pr_id.to_string(),
+        pr_number,
+        previous_sha,
+        head_sha: head_sha.to_string(),
+        paused,
+        mode,
+    })
+}
+
+/// Check a newly seen PR head for a push the monitor didn't make. On one, the
+/// monitor is paused until explicitly resumed (unless `external_push_action`
+/// is "notify", or it is a watch-mode monitor, which has nothing to clobber),
+/// a notification is shown and `monitor:external-push` emitted.
+pub(crate) fn check_external_push<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    state: &AppState,
+    pr_id: &str,
+    head_sha: &str,
+) {
+    let Some(mut push) = detect_external_push(&state.conn(), pr_id, head_sha) else {
+        return;
+    };
+
+    if !push.paused && push.mode != "watch" && db::get_external_push_action(&state.conn()) == "pause" {
+        match state.processes.set_group_stopped(&push.monitor_id, true) {
+            Ok(()) => {
+                let conn = state.conn();
+                push.paused = record_paused(&conn, &push.monitor_id, true, Utc::now()).is_ok();
+            }
+            Err(e) => eprintln!("Failed to pause monitor {}: {}", push.monitor_id, e),
+        }
+    }
+
+    crate::notifications::notify_external_push(app, push.pr_number, &push.pr_id, push.paused);
+    let _ = app.emit("monitor:external-push", &push);
+    emit_state_change(app, state);
+}
+
+/// Pause every active monitor without blocking new starts (unlike
+/// set_monitors_paused). Returns how many were paused.
+#[tauri::command]
+pub fn pause_all_monitors(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let changed = set_active_monitors_paused(&state, true)?;
+    emit_state_change(&app, &state);
+    Ok(changed.len())
+}
+
+/// Resume every paused monitor. Returns how many were resumed.
+#[tauri::command]
+pub fn resume_all_monitors(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    if db::get_monitors_paused(&state.conn()) {
+        return Err(
+            "Automation is paused; turn the pause switch off to resume monitors".to_string(),
+        );
+    }
+    let changed = set_active_monitors_paused(&state, false)?;
+    emit_state_change(&app, &state);
+    Ok(changed.len())
+}
+
+/// Whether a monitor's process exists, independent of its DB status
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Liveness {
+    Alive,
+    Dead,
+    /// No PID to check, or no way to check it on this platform
+    Unknown,
+}
+
+fn monitor_liveness(state: &AppState, monitor_id: &str) -> Result<Liveness, String> {
+    // Spawned by this instance: the registry holds the child and can reap it
+    if state.processes.is_registered(monitor_id) {
+        return Ok(if state.processes.is_running(monitor_id) {
+            Liveness::Alive
+        } else {
+            Liveness::Dead
+        });
+    }
+
+    // Otherwise (e.g. detached before a restart) only the stored PID is left,
+    // checked against the process's group and command line in case it was recycled
+    let (pid, pr_number, repo): (Option<i32>, i32, String) = state
+        .conn()
+        .query_row("SELECT pid, pr_number, repo FROM monitors WHERE id = ?1", [monitor_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .map_err(|e| format!("Monitor not found: {}", e))?;
+    Ok(match pid {
+        Some(pid) if cfg!(unix) => {
+            if crate::process::is_monitor_process(pid, pr_number, &repo) {
+                Liveness::Alive
+            } else {
+                Liveness::Dead
+            }
+        }
+        _ => Liveness::Unknown,
+    })
+}
+
+/// Probe whether a monitor's process is actually alive, so the UI can reconcile
+/// it with the status stored in the DB
+#[tauri::command]
+pub fn is_monitor_alive(state: State<'_, AppState>, monitor_id: String) -> Result<Liveness, String> {
+    monitor_liveness(&state, &monitor_id)
+}
+
+/// Type a line into a running monitor, e.g. to answer a prompt it is stuck on
+#[tauri::command]
+pub fn send_monitor_input(state: State<'_, AppState>, monitor_id: String, text: String) -> Result<(), String> {
+    state.processes.send_input(&monitor_id, &text)
+}
+
+/// List processes spawned by the app: its descendants plus anything left in a
+/// monitor's process group. Unix only; empty elsewhere.
+#[tauri::command]
+pub fn list_app_child_processes(
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::process::AppChildProcess>, String> {
+    let active = active_monitor_ids(&state)?;
+    state.processes.list_app_processes(&active)
+}
+
+/// Terminate processes left in the group of a monitor that is no longer
+/// active. Returns the processes that were signalled.
+#[tauri::command]
+pub fn kill_orphans(state: State<'_, AppState>) -> Result<Vec<crate::process::AppChildProcess>, String> {
+    let active = active_monitor_ids(&state)?;
+    let orphans: Vec<_> = state
+        .processes
+        .list_app_processes(&active)?
+        .into_iter()
+        .filter(|p| p.orphaned)
+        .collect();
+    crate::process::terminate_processes(&orphans);
+    Ok(orphans)
+}
+
+fn active_monitor_ids(state: &AppState) -> Result<Vec<String>, String> {
+    let conn = state.conn();
+    let mut stmt = conn
+        .prepare("SELECT id FROM monitors WHERE status IN ('running', 'sleeping')")
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let ids = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| format!("Failed to query monitors: {}", e))?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| format!("Failed to read monitor: {}", e))?;
+    Ok(ids)
+}
+
+/// How long the "wait" quit behavior gives monitors to finish before killing them
+const QUIT_WAIT_TIMEOUT_SECS: u64 = 30;
+
+/// How long quitting waits for exited monitors' output to be read and their exit recorded
+const QUIT_READER_TIMEOUT_SECS: u64 = 5;
+
+/// Apply the configured quit behavior to running monitors (for app shutdown)
+pub fn shutdown_monitors<R: tauri::Runtime>(app: &tauri::AppHandle<R>, state: &AppState) {
+    let behavior = db::get_quit_behavior(&state.conn());
+
+    println!("Cleaning up monitor processes (quit behavior: {})...", behavior);
+    state.processes.close();
+
+    match behavior.as_str() {
+        "wait" => {
+            let timed_out = state
+                .processes
+                .wait_all(std::time::Duration::from_secs(QUIT_WAIT_TIMEOUT_SECS));
+
+            // Stop the stragglers before killing them, so their readers don't
+            // record the kill as a failure
+            if let Err(e) = db::stop_active_monitors(&state.conn(), "app_quit_timeout", Some(&timed_out)) {
+                eprintln!("Failed to record monitor exit reason: {}", e);
+            }
+            state.processes.kill_all();
+
+            // Monitors that finished on their own record their real outcome
+            state.processes.join_readers(std::time::Duration::from_secs(QUIT_READER_TIMEOUT_SECS));
+            crate::process::record_adopted_exits(app);
+        }
+        "detach" => {
+            let detached = state.processes.detach_all();
+
+            // Leave status untouched so the monitors still read as active
+            let conn = state.conn();
+            for id in &detached {
+                let _ = conn.execute(
+                    "UPDATE monitors SET exit_reason = 'app_quit_detached' WHERE id = ?1 AND status IN ('running', 'sleeping')",
+                    [id],
+                );
+            }
+        }
+        _ => {
+            state.processes.kill_all();
+
+            let conn = state.conn();
+            if let Err(e) = db::stop_active_monitors(&conn, "app_quit", None) {
+                eprintln!("Failed to record monitor exit reason: {}", e);
+            }
+        }
+    }
+}
+
+/// Update monitor iteration (internal use)
+pub fn update_monitor_iteration(
+    state: &State<'_, AppState>,
+    monitor_id: &str,
+    iteration: i32,
+    comments_fixed: i32,
+) -> Result<(), String> {
+    let conn = state.conn();
+
+    let now = Utc::now().to_rfc3339();
+
     conn.execute(
         r#"
         UPDATE monitors
@@ -572,10 +1675,7 @@ pub fn complete_monitor(
     monitor_id: &str,
     exit_reason: &str,
 ) -> Result<(), String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    let conn = state.conn();
 
     let now = Utc::now().to_rfc3339();
 
@@ -598,10 +1698,7 @@ pub fn fail_monitor(
     monitor_id: &str,
     error: &str,
 ) -> Result<(), String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    let conn = state.conn();
 
     let now = Utc::now().to_rfc3339();
 
@@ -633,6 +1730,12 @@ pub struct PRComment {
     pub line: Option<i32>,
     pub created_at: String,
     pub updated_at: String,
+    /// Set when the PR was force-pushed after this comment was fetched
+    pub is_stale: bool,
+    /// Marked as seen in the app; independent of GitHub's resolved state
+    pub acknowledged: bool,
+    /// Resolved thread GitHub no longer returns, kept for history (keep_resolved_history)
+    pub gone_from_github: bool,
 }
 
 /// GitHub review thread structure
@@ -668,48 +1771,49 @@ struct GitHubReviewThreadsResponse {
     review_threads: Vec<GitHubReviewThread>,
 }
 
-/// Fetch all review thread comments for a PR and store in database
+/// Fetch all review thread comments for a PR and store in database.
+/// Emits `pr:updated` if the PR's unresolved thread count changed.
 #[tauri::command]
 pub fn fetch_pr_comments(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     pr_number: i32,
     repo: String,
 ) -> Result<Vec<PRComment>, String> {
-    use std::process::Command;
+    let pr_id = format!("{}#{}", repo, pr_number);
+    let before = {
+        let conn = state.conn();
+        pr_events::snapshot_pr(&conn, &pr_id)
+    };
+
+    let comments = fetch_pr_comments_internal(&state, pr_number, &repo)?;
 
+    pr_events::emit_pr_diff(&app, &state.conn(), &pr_id, &before);
+    Ok(comments)
+}
+
+pub(crate) fn fetch_pr_comments_internal(
+    state: &AppState,
+    pr_number: i32,
+    repo: &str,
+) -> Result<Vec<PRComment>, String> {
     let pr_id = format!("{}#{}", repo, pr_number);
+    crate::comment_refresh::record_fetch(&pr_id);
 
     // Fetch review threads from GitHub
-    let output = Command::new("gh")
-        .args([
-            "pr",
-            "view",
-            &pr_number.to_string(),
-            "--repo",
-            &repo,
-            "--json",
-            "reviewThreads",
-        ])
-        .output()
-        .map_err(|e| format!("Failed to execute gh CLI: {}", e))?;
-
-    if !output.status.success() {
+    let Some(threads) = state.github.review_threads(repo, pr_number)? else {
         // reviewThreads field may not be available in older gh CLI versions
         // Return empty comments list instead of failing
         return Ok(Vec::new());
-    }
+    };
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
     let response: GitHubReviewThreadsResponse =
-        serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+        serde_json::from_value(threads).map_err(|e| format!("Failed to parse JSON: {}", e))?;
 
     let now = Utc::now().to_rfc3339();
 
     // Convert to PRComment and store in database
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    let conn = state.conn();
 
     let mut comments: Vec<PRComment> = Vec::new();
     let mut seen_ids: Vec<String> = Vec::new();
@@ -717,7 +1821,7 @@ pub fn fetch_pr_comments(
     for thread in response.review_threads {
         // Get the first comment in the thread (the main comment)
         if let Some(first_comment) = thread.comments.first() {
-            let comment = PRComment {
+            let mut comment = PRComment {
                 id: first_comment.id.clone(),
                 thread_id: thread.id.clone(),
                 pr_id: pr_id.clone(),
@@ -736,9 +1840,14 @@ pub fn fetch_pr_comments(
                     .updated_at
                     .clone()
                     .unwrap_or_else(|| first_comment.created_at.clone()),
+                is_stale: false,
+                acknowledged: false,
+                gone_from_github: false,
             };
 
-            // Store in database
+            // Store in database. On re-fetch only GitHub-sourced columns are
+            // updated (and the re-fetch clears is_stale); local-only columns
+            // such as acknowledged must never be listed in the update.
             conn.execute(
                 r#"
                 INSERT INTO pr_comments (
@@ -749,7 +1858,9 @@ pub fn fetch_pr_comments(
                     is_resolved = excluded.is_resolved,
                     body = excluded.body,
                     updated_at = excluded.updated_at,
-                    fetched_at = excluded.fetched_at
+                    fetched_at = excluded.fetched_at,
+                    is_stale = 0,
+                    gone_from_github = 0
                 "#,
                 params![
                     comment.id,
@@ -768,26 +1879,44 @@ pub fn fetch_pr_comments(
             )
             .map_err(|e| format!("Failed to insert comment: {}", e))?;
 
+            // The upsert leaves the local ack alone; report what is stored
+            comment.acknowledged = conn
+                .query_row(
+                    "SELECT acknowledged FROM pr_comments WHERE id = ?1",
+                    [&comment.id],
+                    |row| row.get::<_, i32>(0),
+                )
+                .map(|ack| ack != 0)
+                .unwrap_or(false);
+
             seen_ids.push(comment.id.clone());
             comments.push(comment);
         }
     }
 
-    // Delete stale comments that are no longer in GitHub (resolved/deleted threads)
-    if !seen_ids.is_empty() {
-        let placeholders: String = seen_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+    // Delete stale comments that are no longer in GitHub (resolved/deleted threads).
+    // With keep_resolved_history, resolved ones are flagged instead.
+    let placeholders: String = seen_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+    let not_seen = if seen_ids.is_empty() {
+        String::new()
+    } else {
+        format!(" AND id NOT IN ({})", placeholders)
+    };
+    let mut params: Vec<&dyn rusqlite::ToSql> = vec![&pr_id as &dyn rusqlite::ToSql];
+    for id in &seen_ids {
+        params.push(id as &dyn rusqlite::ToSql);
+    }
+    if db::get_keep_resolved_history(&conn) {
         let query = format!(
-            "DELETE FROM pr_comments WHERE pr_id = ?1 AND id NOT IN ({})",
-            placeholders
+            "UPDATE pr_comments SET gone_from_github = 1 WHERE pr_id = ?1 AND is_resolved = 1{}",
+            not_seen
         );
-        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&pr_id as &dyn rusqlite::ToSql];
-        for id in &seen_ids {
-            params.push(id as &dyn rusqlite::ToSql);
-        }
+        conn.execute(&query, params.as_slice()).ok();
+        let query = format!("DELETE FROM pr_comments WHERE pr_id = ?1 AND is_resolved = 0{}", not_seen);
         conn.execute(&query, params.as_slice()).ok();
     } else {
-        // No comments fetched - clear all for this PR
-        conn.execute("DELETE FROM pr_comments WHERE pr_id = ?1", [&pr_id]).ok();
+        let query = format!("DELETE FROM pr_comments WHERE pr_id = ?1{}", not_seen);
+        conn.execute(&query, params.as_slice()).ok();
     }
 
     // Update unresolved_threads count in pr_cache
@@ -801,6 +1930,137 @@ pub fn fetch_pr_comments(
     Ok(comments)
 }
 
+/// Set by `cancel_comment_fetch`; checked between PRs in `fetch_all_pr_comments`
+static COMMENT_FETCH_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Event payload for `comments:progress`
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentFetchProgress {
+    pub current: usize,
+    pub total: usize,
+    pub pr_id: String,
+}
+
+/// Result of `fetch_all_pr_comments`
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchAllCommentsResult {
+    /// Comments per PR id, for every PR fetched before finishing or cancelling
+    pub comments: HashMap<String, Vec<PRComment>>,
+    /// PR ids whose fetch failed
+    pub failed: Vec<String>,
+    pub cancelled: bool,
+    /// Stopped early because the GraphQL budget is down to the reserve
+    pub deferred: bool,
+}
+
+/// Refresh review comments for every open PR (optionally one repo), emitting
+/// `comments:progress` after each PR. Stops early after `cancel_comment_fetch`
+/// or when the rate-limit budget runs low, returning what was fetched so far.
+/// Close to the limit, requests are spaced out (see `throttle`).
+#[tauri::command(async)]
+pub fn fetch_all_pr_comments(
+    app: tauri::AppHandle,
+    repo: Option<String>,
+) -> Result<FetchAllCommentsResult, String> {
+    COMMENT_FETCH_CANCELLED.store(false, Ordering::SeqCst);
+    let repo = repo.as_deref().map(crate::parse_repo_path);
+
+    let (prs, before): (Vec<(String, i32, String)>, pr_events::Snapshot) = {
+        let state = app.state::<AppState>();
+        let conn = state.conn();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, number, repo FROM pr_cache
+                 WHERE state = 'open' AND muted = 0 AND archived = 0
+                   AND (?1 IS NULL OR repo = ?1)
+                 ORDER BY repo, number",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        let rows = stmt
+            .query_map([&repo], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| format!("Query failed: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read rows: {}", e))?;
+        (rows, pr_events::snapshot_all(&conn))
+    };
+
+    let total = prs.len();
+    let mut result = FetchAllCommentsResult::default();
+
+    for (index, (pr_id, pr_number, pr_repo)) in prs.into_iter().enumerate() {
+        if COMMENT_FETCH_CANCELLED.load(Ordering::SeqCst) {
+            result.cancelled = true;
+            break;
+        }
+
+        match throttle::check(&app.state::<AppState>(), "comment fetch") {
+            Throttle::Proceed => {}
+            Throttle::Delay(delay) => std::thread::sleep(delay),
+            Throttle::Defer => {
+                result.deferred = true;
+                break;
+            }
+        }
+
+        match fetch_pr_comments_internal(&app.state::<AppState>(), pr_number, &pr_repo) {
+            Ok(comments) => {
+                result.comments.insert(pr_id.clone(), comments);
+            }
+            Err(e) => {
+                eprintln!("Failed to fetch comments for {}: {}", pr_id, e);
+                result.failed.push(pr_id.clone());
+            }
+        }
+
+        let _ = app.emit(
+            "comments:progress",
+            CommentFetchProgress {
+                current: index + 1,
+                total,
+                pr_id,
+            },
+        );
+    }
+
+    // One batched pr:updated for every PR whose thread counts changed
+    let after = pr_events::snapshot_all(&app.state::<AppState>().conn());
+    pr_events::emit_changes(&app, &pr_events::diff(&before, &after));
+
+    Ok(result)
+}
+
+/// Cancel a running `fetch_all_pr_comments` after the PR it is currently on
+#[tauri::command]
+pub fn cancel_comment_fetch() {
+    COMMENT_FETCH_CANCELLED.store(true, Ordering::SeqCst);
+}
+
+/// Mark a review comment as seen (or unseen with `acknowledged: false`).
+/// This is a local triage flag and survives comment re-fetches.
+#[tauri::command]
+pub fn acknowledge_comment(
+    state: State<'_, AppState>,
+    comment_id: String,
+    acknowledged: Option<bool>,
+) -> Result<(), String> {
+    let acknowledged = acknowledged.unwrap_or(true);
+    let updated = db::set_comment_acknowledged(&state.conn(), &comment_id, acknowledged)
+        .map_err(|e| format!("Failed to acknowledge comment: {}", e))?;
+    if updated == 0 {
+        return Err(format!("Comment not found: {}", comment_id));
+    }
+    Ok(())
+}
+
+/// Acknowledge every unresolved review comment on a PR. Returns how many were updated.
+#[tauri::command]
+pub fn acknowledge_all_comments(state: State<'_, AppState>, pr_id: String) -> Result<usize, String> {
+    db::acknowledge_all_comments(&state.conn(), &pr_id)
+        .map_err(|e| format!("Failed to acknowledge comments: {}", e))
+}
+
 /// Get cached comments for a PR (without fetching from GitHub)
 #[tauri::command]
 pub fn get_pr_comments(
@@ -808,15 +2068,12 @@ pub fn get_pr_comments(
     pr_id: String,
     unresolved_only: Option<bool>,
 ) -> Result<Vec<PRComment>, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    let conn = state.conn();
 
     let query = if unresolved_only.unwrap_or(false) {
-        "SELECT id, thread_id, pr_id, comment_type, is_resolved, author, body, path, line, created_at, updated_at FROM pr_comments WHERE pr_id = ?1 AND is_resolved = 0"
+        "SELECT id, thread_id, pr_id, comment_type, is_resolved, author, body, path, line, created_at, updated_at, is_stale, acknowledged, gone_from_github FROM pr_comments WHERE pr_id = ?1 AND is_resolved = 0"
     } else {
-        "SELECT id, thread_id, pr_id, comment_type, is_resolved, author, body, path, line, created_at, updated_at FROM pr_comments WHERE pr_id = ?1"
+        "SELECT id, thread_id, pr_id, comment_type, is_resolved, author, body, path, line, created_at, updated_at, is_stale, acknowledged, gone_from_github FROM pr_comments WHERE pr_id = ?1"
     };
 
     let mut stmt = conn
@@ -837,6 +2094,9 @@ pub fn get_pr_comments(
                 line: row.get(8)?,
                 created_at: row.get(9)?,
                 updated_at: row.get(10)?,
+                is_stale: row.get::<_, i32>(11)? != 0,
+                acknowledged: row.get::<_, i32>(12)? != 0,
+                gone_from_github: row.get::<_, i32>(13)? != 0,
             })
         })
         .map_err(|e| format!("Query failed: {}", e))?
@@ -845,3 +2105,538 @@ pub fn get_pr_comments(
 
     Ok(comments)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_notification_level() {
+        assert_eq!(normalize_notification_level(None).unwrap(), "default");
+        assert_eq!(normalize_notification_level(Some(" ".to_string())).unwrap(), "default");
+        assert_eq!(normalize_notification_level(Some("all".to_string())).unwrap(), "all");
+        assert_eq!(
+            normalize_notification_level(Some("completion_only".to_string())).unwrap(),
+            "completion_only"
+        );
+        assert!(normalize_notification_level(Some("loud".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_acknowledgements_survive_refetch() {
+        use crate::github::mock::MockGithub;
+        use std::sync::Arc;
+
+        let thread = |id: &str, resolved: bool| {
+            serde_json::json!({
+                "id": format!("T_{}", id),
+                "isResolved": resolved,
+                "comments": [{"id": id, "author": {"login": "rev"}, "body": "fix", "createdAt": ""}],
+            })
+        };
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_view(
+            "o/r",
+            1,
+            serde_json::json!({"state": "OPEN", "reviewThreads": [thread("c1", false), thread("c2", false)]}),
+        );
+        let state = AppState::for_tests(github);
+        crate::api::fetch_and_cache_pr(&state, 1, "o/r").unwrap();
+
+        fetch_pr_comments_internal(&state, 1, "o/r").unwrap();
+        assert_eq!(db::acknowledge_all_comments(&state.conn(), "o/r#1").unwrap(), 2);
+        db::set_comment_acknowledged(&state.conn(), "c2", false).unwrap();
+        db::mark_comments_stale(&state.conn(), "o/r#1").unwrap();
+
+        let comments = fetch_pr_comments_internal(&state, 1, "o/r").unwrap();
+        let acks: Vec<(&str, bool)> =
+            comments.iter().map(|c| (c.id.as_str(), c.acknowledged)).collect();
+        assert_eq!(acks, vec![("c1", true), ("c2", false)]);
+        assert!(comments.iter().all(|c| !c.is_stale));
+
+        // Optionally, fully acknowledged PRs stop needing attention
+        let conn = state.conn();
+        db::set_setting(&conn, db::ATTENTION_EXCLUDES_ACKNOWLEDGED_SETTING_KEY, "true").unwrap();
+        assert_eq!(db::get_attention_count(&conn, None).unwrap(), 1);
+        db::acknowledge_all_comments(&conn, "o/r#1").unwrap();
+        assert_eq!(db::get_attention_count(&conn, None).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_keep_resolved_history() {
+        use crate::github::mock::MockGithub;
+        use std::sync::Arc;
+
+        let thread = |id: &str, resolved: bool| {
+            serde_json::json!({
+                "id": format!("T_{}", id),
+                "isResolved": resolved,
+                "comments": [{"id": id, "author": {"login": "rev"}, "body": "fix", "createdAt": ""}],
+            })
+        };
+        let view = |threads: Vec<serde_json::Value>| {
+            serde_json::json!({"state": "OPEN", "reviewThreads": threads})
+        };
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_view("o/r", 1, view(vec![thread("c1", true), thread("c2", false), thread("c3", true)]));
+        let state = AppState::for_tests(github.clone());
+        crate::api::fetch_and_cache_pr(&state, 1, "o/r").unwrap();
+        let stored = |state: &AppState| -> Vec<(String, bool)> {
+            let conn = state.conn();
+            let mut stmt = conn
+                .prepare("SELECT id, gone_from_github FROM pr_comments ORDER BY id")
+                .unwrap();
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).unwrap();
+            rows.collect::<Result<_, _>>().unwrap()
+        };
+
+        // By default threads GitHub stops returning are deleted
+        fetch_pr_comments_internal(&state, 1, "o/r").unwrap();
+        github.set_view("o/r", 1, view(vec![thread("c1", true)]));
+        fetch_pr_comments_internal(&state, 1, "o/r").unwrap();
+        assert_eq!(stored(&state), [("c1".to_string(), false)]);
+
+        // With history on, resolved ones are kept and flagged; unresolved ones still go
+        db::set_setting(&state.conn(), db::KEEP_RESOLVED_HISTORY_SETTING_KEY, "true").unwrap();
+        github.set_view("o/r", 1, view(vec![thread("c1", true), thread("c2", false), thread("c3", true)]));
+        fetch_pr_comments_internal(&state, 1, "o/r").unwrap();
+        github.set_view("o/r", 1, view(vec![thread("c1", true)]));
+        fetch_pr_comments_internal(&state, 1, "o/r").unwrap();
+        assert_eq!(stored(&state), [("c1".to_string(), false), ("c3".to_string(), true)]);
+        github.set_view("o/r", 1, view(vec![]));
+        fetch_pr_comments_internal(&state, 1, "o/r").unwrap();
+        assert_eq!(stored(&state), [("c1".to_string(), true), ("c3".to_string(), true)]);
+
+        // A thread GitHub returns again is no longer gone
+        github.set_view("o/r", 1, view(vec![thread("c3", true)]));
+        fetch_pr_comments_internal(&state, 1, "o/r").unwrap();
+        assert_eq!(stored(&state), [("c1".to_string(), true), ("c3".to_string(), false)]);
+    }
+
+    #[test]
+    fn test_notification_level_allows() {
+        let types = ["pr_clean", "comment_found", "monitor_complete", "monitor_failed"];
+        let allowed = |level: &str| -> Vec<&str> {
+            types
+                .iter()
+                .copied()
+                .filter(|t| notification_level_allows(level, t))
+                .collect()
+        };
+
+        assert_eq!(allowed("default"), types);
+        assert_eq!(allowed("all"), types);
+        assert_eq!(allowed("important"), ["pr_clean", "monitor_complete", "monitor_failed"]);
+        assert_eq!(allowed("completion_only"), ["monitor_complete", "monitor_failed"]);
+        assert!(allowed("silent").is_empty());
+    }
+
+    #[test]
+    fn test_start_monitor_core() {
+        use crate::github::mock::MockGithub;
+        use std::sync::Arc;
+        use tauri::Manager;
+
+        let github = Arc::new(MockGithub::new("me"));
+        for number in 1..=2 {
+            github.set_view("o/r", number, serde_json::json!({"state": "OPEN", "title": "Open"}));
+        }
+        // Keep monitor logs out of the real app data dir
+        let dir = std::env::temp_dir().join(format!("clanker-start-{}", Uuid::new_v4()));
+        let mut test_state = AppState::for_tests(github);
+        test_state.data_dir = Some(dir.clone());
+        let app = tauri::test::mock_app();
+        app.manage(test_state);
+        let state = app.state::<AppState>();
+        let start = |pr_number: i32| {
+            let params = MonitorStartParams {
+                pr_number,
+                repo: "o/r".to_string(),
+                mode: Some("watch".to_string()),
+                ..Default::default()
+            };
+            start_monitor_core(app.handle(), &state, params)
+        };
+        let rows = |pr_id: &str| -> i32 {
+            state
+                .conn()
+                .query_row("SELECT COUNT(*) FROM monitors WHERE pr_id = ?1", [pr_id], |row| row.get(0))
+                .unwrap()
+        };
+
+        assert!(matches!(start(0), Err(MonitorStartError::Invalid(_))));
+
+        // A spawn failure leaves no 'running' row behind
+        db::set_setting(&state.conn(), db::MONITOR_SCRIPT_PATH_SETTING_KEY, "/nonexistent/monitor.sh")
+            .unwrap();
+        assert!(matches!(start(1), Err(MonitorStartError::Failed(_))));
+        assert_eq!(rows("o/r#1"), 0);
+        let column = || -> String {
+            state
+                .conn()
+                .query_row("SELECT column_assignment FROM pr_cache WHERE id = 'o/r#1'", [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(column(), "todo");
+
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("monitor.sh");
+        std::fs::write(&script, "sleep 5\n").unwrap();
+        db::set_setting(&state.conn(), db::MONITOR_SCRIPT_PATH_SETTING_KEY, script.to_str().unwrap())
+            .unwrap();
+        // ...so the PR can be started again once spawning works
+        let monitor = start(1).unwrap();
+        assert_eq!((monitor.status.as_str(), monitor.mode.as_str()), ("running", "watch"));
+        assert_eq!(monitor.notification_level, DEFAULT_NOTIFICATION_LEVEL);
+        assert!(monitor.pid.is_some());
+        assert_eq!(column(), "monitoring");
+
+        match start(1) {
+            Err(MonitorStartError::AlreadyRunning(pr_id)) => assert_eq!(pr_id, "o/r#1"),
+            other => panic!("expected AlreadyRunning, got {:?}", other.map(|m| m.id)),
+        }
+        assert_eq!(rows("o/r#1"), 1);
+        state.processes.kill(&monitor.id).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_quit_wait_keeps_real_outcome() {
+        use crate::github::mock::MockGithub;
+        use std::sync::Arc;
+        use tauri::Manager;
+
+        let github = Arc::new(MockGithub::new("me"));
+        for number in 1..=2 {
+            github.set_view("o/r", number, serde_json::json!({"state": "OPEN", "title": "Open"}));
+        }
+        let dir = std::env::temp_dir().join(format!("clanker-quit-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("monitor.sh");
+        std::fs::write(&script, "sleep 0.3\necho '@@STATUS:clean@@'\n").unwrap();
+        let mut test_state = AppState::for_tests(github);
+        test_state.data_dir = Some(dir.clone());
+        let app = tauri::test::mock_app();
+        app.manage(test_state);
+        let state = app.state::<AppState>();
+        {
+            let conn = state.conn();
+            db::set_setting(&conn, db::MONITOR_SCRIPT_PATH_SETTING_KEY, script.to_str().unwrap()).unwrap();
+            db::set_setting(&conn, db::QUIT_BEHAVIOR_SETTING_KEY, "wait").unwrap();
+        }
+        let start = |pr_number: i32| {
+            let params = MonitorStartParams { pr_number, repo: "o/r".to_string(), ..Default::default() };
+            start_monitor_core(app.handle(), &state, params)
+        };
+        let monitor = start(1).unwrap();
+
+        // The monitor finished by itself while quitting, so it keeps its own outcome
+        shutdown_monitors(app.handle(), &state);
+        let (status, exit_reason): (String, String) = state
+            .conn()
+            .query_row("SELECT status, exit_reason FROM monitors WHERE id = ?1", [&monitor.id], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((status.as_str(), exit_reason.as_str()), ("completed", "pr_clean"));
+
+        // Nothing new starts once the app is quitting
+        assert!(matches!(start(2), Err(MonitorStartError::Failed(_))));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_monitors_csv() {
+        assert_eq!(export_columns(None).unwrap().len(), MONITOR_EXPORT_COLUMNS.len());
+        assert_eq!(export_columns(Some(vec![])).unwrap().len(), MONITOR_EXPORT_COLUMNS.len());
+        let err = export_columns(Some(vec!["prId".to_string(), "pid".to_string()])).unwrap_err();
+        assert!(err.contains("pid") && err.contains("valid columns: id, prId"), "{}", err);
+
+        let monitor = Monitor {
+            id: "m1".to_string(),
+            pr_id: "o/r#1".to_string(),
+            pr_number: 1,
+            repo: "o/r".to_string(),
+            pid: None,
+            status: "failed".to_string(),
+            iteration: 2,
+            max_iterations: 10,
+            interval_minutes: 15,
+            started_at: "2026-01-01T00:00:00Z".to_string(),
+            last_check_at: None,
+            next_check_at: None,
+            ended_at: None,
+            comments_fixed: 0,
+            exit_reason: Some("said \"no\", twice".to_string()),
+            log_file: String::new(),
+            tags: vec!["ci".to_string(), "urgent".to_string()],
+            notification_level: "all".to_string(),
+            head_sha: None,
+            paused: false,
+            mode: "watch".to_string(),
+            working_dir: None,
+            estimated_completion_at: None,
+            warning: None,
+        };
+        let columns = export_columns(Some(
+            ["prNumber", "exitReason", "tags", "endedAt", "paused"].map(String::from).to_vec(),
+        ))
+        .unwrap();
+        assert_eq!(
+            monitors_csv(&[monitor], &columns),
+            "prNumber,exitReason,tags,endedAt,paused\n1,\"said \"\"no\"\", twice\",ci;urgent,,false\n"
+        );
+    }
+
+    #[test]
+    fn test_validate_start_request() {
+        assert_eq!(validate_start_request("o/r", 1, None, None, None, None), Ok(()));
+        assert_eq!(
+            validate_start_request("o/r.js", 9, Some(100), Some(1440), Some("silent"), Some("watch")),
+            Ok(())
+        );
+
+        let fields = |repo: &str, number: i32, max: Option<i32>, interval: Option<i32>| {
+            validate_start_request(repo, number, max, interval, None, None)
+                .unwrap_err()
+                .into_iter()
+                .map(|e| e.field)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(fields("o/r --template x", 1, None, None), ["repo"]);
+        assert_eq!(fields("", 1, None, None), ["repo"]);
+        assert_eq!(fields("o/r/extra", 1, None, None), ["repo"]);
+        assert_eq!(
+            fields("o/r", 0, Some(0), Some(-5)),
+            ["prNumber", "maxIterations", "intervalMinutes"]
+        );
+        assert_eq!(fields("o/r", 1, Some(101), Some(1441)), ["maxIterations", "intervalMinutes"]);
+        assert!(validate_start_request("o/r", 1, None, None, Some("loud"), None).is_err());
+        assert!(validate_start_request("o/r", 1, None, None, None, Some("yolo")).is_err());
+        assert_eq!(normalize_monitor_mode(None).unwrap(), "auto_fix");
+        assert_eq!(normalize_monitor_mode(Some(" watch ".to_string())).unwrap(), "watch");
+    }
+
+    #[test]
+    fn test_monitor_liveness() {
+        let state = AppState::for_tests_default();
+        let insert = "INSERT INTO monitors (id, pr_id, pr_number, repo, pid, status, started_at, log_file)
+                      VALUES (?1, 'o/r#1', 1, 'o/r', ?2, 'running', '', '/tmp/log')";
+        {
+            let conn = state.conn();
+            // Not in the registry, so only the stored PID can tell
+            conn.execute(insert, params!["mon-dead", i32::MAX]).unwrap();
+            // Alive, but not a monitor process: a recycled PID
+            conn.execute(insert, params!["mon-reused", std::process::id() as i32]).unwrap();
+            conn.execute(insert, params!["mon-no-pid", None::<i32>]).unwrap();
+        }
+
+        let liveness = |id: &str| monitor_liveness(&state, id);
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            let mut monitor = std::process::Command::new("sh")
+                .args(["-c", "sleep 10; :", "monitor-pr-loop.sh", "1", "o/r", "10", "5"])
+                .process_group(0)
+                .spawn()
+                .unwrap();
+            state.conn().execute(insert, params!["mon-alive", monitor.id() as i32]).unwrap();
+            let alive = liveness("mon-alive");
+            let _ = monitor.kill();
+            let _ = monitor.wait();
+            assert_eq!(alive, Ok(Liveness::Alive));
+            assert_eq!(liveness("mon-dead"), Ok(Liveness::Dead));
+            assert_eq!(liveness("mon-reused"), Ok(Liveness::Dead));
+        }
+        assert_eq!(liveness("mon-no-pid"), Ok(Liveness::Unknown));
+        assert!(liveness("missing").is_err());
+    }
+
+    #[test]
+    fn test_estimate_completion() {
+        let start = Utc::now() - Duration::minutes(60);
+        let timing = |iteration: i32, last_check: Option<DateTime<Utc>>| IterationTiming {
+            status: "sleeping".to_string(),
+            iteration,
+            max_iterations: 5,
+            interval_minutes: 15,
+            started_at: start.to_rfc3339(),
+            last_check_at: last_check.map(|t| t.to_rfc3339()),
+            paused_seconds: 0,
+            paused_at: None,
+        };
+
+        // Nothing measured yet: 4 more intervals after the first check
+        let first = estimate_completion(&timing(1, Some(start)), start).unwrap();
+        assert_eq!(first, start + Duration::minutes(60));
+
+        // Three 20-minute cycles so far: one more cycle, then the 5-minute work part
+        let now = start + Duration::minutes(65);
+        let third = timing(4, Some(start + Duration::minutes(60)));
+        assert_eq!(
+            estimate_completion(&third, now).unwrap(),
+            start + Duration::minutes(60 + 20 + 5)
+        );
+
+        // Paused time is not part of a cycle, and a current pause pushes the estimate out
+        let mut paused = timing(4, Some(start + Duration::minutes(60)));
+        paused.paused_seconds = 15 * 60;
+        paused.paused_at = Some((now - Duration::minutes(10)).to_rfc3339());
+        assert_eq!(
+            estimate_completion(&paused, now).unwrap(),
+            start + Duration::minutes(60 + 15 + 10)
+        );
+
+        let mut done = timing(5, None);
+        done.status = "completed".to_string();
+        assert_eq!(estimate_completion(&done, now), None);
+    }
+
+    #[test]
+    fn test_set_active_monitors_paused() {
+        let state = AppState::for_tests_default();
+        {
+            let conn = state.conn();
+            let insert = "INSERT INTO monitors (id, pr_id, pr_number, repo, status, paused, paused_at,
+                                                started_at, log_file)
+                          VALUES (?1, 'o/r#1', 1, 'o/r', ?2, ?3, ?4, '', '')";
+            let paused_at = (Utc::now() - Duration::minutes(2)).to_rfc3339();
+            conn.execute(insert, params!["m-paused", "sleeping", true, paused_at]).unwrap();
+            conn.execute(insert, params!["m-running", "running", false, None::<String>]).unwrap();
+            conn.execute(insert, params!["m-done", "completed", true, None::<String>]).unwrap();
+        }
+
+        // No process registered for m-running, so there is nothing to suspend
+        assert!(set_active_monitors_paused(&state, true).unwrap().is_empty());
+        // Resuming clears the flag even when the process is gone; finished runs are left alone
+        assert_eq!(set_active_monitors_paused(&state, false).unwrap(), ["m-paused"]);
+        let (paused, seconds): (bool, i64) = state
+            .conn()
+            .query_row(
+                "SELECT paused, paused_seconds FROM monitors WHERE id = 'm-paused'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert!(!paused);
+        assert!(seconds >= 120);
+    }
+
+    #[test]
+    fn test_detect_external_push() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        db::init_schema(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO monitors (id, pr_id, pr_number, repo, status, started_at, log_file)
+             VALUES ('m1', 'o/r#1', 1, 'o/r', 'sleeping', '', ''),
+                    ('m0', 'o/r#1', 1, 'o/r', 'completed', '', '')",
+        )
+        .unwrap();
+
+        // Nothing to compare against until the monitor reports a head
+        assert_eq!(detect_external_push(&conn, "o/r#1", "aaa"), None);
+        db::record_monitor_head(&conn, "m1", "aaa").unwrap();
+        assert_eq!(detect_external_push(&conn, "o/r#1", "aaa"), None);
+        // A fetch may see the monitor's own push before `@@PUSHED` arrives
+        db::record_monitor_pushing(&conn, "m1", "bbb").unwrap();
+        assert_eq!(detect_external_push(&conn, "o/r#1", "bbb"), None);
+        db::record_monitor_push(&conn, "m1", "bbb").unwrap();
+        assert_eq!(detect_external_push(&conn, "o/r#1", "bbb"), None);
+
+        let push = detect_external_push(&conn, "o/r#1", "ccc1234567").unwrap();
+        assert_eq!(
+            (push.monitor_id.as_str(), push.pr_number, push.previous_sha.as_str(), push.paused),
+            ("m1", 1, "bbb", false)
+        );
+        // Reported once; the new head is what the monitor knows about now
+        assert_eq!(detect_external_push(&conn, "o/r#1", "ccc1234567"), None);
+        assert_eq!(detect_external_push(&conn, "o/r#2", "ddd"), None);
+
+        let message: String = conn
+            .query_row(
+                "SELECT message FROM monitor_logs WHERE monitor_id = 'm1' AND action = 'external_push'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(message, "External push: bbb -> ccc1234");
+
+        assert_eq!(db::get_external_push_action(&conn), "pause");
+        db::set_setting(&conn, db::EXTERNAL_PUSH_ACTION_SETTING_KEY, "Notify").unwrap();
+        assert_eq!(db::get_external_push_action(&conn), "notify");
+    }
+
+    #[test]
+    fn test_jittered_interval() {
+        let ids: Vec<String> = (0..200).map(|_| Uuid::new_v4().to_string()).collect();
+        let intervals: Vec<i32> = ids.iter().map(|id| jittered_interval(15, 20, id)).collect();
+        assert!(intervals.iter().all(|i| (12..=18).contains(i)));
+        // Spread out, and fixed per monitor
+        assert!(intervals.iter().any(|i| *i < 15) && intervals.iter().any(|i| *i > 15));
+        assert_eq!(jittered_interval(15, 20, &ids[0]), intervals[0]);
+
+        assert!(ids.iter().all(|id| jittered_interval(15, 0, id) == 15));
+        assert!(ids.iter().all(|id| jittered_interval(1, 50, id) >= 1));
+        assert_eq!(jittered_interval(15, 20, "not-a-uuid"), 12);
+    }
+
+    #[test]
+    fn test_tail_log() {
+        let path = std::env::temp_dir().join(format!("clanker-tail-{}.log", Uuid::new_v4()));
+        assert!(tail_log(&path, 10).is_err());
+
+        std::fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        assert_eq!(tail_log(&path, 2).unwrap(), ["two", "three"]);
+        assert_eq!(tail_log(&path, 10).unwrap(), ["one", "two", "three"]);
+
+        // Past the read window, the partial first line is dropped
+        let long = format!("{}\nlast\n", "x".repeat(LOG_TAIL_BYTES as usize));
+        std::fs::write(&path, long).unwrap();
+        assert_eq!(tail_log(&path, 10).unwrap(), ["last"]);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_recent_monitor_for_pr() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        db::init_schema(&conn).unwrap();
+        let insert = "INSERT INTO monitors (id, pr_id, pr_number, repo, status, started_at, ended_at,
+                                            log_file)
+                      VALUES (?1, 'o/r#1', 1, 'o/r', ?2, ?3, ?4, '')";
+        let seed = [
+            ("m1", "completed", "2026-01-01T10:00:00+00:00", Some("2026-01-01T11:00:00+00:00")),
+            ("m2", "completed", "2026-01-02T10:00:00+00:00", Some("2026-01-02T12:00:00+00:00")),
+            // Same second as m2, inserted later: the newer run
+            ("m3", "failed", "2026-01-02T10:00:00+00:00", Some("2026-01-02T10:30:00+00:00")),
+            ("m4", "stopped", "2026-01-01T12:00:00+00:00", Some("2026-01-01T12:05:00+00:00")),
+        ];
+        for (id, status, started_at, ended_at) in seed {
+            conn.execute(insert, params![id, status, started_at, ended_at]).unwrap();
+        }
+
+        let recent = |statuses: Option<&[String]>| {
+            recent_monitor_for_pr(&conn, "o/r#1", statuses).unwrap().unwrap()
+        };
+        let latest = recent(None);
+        assert_eq!(latest.monitor.id, "m3");
+        assert!(!latest.has_active);
+        assert_eq!(latest.last_completed_at.as_deref(), Some("2026-01-02T12:00:00+00:00"));
+
+        let completed = ["completed".to_string()];
+        assert_eq!(recent(Some(&completed)).monitor.id, "m2");
+        let stopped_or_failed = ["stopped".to_string(), "failed".to_string()];
+        assert_eq!(recent(Some(&stopped_or_failed)).monitor.id, "m3");
+        assert_eq!(recent(Some(&[])).monitor.id, "m3");
+        let running = ["running".to_string()];
+        assert!(recent_monitor_for_pr(&conn, "o/r#1", Some(&running)).unwrap().is_none());
+        assert!(recent_monitor_for_pr(&conn, "o/r#2", None).unwrap().is_none());
+
+        // A new run in the same second again wins, and is reported as active
+        conn.execute(insert, params!["m5", "running", "2026-01-02T10:00:00+00:00", None::<String>])
+            .unwrap();
+        let latest = recent(None);
+        assert_eq!(latest.monitor.id, "m5");
+        assert!(latest.has_active);
+        assert_eq!(latest.last_completed_at.as_deref(), Some("2026-01-02T12:00:00+00:00"));
+    }
+}
@@ -0,0 +1,320 @@
+//! Durable queue for monitor starts over the concurrency cap
+//!
+//! With `max_concurrent_monitors` set, `enqueue_monitor` starts a monitor right
+//! away when there's a free slot and otherwise persists the request to the
+//! `monitor_queue` table. Queued requests are started oldest-first whenever a
+//! slot frees up and on app startup, so they survive restarts.
+
+use crate::db::{self, AppState};
+use crate::monitor::{self, Monitor};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// A monitor start waiting for a free slot
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedMonitor {
+    pub pr_id: String,
+    pub pr_number: i32,
+    pub repo: String,
+    pub max_iterations: Option<i32>,
+    pub interval_minutes: Option<i32>,
+    pub tags: Vec<String>,
+    pub notification_level: Option<String>,
+    pub mode: Option<String>,
+    pub queued_at: String,
+}
+
+/// Start parameters stored in the `params` JSON column
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueueParams {
+    max_iterations: Option<i32>,
+    interval_minutes: Option<i32>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    notification_level: Option<String>,
+    #[serde(default)]
+    mode: Option<String>,
+}
+
+/// Returned when starting a monitor while the pause-all switch is on
+pub const MONITORS_PAUSED_ERROR: &str = "Monitors are paused; resume them or queue the monitor instead";
+
+/// Whether another monitor may start under `max_concurrent_monitors`
+/// (never while monitors are paused)
+pub fn has_capacity(conn: &Connection) -> bool {
+    if db::get_monitors_paused(conn) {
+        return false;
+    }
+    match db::get_max_concurrent_monitors(conn) {
+        Some(max) => db::count_active_monitors(conn).unwrap_or(0) < max,
+        None => true,
+    }
+}
+
+/// Error out when starting a monitor would exceed `max_concurrent_monitors`
+pub fn check_capacity(conn: &Connection) -> Result<(), String> {
+    if db::get_monitors_paused(conn) {
+        return Err(MONITORS_PAUSED_ERROR.to_string());
+    }
+    if has_capacity(conn) {
+        return Ok(());
+    }
+    Err(format!(
+        "Monitor limit reached ({} running); queue the monitor instead",
+        db::get_max_concurrent_monitors(conn).unwrap_or(0)
+    ))
+}
+
+/// Drop a PR's queue entry (once its monitor starts, or when cancelled)
+pub fn remove_queued(conn: &Connection, pr_id: &str) -> rusqlite::Result<usize> {
+    conn.execute("DELETE FROM monitor_queue WHERE pr_id = ?1", [pr_id])
+}
+
+fn list_queued(conn: &Connection) -> rusqlite::Result<Vec<QueuedMonitor>> {
+    let mut stmt = conn.prepare(
+        "SELECT pr_id, pr_number, repo, params, queued_at FROM monitor_queue ORDER BY queued_at, rowid",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let params: QueueParams =
+            serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default();
+        Ok(QueuedMonitor {
+            pr_id: row.get(0)?,
+            pr_number: row.get(1)?,
+            repo: row.get(2)?,
+            max_iterations: params.max_iterations,
+            interval_minutes: params.interval_minutes,
+            tags: params.tags,
+            notification_level: params.notification_level,
+            mode: params.mode,
+            queued_at: row.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Start a monitor now if under the cap and not paused, otherwise queue it.
+/// Returns the started monitor, or None when the request was queued.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn enqueue_monitor(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    pr_id: String,
+    pr_number: i32,
+    repo: String,
+    max_iterations: Option<i32>,
+    interval_minutes: Option<i32>,
+    tags: Option<Vec<String>>,
+    notification_level: Option<String>,
+    mode: Option<String>,
+) -> Result<Option<Monitor>, String> {
+    let tags = monitor::normalize_tags(tags)?;
+    let notification_level = monitor::normalize_notification_level(notification_level)?;
+    let mode = monitor::normalize_monitor_mode(mode)?;
+
+    {
+        let conn = state.conn();
+
+        if has_capacity(&conn) {
+            drop(conn);
+            let params = monitor::MonitorStartParams {
+                pr_number,
+                repo,
+                max_iterations,
+                interval_minutes,
+                tags: Some(tags),
+                notification_level: Some(notification_level),
+                mode: Some(mode),
+                ..Default::default()
+            };
+            return Ok(Some(monitor::start_monitor_core(&app, &state, params)?));
+        }
+
+        if db::is_pr_muted(&conn, &pr_id) {
+            return Err(format!("Cannot monitor {}: its author is muted", pr_id));
+        }
+
+        let params = serde_json::to_string(&QueueParams {
+            max_iterations,
+            interval_minutes,
+            tags,
+            notification_level: Some(notification_level),
+            mode: Some(mode),
+        })
+        .map_err(|e| format!("Failed to serialize monitor params: {}", e))?;
+        let inserted = conn
+            .execute(
+                "INSERT OR IGNORE INTO monitor_queue (pr_id, pr_number, repo, params, queued_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![pr_id, pr_number, repo, params, chrono::Utc::now().to_rfc3339()],
+            )
+            .map_err(|e| format!("Failed to queue monitor: {}", e))?;
+        if inserted == 0 {
+            return Err(format!("Monitor already queued for PR: {}", pr_id));
+        }
+    }
+
+    emit_queue_changed(&app, &state);
+    Ok(None)
+}
+
+/// Remove a PR's pending monitor start from the queue
+#[tauri::command]
+pub fn cancel_queued_monitor(app: AppHandle, state: State<'_, AppState>, pr_id: String) -> Result<(), String> {
+    let removed = {
+        let conn = state.conn();
+        remove_queued(&conn, &pr_id).map_err(|e| format!("Failed to cancel queued monitor: {}", e))?
+    };
+
+    if removed == 0 {
+        return Err(format!("No queued monitor for PR: {}", pr_id));
+    }
+
+    emit_queue_changed(&app, &state);
+    Ok(())
+}
+
+/// List pending monitor starts, oldest first
+#[tauri::command]
+pub fn get_queued_monitors(state: State<'_, AppState>) -> Result<Vec<QueuedMonitor>, String> {
+    let conn = state.conn();
+    list_queued(&conn).map_err(|e| format!("Failed to read monitor queue: {}", e))
+}
+
+/// Start queued monitors (oldest first) while there are free slots.
+/// Entries that can never start are dropped so one bad request can't block the
+/// queue; on a failure that may pass the entry stays for the next call (after
+/// a monitor exits or the next environment check).
+pub fn start_queued_monitors<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+
+    let mut changed = false;
+    loop {
+        let next = {
+            let conn = state.conn();
+            // Starts would only fail while gh is logged out; check_environment retries them
+            if !has_capacity(&conn) || crate::environment::gh_auth_missing(&state) {
+                break;
+            }
+            match list_queued(&conn) {
+                Ok(queued) => queued.into_iter().next(),
+                Err(e) => {
+                    eprintln!("Failed to read monitor queue: {}", e);
+                    None
+                }
+            }
+        };
+        let Some(next) = next else {
+            break;
+        };
+
+        changed = true;
+        let params = monitor::MonitorStartParams {
+            pr_number: next.pr_number,
+            repo: next.repo,
+            max_iterations: next.max_iterations,
+            interval_minutes: next.interval_minutes,
+            tags: Some(next.tags),
+            notification_level: next.notification_level,
+            mode: next.mode,
+            ..Default::default()
+        };
+        if let Err(e) = monitor::start_monitor_core(app, &state, params) {
+            eprintln!("Failed to start queued monitor for {}: {}", next.pr_id, e);
+            if !e.is_permanent() {
+                break;
+            }
+            let _ = remove_queued(&state.conn(), &next.pr_id);
+        }
+    }
+
+    if changed {
+        emit_queue_changed(app, &state);
+    }
+}
+
+fn emit_queue_changed<R: Runtime>(app: &AppHandle<R>, state: &AppState) {
+    let count = list_queued(&state.conn())
+        .map(|queued| queued.len())
+        .unwrap_or(0);
+    let _ = app.emit("monitor:queue-changed", serde_json::json!({ "queued": count }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_capacity_and_order() {
+        let conn = Connection::open_in_memory().unwrap();
+        db::init_schema(&conn).unwrap();
+        assert!(has_capacity(&conn));
+
+        db::set_setting(&conn, db::MONITORS_PAUSED_SETTING_KEY, "true").unwrap();
+        assert!(!has_capacity(&conn));
+        assert_eq!(check_capacity(&conn).unwrap_err(), MONITORS_PAUSED_ERROR);
+        db::set_setting(&conn, db::MONITORS_PAUSED_SETTING_KEY, "false").unwrap();
+
+        db::set_setting(&conn, db::MAX_CONCURRENT_MONITORS_SETTING_KEY, "1").unwrap();
+        conn.execute(
+            "INSERT INTO monitors (id, pr_id, pr_number, repo, status, started_at, log_file)
+             VALUES ('m1', 'o/r#1', 1, 'o/r', 'running', '', '')",
+            [],
+        )
+        .unwrap();
+        assert!(!has_capacity(&conn));
+        assert!(check_capacity(&conn).is_err());
+
+        for (pr_id, number, queued_at) in [("o/r#3", 3, "2024-01-02T00:00:00Z"), ("o/r#2", 2, "2024-01-01T00:00:00Z")] {
+            conn.execute(
+                "INSERT INTO monitor_queue (pr_id, pr_number, repo, params, queued_at)
+                 VALUES (?1, ?2, 'o/r', '{\"maxIterations\":3,\"tags\":[\"x\"]}', ?3)",
+                rusqlite::params![pr_id, number, queued_at],
+            )
+            .unwrap();
+        }
+
+        let queued = list_queued(&conn).unwrap();
+        assert_eq!(queued.len(), 2);
+        assert_eq!(queued[0].pr_id, "o/r#2");
+        assert_eq!(queued[0].max_iterations, Some(3));
+        assert_eq!(queued[0].tags, vec!["x".to_string()]);
+
+        assert_eq!(remove_queued(&conn, "o/r#2").unwrap(), 1);
+        assert_eq!(list_queued(&conn).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_start_queued_monitors_keeps_retryable_entries() {
+        use crate::github::mock::MockGithub;
+        use std::sync::Arc;
+
+        // #3 is merged, so it can never start; #2 isn't on the mock, like a PR
+        // gh can't reach right now
+        let github = Arc::new(MockGithub::new("me"));
+        github.set_view("o/r", 3, serde_json::json!({"state": "MERGED", "title": "Merged"}));
+        let app = tauri::test::mock_app();
+        app.manage(AppState::for_tests(github));
+        let state = app.state::<AppState>();
+        for (pr_id, number, queued_at) in [("o/r#3", 3, "2024-01-01T00:00:00Z"), ("o/r#2", 2, "2024-01-02T00:00:00Z")] {
+            state
+                .conn()
+                .execute(
+                    "INSERT INTO monitor_queue (pr_id, pr_number, repo, params, queued_at)
+                     VALUES (?1, ?2, 'o/r', '{}', ?3)",
+                    rusqlite::params![pr_id, number, queued_at],
+                )
+                .unwrap();
+        }
+
+        start_queued_monitors(app.handle());
+        let queued: Vec<String> = list_queued(&state.conn()).unwrap().into_iter().map(|q| q.pr_id).collect();
+        assert_eq!(queued, ["o/r#2"]);
+    }
+}
@@ -0,0 +1,158 @@
+//! Next-check countdowns for active monitors
+//!
+//! The board used to count down from `next_check_at` on its own, which drifts
+//! from the script's real sleep. `get_monitor_ticks` computes the remaining
+//! seconds here instead, from an in-memory copy of the active monitors'
+//! progress so it can be polled every few seconds without touching SQLite.
+//! The copy is reloaded whenever that progress changes: on `@@ITERATION` and
+//! `@@SLEEPING` markers, on starts and stops (`emit_state_change`) and when a
+//! monitor process exits. A paused monitor's countdown stands still.
+//!
+//! `refresh` is called with the database locked, so SNAPSHOT is always taken
+//! after the database lock, never before it.
+
+use crate::db::AppState;
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::State;
+
+/// Countdown for one active monitor
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorTick {
+    pub monitor_id: String,
+    pub status: String,
+    /// None until the script reports its first sleep
+    pub seconds_until_next_check: Option<i64>,
+    pub iteration: i32,
+    pub max_iterations: i32,
+}
+
+#[derive(Debug, Clone)]
+struct ActiveMonitor {
+    status: String,
+    iteration: i32,
+    max_iterations: i32,
+    next_check_at: Option<DateTime<Utc>>,
+    /// Set while paused; the countdown is frozen at this moment
+    paused_at: Option<DateTime<Utc>>,
+}
+
+fn parse_time(time: Option<String>) -> Option<DateTime<Utc>> {
+    time.and_then(|t| DateTime::parse_from_rfc3339(&t).ok())
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Active monitors by id; None until first loaded
+static SNAPSHOT: Mutex<Option<HashMap<String, ActiveMonitor>>> = Mutex::new(None);
+
+fn load_active(conn: &Connection) -> rusqlite::Result<HashMap<String, ActiveMonitor>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, status, iteration, max_iterations, next_check_at,
+                CASE WHEN paused = 1 THEN paused_at END
+         FROM monitors WHERE status IN ('running', 'sleeping')",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            ActiveMonitor {
+                status: row.get(1)?,
+                iteration: row.get(2)?,
+                max_iterations: row.get(3)?,
+                next_check_at: parse_time(row.get(4)?),
+                paused_at: parse_time(row.get(5)?),
+            },
+        ))
+    })?;
+    rows.collect()
+}
+
+/// Reload the active monitors' progress (called whenever it changes)
+pub(crate) fn refresh(conn: &Connection) {
+    match load_active(conn) {
+        Ok(active) => {
+            if let Ok(mut snapshot) = SNAPSHOT.lock() {
+                *snapshot = Some(active);
+            }
+        }
+        Err(e) => eprintln!("Failed to refresh monitor ticks: {}", e),
+    }
+}
+
+fn ticks(active: &HashMap<String, ActiveMonitor>, now: DateTime<Utc>) -> Vec<MonitorTick> {
+    let mut ticks: Vec<MonitorTick> = active
+        .iter()
+        .map(|(id, monitor)| MonitorTick {
+            monitor_id: id.clone(),
+            status: monitor.status.clone(),
+            seconds_until_next_check: monitor
+                .next_check_at
+                .map(|at| (at - monitor.paused_at.unwrap_or(now)).num_seconds().max(0)),
+            iteration: monitor.iteration,
+            max_iterations: monitor.max_iterations,
+        })
+        .collect();
+    ticks.sort_by(|a, b| a.monitor_id.cmp(&b.monitor_id));
+    ticks
+}
+
+/// Seconds until each active monitor's next check, cheap enough to poll
+#[tauri::command]
+pub fn get_monitor_ticks(state: State<'_, AppState>) -> Result<Vec<MonitorTick>, String> {
+    let now = Utc::now();
+    if let Some(active) = SNAPSHOT.lock().map_err(|e| format!("Lock error: {}", e))?.as_ref() {
+        return Ok(ticks(active, now));
+    }
+
+    // First poll: load without holding SNAPSHOT (refresh takes it under the DB lock)
+    let conn = state.conn();
+    let active = load_active(&conn).map_err(|e| format!("Failed to read active monitors: {}", e))?;
+    let ticks = ticks(&active, now);
+    let mut snapshot = SNAPSHOT.lock().map_err(|e| format!("Lock error: {}", e))?;
+    snapshot.get_or_insert(active);
+    Ok(ticks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    #[test]
+    fn test_ticks() {
+        let conn = Connection::open_in_memory().unwrap();
+        db::init_schema(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO monitors (id, pr_id, pr_number, repo, status, iteration, max_iterations,
+                                   started_at, next_check_at, log_file)
+             VALUES ('a', 'o/r#1', 1, 'o/r', 'sleeping', 2, 10, '', '2026-01-01T00:10:00Z', ''),
+                    ('b', 'o/r#2', 2, 'o/r', 'running', 1, 5, '', NULL, ''),
+                    ('c', 'o/r#3', 3, 'o/r', 'sleeping', 3, 10, '', '2026-01-01T00:01:00Z', ''),
+                    ('d', 'o/r#4', 4, 'o/r', 'completed', 4, 10, '', NULL, '')",
+        )
+        .unwrap();
+        // Paused two minutes ago with seven minutes to go: still seven minutes
+        conn.execute_batch(
+            "INSERT INTO monitors (id, pr_id, pr_number, repo, status, iteration, max_iterations,
+                                   started_at, next_check_at, log_file, paused, paused_at)
+             VALUES ('e', 'o/r#5', 5, 'o/r', 'sleeping', 1, 10, '', '2026-01-01T00:10:00Z', '',
+                     1, '2026-01-01T00:03:00Z')",
+        )
+        .unwrap();
+
+        let now = DateTime::parse_from_rfc3339("2026-01-01T00:05:00Z").unwrap().with_timezone(&Utc);
+        let ticks = ticks(&load_active(&conn).unwrap(), now);
+        let summary: Vec<(&str, Option<i64>, i32)> = ticks
+            .iter()
+            .map(|t| (t.monitor_id.as_str(), t.seconds_until_next_check, t.iteration))
+            .collect();
+        // Overdue checks count as due now; finished monitors are left out
+        assert_eq!(
+            summary,
+            [("a", Some(300), 2), ("b", None, 1), ("c", Some(0), 3), ("e", Some(420), 1)]
+        );
+    }
+}
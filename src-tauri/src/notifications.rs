@@ -1,5 +1,8 @@
+use crate::db::{self, AppState};
+use crate::monitor;
+use crate::pr_events::PrChanges;
 use serde::Serialize;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 use tauri_plugin_notification::NotificationExt;
 
 /// Payload for notification events
@@ -11,8 +14,96 @@ pub struct NotificationPayload {
     pub pr_number: i32,
 }
 
+/// PRs from muted authors never raise notifications
+fn is_muted<R: Runtime>(app: &AppHandle<R>, pr_id: &str) -> bool {
+    app.try_state::<AppState>()
+        .map(|state| db::is_pr_muted(&state.conn(), pr_id))
+        .unwrap_or(false)
+}
+
+/// Notification level of the PR's active monitor, unless it defers to the global settings
+fn monitor_level<R: Runtime>(app: &AppHandle<R>, pr_id: &str) -> Option<String> {
+    app.try_state::<AppState>()
+        .and_then(|state| db::get_monitor_notification_level(&state.conn(), pr_id))
+        .filter(|level| level != monitor::DEFAULT_NOTIFICATION_LEVEL)
+}
+
+/// Whether a PR notification should go out. Muted authors never notify; a
+/// running monitor's own notification level overrides the global settings,
+/// which (suppression while focused) otherwise decide.
+fn should_show<R: Runtime>(app: &AppHandle<R>, payload: NotificationPayload) -> bool {
+    let level = monitor_level(app, &payload.pr_id);
+    should_show_at(app, payload, level)
+}
+
+/// `should_show` with the monitor notification level already looked up
+fn should_show_at<R: Runtime>(app: &AppHandle<R>, payload: NotificationPayload, level: Option<String>) -> bool {
+    if is_muted(app, &payload.pr_id) {
+        return false;
+    }
+    match level {
+        Some(level) => monitor::notification_level_allows(&level, &payload.notification_type),
+        None => !suppress_while_focused(app, payload),
+    }
+}
+
+/// Whether the notification plugin is running (it isn't in tests)
+fn plugin_loaded<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.try_state::<tauri_plugin_notification::Notification<R>>().is_some()
+}
+
+/// Record a shown notification in notification_log and emit `notification:shown`
+fn mark_shown<R: Runtime>(app: &AppHandle<R>, title: &str, payload: NotificationPayload) {
+    if let Some(state) = app.try_state::<AppState>() {
+        if let Err(e) = db::record_notification(
+            &state.conn(),
+            &payload.notification_type,
+            &payload.pr_id,
+            payload.pr_number,
+            title,
+        ) {
+            eprintln!("Failed to record notification: {}", e);
+        }
+    }
+    let _ = app.emit("notification:shown", payload);
+}
+
+/// Whether the main window is focused
+fn window_focused<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.get_webview_window("main")
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false)
+}
+
+/// With `suppress_notifications_when_focused` on, skip the OS notification while
+/// the user is looking at the app and emit `notification:suppressed` instead so
+/// the frontend can show an in-app toast
+fn suppress_while_focused<R: Runtime>(app: &AppHandle<R>, payload: NotificationPayload) -> bool {
+    let enabled = app
+        .try_state::<AppState>()
+        .map(|state| db::get_suppress_notifications_when_focused(&state.conn()))
+        .unwrap_or(false);
+    if !enabled || !window_focused(app) {
+        return false;
+    }
+
+    let _ = app.emit("notification:suppressed", payload);
+    true
+}
+
 #[tauri::command]
 pub fn notify_pr_clean(app: AppHandle, pr_number: i32, pr_id: String) -> Result<(), String> {
+    if !should_show(
+        &app,
+        NotificationPayload {
+            notification_type: "pr_clean".to_string(),
+            pr_id: pr_id.clone(),
+            pr_number,
+        },
+    ) {
+        return Ok(());
+    }
+
     app.notification()
         .builder()
         .title("PR is clean!")
@@ -20,9 +111,10 @@ pub fn notify_pr_clean(app: AppHandle, pr_number: i32, pr_id: String) -> Result<
         .show()
         .map_err(|e| e.to_string())?;
 
-    // Emit event for frontend tracking
-    let _ = app.emit(
-        "notification:shown",
+    // Record in the history and emit for frontend tracking
+    mark_shown(
+        &app,
+        "PR is clean!",
         NotificationPayload {
             notification_type: "pr_clean".to_string(),
             pr_id,
@@ -40,6 +132,17 @@ pub fn notify_comment_found(
     pr_id: String,
     count: i32,
 ) -> Result<(), String> {
+    if !should_show(
+        &app,
+        NotificationPayload {
+            notification_type: "comment_found".to_string(),
+            pr_id: pr_id.clone(),
+            pr_number,
+        },
+    ) {
+        return Ok(());
+    }
+
     app.notification()
         .builder()
         .title("Comments found")
@@ -47,8 +150,9 @@ pub fn notify_comment_found(
         .show()
         .map_err(|e| e.to_string())?;
 
-    let _ = app.emit(
-        "notification:shown",
+    mark_shown(
+        &app,
+        "Comments found",
         NotificationPayload {
             notification_type: "comment_found".to_string(),
             pr_id,
@@ -60,32 +164,123 @@ pub fn notify_comment_found(
 }
 
 #[tauri::command]
-pub fn notify_monitor_complete(
-    app: AppHandle,
+pub fn notify_monitor_complete<R: Runtime>(
+    app: AppHandle<R>,
     pr_number: i32,
     pr_id: String,
     comments_fixed: i32,
 ) -> Result<(), String> {
-    let body = if comments_fixed > 0 {
+    if !should_show(
+        &app,
+        NotificationPayload {
+            notification_type: "monitor_complete".to_string(),
+            pr_id: pr_id.clone(),
+            pr_number,
+        },
+    ) {
+        return Ok(());
+    }
+
+    app.notification()
+        .builder()
+        .title("Monitor Complete")
+        .body(monitor_complete_body(pr_number, comments_fixed))
+        .show()
+        .map_err(|e| e.to_string())?;
+
+    mark_shown(
+        &app,
+        "Monitor Complete",
+        NotificationPayload {
+            notification_type: "monitor_complete".to_string(),
+            pr_id,
+            pr_number,
+        },
+    );
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn notify_monitor_failed<R: Runtime>(
+    app: AppHandle<R>,
+    pr_number: i32,
+    pr_id: String,
+    reason: String,
+) -> Result<(), String> {
+    if !should_show(
+        &app,
+        NotificationPayload {
+            notification_type: "monitor_failed".to_string(),
+            pr_id: pr_id.clone(),
+            pr_number,
+        },
+    ) {
+        return Ok(());
+    }
+
+    app.notification()
+        .builder()
+        .title("Monitor Failed")
+        .body(format!("PR #{}: {}", pr_number, reason))
+        .show()
+        .map_err(|e| e.to_string())?;
+
+    mark_shown(
+        &app,
+        "Monitor Failed",
+        NotificationPayload {
+            notification_type: "monitor_failed".to_string(),
+            pr_id,
+            pr_number,
+        },
+    );
+
+    Ok(())
+}
+
+fn monitor_complete_body(pr_number: i32, comments_fixed: i32) -> String {
+    if comments_fixed > 0 {
         format!(
             "PR #{} complete - {} comments fixed",
             pr_number, comments_fixed
         )
     } else {
         format!("PR #{} monitoring complete", pr_number)
-    };
+    }
+}
+
+/// A PR was approved; `message` says what the app did about it
+#[tauri::command]
+pub fn notify_pr_approved(
+    app: AppHandle,
+    pr_number: i32,
+    pr_id: String,
+    message: String,
+) -> Result<(), String> {
+    if !should_show(
+        &app,
+        NotificationPayload {
+            notification_type: "pr_approved".to_string(),
+            pr_id: pr_id.clone(),
+            pr_number,
+        },
+    ) {
+        return Ok(());
+    }
 
     app.notification()
         .builder()
-        .title("Monitor Complete")
-        .body(body)
+        .title("PR approved")
+        .body(message)
         .show()
         .map_err(|e| e.to_string())?;
 
-    let _ = app.emit(
-        "notification:shown",
+    mark_shown(
+        &app,
+        "PR approved",
         NotificationPayload {
-            notification_type: "monitor_complete".to_string(),
+            notification_type: "pr_approved".to_string(),
             pr_id,
             pr_number,
         },
@@ -94,24 +289,37 @@ pub fn notify_monitor_complete(
     Ok(())
 }
 
+/// My review (or one of my teams') was requested on a PR
 #[tauri::command]
-pub fn notify_monitor_failed(
+pub fn notify_review_requested(
     app: AppHandle,
     pr_number: i32,
     pr_id: String,
-    reason: String,
+    message: String,
 ) -> Result<(), String> {
+    if !should_show(
+        &app,
+        NotificationPayload {
+            notification_type: "review_requested".to_string(),
+            pr_id: pr_id.clone(),
+            pr_number,
+        },
+    ) {
+        return Ok(());
+    }
+
     app.notification()
         .builder()
-        .title("Monitor Failed")
-        .body(format!("PR #{}: {}", pr_number, reason))
+        .title("Review requested")
+        .body(message)
         .show()
         .map_err(|e| e.to_string())?;
 
-    let _ = app.emit(
-        "notification:shown",
+    mark_shown(
+        &app,
+        "Review requested",
         NotificationPayload {
-            notification_type: "monitor_failed".to_string(),
+            notification_type: "review_requested".to_string(),
             pr_id,
             pr_number,
         },
@@ -120,6 +328,150 @@ pub fn notify_monitor_failed(
     Ok(())
 }
 
+/// Notify about board PRs on which a fetch newly saw my review requested,
+/// directly or through one of my teams. PRs new to the board are skipped so a
+/// first fetch doesn't raise one notification per waiting PR.
+pub fn notify_review_requests(app: &AppHandle, changes: &PrChanges) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    for update in &changes.updated {
+        if update.changes.get("reviewRequestedAt").is_none_or(|at| at.is_null()) {
+            continue;
+        }
+        let (message, number) = {
+            let conn = state.conn();
+            let pr: Option<(i32, String, String)> = conn
+                .query_row(
+                    "SELECT number, title, reviewer_teams FROM pr_cache WHERE id = ?1",
+                    [&update.pr_id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .ok();
+            let Some((number, title, teams_json)) = pr else {
+                continue;
+            };
+            let my_teams = db::get_my_teams(&conn);
+            let team = serde_json::from_str::<Vec<String>>(&teams_json)
+                .unwrap_or_default()
+                .into_iter()
+                .find(|team| my_teams.contains(&team.to_lowercase()));
+            let message = match team {
+                Some(team) => format!("@{} was asked to review #{} {}", team, number, title),
+                None => format!("Your review was requested on #{} {}", number, title),
+            };
+            (message, number)
+        };
+        let _ = notify_review_requested(app.clone(), number, update.pr_id.clone(), message);
+    }
+}
+
+/// The gh rate-limit budget dropped below `rate_limit_warning_threshold`
+pub fn notify_rate_limit_low(app: &AppHandle, remaining: i64, limit: i64, reset_at: &str) {
+    let payload = NotificationPayload {
+        notification_type: "rate_limit_low".to_string(),
+        pr_id: String::new(),
+        pr_number: 0,
+    };
+    if suppress_while_focused(app, payload.clone()) {
+        return;
+    }
+
+    let resets = chrono::DateTime::parse_from_rfc3339(reset_at)
+        .map(|t| t.with_timezone(&chrono::Local).format("%H:%M").to_string())
+        .unwrap_or_else(|_| reset_at.to_string());
+    let shown = app
+        .notification()
+        .builder()
+        .title("GitHub rate limit running low")
+        .body(format!(
+            "{} of {} GraphQL requests left until {}; auto-refresh is slowed down",
+            remaining, limit, resets
+        ))
+        .show();
+    match shown {
+        Ok(()) => mark_shown(app, "GitHub rate limit running low", payload),
+        Err(e) => eprintln!("Failed to show rate limit notification: {}", e),
+    }
+}
+
+/// Someone else pushed to a monitored PR; `paused` says whether its monitor was
+/// paused. A pause always notifies, whatever the notification level: the
+/// monitor sits idle until someone resumes it.
+pub fn notify_external_push<R: Runtime>(app: &AppHandle<R>, pr_number: i32, pr_id: &str, paused: bool) {
+    // Called from monitor output readers, which also run without the plugin in tests
+    if !plugin_loaded(app) {
+        return;
+    }
+    let payload = NotificationPayload {
+        notification_type: "external_push".to_string(),
+        pr_id: pr_id.to_string(),
+        pr_number,
+    };
+    if !paused && !should_show(app, payload.clone()) {
+        return;
+    }
+
+    let body = if paused {
+        format!("External push detected on #{}, monitor paused", pr_number)
+    } else {
+        format!("External push detected on #{}", pr_number)
+    };
+    match app.notification().builder().title("External push").body(body).show() {
+        Ok(()) => mark_shown(app, "External push", payload),
+        Err(e) => eprintln!("Failed to show external push notification: {}", e),
+    }
+}
+
+/// A monitor's process ended on its own: notify completion or failure per its
+/// status. The monitor is no longer active, so its own notification level is
+/// looked up by id rather than through `should_show`. Returns the notification
+/// when it was let through (whether or not the plugin is there to show it).
+pub(crate) fn notify_monitor_exit<R: Runtime>(
+    app: &AppHandle<R>,
+    monitor_id: &str,
+    pr_number: i32,
+    pr_id: &str,
+    status: &str,
+    exit_reason: &str,
+    comments_fixed: i32,
+) -> Option<NotificationPayload> {
+    let completed = status == "completed";
+    let payload = NotificationPayload {
+        notification_type: if completed { "monitor_complete" } else { "monitor_failed" }.to_string(),
+        pr_id: pr_id.to_string(),
+        pr_number,
+    };
+    let level = app
+        .try_state::<AppState>()
+        .and_then(|state| db::get_notification_level_for_monitor(&state.conn(), monitor_id))
+        .filter(|level| level != monitor::DEFAULT_NOTIFICATION_LEVEL);
+    if !should_show_at(app, payload.clone(), level) {
+        return None;
+    }
+    // Called from monitor exit handling, which also runs without the plugin in tests
+    if !plugin_loaded(app) {
+        return Some(payload);
+    }
+
+    let (title, body) = if completed {
+        ("Monitor Complete", monitor_complete_body(pr_number, comments_fixed))
+    } else {
+        ("Monitor Failed", format!("PR #{}: {}", pr_number, exit_reason.replace('_', " ")))
+    };
+    match app.notification().builder().title(title).body(body).show() {
+        Ok(()) => mark_shown(app, title, payload.clone()),
+        Err(e) => eprintln!("Failed to show monitor exit notification: {}", e),
+    }
+    Some(payload)
+}
+
+/// Whether the app's main window is currently focused
+#[tauri::command]
+pub fn is_window_focused(app: AppHandle) -> bool {
+    window_focused(&app)
+}
+
 /// Show window and emit event to focus a specific PR
 #[tauri::command]
 pub fn show_and_focus_pr(app: AppHandle, pr_id: String) -> Result<(), String> {
@@ -133,3 +485,45 @@ pub fn show_and_focus_pr(app: AppHandle, pr_id: String) -> Result<(), String> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(notification_type: &str) -> NotificationPayload {
+        NotificationPayload {
+            notification_type: notification_type.to_string(),
+            pr_id: "o/r#1".to_string(),
+            pr_number: 1,
+        }
+    }
+
+    #[test]
+    fn test_should_show() {
+        let app = tauri::test::mock_app();
+        app.manage(AppState::for_tests_default());
+        let app = app.handle();
+        let show = |notification_type: &str, level: Option<&str>| {
+            should_show_at(app, payload(notification_type), level.map(str::to_string))
+        };
+
+        // No monitor level and no focused window: the globals let it through
+        assert!(show("pr_clean", None));
+        assert!(show("monitor_complete", Some("completion_only")));
+        assert!(!show("pr_clean", Some("completion_only")));
+        assert!(!show("monitor_failed", Some("silent")));
+
+        let state = app.state::<AppState>();
+        state
+            .conn()
+            .execute_batch(
+                "INSERT INTO monitors (id, pr_id, pr_number, repo, status, started_at, log_file, notification_level)
+                 VALUES ('m1', 'o/r#1', 1, 'o/r', 'completed', '2026-01-01T00:00:00Z', '', 'silent')",
+            )
+            .unwrap();
+        // A finished monitor's level no longer applies
+        assert!(should_show(app, payload("pr_clean")));
+        state.conn().execute("UPDATE monitors SET status = 'running'", []).unwrap();
+        assert!(!should_show(app, payload("pr_clean")));
+    }
+}
@@ -0,0 +1,312 @@
+//! Command palette search
+//!
+//! `palette_search` matches one query against cached PRs, configured repos,
+//! active monitors and recent notifications and returns a single ranked list.
+//! Everything is matched in memory: even a few thousand cached PRs take a few
+//! milliseconds, and substring matches could not use an index anyway.
+
+use crate::db::{self, AppState};
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::State;
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 100;
+
+/// One palette entry, with the identifiers needed to act on it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteItem {
+    /// "pr" | "repo" | "monitor" | "notification"
+    pub kind: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub pr_id: Option<String>,
+    pub repo: Option<String>,
+    pub pr_number: Option<i32>,
+    pub monitor_id: Option<String>,
+    #[serde(skip)]
+    score: f64,
+}
+
+impl PaletteItem {
+    fn new(kind: &str, title: String) -> Self {
+        Self {
+            kind: kind.to_string(),
+            title,
+            subtitle: None,
+            pr_id: None,
+            repo: None,
+            pr_number: None,
+            monitor_id: None,
+            score: 0.0,
+        }
+    }
+}
+
+/// Case-insensitive text match: 3 exact, 2 prefix, 1 substring, 0 none.
+/// `query` must already be lowercase.
+fn text_match(query: &str, text: &str) -> u8 {
+    let text = text.to_lowercase();
+    if text == query {
+        3
+    } else if text.starts_with(query) {
+        2
+    } else if text.contains(query) {
+        1
+    } else {
+        0
+    }
+}
+
+/// Match a query like "12" or "#12" against a PR number: 2 exact, 1 prefix
+fn number_match(query: &str, number: i32) -> u8 {
+    let digits = query.trim_start_matches('#');
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return 0;
+    }
+    let number = number.to_string();
+    if number == digits {
+        2
+    } else if number.starts_with(digits) {
+        1
+    } else {
+        0
+    }
+}
+
+/// Bonus of up to 10 for recently updated items, halving after about a day
+fn recency(timestamp: Option<&str>, now: DateTime<Utc>) -> f64 {
+    let Some(at) = timestamp.and_then(|t| DateTime::parse_from_rfc3339(t).ok()) else {
+        return 0.0;
+    };
+    let days = (now - at.with_timezone(&Utc)).num_minutes().max(0) as f64 / (24.0 * 60.0);
+    10.0 / (1.0 + days)
+}
+
+/// Base score for a PR-like item: number matches first, then title, then repo
+fn pr_score(query: &str, number: i32, title: &str, repo: &str) -> f64 {
+    if query.is_empty() {
+        return 1.0;
+    }
+    let by_number: f64 = match number_match(query, number) {
+        2 => 100.0,
+        1 => 80.0,
+        _ => 0.0,
+    };
+    let by_title = match text_match(query, title) {
+        3 => 70.0,
+        2 => 60.0,
+        1 => 40.0,
+        _ => 0.0,
+    };
+    let by_repo = match text_match(query, repo) {
+        0 => 0.0,
+        1 => 20.0,
+        _ => 30.0,
+    };
+    by_number.max(by_title).max(by_repo)
+}
+
+/// Ranked palette entries for `query` (all kinds mixed, best first)
+pub fn search(
+    conn: &Connection,
+    query: &str,
+    limit: usize,
+    now: DateTime<Utc>,
+) -> rusqlite::Result<Vec<PaletteItem>> {
+    let query = query.trim().to_lowercase();
+    let mut items = Vec::new();
+
+    // PRs on the board
+    let mut stmt = conn.prepare(
+        "SELECT id, number, repo, title, state, updated_at FROM pr_cache
+         WHERE archived = 0 AND muted = 0",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i32>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, String>(5)?,
+        ))
+    })?;
+    for row in rows {
+        let (id, number, repo, title, state, updated_at) = row?;
+        let score = pr_score(&query, number, &title, &repo);
+        if score == 0.0 {
+            continue;
+        }
+        let mut item = PaletteItem::new("pr", format!("#{} {}", number, title));
+        item.subtitle = Some(format!("{} · {}", repo, state));
+        item.score = score + recency(Some(&updated_at), now);
+        item.pr_id = Some(id);
+        item.repo = Some(repo);
+        item.pr_number = Some(number);
+        items.push(item);
+    }
+
+    // Configured repos
+    let repos: Vec<String> = db::get_setting(conn, "repos")?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    for repo in repos {
+        let repo = crate::parse_repo_path(&repo);
+        let name = repo.rsplit('/').next().unwrap_or(&repo);
+        let score = if query.is_empty() {
+            0.5
+        } else {
+            match text_match(&query, &repo).max(text_match(&query, name)) {
+                3 => 90.0,
+                2 => 65.0,
+                1 => 45.0,
+                _ => continue,
+            }
+        };
+        let mut item = PaletteItem::new("repo", repo.clone());
+        item.score = score;
+        item.repo = Some(repo);
+        items.push(item);
+    }
+
+    // Active monitors
+    let mut stmt = conn.prepare(
+        "SELECT m.id, m.pr_id, m.pr_number, m.repo, m.status,
+                COALESCE(m.last_check_at, m.started_at), p.title
+         FROM monitors m LEFT JOIN pr_cache p ON p.id = m.pr_id
+         WHERE m.status IN ('running', 'sleeping')",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i32>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, Option<String>>(5)?,
+            row.get::<_, Option<String>>(6)?,
+        ))
+    })?;
+    for row in rows {
+        let (id, pr_id, number, repo, status, last_active, title) = row?;
+        let title = title.unwrap_or_else(|| pr_id.clone());
+        // Just below the PR itself for the same match
+        let score = pr_score(&query, number, &title, &repo) * 0.95;
+        if score == 0.0 {
+            continue;
+        }
+        let mut item = PaletteItem::new("monitor", format!("Monitor #{} {}", number, title));
+        item.subtitle = Some(format!("{} · {}", repo, status));
+        item.score = score + recency(last_active.as_deref(), now);
+        item.pr_id = Some(pr_id);
+        item.repo = Some(repo);
+        item.pr_number = Some(number);
+        item.monitor_id = Some(id);
+        items.push(item);
+    }
+
+    // Recent notifications
+    let mut stmt = conn.prepare(
+        "SELECT notification_type, pr_id, pr_number, title, created_at FROM notification_log
+         ORDER BY id DESC LIMIT 100",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i32>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+        ))
+    })?;
+    for row in rows {
+        let (notification_type, pr_id, number, title, created_at) = row?;
+        let score = if query.is_empty() {
+            0.5
+        } else if number_match(&query, number) > 0 || text_match(&query, &title) > 0 {
+            25.0
+        } else if text_match(&query, &pr_id) > 0 {
+            15.0
+        } else {
+            continue;
+        };
+        let repo = pr_id.split('#').next().unwrap_or_default().to_string();
+        let mut item = PaletteItem::new("notification", format!("{} (#{})", title, number));
+        item.subtitle = Some(format!("{} · {}", notification_type, created_at));
+        item.score = score + recency(Some(&created_at), now);
+        item.pr_id = Some(pr_id);
+        item.repo = Some(repo);
+        item.pr_number = Some(number);
+        items.push(item);
+    }
+
+    items.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.title.cmp(&b.title)));
+    items.truncate(limit);
+    Ok(items)
+}
+
+/// Search PRs, repos, active monitors and recent notifications for the command palette
+#[tauri::command]
+pub fn palette_search(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<PaletteItem>, String> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    search(&state.conn(), &query, limit, Utc::now()).map_err(|e| format!("Search failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_ranking() {
+        let conn = Connection::open_in_memory().unwrap();
+        db::init_schema(&conn).unwrap();
+        let now = Utc::now();
+        let recent = now.to_rfc3339();
+        let old = (now - chrono::Duration::days(30)).to_rfc3339();
+
+        for (number, title, updated_at) in [
+            (12, "Fix login", &old),
+            (120, "Add 12 factor config", &recent),
+            (7, "Bump deps", &recent),
+            (121, "Refactor", &old),
+        ] {
+            conn.execute(
+                "INSERT INTO pr_cache (id, number, repo, title, url, author, state, review_status,
+                                       branch, base_branch, created_at, updated_at)
+                 VALUES (?1, ?2, 'o/r', ?3, 'u', 'a', 'open', 'pending', 'b', 'main', '', ?4)",
+                rusqlite::params![format!("o/r#{}", number), number, title, updated_at],
+            )
+            .unwrap();
+        }
+        db::set_setting(&conn, "repos", r#"["https://github.com/o/r", "o/other"]"#).unwrap();
+
+        let numbers = |query: &str| -> Vec<Option<i32>> {
+            search(&conn, query, 10, now)
+                .unwrap()
+                .into_iter()
+                .filter(|item| item.kind == "pr")
+                .map(|item| item.pr_number)
+                .collect()
+        };
+        // Exact number first, then prefixes with the recently updated one ahead
+        assert_eq!(numbers("12"), vec![Some(12), Some(120), Some(121)]);
+        assert_eq!(numbers("#7"), vec![Some(7)]);
+        assert_eq!(numbers("login"), vec![Some(12)]);
+
+        let results = search(&conn, "other", 10, now).unwrap();
+        assert_eq!(results[0].kind, "repo");
+        assert_eq!(results[0].repo.as_deref(), Some("o/other"));
+
+        // Empty query lists recent items first, capped at the limit
+        let results = search(&conn, "", 2, now).unwrap();
+        let recent: Vec<Option<i32>> = results.iter().map(|item| item.pr_number).collect();
+        assert_eq!(recent, vec![Some(120), Some(7)]);
+    }
+}
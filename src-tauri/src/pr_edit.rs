@@ -0,0 +1,161 @@
+//! Light PR grooming: labels and draft state
+//!
+//! Edits go through `gh` and are mirrored into the PR cache on success so the
+//! board updates without waiting for the next fetch.
+
+use crate::db::AppState;
+use crate::pr_events;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, State};
+
+/// How long a repo's label list is reused for autocomplete
+const REPO_LABELS_CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// repo -> (fetched at, label names)
+type RepoLabelsCache = HashMap<String, (Instant, Vec<String>)>;
+
+static REPO_LABELS_CACHE: Mutex<Option<RepoLabelsCache>> = Mutex::new(None);
+
+/// Look up a cached PR's repo and number
+fn lookup_pr(state: &AppState, pr_id: &str) -> Result<(String, i32), String> {
+    let conn = state.conn();
+    conn.query_row(
+        "SELECT repo, number FROM pr_cache WHERE id = ?1",
+        [pr_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .map_err(|e| format!("PR not found in cache: {} ({})", pr_id, e))
+}
+
+fn cached_labels(conn: &rusqlite::Connection, pr_id: &str) -> Result<Vec<String>, String> {
+    let labels_json: String = conn
+        .query_row("SELECT labels FROM pr_cache WHERE id = ?1", [pr_id], |row| row.get(0))
+        .map_err(|e| format!("Failed to read labels: {}", e))?;
+    Ok(serde_json::from_str(&labels_json).unwrap_or_default())
+}
+
+/// Add and remove labels on a PR. Returns the updated label list.
+#[tauri::command]
+pub fn set_pr_labels(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    pr_id: String,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let add: Vec<String> = add
+        .into_iter()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+    let remove: Vec<String> = remove
+        .into_iter()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    let (repo, number) = lookup_pr(&state, &pr_id)?;
+
+    if add.is_empty() && remove.is_empty() {
+        let conn = state.conn();
+        return cached_labels(&conn, &pr_id);
+    }
+
+    state.github.edit_labels(&repo, number, &add, &remove)?;
+
+    let labels = {
+        let conn = state.conn();
+        let before = pr_events::snapshot_pr(&conn, &pr_id);
+        let labels = apply_label_changes(cached_labels(&conn, &pr_id)?, &add, &remove);
+        conn.execute(
+            "UPDATE pr_cache SET labels = ?1 WHERE id = ?2",
+            [
+                &serde_json::to_string(&labels).unwrap_or_else(|_| "[]".to_string()),
+                &pr_id,
+            ],
+        )
+        .map_err(|e| format!("Failed to update labels: {}", e))?;
+        pr_events::emit_pr_diff(&app, &conn, &pr_id, &before);
+        labels
+    };
+
+    Ok(labels)
+}
+
+/// Mark a PR as draft or ready for review
+#[tauri::command]
+pub fn set_pr_draft(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    pr_id: String,
+    draft: bool,
+) -> Result<(), String> {
+    let (repo, number) = lookup_pr(&state, &pr_id)?;
+
+    state.github.set_draft(&repo, number, draft)?;
+
+    {
+        let conn = state.conn();
+        let before = pr_events::snapshot_pr(&conn, &pr_id);
+        conn.execute(
+            "UPDATE pr_cache SET is_draft = ?1 WHERE id = ?2",
+            rusqlite::params![draft as i32, pr_id],
+        )
+        .map_err(|e| format!("Failed to update draft state: {}", e))?;
+        pr_events::emit_pr_diff(&app, &conn, &pr_id, &before);
+    }
+
+    Ok(())
+}
+
+/// List a repo's labels for the label picker (cached briefly)
+#[tauri::command]
+pub fn get_repo_labels(state: State<'_, AppState>, repo: String) -> Result<Vec<String>, String> {
+    if let Ok(cache) = REPO_LABELS_CACHE.lock() {
+        if let Some((fetched, labels)) = cache.as_ref().and_then(|c| c.get(&repo)) {
+            if fetched.elapsed() < REPO_LABELS_CACHE_TTL {
+                return Ok(labels.clone());
+            }
+        }
+    }
+
+    let mut labels = state.github.repo_labels(&repo)?;
+    labels.sort_by_key(|l| l.to_lowercase());
+
+    if let Ok(mut cache) = REPO_LABELS_CACHE.lock() {
+        cache
+            .get_or_insert_with(HashMap::new)
+            .insert(repo, (Instant::now(), labels.clone()));
+    }
+
+    Ok(labels)
+}
+
+/// Apply label additions/removals to a cached label list
+fn apply_label_changes(mut labels: Vec<String>, add: &[String], remove: &[String]) -> Vec<String> {
+    labels.retain(|l| !remove.iter().any(|r| r.eq_ignore_ascii_case(l)));
+    for label in add {
+        if !labels.iter().any(|l| l.eq_ignore_ascii_case(label)) {
+            labels.push(label.clone());
+        }
+    }
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_label_changes() {
+        let labels = vec!["bug".to_string(), "WIP".to_string()];
+        let result = apply_label_changes(
+            labels,
+            &["needs-review".to_string(), "Bug".to_string()],
+            &["wip".to_string()],
+        );
+        assert_eq!(result, vec!["bug".to_string(), "needs-review".to_string()]);
+    }
+}
@@ -0,0 +1,205 @@
+//! Granular PR change events
+//!
+//! Instead of a coarse `pr:refresh`, writers snapshot the board PRs they are
+//! about to touch, then diff against the new state and emit batched
+//! `pr:added`, `pr:updated` and `pr:removed` events (one array payload each)
+//! so the UI can patch cards in place. Nothing is emitted when nothing changed.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Board-visible PRs keyed by id, as serialized for the frontend
+pub type Snapshot = HashMap<String, Map<String, Value>>;
+
+/// Fields recomputed on every read; diffing them would flag every PR on every refresh
+const VOLATILE_FIELDS: &[&str] = &["waitingHours"];
+
+/// Changed fields of one PR, with their new values
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrUpdate {
+    pub pr_id: String,
+    pub changes: Map<String, Value>,
+}
+
+#[derive(Debug, Default)]
+pub struct PrChanges {
+    pub added: Vec<Value>,
+    pub updated: Vec<PrUpdate>,
+    pub removed: Vec<String>,
+}
+
+impl PrChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Snapshot the board PRs of the given repos
+pub fn snapshot_repos(conn: &rusqlite::Connection, repos: &[String]) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+    for repo in repos {
+        match crate::get_cached_prs_for_repo(conn, repo, false) {
+            Ok(prs) => snapshot.extend(prs.iter().filter_map(to_entry)),
+            Err(e) => eprintln!("Failed to snapshot PRs for {}: {}", repo, e),
+        }
+    }
+    snapshot
+}
+
+/// Snapshot every board PR across repos
+pub fn snapshot_all(conn: &rusqlite::Connection) -> Snapshot {
+    crate::query_cached_prs(conn, "archived = 0 AND muted = 0", [])
+        .unwrap_or_default()
+        .iter()
+        .filter_map(to_entry)
+        .collect()
+}
+
+/// Snapshot a single PR (empty if it isn't on the board)
+pub fn snapshot_pr(conn: &rusqlite::Connection, pr_id: &str) -> Snapshot {
+    crate::query_cached_prs(conn, "id = ?1 AND archived = 0 AND muted = 0", [pr_id])
+        .unwrap_or_default()
+        .iter()
+        .filter_map(to_entry)
+        .collect()
+}
+
+fn to_entry(pr: &crate::PR) -> Option<(String, Map<String, Value>)> {
+    match serde_json::to_value(pr) {
+        Ok(Value::Object(fields)) => Some((pr.id.clone(), fields)),
+        _ => None,
+    }
+}
+
+/// Compare two snapshots of the same scope
+pub fn diff(before: &Snapshot, after: &Snapshot) -> PrChanges {
+    let mut changes = PrChanges::default();
+
+    for (pr_id, fields) in after {
+        let Some(previous) = before.get(pr_id) else {
+            changes.added.push(Value::Object(fields.clone()));
+            continue;
+        };
+
+        let changed: Map<String, Value> = fields
+            .iter()
+            .filter(|(key, value)| {
+                !VOLATILE_FIELDS.contains(&key.as_str()) && previous.get(*key) != Some(*value)
+            })
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        if !changed.is_empty() {
+            changes.updated.push(PrUpdate {
+                pr_id: pr_id.clone(),
+                changes: changed,
+            });
+        }
+    }
+
+    changes.removed = before
+        .keys()
+        .filter(|pr_id| !after.contains_key(*pr_id))
+        .cloned()
+        .collect();
+
+    // Stable order for the frontend (and tests)
+    changes.added.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+    changes.updated.sort_by(|a, b| a.pr_id.cmp(&b.pr_id));
+    changes.removed.sort();
+    changes
+}
+
+/// Emit one batched event per non-empty change kind
+pub fn emit_changes<R: Runtime>(app: &AppHandle<R>, changes: &PrChanges) {
+    if changes.is_empty() {
+        return;
+    }
+    if !changes.added.is_empty() {
+        let _ = app.emit("pr:added", &changes.added);
+    }
+    if !changes.updated.is_empty() {
+        let _ = app.emit("pr:updated", &changes.updated);
+    }
+    if !changes.removed.is_empty() {
+        let _ = app.emit("pr:removed", &changes.removed);
+    }
+}
+
+/// Diff `before` against a fresh snapshot of `pr_id` and emit the result
+pub fn emit_pr_diff<R: Runtime>(
+    app: &AppHandle<R>,
+    conn: &rusqlite::Connection,
+    pr_id: &str,
+    before: &Snapshot,
+) {
+    emit_changes(app, &diff(before, &snapshot_pr(conn, pr_id)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(pr_id: &str, value: Value) -> (String, Map<String, Value>) {
+        match value {
+            Value::Object(fields) => (pr_id.to_string(), fields),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_diff_unchanged_emits_nothing() {
+        let before: Snapshot = [
+            entry("o/r#1", json!({"id": "o/r#1", "title": "a", "waitingHours": 1.0})),
+            entry("o/r#2", json!({"id": "o/r#2", "title": "b"})),
+        ]
+        .into_iter()
+        .collect();
+        let mut after = before.clone();
+        // Read-time values drift between snapshots without being a real change
+        after
+            .get_mut("o/r#1")
+            .unwrap()
+            .insert("waitingHours".to_string(), json!(2.0));
+
+        assert!(diff(&before, &after).is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_changed_fields() {
+        let before: Snapshot = [
+            entry("o/r#1", json!({"id": "o/r#1", "title": "a", "ciStatus": "pending"})),
+            entry("o/r#2", json!({"id": "o/r#2", "title": "b"})),
+        ]
+        .into_iter()
+        .collect();
+        let after: Snapshot = [
+            entry("o/r#1", json!({"id": "o/r#1", "title": "a", "ciStatus": "passing"})),
+            entry("o/r#5", json!({"id": "o/r#5", "title": "e"})),
+            entry("o/r#3", json!({"id": "o/r#3", "title": "c"})),
+            entry("o/r#4", json!({"id": "o/r#4", "title": "d"})),
+        ]
+        .into_iter()
+        .collect();
+
+        let changes = diff(&before, &after);
+        assert_eq!(
+            changes.added,
+            vec![
+                json!({"id": "o/r#3", "title": "c"}),
+                json!({"id": "o/r#4", "title": "d"}),
+                json!({"id": "o/r#5", "title": "e"}),
+            ]
+        );
+        assert_eq!(changes.removed, vec!["o/r#2".to_string()]);
+        assert_eq!(changes.updated.len(), 1);
+        assert_eq!(changes.updated[0].pr_id, "o/r#1");
+        assert_eq!(
+            Value::Object(changes.updated[0].changes.clone()),
+            json!({"ciStatus": "passing"})
+        );
+    }
+}
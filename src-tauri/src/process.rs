@@ -2,19 +2,23 @@ use crate::db::{self, AppState};
 use crate::sleep_prevention;
 use serde::Serialize;
 use std::collections::HashMap;
-use std::io::{BufRead, BufReader};
-use std::process::{Child, Command, Stdio};
-use std::sync::Mutex;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use tauri::{AppHandle, Emitter, Manager, Runtime};
 
-/// Kill an entire process group (the process and all its children)
+/// Kill an entire process group (the leader `pid` and all its children)
 #[cfg(unix)]
-fn kill_process_group(child: &Child) {
-    let pid = child.id() as i32;
+fn kill_process_group(pid: i32) {
     // Kill the process group (negative pid = process group)
     unsafe {
         libc::kill(-pid, libc::SIGTERM);
+        // A paused group only sees the SIGTERM once it's continued
+        libc::kill(-pid, libc::SIGCONT);
     }
     // Give children a moment to exit gracefully, then force kill
     std::thread::sleep(std::time::Duration::from_millis(500));
@@ -23,6 +27,302 @@ fn kill_process_group(child: &Child) {
     }
 }
 
+/// Whether any process is left in a process group
+#[cfg(unix)]
+fn group_alive(pgid: i32) -> bool {
+    let result = unsafe { libc::kill(-pgid, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn group_alive(_pgid: i32) -> bool {
+    false
+}
+
+/// `owner/name` of the fork a PR's head branch lives in (None for same-repo PRs)
+fn fork_head_repo<R: Runtime>(app: &AppHandle<R>, pr_id: &str) -> Option<String> {
+    let state = app.try_state::<AppState>()?;
+    let conn = state.conn();
+    conn.query_row(
+        "SELECT head_repo FROM pr_cache WHERE id = ?1 AND is_fork = 1",
+        [pr_id],
+        |row| row.get(0),
+    )
+    .ok()
+    .flatten()
+}
+
+/// Shared handle to a monitor's log file, written by its stdout and stderr readers
+type MonitorLog = Option<Arc<Mutex<File>>>;
+
+/// The monitor's `log_file`, if it has one
+fn monitor_log_path<R: Runtime>(app: &AppHandle<R>, monitor_id: &str) -> Option<String> {
+    let state = app.try_state::<AppState>()?;
+    let path: String = state
+        .conn()
+        .query_row("SELECT log_file FROM monitors WHERE id = ?1", [monitor_id], |row| row.get(0))
+        .ok()?;
+    (!path.is_empty()).then_some(path)
+}
+
+/// Open the monitor's `log_file` for appending (None if it has none or it can't be opened)
+fn open_monitor_log(path: Option<&str>) -> MonitorLog {
+    let path = path?;
+    match std::fs::OpenOptions::new().create(true).append(true).open(path) {
+        Ok(file) => Some(Arc::new(Mutex::new(file))),
+        Err(e) => {
+            eprintln!("Failed to open monitor log {}: {}", path, e);
+            None
+        }
+    }
+}
+
+fn append_log(log: &MonitorLog, line: &str) {
+    if let Some(file) = log {
+        if let Ok(mut file) = file.lock() {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+/// Check whether a process with the given PID still exists
+#[cfg(unix)]
+pub fn is_pid_alive(pid: i32) -> bool {
+    if pid <= 0 {
+        return false;
+    }
+    // Signal 0 performs error checking only; EPERM means it exists but isn't ours
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+pub fn is_pid_alive(_pid: i32) -> bool {
+    false
+}
+
+/// Whether `pid` is still the monitor script for `pr_number` in `repo` rather
+/// than another process that reused the pid: monitors lead their own process
+/// group and have the PR on their command line (`script <number> <repo> ...`)
+#[cfg(unix)]
+pub fn is_monitor_process(pid: i32, pr_number: i32, repo: &str) -> bool {
+    if !is_pid_alive(pid) {
+        return false;
+    }
+    let output = Command::new("ps")
+        .args(["-o", "pgid=,command=", "-p", &pid.to_string()])
+        .stderr(Stdio::null())
+        .output();
+    let Ok(output) = output else {
+        // Without ps the pid check is all there is
+        return true;
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (pgid, command) = stdout.trim().split_once(char::is_whitespace).unwrap_or(("", ""));
+    pgid.parse() == Ok(pid) && format!("{} ", command.trim()).contains(&format!(" {} {} ", pr_number, repo))
+}
+
+#[cfg(not(unix))]
+pub fn is_monitor_process(_pid: i32, _pr_number: i32, _repo: &str) -> bool {
+    false
+}
+
+/// The first auto-answer whose pattern appears in `line`
+fn auto_answer<'a>(answers: &'a [db::AutoAnswer], line: &str) -> Option<&'a db::AutoAnswer> {
+    answers.iter().find(|answer| line.contains(&answer.pattern))
+}
+
+/// The HTTP API token, passed to monitor scripts so they can call back into the API
+fn api_token<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    let state = app.try_state::<AppState>()?;
+    let token = db::get_api_token(&state.conn());
+    token
+}
+
+/// The monitor script to run: the bundled demo script in demo mode, else the
+/// `monitor_script_path` setting if set, otherwise the bundled
+/// monitor-pr-loop.sh installed into the app data dir
+fn monitor_script<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    if crate::demo::is_active() {
+        return install_script("demo-monitor.sh", include_str!("../scripts/demo-monitor.sh"));
+    }
+
+    let override_path = app
+        .try_state::<AppState>()
+        .and_then(|state| db::get_monitor_script_path(&state.conn()));
+    if let Some(path) = override_path {
+        if !path.is_file() {
+            return Err(format!("Monitor script not found: {}", path.display()));
+        }
+        return Ok(path);
+    }
+
+    install_script("monitor-pr-loop.sh", include_str!("../scripts/monitor-pr-loop.sh"))
+}
+
+/// Write an embedded script into the app data dir's scripts/ and make it executable
+pub(crate) fn install_script(name: &str, content: &str) -> Result<PathBuf, String> {
+    let scripts_dir = db::app_data_dir()?.join("scripts");
+    let script_path = scripts_dir.join(name);
+
+    // Ensure scripts directory exists and script is installed
+    std::fs::create_dir_all(&scripts_dir)
+        .map_err(|e| format!("Failed to create scripts directory: {}", e))?;
+
+    // Always write the embedded script (to update if changed)
+    std::fs::write(&script_path, content)
+        .map_err(|e| format!("Failed to write script: {}", e))?;
+
+    // Make script executable
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = std::fs::metadata(&script_path)
+            .map_err(|e| format!("Failed to get script metadata: {}", e))?
+            .permissions();
+        perms.set_mode(0o755);
+        std::fs::set_permissions(&script_path, perms)
+            .map_err(|e| format!("Failed to set script permissions: {}", e))?;
+    }
+
+    Ok(script_path)
+}
+
+/// Outcome of installing and inspecting the monitor script
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptCheck {
+    /// True when every check passed
+    pub ok: bool,
+    pub path: Option<String>,
+    /// False when the `monitor_script_path` setting points elsewhere
+    pub embedded: bool,
+    pub exists: bool,
+    pub executable: bool,
+    pub syntax_ok: bool,
+    /// The first problem found
+    pub error: Option<String>,
+}
+
+/// Check that a script exists, is executable and passes `bash -n`
+fn inspect_script(path: &Path) -> ScriptCheck {
+    let mut check = ScriptCheck {
+        path: Some(path.display().to_string()),
+        ..Default::default()
+    };
+    let metadata = match std::fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() => metadata,
+        _ => {
+            check.error = Some(format!("Monitor script not found: {}", path.display()));
+            return check;
+        }
+    };
+    check.exists = true;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        check.executable = metadata.permissions().mode() & 0o111 != 0;
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        check.executable = true;
+    }
+    if !check.executable {
+        check.error = Some(format!("Monitor script is not executable: {}", path.display()));
+        return check;
+    }
+
+    match Command::new("bash").arg("-n").arg(path).output() {
+        Ok(output) if output.status.success() => check.syntax_ok = true,
+        Ok(output) => {
+            check.error = Some(format!(
+                "Monitor script has syntax errors: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+        Err(e) => check.error = Some(format!("Failed to run bash: {}", e)),
+    }
+    check.ok = check.syntax_ok;
+    check
+}
+
+/// Install the monitor script the way `spawn_monitor` does, then inspect it
+pub fn check_monitor_script<R: Runtime>(app: &AppHandle<R>) -> ScriptCheck {
+    let embedded = app
+        .try_state::<AppState>()
+        .and_then(|state| db::get_monitor_script_path(&state.conn()))
+        .is_none();
+    let mut check = match monitor_script(app) {
+        Ok(path) => inspect_script(&path),
+        Err(e) => ScriptCheck {
+            error: Some(e),
+            ..Default::default()
+        },
+    };
+    check.embedded = embedded;
+    check
+}
+
+/// The configured working directory for a repo (its `local_path` clone).
+/// Returns `Ok(None)` when none is configured, and an error
+/// when the configured path is missing or isn't inside a git work tree.
+pub fn configured_working_dir(
+    conn: &rusqlite::Connection,
+    repo: &str,
+) -> Result<Option<PathBuf>, String> {
+    let Some(dir) = db::get_repo_clone_path(conn, repo) else {
+        return Ok(None);
+    };
+
+    if !dir.is_dir() {
+        return Err(format!(
+            "Working directory for {} does not exist: {}",
+            repo,
+            dir.display()
+        ));
+    }
+
+    let is_git_repo = Command::new("git")
+        .arg("-C")
+        .arg(&dir)
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !is_git_repo {
+        return Err(format!(
+            "Working directory for {} is not a git repository: {}",
+            repo,
+            dir.display()
+        ));
+    }
+
+    Ok(Some(dir))
+}
+
+/// Resolve the working directory for a repo's monitor: the configured one
+/// (see `configured_working_dir`), which must also have `repo` as its origin
+/// remote since the script edits and pushes from there
+pub fn resolve_working_dir(conn: &rusqlite::Connection, repo: &str) -> Result<Option<PathBuf>, String> {
+    let Some(dir) = configured_working_dir(conn, repo)? else {
+        return Ok(None);
+    };
+
+    let origin = crate::branch_update::run_git(&dir, &["remote", "get-url", "origin"])
+        .map_err(|e| format!("Working directory for {} has no origin remote: {}", repo, e))?;
+    match crate::verify_clone::repo_from_remote_url(&origin) {
+        Some(found) if found.eq_ignore_ascii_case(repo) => Ok(Some(dir)),
+        found => Err(format!(
+            "Working directory {} is a clone of {}, not {}",
+            dir.display(),
+            found.unwrap_or(origin),
+            repo
+        )),
+    }
+}
+
 /// Event payload for terminal output
 #[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -32,18 +332,88 @@ pub struct MonitorOutputPayload {
     pub line: String,
 }
 
+/// A process in the app's own tree, as reported by `list_app_processes`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppChildProcess {
+    pub pid: i32,
+    pub ppid: i32,
+    pub pgid: i32,
+    pub command: String,
+    /// Monitor whose process group the process is in, if any
+    pub monitor_id: Option<String>,
+    /// Left behind in the group of a monitor that is no longer active
+    pub orphaned: bool,
+}
+
 /// Registry for tracking spawned monitor processes
 pub struct ProcessRegistry {
     processes: Mutex<HashMap<String, Child>>,
+    /// Each running monitor's stdin, kept apart from `processes` so a write
+    /// that blocks (a child not reading) never holds up kills and stops
+    inputs: Mutex<HashMap<String, Arc<Mutex<ChildStdin>>>>,
+    /// Process group of every monitor spawned this session. Kept after the
+    /// monitor exits while processes it left behind are still in the group, so
+    /// they can be traced back to it; pruned once the group is empty.
+    groups: Mutex<HashMap<String, i32>>,
+    /// Each running monitor's output pipes, for `detach_all` to hand over
+    #[cfg(unix)]
+    outputs: Mutex<HashMap<String, DetachableOutput>>,
+    /// Each monitor's stdout reader, which records the monitor's exit once
+    /// its output ends
+    readers: Mutex<HashMap<String, thread::JoinHandle<()>>>,
+    /// Set once the app starts shutting down; no new monitors are spawned after
+    closed: AtomicBool,
+    /// Monitors left running by a detached quit and picked up again at launch.
+    /// They aren't our children, so they're tracked (and killed) by pid.
+    adopted: Mutex<HashMap<String, AdoptedMonitor>>,
+}
+
+/// A monitor started by an earlier app instance (see `adopt_detached_monitors`)
+#[derive(Debug, Clone)]
+struct AdoptedMonitor {
+    pid: i32,
+    pr_id: String,
+    pr_number: i32,
+    repo: String,
+}
+
+impl AdoptedMonitor {
+    /// Whether the pid still belongs to this monitor's script
+    fn alive(&self) -> bool {
+        is_monitor_process(self.pid, self.pr_number, &self.repo)
+    }
+}
+
+/// Duplicates of a monitor's stdout and stderr read ends, plus its log file.
+/// On detach they go to readers that outlive the app, so the script doesn't
+/// die of SIGPIPE once the app's own readers are gone.
+#[cfg(unix)]
+struct DetachableOutput {
+    pipes: Vec<std::os::fd::OwnedFd>,
+    log_file: Option<String>,
 }
 
 impl ProcessRegistry {
     pub fn new() -> Self {
         Self {
             processes: Mutex::new(HashMap::new()),
+            inputs: Mutex::new(HashMap::new()),
+            groups: Mutex::new(HashMap::new()),
+            #[cfg(unix)]
+            outputs: Mutex::new(HashMap::new()),
+            readers: Mutex::new(HashMap::new()),
+            closed: AtomicBool::new(false),
+            adopted: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Refuse further spawns (for app shutdown), so a monitor that exits while
+    /// the app quits can't start a queued one behind the shutdown's back
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
     /// Spawn a monitor process and stream its output via Tauri events
     pub fn spawn_monitor<R: Runtime>(
         &self,
@@ -58,35 +428,13 @@ impl ProcessRegistry {
         ai_model: Option<&str>,
         dirty_worktree_policy: &str,
         skip_ci_fix: &str,
+        mode: &str,
+        working_dir: Option<&Path>,
     ) -> Result<u32, String> {
-        // Get the scripts directory path using dirs crate
-        let app_data_dir = dirs::data_local_dir()
-            .ok_or_else(|| "Failed to get local data directory".to_string())?
-            .join("com.clanker-spanker.app");
-
-        let scripts_dir = app_data_dir.join("scripts");
-        let script_path = scripts_dir.join("monitor-pr-loop.sh");
-
-        // Ensure scripts directory exists and script is installed
-        std::fs::create_dir_all(&scripts_dir)
-            .map_err(|e| format!("Failed to create scripts directory: {}", e))?;
-
-        // Always write the embedded script (to update if changed)
-        let script_content = include_str!("../scripts/monitor-pr-loop.sh");
-        std::fs::write(&script_path, script_content)
-            .map_err(|e| format!("Failed to write script: {}", e))?;
-
-        // Make script executable
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = std::fs::metadata(&script_path)
-                .map_err(|e| format!("Failed to get script metadata: {}", e))?
-                .permissions();
-            perms.set_mode(0o755);
-            std::fs::set_permissions(&script_path, perms)
-                .map_err(|e| format!("Failed to set script permissions: {}", e))?;
+        if self.closed.load(Ordering::SeqCst) {
+            return Err("The app is shutting down".to_string());
         }
+        let script_path = monitor_script(app)?;
 
         // Spawn the monitor script in its own process group
         // so we can kill the entire tree (bash + AI CLI + helpers) on stop
@@ -100,9 +448,28 @@ impl ProcessRegistry {
             .env("CLANKER_AI_MODEL", ai_model.unwrap_or(""))
             .env("CLANKER_DIRTY_WORKTREE_POLICY", dirty_worktree_policy)
             .env("CLANKER_SKIP_CI_FIX", skip_ci_fix)
+            .env("CLANKER_MONITOR_MODE", mode)
+            .env("CLANKER_HEAD_REPO", fork_head_repo(app, pr_id).unwrap_or_default())
+            .env("CLANKER_API_ADDRESS", crate::api::get_api_address().unwrap_or_default())
+            .env("CLANKER_API_TOKEN", api_token(app).unwrap_or_default())
+            // Kept open (on the Child in the registry) for send_input
+            .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
+        // Run from the repo's local clone when configured, and pass it as the
+        // script's REPO_DIR argument; otherwise the script falls back to
+        // searching common clone locations
+        match working_dir {
+            Some(dir) => {
+                cmd.arg(dir).current_dir(dir);
+            }
+            None => eprintln!(
+                "Warning: No working_dir configured for {}; monitor will search for a local clone",
+                repo
+            ),
+        }
+
         #[cfg(unix)]
         {
             use std::os::unix::process::CommandExt;
@@ -114,10 +481,28 @@ impl ProcessRegistry {
             .map_err(|e| format!("Failed to spawn process: {}", e))?;
 
         let pid = child.id();
+        if let Ok(mut groups) = self.groups.lock() {
+            groups.insert(monitor_id.to_string(), pid as i32);
+        }
+
+        let log_path = monitor_log_path(app, monitor_id);
 
         // Take stdout and stderr handles
         let stdout = child.stdout.take();
         let stderr = child.stderr.take();
+        #[cfg(unix)]
+        {
+            use std::os::fd::AsFd;
+            let pipes = [stdout.as_ref().map(|s| s.as_fd()), stderr.as_ref().map(|s| s.as_fd())]
+                .into_iter()
+                .flatten()
+                .filter_map(|fd| fd.try_clone_to_owned().ok())
+                .collect();
+            if let Ok(mut outputs) = self.outputs.lock() {
+                let output = DetachableOutput { pipes, log_file: log_path.clone() };
+                outputs.insert(monitor_id.to_string(), output);
+            }
+        }
 
         // Store the child process
         {
@@ -125,21 +510,55 @@ impl ProcessRegistry {
                 .processes
                 .lock()
                 .map_err(|e| format!("Failed to lock process registry: {}", e))?;
+            if let (Some(stdin), Ok(mut inputs)) = (child.stdin.take(), self.inputs.lock()) {
+                inputs.insert(monitor_id.to_string(), Arc::new(Mutex::new(stdin)));
+            }
             processes.insert(monitor_id.to_string(), child);
         }
 
+        // Output is also appended to the monitor's log file, if it has one
+        let log = open_monitor_log(log_path.as_deref());
+
+        let auto_answers = app
+            .try_state::<AppState>()
+            .map(|state| db::get_monitor_auto_answers(&state.conn()))
+            .unwrap_or_default();
+
         // Spawn thread to read stdout and emit events
         if let Some(stdout) = stdout {
             let app_handle = app.clone();
+            let log = log.clone();
             let monitor_id_clone = monitor_id.to_string();
             let pr_id_clone = pr_id.to_string();
+            let repo_clone = repo.to_string();
 
-            thread::spawn(move || {
+            let reader_thread = thread::spawn(move || {
                 let reader = BufReader::new(stdout);
                 let mut last_status_line = String::new();
+                // Patterns already answered this iteration; a prompt echoed again
+                // (e.g. in a log excerpt) must not get a second reply
+                let mut answered: std::collections::HashSet<String> = Default::default();
 
                 for line_result in reader.lines() {
                     if let Ok(line) = line_result {
+                        append_log(&log, &line);
+
+                        // Answer known prompts so the loop doesn't hang waiting on them
+                        if line.starts_with("@@ITERATION:") {
+                            answered.clear();
+                        }
+                        let answer = auto_answer(&auto_answers, &line)
+                            .filter(|answer| answered.insert(answer.pattern.clone()));
+                        if let Some(answer) = answer {
+                            if let Some(state) = app_handle.try_state::<AppState>() {
+                                if let Err(e) =
+                                    state.processes.send_input(&monitor_id_clone, &answer.response)
+                                {
+                                    eprintln!("Failed to auto-answer monitor {}: {}", monitor_id_clone, e);
+                                }
+                            }
+                        }
+
                         // Track status lines for exit reason
                         if line.contains("@@STATUS:") {
                             last_status_line = line.clone();
@@ -151,18 +570,82 @@ impl ProcessRegistry {
                             if let Some((iter_str, max_str)) = inner.split_once('/') {
                                 if let (Ok(iter), Ok(_max)) = (iter_str.parse::<i32>(), max_str.parse::<i32>()) {
                                     if let Some(state) = app_handle.try_state::<AppState>() {
-                                        if let Ok(conn) = state.db.lock() {
-                                            let now = chrono::Utc::now().to_rfc3339();
-                                            let _ = conn.execute(
-                                                "UPDATE monitors SET iteration = ?1, last_check_at = ?2 WHERE id = ?3",
-                                                rusqlite::params![iter, now, monitor_id_clone],
-                                            );
-                                        }
+                                        let conn = state.conn();
+                                        let now = chrono::Utc::now().to_rfc3339();
+                                        let _ = conn.execute(
+                                            "UPDATE monitors SET iteration = ?1, last_check_at = ?2 WHERE id = ?3",
+                                            rusqlite::params![iter, now, monitor_id_clone],
+                                        );
+                                        crate::monitor_ticks::refresh(&conn);
+                                        // Live countdown: the estimate shifts with every iteration
+                                        let estimated_completion_at =
+                                            crate::monitor::estimated_completion_at(&conn, &monitor_id_clone);
+                                        drop(conn);
+                                        let _ = app_handle.emit(
+                                            "monitor:state-changed",
+                                            crate::monitor::MonitorStatePayload {
+                                                active_count: crate::monitor::get_active_monitor_count(&state)
+                                                    .unwrap_or(0),
+                                                monitor_id: Some(monitor_id_clone.clone()),
+                                                estimated_completion_at,
+                                            },
+                                        );
                                     }
+                                    // Pick up the threads this iteration replied to or resolved
+                                    crate::comment_refresh::schedule(&pr_id_clone, pr_number, &repo_clone);
                                 }
                             }
                         }
 
+                        // Record which PR head each iteration checks
+                        if let Some(sha) = line
+                            .strip_prefix("@@HEAD:")
+                            .and_then(|rest| rest.strip_suffix("@@"))
+                            .filter(|sha| !sha.is_empty())
+                        {
+                            if let Some(state) = app_handle.try_state::<AppState>() {
+                                crate::monitor::check_external_push(&app_handle, &state, &pr_id_clone, sha);
+                                let _ = db::record_monitor_head(&state.conn(), &monitor_id_clone, sha);
+                            }
+                        }
+
+                        // The script's own pushes aren't external pushes
+                        if let Some(sha) = line
+                            .strip_prefix("@@PUSHING:")
+                            .and_then(|rest| rest.strip_suffix("@@"))
+                            .filter(|sha| !sha.is_empty())
+                        {
+                            if let Some(state) = app_handle.try_state::<AppState>() {
+                                let _ = db::record_monitor_pushing(&state.conn(), &monitor_id_clone, sha);
+                            }
+                        }
+                        if let Some(sha) = line
+                            .strip_prefix("@@PUSHED:")
+                            .and_then(|rest| rest.strip_suffix("@@"))
+                            .filter(|sha| !sha.is_empty())
+                        {
+                            if let Some(state) = app_handle.try_state::<AppState>() {
+                                let _ = db::record_monitor_push(&state.conn(), &monitor_id_clone, sha);
+                            }
+                        }
+
+                        // Track when the next check is due so stuck monitors can be spotted
+                        if let Some(minutes) = line
+                            .strip_prefix("@@SLEEPING:")
+                            .and_then(|rest| rest.strip_suffix("@@"))
+                            .and_then(|n| n.parse::<i64>().ok())
+                        {
+                            if let Some(state) = app_handle.try_state::<AppState>() {
+                                let conn = state.conn();
+                                let next = chrono::Utc::now() + chrono::Duration::minutes(minutes);
+                                let _ = conn.execute(
+                                    "UPDATE monitors SET next_check_at = ?1 WHERE id = ?2",
+                                    rusqlite::params![next.to_rfc3339(), monitor_id_clone],
+                                );
+                                crate::monitor_ticks::refresh(&conn);
+                            }
+                        }
+
                         let _ = app_handle.emit(
                             "monitor:output",
                             MonitorOutputPayload {
@@ -182,6 +665,9 @@ impl ProcessRegistry {
                     state.processes.cleanup_finished();
                 }
             });
+            if let Ok(mut readers) = self.readers.lock() {
+                readers.insert(monitor_id.to_string(), reader_thread);
+            }
         }
 
         // Spawn thread to read stderr and emit as output too
@@ -194,12 +680,14 @@ impl ProcessRegistry {
                 let reader = BufReader::new(stderr);
                 for line_result in reader.lines() {
                     if let Ok(line) = line_result {
+                        let line = format!("[stderr] {}", line);
+                        append_log(&log, &line);
                         let _ = app_handle.emit(
                             "monitor:output",
                             MonitorOutputPayload {
                                 monitor_id: monitor_id_clone.clone(),
                                 pr_id: pr_id_clone.clone(),
-                                line: format!("[stderr] {}", line),
+                                line,
                             },
                         );
                     }
@@ -217,29 +705,309 @@ impl ProcessRegistry {
             .lock()
             .map_err(|e| format!("Failed to lock process registry: {}", e))?;
 
+        self.forget_pipes(monitor_id);
         if let Some(mut child) = processes.remove(monitor_id) {
             // Kill the entire process group (bash + AI CLI + helpers)
             #[cfg(unix)]
-            kill_process_group(&child);
+            kill_process_group(child.id() as i32);
 
             let _ = child.kill(); // Fallback: also kill the direct child
             let _ = child.wait();
         }
+        drop(processes);
+
+        // Left by a detached quit: killed by pid, unless the pid went to another process
+        if let Some(adopted) = self.take_adopted(monitor_id) {
+            #[cfg(unix)]
+            if adopted.alive() {
+                kill_process_group(adopted.pid);
+            }
+        }
 
         Ok(())
     }
 
+    fn take_adopted(&self, monitor_id: &str) -> Option<AdoptedMonitor> {
+        self.adopted.lock().ok()?.remove(monitor_id)
+    }
+
+    /// Track a monitor left running by an earlier app instance, so it can be
+    /// stopped and its exit recorded
+    fn adopt(&self, monitor_id: &str, monitor: AdoptedMonitor) {
+        if let Ok(mut groups) = self.groups.lock() {
+            groups.insert(monitor_id.to_string(), monitor.pid);
+        }
+        if let Ok(mut adopted) = self.adopted.lock() {
+            adopted.insert(monitor_id.to_string(), monitor);
+        }
+    }
+
+    /// Stop tracking adopted monitors whose script has exited, returning them
+    fn take_exited_adopted(&self) -> Vec<(String, AdoptedMonitor)> {
+        let Ok(mut adopted) = self.adopted.lock() else {
+            return Vec::new();
+        };
+        let exited: Vec<String> = adopted
+            .iter()
+            .filter(|(_, monitor)| !monitor.alive())
+            .map(|(id, _)| id.clone())
+            .collect();
+        exited
+            .into_iter()
+            .filter_map(|id| adopted.remove(&id).map(|monitor| (id, monitor)))
+            .collect()
+    }
+
+    fn has_adopted(&self) -> bool {
+        self.adopted.lock().is_ok_and(|adopted| !adopted.is_empty())
+    }
+
+    /// Write a line (newline appended) to a running monitor's stdin
+    pub fn send_input(&self, monitor_id: &str, text: &str) -> Result<(), String> {
+        {
+            let mut processes = self
+                .processes
+                .lock()
+                .map_err(|e| format!("Failed to lock process registry: {}", e))?;
+            let child = processes
+                .get_mut(monitor_id)
+                .ok_or_else(|| format!("Monitor {} has no running process", monitor_id))?;
+            if let Ok(Some(status)) = child.try_wait() {
+                return Err(format!("Monitor {} process has exited ({})", monitor_id, status));
+            }
+        }
+        let stdin = self
+            .inputs
+            .lock()
+            .map_err(|e| format!("Failed to lock process registry: {}", e))?
+            .get(monitor_id)
+            .cloned()
+            .ok_or_else(|| format!("Monitor {} has no stdin", monitor_id))?;
+
+        // Only this monitor's stdin stays locked if the write blocks
+        let mut stdin = stdin
+            .lock()
+            .map_err(|e| format!("Failed to lock stdin of monitor {}: {}", monitor_id, e))?;
+        stdin
+            .write_all(format!("{}\n", text).as_bytes())
+            .and_then(|_| stdin.flush())
+            .map_err(|e| format!("Failed to write to monitor {}: {}", monitor_id, e))
+    }
+
+    /// Drop a monitor's stdin handle, closing the pipe once no write holds it,
+    /// and the duplicates of its output pipes
+    fn forget_pipes(&self, monitor_id: &str) {
+        if let Ok(mut inputs) = self.inputs.lock() {
+            inputs.remove(monitor_id);
+        }
+        #[cfg(unix)]
+        if let Ok(mut outputs) = self.outputs.lock() {
+            outputs.remove(monitor_id);
+        }
+    }
+
+    /// Drop the process groups of monitors that have exited and left nothing
+    /// behind, reaping `monitor_id`'s script first so its zombie doesn't keep
+    /// its group alive
+    pub fn forget_empty_groups(&self, monitor_id: &str) {
+        if let Ok(mut processes) = self.processes.lock() {
+            if let Some(child) = processes.get_mut(monitor_id) {
+                let _ = child.try_wait();
+            }
+        }
+        if let Ok(mut groups) = self.groups.lock() {
+            groups.retain(|_, pgid| group_alive(*pgid));
+        }
+    }
+
     /// Kill all running processes and their groups (for app shutdown)
     pub fn kill_all(&self) {
+        if let Ok(mut inputs) = self.inputs.lock() {
+            inputs.clear();
+        }
+        #[cfg(unix)]
+        if let Ok(mut outputs) = self.outputs.lock() {
+            outputs.clear();
+        }
         if let Ok(mut processes) = self.processes.lock() {
             for (_, mut child) in processes.drain() {
                 #[cfg(unix)]
-                kill_process_group(&child);
+                kill_process_group(child.id() as i32);
 
                 let _ = child.kill();
                 let _ = child.wait();
             }
         }
+        if let Ok(mut adopted) = self.adopted.lock() {
+            #[cfg(unix)]
+            for monitor in adopted.values().filter(|monitor| monitor.alive()) {
+                kill_process_group(monitor.pid);
+            }
+            adopted.clear();
+        }
+    }
+
+    /// Ask every running monitor to skip its current sleep and check now.
+    /// Signals only the script itself, not its process group, so in-flight
+    /// gh/agent commands are left alone. Returns the signalled monitor IDs.
+    #[cfg(unix)]
+    pub fn signal_check_now(&self) -> Vec<String> {
+        let mut signalled: Vec<String> = match self.processes.lock() {
+            Ok(processes) => processes
+                .iter()
+                .filter(|(_, child)| unsafe { libc::kill(child.id() as i32, libc::SIGUSR1) } == 0)
+                .map(|(id, _)| id.clone())
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        if let Ok(adopted) = self.adopted.lock() {
+            signalled.extend(
+                adopted
+                    .iter()
+                    .filter(|(_, monitor)| monitor.alive())
+                    .filter(|(_, monitor)| unsafe { libc::kill(monitor.pid, libc::SIGUSR1) } == 0)
+                    .map(|(id, _)| id.clone()),
+            );
+        }
+        signalled
+    }
+
+    #[cfg(not(unix))]
+    pub fn signal_check_now(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Wait for all processes to exit on their own, up to `timeout`.
+    /// Returns the monitor IDs that were still running when the timeout elapsed.
+    pub fn wait_all(&self, timeout: std::time::Duration) -> Vec<String> {
+        let deadline = std::time::Instant::now() + timeout;
+
+        loop {
+            let mut still_running: Vec<String> = match self.processes.lock() {
+                Ok(mut processes) => processes
+                    .iter_mut()
+                    .filter_map(|(id, child)| {
+                        matches!(child.try_wait(), Ok(None)).then(|| id.clone())
+                    })
+                    .collect(),
+                Err(_) => return Vec::new(),
+            };
+            if let Ok(adopted) = self.adopted.lock() {
+                still_running.extend(
+                    adopted.iter().filter(|(_, monitor)| monitor.alive()).map(|(id, _)| id.clone()),
+                );
+            }
+
+            if still_running.is_empty() || std::time::Instant::now() >= deadline {
+                return still_running;
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(200));
+        }
+    }
+
+    /// Wait up to `timeout` for the stdout readers to finish, so monitors that
+    /// have exited get their real outcome recorded. Readers still going at the
+    /// deadline are left behind.
+    pub fn join_readers(&self, timeout: std::time::Duration) {
+        let deadline = std::time::Instant::now() + timeout;
+        let Ok(mut readers) = self.readers.lock().map(|mut readers| std::mem::take(&mut *readers)) else {
+            return;
+        };
+
+        while !readers.is_empty() && std::time::Instant::now() < deadline {
+            let finished: Vec<String> = readers
+                .iter()
+                .filter(|(_, reader)| reader.is_finished())
+                .map(|(id, _)| id.clone())
+                .collect();
+            for id in finished {
+                if let Some(reader) = readers.remove(&id) {
+                    let _ = reader.join();
+                }
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+    }
+
+    /// Release all processes without killing them (for app shutdown in detach mode)
+    pub fn detach_all(&self) -> Vec<String> {
+        // Nothing writes to the monitors' stdin any more; closing it gives reads EOF
+        if let Ok(mut inputs) = self.inputs.lock() {
+            inputs.clear();
+        }
+        #[cfg(unix)]
+        self.hand_off_outputs();
+        // Adopted monitors already outlive the app; they stay detached
+        let mut detached: Vec<String> = match self.adopted.lock() {
+            Ok(mut adopted) => adopted.drain().map(|(id, _)| id).collect(),
+            Err(_) => Vec::new(),
+        };
+        if let Ok(mut processes) = self.processes.lock() {
+            // Paused monitors would otherwise stay stopped with nothing left to resume them
+            #[cfg(unix)]
+            for child in processes.values() {
+                unsafe {
+                    libc::kill(-(child.id() as i32), libc::SIGCONT);
+                }
+            }
+            // Dropping a Child does not kill it
+            detached.extend(processes.drain().map(|(id, _)| id));
+        }
+        detached
+    }
+
+    /// Give each monitor's output pipes to a detached `cat` that appends to the
+    /// monitor's log file (or discards the output when it has none), so the
+    /// pipes keep a reader after the app exits
+    #[cfg(unix)]
+    fn hand_off_outputs(&self) {
+        use std::os::unix::process::CommandExt;
+
+        let Ok(mut outputs) = self.outputs.lock() else {
+            return;
+        };
+        for (monitor_id, output) in outputs.drain() {
+            for pipe in output.pipes {
+                let sink = output
+                    .log_file
+                    .as_deref()
+                    .and_then(|path| std::fs::OpenOptions::new().create(true).append(true).open(path).ok())
+                    .map(Stdio::from)
+                    .unwrap_or_else(Stdio::null);
+                let drained = Command::new("cat")
+                    .stdin(Stdio::from(pipe))
+                    .stdout(sink)
+                    .stderr(Stdio::null())
+                    .process_group(0)
+                    .spawn();
+                if let Err(e) = drained {
+                    eprintln!("Failed to hand off output of monitor {}: {}", monitor_id, e);
+                }
+            }
+        }
+    }
+
+    /// Suspend (SIGSTOP) or resume (SIGCONT) a monitor's whole process group
+    #[cfg(unix)]
+    pub fn set_group_stopped(&self, monitor_id: &str, stopped: bool) -> Result<(), String> {
+        let pid = self
+            .get_pid(monitor_id)
+            .ok_or_else(|| format!("No running process for monitor {}", monitor_id))?;
+        let signal = if stopped { libc::SIGSTOP } else { libc::SIGCONT };
+        if unsafe { libc::kill(-(pid as i32), signal) } != 0 {
+            return Err(format!(
+                "Failed to signal monitor {}: {}",
+                monitor_id,
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    pub fn set_group_stopped(&self, _monitor_id: &str, _stopped: bool) -> Result<(), String> {
+        Err("Pausing monitors is only supported on Unix".to_string())
     }
 
     /// Check if a process is still running
@@ -250,7 +1018,17 @@ impl ProcessRegistry {
                 return matches!(child.try_wait(), Ok(None));
             }
         }
-        false
+        self.adopted
+            .lock()
+            .is_ok_and(|adopted| adopted.get(monitor_id).is_some_and(AdoptedMonitor::alive))
+    }
+
+    /// Whether a monitor's process was spawned by this app instance (running or not)
+    pub fn is_registered(&self, monitor_id: &str) -> bool {
+        self.processes
+            .lock()
+            .map(|processes| processes.contains_key(monitor_id))
+            .unwrap_or(false)
     }
 
     /// Get the PID for a monitor
@@ -263,6 +1041,52 @@ impl ProcessRegistry {
         None
     }
 
+    /// Processes descended from the app or in a monitor's process group.
+    /// A process is orphaned when its monitor's script has exited or the
+    /// monitor is no longer active in `active_monitor_ids`.
+    #[cfg(unix)]
+    pub fn list_app_processes(
+        &self,
+        active_monitor_ids: &[String],
+    ) -> Result<Vec<AppChildProcess>, String> {
+        let child = Command::new("ps")
+            .args(["-A", "-o", "pid=,ppid=,pgid=,command="])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to run ps: {}", e))?;
+        let ps_pid = child.id() as i32;
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("Failed to run ps: {}", e))?;
+        if !output.status.success() {
+            return Err("ps exited with an error".to_string());
+        }
+
+        let groups: HashMap<i32, String> = match self.groups.lock() {
+            Ok(groups) => groups.iter().map(|(id, pgid)| (*pgid, id.clone())).collect(),
+            Err(_) => HashMap::new(),
+        };
+        let rows = parse_ps_output(&String::from_utf8_lossy(&output.stdout));
+        let mut processes = app_process_tree(&rows, std::process::id() as i32, &groups);
+        processes.retain(|p| p.pid != ps_pid);
+        for process in &mut processes {
+            if let Some(monitor_id) = &process.monitor_id {
+                process.orphaned =
+                    !self.is_running(monitor_id) || !active_monitor_ids.contains(monitor_id);
+            }
+        }
+        Ok(processes)
+    }
+
+    #[cfg(not(unix))]
+    pub fn list_app_processes(
+        &self,
+        _active_monitor_ids: &[String],
+    ) -> Result<Vec<AppChildProcess>, String> {
+        Ok(Vec::new())
+    }
+
     /// Clean up finished processes
     pub fn cleanup_finished(&self) -> Vec<String> {
         let mut finished = Vec::new();
@@ -279,9 +1103,13 @@ impl ProcessRegistry {
 
             for id in to_remove {
                 processes.remove(&id);
+                self.forget_pipes(&id);
                 finished.push(id);
             }
         }
+        if let Ok(mut readers) = self.readers.lock() {
+            readers.retain(|_, reader| !reader.is_finished());
+        }
 
         finished
     }
@@ -293,46 +1121,216 @@ impl Default for ProcessRegistry {
     }
 }
 
-/// Handle monitor process exit - update database and sleep state
-fn handle_process_exit<R: Runtime>(app: &AppHandle<R>, monitor_id: &str, pr_id: &str, last_status_line: &str) {
-    // Determine exit reason from the last status line
-    let exit_reason = if last_status_line.contains("@@STATUS:clean@@") {
-        "pr_clean"
-    } else if last_status_line.contains("@@STATUS:max_iterations@@") {
-        "max_iterations"
-    } else {
-        "process_exited"
+/// Parse `ps -o pid=,ppid=,pgid=,command=` output into (pid, ppid, pgid, command) rows
+fn parse_ps_output(output: &str) -> Vec<(i32, i32, i32, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse().ok()?;
+            let ppid = fields.next()?.parse().ok()?;
+            let pgid = fields.next()?.parse().ok()?;
+            Some((pid, ppid, pgid, fields.collect::<Vec<_>>().join(" ")))
+        })
+        .collect()
+}
+
+/// Select the processes descended from `root_pid` (excluding it) plus any in a
+/// monitor process group from `groups` (pgid -> monitor ID), which catches
+/// leftovers that were reparented after their monitor script exited
+fn app_process_tree(
+    rows: &[(i32, i32, i32, String)],
+    root_pid: i32,
+    groups: &HashMap<i32, String>,
+) -> Vec<AppChildProcess> {
+    let mut in_tree = std::collections::HashSet::from([root_pid]);
+    // Parents can sort after their children, so repeat until nothing new is added
+    loop {
+        let before = in_tree.len();
+        for (pid, ppid, _, _) in rows {
+            if in_tree.contains(ppid) {
+                in_tree.insert(*pid);
+            }
+        }
+        if in_tree.len() == before {
+            break;
+        }
+    }
+
+    rows.iter()
+        .filter(|(pid, _, pgid, _)| {
+            *pid != root_pid && (in_tree.contains(pid) || groups.contains_key(pgid))
+        })
+        .map(|(pid, ppid, pgid, command)| AppChildProcess {
+            pid: *pid,
+            ppid: *ppid,
+            pgid: *pgid,
+            command: command.clone(),
+            monitor_id: groups.get(pgid).cloned(),
+            orphaned: false,
+        })
+        .collect()
+}
+
+/// Terminate the given processes: SIGTERM, then SIGKILL for any still alive
+/// after a short grace period
+#[cfg(unix)]
+pub fn terminate_processes(processes: &[AppChildProcess]) {
+    for process in processes {
+        unsafe {
+            libc::kill(process.pid, libc::SIGTERM);
+        }
+    }
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    for process in processes {
+        if is_pid_alive(process.pid) {
+            unsafe {
+                libc::kill(process.pid, libc::SIGKILL);
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn terminate_processes(_processes: &[AppChildProcess]) {}
+
+/// How often adopted monitors are checked for having exited
+const ADOPTED_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Pick up monitors a detached quit left running: they stay active, can be
+/// stopped through the registry, and get their exit recorded once their
+/// script is gone
+pub fn adopt_detached_monitors<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let active: Vec<(String, String, i32, i32, String)> = {
+        let conn = state.conn();
+        let rows = conn
+            .prepare(
+                "SELECT id, pr_id, pid, pr_number, repo FROM monitors
+                 WHERE status IN ('running', 'sleeping') AND pid IS NOT NULL",
+            )
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))?
+                    .collect()
+            });
+        match rows {
+            Ok(rows) => rows,
+            Err(e) => {
+                eprintln!("Failed to read detached monitors: {}", e);
+                return;
+            }
+        }
     };
 
-    let status = if exit_reason == "pr_clean" {
-        "completed"
-    } else {
-        "failed"
+    for (id, pr_id, pid, pr_number, repo) in active {
+        let monitor = AdoptedMonitor { pid, pr_id, pr_number, repo };
+        if !state.processes.is_registered(&id) && monitor.alive() {
+            state.processes.adopt(&id, monitor);
+        }
+    }
+    if !state.processes.has_adopted() {
+        return;
+    }
+
+    let app = app.clone();
+    thread::spawn(move || loop {
+        thread::sleep(ADOPTED_POLL_INTERVAL);
+        record_adopted_exits(&app);
+        match app.try_state::<AppState>() {
+            Some(state) if state.processes.has_adopted() => {}
+            _ => break,
+        }
+    });
+}
+
+/// Record the exit of adopted monitors whose script is gone, using the last
+/// status line their detached output left in the log
+pub fn record_adopted_exits<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
     };
+    for (id, monitor) in state.processes.take_exited_adopted() {
+        let last_status_line = monitor_log_path(app, &id)
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|log| log.lines().rfind(|line| line.contains("@@STATUS:")).map(String::from))
+            .unwrap_or_default();
+        handle_process_exit(app, &id, &monitor.pr_id, &last_status_line);
+    }
+}
+
+/// Map the last `@@STATUS:...@@` line to an (exit_reason, status) pair.
+/// Unknown or missing statuses fall back to ("process_exited", "failed").
+fn exit_reason_for_status(last_status_line: &str) -> (&'static str, &'static str) {
+    let marker = last_status_line
+        .split_once("@@STATUS:")
+        .and_then(|(_, rest)| rest.split_once("@@"))
+        .map(|(status, _)| status.trim())
+        .unwrap_or_default();
+
+    match marker {
+        "clean" => ("pr_clean", "completed"),
+        "no_changes_needed" => ("no_changes_needed", "completed"),
+        "pr_not_open" => ("pr_not_open", "completed"),
+        "max_iterations" => ("max_iterations", "failed"),
+        "push_failed" => ("push_failed", "failed"),
+        "auth_expired" => ("auth_expired", "failed"),
+        "conflicts_unresolved" => ("conflicts_unresolved", "failed"),
+        "workspace_blocked" => ("workspace_blocked", "failed"),
+        "history_clean_failed" => ("history_clean_failed", "failed"),
+        "preflight_failed" => ("preflight_failed", "failed"),
+        _ => ("process_exited", "failed"),
+    }
+}
+
+/// Record a monitor's exit and notify about it. Returns the exit notification
+/// that was let through, if any.
+fn handle_process_exit<R: Runtime>(
+    app: &AppHandle<R>,
+    monitor_id: &str,
+    pr_id: &str,
+    last_status_line: &str,
+) -> Option<crate::notifications::NotificationPayload> {
+    let (exit_reason, status) = exit_reason_for_status(last_status_line);
 
     let mut pr_number: Option<i32> = None;
     let mut iteration: i32 = 0;
     let mut max_iterations: i32 = 0;
+    let mut comments_fixed: i32 = 0;
+    // False when the monitor was already stopped or paused, so its exit isn't news
+    let mut ended = false;
 
     // Update database
     if let Some(state) = app.try_state::<AppState>() {
-        if let Ok(conn) = state.db.lock() {
-            // Get pr_number, iteration, max_iterations from database
-            if let Ok((num, iter, max_iter)) = conn.query_row(
-                "SELECT pr_number, iteration, max_iterations FROM monitors WHERE id = ?1",
+        state.processes.forget_empty_groups(monitor_id);
+        {
+            let conn = state.conn();
+            // Get pr_number, iteration, max_iterations, comments_fixed from database
+            if let Ok((num, iter, max_iter, fixed)) = conn.query_row(
+                "SELECT pr_number, iteration, max_iterations, comments_fixed FROM monitors WHERE id = ?1",
                 [monitor_id],
-                |row| Ok((row.get::<_, i32>(0)?, row.get::<_, i32>(1)?, row.get::<_, i32>(2)?)),
+                |row| Ok((row.get::<_, i32>(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
             ) {
                 pr_number = Some(num);
                 iteration = iter;
                 max_iterations = max_iter;
+                comments_fixed = fixed;
+            }
+
+            // The script's preflight found gh logged out; hold further starts
+            if exit_reason == "auth_expired" {
+                crate::environment::set_gh_auth_missing(&state, true);
             }
 
             let now = chrono::Utc::now().to_rfc3339();
-            let _ = conn.execute(
-                "UPDATE monitors SET status = ?1, ended_at = ?2, exit_reason = ?3 WHERE id = ?4 AND status IN ('running', 'sleeping')",
-                rusqlite::params![status, now, exit_reason, monitor_id],
-            );
+            ended = conn
+                .execute(
+                    "UPDATE monitors SET status = ?1, ended_at = ?2, exit_reason = ?3 WHERE id = ?4 AND status IN ('running', 'sleeping')",
+                    rusqlite::params![status, now, exit_reason, monitor_id],
+                )
+                .is_ok_and(|updated| updated > 0);
+            crate::monitor_ticks::refresh(&conn);
 
             // Update sleep prevention state
             let sleep_enabled = db::get_setting_value(&conn, "sleep_prevention_enabled")
@@ -347,11 +1345,11 @@ fn handle_process_exit<R: Runtime>(app: &AppHandle<R>, monitor_id: &str, pr_id:
                 )
                 .unwrap_or(0);
 
-            sleep_prevention::update_sleep_state(count, sleep_enabled);
+            sleep_prevention::update_sleep_state(app, count, sleep_enabled);
 
             // Update tray and dock
             crate::tray::update_tray_status(count);
-            crate::dock::set_dock_badge(if count > 0 { Some(count) } else { None });
+            crate::dock::set_dock_badge(crate::dock::badge_count(&conn, count));
         }
 
         // Emit state change event
@@ -359,6 +1357,7 @@ fn handle_process_exit<R: Runtime>(app: &AppHandle<R>, monitor_id: &str, pr_id:
             "monitor:state-changed",
             crate::monitor::MonitorStatePayload {
                 active_count: crate::monitor::get_active_monitor_count(&state).unwrap_or(0),
+                ..Default::default()
             },
         );
 
@@ -374,7 +1373,25 @@ fn handle_process_exit<R: Runtime>(app: &AppHandle<R>, monitor_id: &str, pr_id:
         }));
     }
 
+    // OS notification, subject to the monitor's notification level
+    let notification = match (ended, pr_number) {
+        (true, Some(number)) => crate::notifications::notify_monitor_exit(
+            app,
+            monitor_id,
+            number,
+            pr_id,
+            status,
+            exit_reason,
+            comments_fixed,
+        ),
+        _ => None,
+    };
+
     println!("Monitor {} exited: {} ({})", monitor_id, status, exit_reason);
+
+    // A slot freed up; start the next queued monitor if any
+    crate::monitor_queue::start_queued_monitors(app);
+    notification
 }
 
 #[cfg(test)]
@@ -386,4 +1403,293 @@ mod tests {
         let registry = ProcessRegistry::new();
         assert!(!registry.is_running("nonexistent"));
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_inspect_script() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("clanker-script-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = |name: &str, body: &str, mode: u32| {
+            let path = dir.join(name);
+            std::fs::write(&path, body).unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+            path
+        };
+
+        let good = inspect_script(&script("good.sh", "echo ok\n", 0o755));
+        assert!(good.ok && good.exists && good.executable && good.syntax_ok);
+        assert_eq!(good.error, None);
+
+        let missing = inspect_script(&dir.join("missing.sh"));
+        assert!(!missing.ok && !missing.exists);
+
+        let not_executable = inspect_script(&script("plain.sh", "echo ok\n", 0o644));
+        assert!(not_executable.exists && !not_executable.executable && !not_executable.ok);
+
+        let broken = inspect_script(&script("broken.sh", "if then fi (\n", 0o755));
+        assert!(broken.executable && !broken.syntax_ok && !broken.ok);
+        assert!(broken.error.unwrap().contains("syntax"));
+
+        // The embedded script itself must parse
+        let embedded = script("embedded.sh", include_str!("../scripts/monitor-pr-loop.sh"), 0o755);
+        assert!(inspect_script(&embedded).ok);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_resolve_working_dir() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        db::init_schema(&conn).unwrap();
+        let dir = std::env::temp_dir().join(format!("clanker-clone-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let git = |args: &[&str]| crate::branch_update::run_git(&dir, args).unwrap();
+        let path = dir.display().to_string();
+
+        assert_eq!(resolve_working_dir(&conn, "o/r"), Ok(None));
+
+        db::set_repo_setting(&conn, "o/r", db::LOCAL_PATH_REPO_SETTING_KEY, &path).unwrap();
+        assert!(resolve_working_dir(&conn, "o/r").unwrap_err().contains("not a git repository"));
+
+        git(&["init", "-q"]);
+        assert!(resolve_working_dir(&conn, "o/r").unwrap_err().contains("no origin remote"));
+        git(&["remote", "add", "origin", "git@github.com:o/other.git"]);
+        let wrong = resolve_working_dir(&conn, "o/r").unwrap_err();
+        assert!(wrong.contains("clone of o/other, not o/r"), "{}", wrong);
+        git(&["remote", "set-url", "origin", "https://github.com/O/R.git"]);
+        assert_eq!(resolve_working_dir(&conn, "o/r"), Ok(Some(dir.clone())));
+
+        db::set_repo_setting(&conn, "o/r", db::LOCAL_PATH_REPO_SETTING_KEY, "/nonexistent/clone")
+            .unwrap();
+        assert!(resolve_working_dir(&conn, "o/r").unwrap_err().contains("does not exist"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_auto_answer() {
+        let answers = vec![
+            db::AutoAnswer { pattern: "Proceed? [y/N]".to_string(), response: "y".to_string() },
+            db::AutoAnswer { pattern: "Proceed?".to_string(), response: "yes".to_string() },
+        ];
+        let response = |line: &str| auto_answer(&answers, line).map(|a| a.response.as_str());
+        assert_eq!(response("Proceed? [y/N] "), Some("y"));
+        assert_eq!(response("> Proceed?"), Some("yes"));
+        assert_eq!(response("proceeding"), None);
+        assert_eq!(auto_answer(&[], "Proceed?"), None);
+    }
+
+    #[test]
+    fn test_exit_reason_for_status() {
+        assert_eq!(exit_reason_for_status("@@STATUS:clean@@"), ("pr_clean", "completed"));
+        assert_eq!(
+            exit_reason_for_status("@@STATUS:no_changes_needed@@"),
+            ("no_changes_needed", "completed")
+        );
+        assert_eq!(
+            exit_reason_for_status("@@STATUS:push_failed@@"),
+            ("push_failed", "failed")
+        );
+        assert_eq!(
+            exit_reason_for_status("@@STATUS:something_new@@"),
+            ("process_exited", "failed")
+        );
+        assert_eq!(exit_reason_for_status(""), ("process_exited", "failed"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_detach_all_hands_off_output() {
+        use std::os::fd::AsFd;
+
+        let dir = std::env::temp_dir().join(format!("clanker-detach-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("monitor.log");
+        // Writes only after the app's reader is gone, like a monitor between checks
+        let mut child = Command::new("sh")
+            .args(["-c", "read -r _; sleep 0.3; echo late; echo oops >&2"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let stderr = child.stderr.take().unwrap();
+        let pipes = vec![
+            stdout.as_fd().try_clone_to_owned().unwrap(),
+            stderr.as_fd().try_clone_to_owned().unwrap(),
+        ];
+        let registry = ProcessRegistry::new();
+        let stdin = Arc::new(Mutex::new(child.stdin.take().unwrap()));
+        registry.inputs.lock().unwrap().insert("m1".to_string(), stdin);
+        let output = DetachableOutput { pipes, log_file: Some(log.display().to_string()) };
+        registry.outputs.lock().unwrap().insert("m1".to_string(), output);
+        registry.processes.lock().unwrap().insert("m1".to_string(), child);
+
+        assert_eq!(registry.detach_all(), ["m1"]);
+        assert!(registry.inputs.lock().unwrap().is_empty());
+        drop((stdout, stderr));
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let mut logged = String::new();
+        while std::time::Instant::now() < deadline && !(logged.contains("late") && logged.contains("oops")) {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            logged = std::fs::read_to_string(&log).unwrap_or_default();
+        }
+        assert!(logged.contains("late") && logged.contains("oops"), "{:?}", logged);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_forget_empty_groups() {
+        use std::os::unix::process::CommandExt;
+
+        let registry = ProcessRegistry::new();
+        let spawn = |id: &str, script: &str| {
+            let child = Command::new("sh").args(["-c", script]).process_group(0).spawn().unwrap();
+            registry.groups.lock().unwrap().insert(id.to_string(), child.id() as i32);
+            registry.processes.lock().unwrap().insert(id.to_string(), child);
+        };
+        spawn("clean", "exit 0");
+        // Exits at once but leaves a sleep behind in its group
+        spawn("leaky", "sleep 10 & exit 0");
+        spawn("running", "sleep 10");
+        std::thread::sleep(std::time::Duration::from_millis(300));
+
+        registry.forget_empty_groups("clean");
+        registry.forget_empty_groups("leaky");
+        let mut left: Vec<String> = registry.groups.lock().unwrap().keys().cloned().collect();
+        left.sort();
+        assert_eq!(left, ["leaky", "running"]);
+
+        for id in ["leaky", "running"] {
+            let pgid = registry.groups.lock().unwrap()[id];
+            unsafe {
+                libc::kill(-pgid, libc::SIGKILL);
+            }
+            let _ = registry.processes.lock().unwrap().get_mut(id).unwrap().wait();
+        }
+        // The leftover sleep was reparented, so its new parent reaps it; give it a moment
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while !registry.groups.lock().unwrap().is_empty() && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            registry.forget_empty_groups("leaky");
+        }
+        assert!(registry.groups.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_adopt_detached_monitors() {
+        use std::os::unix::process::CommandExt;
+
+        let dir = std::env::temp_dir().join(format!("clanker-adopt-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let app = tauri::test::mock_app();
+        app.manage(AppState::for_tests_default());
+        let state = app.state::<AppState>();
+        // Stand-ins for monitor scripts a detached quit left behind
+        let spawn = |id: &str, number: &str, script: &str| {
+            let child = Command::new("sh")
+                .args(["-c", script, "monitor-pr-loop.sh", number, "o/r", "10", "5"])
+                .process_group(0)
+                .spawn()
+                .unwrap();
+            let log = dir.join(format!("{}.log", id));
+            std::fs::write(&log, "@@ITERATION:1/10@@\n@@STATUS:clean@@\n").unwrap();
+            state
+                .conn()
+                .execute(
+                    "INSERT INTO monitors (id, pr_id, pr_number, repo, pid, status, started_at, log_file)
+                     VALUES (?1, 'o/r#' || ?2, ?2, 'o/r', ?3, 'running', '', ?4)",
+                    rusqlite::params![id, number, child.id() as i32, log.to_str().unwrap()],
+                )
+                .unwrap();
+            child
+        };
+        let mut stopped = spawn("stopped", "1", "sleep 10; :");
+        let mut finished = spawn("finished", "2", "sleep 0.5; :");
+        let status = |id: &str| -> String {
+            state
+                .conn()
+                .query_row("SELECT status FROM monitors WHERE id = ?1", [id], |row| row.get(0))
+                .unwrap()
+        };
+
+        adopt_detached_monitors(app.handle());
+        assert!(state.processes.is_running("stopped"));
+        assert!(!state.processes.is_registered("stopped"));
+
+        // Stopping kills the adopted script through its stored pid
+        state.processes.kill("stopped").unwrap();
+        assert!(stopped.wait().unwrap().code().is_none());
+
+        // The other one's exit is noticed and recorded from its log
+        finished.wait().unwrap();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while status("finished") == "running" && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        assert_eq!(status("finished"), "completed");
+        assert_eq!(status("stopped"), "running");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_handle_process_exit_notifies() {
+        let app = tauri::test::mock_app();
+        app.manage(AppState::for_tests_default());
+        let state = app.state::<AppState>();
+        state
+            .conn()
+            .execute_batch(
+                "INSERT INTO monitors (id, pr_id, pr_number, repo, status, started_at, log_file, notification_level)
+                 VALUES ('m1', 'o/r#1', 1, 'o/r', 'running', '2026-01-01T00:00:00Z', '', 'completion_only'),
+                        ('m2', 'o/r#2', 2, 'o/r', 'running', '2026-01-01T00:00:00Z', '', 'silent')",
+            )
+            .unwrap();
+        let status = |id: &str| -> String {
+            state
+                .conn()
+                .query_row("SELECT status FROM monitors WHERE id = ?1", [id], |row| row.get(0))
+                .unwrap()
+        };
+
+        // The monitor has already ended when it notifies, so its own level still counts
+        let shown = handle_process_exit(app.handle(), "m1", "o/r#1", "@@STATUS:clean@@").unwrap();
+        assert_eq!((shown.notification_type.as_str(), shown.pr_number), ("monitor_complete", 1));
+        assert_eq!(status("m1"), "completed");
+
+        assert!(handle_process_exit(app.handle(), "m2", "o/r#2", "@@STATUS:push_failed@@").is_none());
+        assert_eq!(status("m2"), "failed");
+
+        // A monitor that was already stopped doesn't notify again
+        assert!(handle_process_exit(app.handle(), "m1", "o/r#1", "@@STATUS:clean@@").is_none());
+    }
+
+    #[test]
+    fn test_app_process_tree() {
+        let rows = parse_ps_output(
+            "  100     1   100 /Applications/Clanker Spanker\n\
+               300   200   300 bash monitor-pr-loop.sh 1 o/r\n\
+               200   100   200 gh pr list\n\
+               301   300   300 claude -p fix\n\
+               400     1   400 bash leftover.sh\n\
+               401     1   400 claude -p stale\n\
+               500     1   500 unrelated\n\
+               garbage line\n",
+        );
+        assert_eq!(rows.len(), 7);
+        assert_eq!(rows[0].3, "/Applications/Clanker Spanker");
+
+        let groups = HashMap::from([(300, "m1".to_string()), (400, "m0".to_string())]);
+        let tree = app_process_tree(&rows, 100, &groups);
+        let pids: Vec<(i32, Option<&str>)> =
+            tree.iter().map(|p| (p.pid, p.monitor_id.as_deref())).collect();
+        assert_eq!(
+            pids,
+            [(300, Some("m1")), (200, None), (301, Some("m1")), (400, Some("m0")), (401, Some("m0"))]
+        );
+    }
 }
@@ -0,0 +1,138 @@
+//! gh API usage per refresh
+//!
+//! The PAT's rate limit is often shared with other tooling, so after every PR
+//! fetch cycle the GraphQL budget is sampled in the background with
+//! `gh api rate_limit` and stored in `rate_limit_samples` along with the number
+//! of gh calls the cycle made. Dropping below `rate_limit_warning_threshold` raises one warning
+//! notification and `rate-limit:sampled` reports `low`, which the board uses to
+//! stretch its auto-refresh interval until the budget recovers.
+
+use crate::db::{self, AppState};
+use crate::github;
+use crate::notifications;
+use crate::throttle;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Whether the last sample was below the warning threshold
+static LOW: AtomicBool = AtomicBool::new(false);
+
+/// Set while a sample runs in the background
+static SAMPLING: AtomicBool = AtomicBool::new(false);
+
+/// gh calls of cycles not sampled yet; cycles that end while a sample runs
+/// are counted in the next one
+static PENDING_CALLS: AtomicU64 = AtomicU64::new(0);
+
+/// Payload for `rate-limit:sampled`
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SamplePayload {
+    #[serde(flatten)]
+    sample: db::RateLimitSample,
+    low: bool,
+    threshold: i64,
+}
+
+/// Whether the GraphQL budget was below the warning threshold at the last sample
+pub fn is_low() -> bool {
+    LOW.load(Ordering::SeqCst)
+}
+
+/// Sample the budget and store it. Returns the sample, the threshold and whether
+/// the budget just dropped below it.
+fn sample(state: &AppState, gh_calls: u64) -> Result<(db::RateLimitSample, i64, bool), String> {
+    let status = github::parse_rate_limit(&state.github.rate_limit()?)?;
+    throttle::record(status.clone());
+
+    let conn = state.conn();
+    let bucket = &status.graphql;
+    db::record_rate_limit_sample(&conn, bucket.remaining, bucket.limit, &bucket.reset_at, gh_calls as i64)
+        .map_err(|e| format!("Failed to record rate limit sample: {}", e))?;
+    let sample = db::get_rate_limit_samples(&conn, 1)
+        .map_err(|e| format!("Failed to read rate limit sample: {}", e))?
+        .pop()
+        .ok_or("Rate limit sample was not stored")?;
+
+    let threshold = db::get_rate_limit_warning_threshold(&conn);
+    let low = sample.remaining < threshold;
+    let was_low = LOW.swap(low, Ordering::SeqCst);
+    Ok((sample, threshold, low && !was_low))
+}
+
+/// Record what a fetch cycle cost (`gh_calls` invocations) and sample the
+/// budget on a background thread, warning when it runs low
+pub(crate) fn record_cycle(app: &AppHandle, gh_calls: u64) {
+    PENDING_CALLS.fetch_add(gh_calls, Ordering::SeqCst);
+    if SAMPLING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let Some(state) = app.try_state::<AppState>() {
+            record_sample(&app, &state, PENDING_CALLS.swap(0, Ordering::SeqCst));
+        }
+        SAMPLING.store(false, Ordering::SeqCst);
+    });
+}
+
+fn record_sample(app: &AppHandle, state: &AppState, gh_calls: u64) {
+    let (sample, threshold, newly_low) = match sample(state, gh_calls) {
+        Ok(sampled) => sampled,
+        Err(e) => {
+            eprintln!("Failed to sample rate limit: {}", e);
+            return;
+        }
+    };
+
+    if newly_low {
+        notifications::notify_rate_limit_low(app, sample.remaining, sample.limit, &sample.reset_at);
+    }
+    let _ = app.emit(
+        "rate-limit:sampled",
+        SamplePayload {
+            sample,
+            low: is_low(),
+            threshold,
+        },
+    );
+}
+
+/// Most recent rate-limit samples (default 100), newest first
+#[tauri::command]
+pub fn get_rate_limit_history(
+    state: State<'_, AppState>,
+    limit: Option<i64>,
+) -> Result<Vec<db::RateLimitSample>, String> {
+    db::get_rate_limit_samples(&state.conn(), limit.unwrap_or(100).clamp(1, 500))
+        .map_err(|e| format!("Failed to get rate limit history: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample() {
+        let state = AppState::for_tests_default();
+        LOW.store(false, Ordering::SeqCst);
+
+        // The mock reports a full 5000 budget
+        let (first, threshold, newly_low) = sample(&state, 7).unwrap();
+        assert_eq!((first.remaining, first.limit, first.gh_calls), (5000, 5000, 7));
+        assert_eq!(threshold, 1000);
+        assert!(!newly_low && !is_low());
+
+        db::set_setting(&state.conn(), db::RATE_LIMIT_WARNING_THRESHOLD_SETTING_KEY, "6000").unwrap();
+        assert!(sample(&state, 3).unwrap().2);
+        // Only the drop below the threshold warns
+        assert!(!sample(&state, 3).unwrap().2);
+        assert!(is_low());
+
+        let history = db::get_rate_limit_samples(&state.conn(), 10).unwrap();
+        let calls: Vec<i64> = history.iter().map(|s| s.gh_calls).collect();
+        assert_eq!(calls, [3, 3, 7]);
+        LOW.store(false, Ordering::SeqCst);
+    }
+}
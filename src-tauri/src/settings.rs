@@ -1,14 +1,13 @@
 use crate::db::{self, AppState};
-use serde::Serialize;
-use tauri::State;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Emitter, Runtime, State};
 
 /// Get all configured repositories
 #[tauri::command]
 pub fn get_repos(state: State<'_, AppState>) -> Result<Vec<String>, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    let conn = state.conn();
 
     let repos_json = db::get_setting(&conn, "repos")
         .map_err(|e| format!("Database error: {}", e))?
@@ -17,13 +16,84 @@ pub fn get_repos(state: State<'_, AppState>) -> Result<Vec<String>, String> {
     serde_json::from_str(&repos_json).map_err(|e| format!("Failed to parse repos: {}", e))
 }
 
+/// A configured repo with the name to show for it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoSummary {
+    /// Full owner/repo path; what everything is keyed and matched by
+    pub repo: String,
+    /// Bare repo name, with the owner added when another repo shares the name ("api (acme)")
+    pub short_name: String,
+    /// Set with `set_repo_alias`; display-only
+    pub alias: Option<String>,
+    /// The alias if set, otherwise `short_name`
+    pub display_name: String,
+}
+
+/// Display names for repos, disambiguating ones that share a name across owners
+pub(crate) fn repo_summaries(repos: &[String], aliases: &BTreeMap<String, String>) -> Vec<RepoSummary> {
+    let paths: Vec<String> = repos.iter().map(|r| crate::parse_repo_path(r)).collect();
+    let split = |path: &str| -> (String, String) {
+        match path.split_once('/') {
+            Some((owner, name)) => (owner.to_string(), name.to_string()),
+            None => (String::new(), path.to_string()),
+        }
+    };
+    paths
+        .iter()
+        .map(|path| {
+            let (owner, name) = split(path);
+            let shared = paths
+                .iter()
+                .filter(|other| split(other).1.eq_ignore_ascii_case(&name))
+                .count()
+                > 1;
+            let short_name = if shared && !owner.is_empty() {
+                format!("{} ({})", name, owner)
+            } else {
+                name
+            };
+            let alias = aliases.get(path).cloned();
+            RepoSummary {
+                repo: path.clone(),
+                display_name: alias.clone().unwrap_or_else(|| short_name.clone()),
+                short_name,
+                alias,
+            }
+        })
+        .collect()
+}
+
+/// Configured repositories with their display names
+#[tauri::command]
+pub fn get_repo_summaries(state: State<'_, AppState>) -> Result<Vec<RepoSummary>, String> {
+    let aliases = db::get_repo_aliases(&state.conn()).map_err(|e| format!("Database error: {}", e))?;
+    Ok(repo_summaries(&get_repos(state)?, &aliases))
+}
+
+/// Give a repo a display alias ("Backend"), or clear it with None/blank.
+/// The owner/repo path stays the key everywhere else.
+#[tauri::command]
+pub fn set_repo_alias(state: State<'_, AppState>, repo: String, alias: Option<String>) -> Result<(), String> {
+    let repo = crate::parse_repo_path(&repo);
+    let conn = state.conn();
+    let result = match alias.as_deref().map(str::trim).filter(|a| !a.is_empty()) {
+        Some(alias) => db::set_repo_setting(&conn, &repo, db::ALIAS_REPO_SETTING_KEY, alias),
+        None => db::delete_repo_setting(&conn, &repo, db::ALIAS_REPO_SETTING_KEY),
+    };
+    result.map_err(|e| format!("Database error: {}", e))
+}
+
+/// Display aliases by owner/repo
+#[tauri::command]
+pub fn get_repo_aliases(state: State<'_, AppState>) -> Result<BTreeMap<String, String>, String> {
+    db::get_repo_aliases(&state.conn()).map_err(|e| format!("Database error: {}", e))
+}
+
 /// Add a repository to the list
 #[tauri::command]
 pub fn add_repo(state: State<'_, AppState>, repo: String) -> Result<(), String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    let conn = state.conn();
 
     // Get current repos
     let repos_json = db::get_setting(&conn, "repos")
@@ -45,38 +115,113 @@ pub fn add_repo(state: State<'_, AppState>, repo: String) -> Result<(), String>
     Ok(())
 }
 
-/// Remove a repository from the list
+/// What `remove_repo` cleaned up
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoRemoval {
+    pub repo: String,
+    /// IDs of the active monitors that were stopped
+    pub monitors_stopped: Vec<String>,
+    #[serde(flatten)]
+    pub removed: db::RemovedRepoData,
+}
+
+/// Remove a repository from the list. Its PRs leave the board either way; with
+/// `cascade` its cached data and settings are deleted and its active monitors
+/// stopped, which needs `force` when any are running. Monitors still blocked
+/// on the gh login are always stopped, since they'd start for a repo that is gone.
 #[tauri::command]
-pub fn remove_repo(state: State<'_, AppState>, repo: String) -> Result<(), String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+pub fn remove_repo(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    repo: String,
+    cascade: Option<bool>,
+    force: Option<bool>,
+) -> Result<RepoRemoval, String> {
+    remove_repo_internal(&app, &state, &repo, cascade.unwrap_or(false), force.unwrap_or(false))
+}
 
-    // Get current repos
-    let repos_json = db::get_setting(&conn, "repos")
-        .map_err(|e| format!("Database error: {}", e))?
-        .unwrap_or_else(|| "[]".to_string());
+fn remove_repo_internal<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AppState,
+    repo: &str,
+    cascade: bool,
+    force: bool,
+) -> Result<RepoRemoval, String> {
+    let repo_path = crate::parse_repo_path(repo);
+
+    let (before, active_monitors) = {
+        let conn = state.conn();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, status FROM monitors
+                 WHERE repo = ?1 AND status IN ('running', 'sleeping', 'blocked')",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        let active: Vec<(String, String)> = stmt
+            .query_map([&repo_path], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| format!("Failed to query monitors: {}", e))?
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to read monitor: {}", e))?;
+        (crate::pr_events::snapshot_repos(&conn, std::slice::from_ref(&repo_path)), active)
+    };
+    if cascade && !active_monitors.is_empty() && !force {
+        return Err(format!(
+            "{} has {} active monitor(s); remove it with force to stop them",
+            repo_path,
+            active_monitors.len()
+        ));
+    }
 
-    let mut repos: Vec<String> =
-        serde_json::from_str(&repos_json).map_err(|e| format!("Failed to parse repos: {}", e))?;
+    {
+        let conn = state.conn();
 
-    // Remove if present
-    repos.retain(|r| r != &repo);
-    let new_json =
-        serde_json::to_string(&repos).map_err(|e| format!("Failed to serialize repos: {}", e))?;
-    db::set_setting(&conn, "repos", &new_json).map_err(|e| format!("Database error: {}", e))?;
+        // Get current repos
+        let repos_json = db::get_setting(&conn, "repos")
+            .map_err(|e| format!("Database error: {}", e))?
+            .unwrap_or_else(|| "[]".to_string());
 
-    Ok(())
+        let mut repos: Vec<String> = serde_json::from_str(&repos_json)
+            .map_err(|e| format!("Failed to parse repos: {}", e))?;
+
+        // Remove if present
+        repos.retain(|r| r != repo);
+        let new_json = serde_json::to_string(&repos)
+            .map_err(|e| format!("Failed to serialize repos: {}", e))?;
+        db::set_setting(&conn, "repos", &new_json)
+            .map_err(|e| format!("Database error: {}", e))?;
+    }
+
+    let mut removal = RepoRemoval {
+        repo: repo_path.clone(),
+        monitors_stopped: Vec::new(),
+        removed: db::RemovedRepoData::default(),
+    };
+    for (monitor_id, status) in active_monitors {
+        if !cascade && status != "blocked" {
+            continue;
+        }
+        match crate::monitor::stop_monitor_with_reason(app, state, &monitor_id, "repo_removed") {
+            Ok(()) => removal.monitors_stopped.push(monitor_id),
+            Err(e) => eprintln!("Failed to stop monitor {}: {}", monitor_id, e),
+        }
+    }
+    if cascade {
+        removal.removed = db::delete_repo_data(&state.conn(), &repo_path)
+            .map_err(|e| format!("Failed to delete repo data: {}", e))?;
+    }
+
+    // Unconfigured repos are off the board even when their cache is kept
+    let changes = crate::pr_events::diff(&before, &crate::pr_events::Snapshot::new());
+    crate::pr_events::emit_changes(app, &changes);
+
+    Ok(removal)
 }
 
 /// Get the currently selected repository
 #[tauri::command]
 pub fn get_selected_repo(state: State<'_, AppState>) -> Result<String, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    let conn = state.conn();
 
     db::get_setting(&conn, "selected_repo")
         .map_err(|e| format!("Database error: {}", e))?
@@ -86,10 +231,7 @@ pub fn get_selected_repo(state: State<'_, AppState>) -> Result<String, String> {
 /// Set the currently selected repository
 #[tauri::command]
 pub fn set_selected_repo(state: State<'_, AppState>, repo: String) -> Result<(), String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    let conn = state.conn();
 
     db::set_setting(&conn, "selected_repo", &repo)
         .map_err(|e| format!("Database error: {}", e))
@@ -98,10 +240,11 @@ pub fn set_selected_repo(state: State<'_, AppState>, repo: String) -> Result<(),
 /// Get a generic setting by key
 #[tauri::command]
 pub fn get_setting(state: State<'_, AppState>, key: String) -> Result<Option<String>, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    if key == db::DEMO_MODE_SETTING_KEY && crate::demo::is_active() {
+        let conn = crate::demo::main_db()?;
+        return db::get_setting(&conn, &key).map_err(|e| format!("Database error: {}", e));
+    }
+    let conn = state.conn();
 
     db::get_setting(&conn, &key).map_err(|e| format!("Database error: {}", e))
 }
@@ -113,12 +256,316 @@ pub fn set_setting(
     key: String,
     value: String,
 ) -> Result<(), String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    validate_setting(&key, &value)?;
+    // Demo mode runs on the demo database; turning it off must reach the real one
+    if key == db::DEMO_MODE_SETTING_KEY && crate::demo::is_active() {
+        let conn = crate::demo::main_db()?;
+        return db::set_setting(&conn, &key, &value).map_err(|e| format!("Database error: {}", e));
+    }
+    let conn = state.conn();
+
+    db::set_setting(&conn, &key, &value).map_err(|e| format!("Database error: {}", e))?;
+
+    // Muting applies to PRs already in the cache, not just future fetches
+    if key == db::MUTED_AUTHORS_SETTING_KEY {
+        db::refresh_muted_prs(&conn).map_err(|e| format!("Database error: {}", e))?;
+    }
+
+    if key == db::GH_PATH_SETTING_KEY {
+        crate::github::set_gh_path(db::get_gh_path(&conn));
+    }
+
+    Ok(())
+}
+
+/// Look up the teams I belong to with `gh api user/teams` and save them as
+/// the `my_teams` setting. Returns the saved `org/slug` list.
+#[tauri::command]
+pub fn discover_my_teams(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    // gh runs without the DB lock held
+    let teams = state.github.user_teams()?;
+    let json = serde_json::to_string(&teams).map_err(|e| e.to_string())?;
+    db::set_setting(&state.conn(), db::MY_TEAMS_SETTING_KEY, &json)
+        .map_err(|e| format!("Database error: {}", e))?;
+    Ok(teams)
+}
+
+/// Get a per-repo setting by key
+#[tauri::command]
+pub fn get_repo_setting(
+    state: State<'_, AppState>,
+    repo: String,
+    key: String,
+) -> Result<Option<String>, String> {
+    let conn = state.conn();
+
+    db::get_repo_setting(&conn, &repo, &key).map_err(|e| format!("Database error: {}", e))
+}
+
+/// Set a per-repo setting by key
+#[tauri::command]
+pub fn set_repo_setting(
+    state: State<'_, AppState>,
+    repo: String,
+    key: String,
+    value: String,
+) -> Result<(), String> {
+    validate_repo_setting(&key, &value)?;
+    let conn = state.conn();
+
+    db::set_repo_setting(&conn, &repo, &key, &value).map_err(|e| format!("Database error: {}", e))
+}
+
+/// Check a setting value before it is saved, by `set_setting` or an import.
+/// Paths to programs the app runs must name an existing file (blank clears them).
+fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    let value = value.trim();
+    match key {
+        db::GH_PATH_SETTING_KEY | db::MONITOR_SCRIPT_PATH_SETTING_KEY if !value.is_empty() => {
+            let path = std::path::Path::new(value);
+            if !path.is_absolute() || !path.is_file() {
+                return Err(format!("{} must be an absolute path to an existing file", key));
+            }
+        }
+        db::MONITOR_AUTO_ANSWERS_SETTING_KEY if !value.is_empty() => {
+            serde_json::from_str::<Vec<db::AutoAnswer>>(value)
+                .map_err(|e| format!("{} must be a JSON array of {{pattern, response}}: {}", key, e))?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Check a per-repo setting value before it is saved (blank clears it)
+fn validate_repo_setting(key: &str, value: &str) -> Result<(), String> {
+    let value = value.trim();
+    match key {
+        db::LOCAL_PATH_REPO_SETTING_KEY if !value.is_empty() => {
+            let path = std::path::Path::new(value);
+            if !path.is_absolute() || !path.is_dir() {
+                return Err(format!("{} must be an absolute path to an existing directory", key));
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Settings that can be exported and imported. Anything else is reported
+/// as unknown on import rather than written.
+const PORTABLE_SETTING_KEYS: &[&str] = &[
+    "selected_repo",
+    "default_max_iterations",
+    "default_interval_minutes",
+    "sleep_prevention_enabled",
+    "theme",
+    db::AI_PROVIDER_SETTING_KEY,
+    db::AI_MODEL_CLAUDE_SETTING_KEY,
+    db::AI_MODEL_CODEX_SETTING_KEY,
+    db::MONITOR_DIRTY_WORKTREE_POLICY_SETTING_KEY,
+    db::SKIP_CI_FIX_SETTING_KEY,
+    db::QUIT_BEHAVIOR_SETTING_KEY,
+    db::CLOSE_BEHAVIOR_SETTING_KEY,
+    db::API_ENABLED_SETTING_KEY,
+    db::REFRESH_ON_WAKE_SETTING_KEY,
+    db::REVIEW_WAITING_THRESHOLD_HOURS_SETTING_KEY,
+    db::STALE_DATA_THRESHOLD_MINUTES_SETTING_KEY,
+    db::RATE_LIMIT_RESERVE_SETTING_KEY,
+    db::RATE_LIMIT_WARNING_THRESHOLD_SETTING_KEY,
+    db::ATTENTION_EXCLUDES_ACKNOWLEDGED_SETTING_KEY,
+    db::DOCK_BADGE_MODE_SETTING_KEY,
+    db::AUTO_DISMISS_MERGED_AFTER_MINUTES_SETTING_KEY,
+    db::REFETCH_COMMENTS_ON_FORCE_PUSH_SETTING_KEY,
+    db::MUTED_AUTHORS_SETTING_KEY,
+    db::MY_TEAMS_SETTING_KEY,
+    db::SUPPRESS_NOTIFICATIONS_WHEN_FOCUSED_SETTING_KEY,
+    db::MAX_CONCURRENT_MONITORS_SETTING_KEY,
+    db::MONITOR_INTERVAL_JITTER_PERCENT_SETTING_KEY,
+    db::ALLOW_FORK_MONITORS_SETTING_KEY,
+    db::DONE_ARCHIVE_AFTER_DAYS_SETTING_KEY,
+    db::DONE_DELETE_AFTER_DAYS_SETTING_KEY,
+    db::KEEP_RESOLVED_HISTORY_SETTING_KEY,
+    db::EXTERNAL_PUSH_ACTION_SETTING_KEY,
+];
+
+/// Settings that never leave this machine: secrets, runtime state, and what
+/// the app runs or types into a running monitor (an imported file must not
+/// choose either)
+const LOCAL_ONLY_SETTING_KEYS: &[&str] = &[
+    db::API_TOKEN_SETTING_KEY,
+    db::MONITORS_PAUSED_SETTING_KEY,
+    db::GH_PATH_SETTING_KEY,
+    db::MONITOR_SCRIPT_PATH_SETTING_KEY,
+    db::MONITOR_AUTO_ANSWERS_SETTING_KEY,
+    db::DEMO_MODE_SETTING_KEY,
+];
+
+/// Per-repo settings that can be exported and imported
+const PORTABLE_REPO_SETTING_KEYS: &[&str] = &[
+    db::MERGE_METHOD_REPO_SETTING_KEY,
+    db::ON_APPROVED_REPO_SETTING_KEY,
+    db::ALIAS_REPO_SETTING_KEY,
+];
+
+/// Per-repo settings that never leave this machine: where its clone lives,
+/// which is where monitors run
+const LOCAL_ONLY_REPO_SETTING_KEYS: &[&str] = &[db::LOCAL_PATH_REPO_SETTING_KEY];
+
+/// Version written to exports; imports of newer versions are refused
+const SETTINGS_EXPORT_VERSION: u32 = 1;
+
+/// Configuration as exported by `export_settings`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsExport {
+    pub version: u32,
+    #[serde(default)]
+    pub repos: Vec<String>,
+    #[serde(default)]
+    pub settings: BTreeMap<String, String>,
+    /// repo -> key -> value
+    #[serde(default)]
+    pub repo_settings: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// What `import_settings` did with each key
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsImportReport {
+    pub imported: Vec<String>,
+    /// Local-only keys (e.g. the API token), left untouched
+    pub skipped: Vec<String>,
+    /// Keys whose value failed validation, not written
+    pub invalid: Vec<String>,
+    /// Keys this version does not know, not written
+    pub unknown: Vec<String>,
+}
+
+fn export(conn: &Connection) -> rusqlite::Result<SettingsExport> {
+    let mut export = SettingsExport {
+        version: SETTINGS_EXPORT_VERSION,
+        repos: Vec::new(),
+        settings: BTreeMap::new(),
+        repo_settings: BTreeMap::new(),
+    };
+
+    let mut stmt = conn.prepare("SELECT key, value FROM settings")?;
+    let rows =
+        stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+    for row in rows {
+        let (key, value) = row?;
+        if key == "repos" {
+            export.repos = serde_json::from_str(&value).unwrap_or_default();
+        } else if !LOCAL_ONLY_SETTING_KEYS.contains(&key.as_str()) {
+            export.settings.insert(key, value);
+        }
+    }
+
+    let mut stmt = conn.prepare("SELECT repo, key, value FROM repo_settings")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+    })?;
+    for row in rows {
+        let (repo, key, value) = row?;
+        if !LOCAL_ONLY_REPO_SETTING_KEYS.contains(&key.as_str()) {
+            export.repo_settings.entry(repo).or_default().insert(key, value);
+        }
+    }
 
-    db::set_setting(&conn, &key, &value).map_err(|e| format!("Database error: {}", e))
+    Ok(export)
+}
+
+fn import(conn: &Connection, json: &str) -> Result<SettingsImportReport, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| format!("Invalid settings file: {}", e))?;
+    // A file without a repo list leaves the current one alone
+    let has_repos = value.get("repos").is_some();
+    let import: SettingsExport =
+        serde_json::from_value(value).map_err(|e| format!("Invalid settings file: {}", e))?;
+    if import.version > SETTINGS_EXPORT_VERSION {
+        return Err(format!(
+            "Settings file version {} is newer than this app supports ({})",
+            import.version, SETTINGS_EXPORT_VERSION
+        ));
+    }
+    if import.repos.iter().any(|repo| repo.trim().is_empty()) {
+        return Err("Invalid settings file: empty repo in repos".to_string());
+    }
+
+    let mut report = SettingsImportReport::default();
+    let db_err = |e: rusqlite::Error| format!("Database error: {}", e);
+
+    if has_repos {
+        let repos = serde_json::to_string(&import.repos)
+            .map_err(|e| format!("Failed to serialize repos: {}", e))?;
+        db::set_setting(conn, "repos", &repos).map_err(db_err)?;
+        report.imported.push("repos".to_string());
+    }
+
+    for (key, value) in &import.settings {
+        if LOCAL_ONLY_SETTING_KEYS.contains(&key.as_str()) {
+            report.skipped.push(key.clone());
+        } else if PORTABLE_SETTING_KEYS.contains(&key.as_str()) {
+            if validate_setting(key, value).is_err() {
+                report.invalid.push(key.clone());
+                continue;
+            }
+            db::set_setting(conn, key, value).map_err(db_err)?;
+            report.imported.push(key.clone());
+        } else {
+            report.unknown.push(key.clone());
+        }
+    }
+
+    for (repo, settings) in &import.repo_settings {
+        for (key, value) in settings {
+            let name = format!("{}:{}", repo, key);
+            if LOCAL_ONLY_REPO_SETTING_KEYS.contains(&key.as_str()) {
+                report.skipped.push(name);
+            } else if PORTABLE_REPO_SETTING_KEYS.contains(&key.as_str()) {
+                if validate_repo_setting(key, value).is_err() {
+                    report.invalid.push(name);
+                    continue;
+                }
+                db::set_repo_setting(conn, repo, key, value).map_err(db_err)?;
+                report.imported.push(name);
+            } else {
+                report.unknown.push(name);
+            }
+        }
+    }
+
+    // Same side effects as saving these through set_setting
+    db::refresh_muted_prs(conn).map_err(db_err)?;
+    crate::github::set_gh_path(db::get_gh_path(conn));
+
+    Ok(report)
+}
+
+/// Export settings, the repo list and per-repo settings as JSON (without the API token)
+#[tauri::command]
+pub fn export_settings(state: State<'_, AppState>) -> Result<SettingsExport, String> {
+    export(&state.conn()).map_err(|e| format!("Database error: {}", e))
+}
+
+/// Import settings from `export_settings` JSON. Local-only keys are skipped and
+/// unknown keys reported instead of written. Emits `settings:reload` afterwards.
+#[tauri::command]
+pub fn import_settings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    json: String,
+) -> Result<SettingsImportReport, String> {
+    let report = import(&state.conn(), &json)?;
+    println!(
+        "Imported {} settings ({} skipped, {} invalid, {} unknown)",
+        report.imported.len(),
+        report.skipped.len(),
+        report.invalid.len(),
+        report.unknown.len()
+    );
+    let _ = app.emit("settings:reload", &report);
+    Ok(report)
 }
 
 #[derive(Serialize)]
@@ -192,10 +639,7 @@ fn read_codex_default_model() -> Option<String> {
 /// Get the effective AI provider/model currently used for new monitors.
 #[tauri::command]
 pub fn get_effective_ai_model(state: State<'_, AppState>) -> Result<EffectiveAiModel, String> {
-    let conn = state
-        .db
-        .lock()
-        .map_err(|e| format!("Failed to lock database: {}", e))?;
+    let conn = state.conn();
 
     let (provider, override_model) = db::get_ai_config(&conn);
     drop(conn);
@@ -226,3 +670,116 @@ pub fn get_effective_ai_model(state: State<'_, AppState>) -> Result<EffectiveAiM
         }),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_export_import() {
+        let source = Connection::open_in_memory().unwrap();
+        db::init_schema(&source).unwrap();
+        db::set_setting(&source, "repos", r#"["o/a", "o/b"]"#).unwrap();
+        db::set_setting(&source, db::API_TOKEN_SETTING_KEY, "secret").unwrap();
+        db::set_setting(&source, db::RATE_LIMIT_RESERVE_SETTING_KEY, "500").unwrap();
+        db::set_repo_setting(&source, "o/a", db::MERGE_METHOD_REPO_SETTING_KEY, "rebase").unwrap();
+        db::set_setting(&source, db::GH_PATH_SETTING_KEY, "/opt/gh").unwrap();
+        db::set_repo_setting(&source, "o/a", db::LOCAL_PATH_REPO_SETTING_KEY, "/src/a").unwrap();
+
+        let exported = export(&source).unwrap();
+        assert_eq!(exported.repos, ["o/a", "o/b"]);
+        assert!(!exported.settings.contains_key(db::API_TOKEN_SETTING_KEY));
+        assert!(!exported.settings.contains_key(db::GH_PATH_SETTING_KEY));
+        assert!(!exported.repo_settings["o/a"].contains_key(db::LOCAL_PATH_REPO_SETTING_KEY));
+        let mut json = serde_json::to_value(&exported).unwrap();
+        json["settings"]["api_token"] = "other-secret".into();
+        // A shared file can't pick the programs this machine runs
+        json["settings"]["gh_path"] = "/tmp/evil".into();
+        json["repoSettings"]["o/a"]["local_path"] = "/tmp/evil".into();
+        json["settings"]["from_the_future"] = "1".into();
+
+        let target = Connection::open_in_memory().unwrap();
+        db::init_schema(&target).unwrap();
+        db::set_setting(&target, db::API_TOKEN_SETTING_KEY, "mine").unwrap();
+        let report = import(&target, &json.to_string()).unwrap();
+
+        assert_eq!(report.skipped, ["api_token", "gh_path", "o/a:local_path"]);
+        assert_eq!(db::get_gh_path(&target), None);
+        assert_eq!(report.unknown, ["from_the_future"]);
+        assert!(report.imported.contains(&"o/a:merge_method".to_string()));
+        assert_eq!(db::get_rate_limit_reserve(&target), 500);
+        assert_eq!(db::get_setting_value(&target, "api_token").as_deref(), Some("mine"));
+        assert_eq!(db::get_setting_value(&target, "from_the_future"), None);
+        assert_eq!(
+            db::get_repo_setting(&target, "o/a", "merge_method").unwrap().as_deref(),
+            Some("rebase")
+        );
+        assert_eq!(export(&target).unwrap().repos, ["o/a", "o/b"]);
+
+        assert!(validate_setting(db::GH_PATH_SETTING_KEY, "gh").is_err());
+        assert!(validate_setting(db::GH_PATH_SETTING_KEY, "/nonexistent/gh").is_err());
+        assert!(validate_setting(db::GH_PATH_SETTING_KEY, " ").is_ok());
+        assert!(validate_repo_setting(db::LOCAL_PATH_REPO_SETTING_KEY, "/nonexistent").is_err());
+
+        assert!(import(&target, "not json").is_err());
+        assert!(import(&target, r#"{"version": 99}"#).is_err());
+        import(&target, r#"{"version": 1, "settings": {}}"#).unwrap();
+        assert_eq!(export(&target).unwrap().repos, ["o/a", "o/b"]);
+    }
+
+    #[test]
+    fn test_repo_summaries() {
+        let repos = ["acme/api", "https://github.com/globex/api", "acme/web", "globex/API-docs"]
+            .map(String::from);
+        let aliases = BTreeMap::from([("acme/web".to_string(), "Website".to_string())]);
+        let summaries = repo_summaries(&repos, &aliases);
+        let display: Vec<&str> = summaries.iter().map(|r| r.display_name.as_str()).collect();
+        assert_eq!(display, ["api (acme)", "api (globex)", "Website", "API-docs"]);
+        assert_eq!(summaries[2].alias.as_deref(), Some("Website"));
+        assert_eq!(summaries[0].alias, None);
+        let names: Vec<(String, String)> =
+            summaries.into_iter().map(|r| (r.repo, r.short_name)).collect();
+        assert_eq!(
+            names,
+            [
+                ("acme/api", "api (acme)"),
+                ("globex/api", "api (globex)"),
+                ("acme/web", "web"),
+                ("globex/API-docs", "API-docs"),
+            ]
+            .map(|(repo, name)| (repo.to_string(), name.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_remove_repo_stops_blocked_monitors() {
+        use tauri::Manager;
+
+        let app = tauri::test::mock_app();
+        app.manage(AppState::for_tests_default());
+        let state = app.state::<AppState>();
+        {
+            let conn = state.conn();
+            db::set_setting(&conn, "repos", r#"["o/r"]"#).unwrap();
+            conn.execute_batch(
+                "INSERT INTO monitors (id, pr_id, pr_number, repo, status, started_at, log_file)
+                 VALUES ('sleeping', 'o/r#1', 1, 'o/r', 'sleeping', '', ''),
+                        ('blocked', 'o/r#2', 2, 'o/r', 'blocked', '', '')",
+            )
+            .unwrap();
+        }
+        let status = |id: &str| -> String {
+            state
+                .conn()
+                .query_row("SELECT status FROM monitors WHERE id = ?1", [id], |row| row.get(0))
+                .unwrap()
+        };
+
+        // Without cascade, running monitors keep going but blocked ones never start
+        let removal = remove_repo_internal(app.handle(), &state, "o/r", false, false).unwrap();
+        assert_eq!(removal.monitors_stopped, ["blocked"]);
+        assert_eq!((status("sleeping").as_str(), status("blocked").as_str()), ("sleeping", "stopped"));
+        assert!(crate::environment::start_blocked_monitors(app.handle(), &state).is_empty());
+        assert_eq!(db::get_setting(&state.conn(), "repos").unwrap().as_deref(), Some("[]"));
+    }
+}
@@ -2,7 +2,10 @@
 //!
 //! Prevents idle sleep while monitors are running to ensure uninterrupted monitoring.
 
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Runtime};
 
 /// IOKit type aliases for FFI
 #[cfg(target_os = "macos")]
@@ -33,6 +36,55 @@ extern "C" {
 /// Global state for sleep assertion
 static SLEEP_ASSERTION_ID: Mutex<Option<u32>> = Mutex::new(None);
 
+/// Session-only "let it sleep now" override; never persisted, so a restart
+/// falls back to the sleep_prevention_enabled setting
+static SLEEP_PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Bookkeeping kept alongside the assertion for diagnostics
+struct SleepDiagnostics {
+    acquired_at: Option<String>,
+    last_active_count: Option<i32>,
+    last_feature_enabled: Option<bool>,
+    last_error: Option<String>,
+}
+
+static SLEEP_DIAGNOSTICS: Mutex<SleepDiagnostics> = Mutex::new(SleepDiagnostics {
+    acquired_at: None,
+    last_active_count: None,
+    last_feature_enabled: None,
+    last_error: None,
+});
+
+/// Detailed sleep prevention state for troubleshooting
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SleepPreventionDetails {
+    pub enabled: bool,
+    pub prevented: bool,
+    pub paused: bool,
+    pub assertion_ids: Vec<u32>,
+    pub acquired_at: Option<String>,
+    pub active_monitor_count: Option<i32>,
+    pub last_decision_enabled: Option<bool>,
+    pub last_error: Option<String>,
+}
+
+/// Current sleep prevention state
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SleepPreventionStatus {
+    pub prevented: bool,
+    pub paused: bool,
+}
+
+/// Event payload for sleep prevention transitions
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SleepStatePayload {
+    pub prevented: bool,
+    pub active_count: i32,
+}
+
 /// Prevent system idle sleep (macOS only)
 ///
 /// Creates an IOKit power assertion that prevents the system from
@@ -70,6 +122,9 @@ pub fn prevent_sleep() -> Result<(), String> {
 
     if result == IORETURN_SUCCESS {
         *assertion_id_guard = Some(assertion_id);
+        if let Ok(mut diag) = SLEEP_DIAGNOSTICS.lock() {
+            diag.acquired_at = Some(chrono::Utc::now().to_rfc3339());
+        }
         println!("Sleep prevention enabled (assertion ID: {})", assertion_id);
         Ok(())
     } else {
@@ -94,6 +149,9 @@ pub fn allow_sleep() -> Result<(), String> {
         let result = unsafe { IOPMAssertionRelease(assertion_id) };
 
         if result == IORETURN_SUCCESS {
+            if let Ok(mut diag) = SLEEP_DIAGNOSTICS.lock() {
+                diag.acquired_at = None;
+            }
             println!(
                 "Sleep prevention disabled (released assertion ID: {})",
                 assertion_id
@@ -132,15 +190,77 @@ pub fn allow_sleep() -> Result<(), String> {
     Ok(()) // No-op on non-macOS
 }
 
-/// Update sleep prevention based on active monitor count and user setting
-pub fn update_sleep_state(active_monitors: i32, feature_enabled: bool) {
-    if feature_enabled && active_monitors > 0 {
-        if let Err(e) = prevent_sleep() {
-            eprintln!("Warning: Failed to prevent sleep: {}", e);
-        }
+/// Pause or resume sleep prevention for this session only
+pub fn set_paused(paused: bool) {
+    SLEEP_PAUSED.store(paused, Ordering::SeqCst);
+}
+
+/// Whether sleep prevention is paused for this session
+pub fn is_paused() -> bool {
+    SLEEP_PAUSED.load(Ordering::SeqCst)
+}
+
+/// Update sleep prevention based on active monitor count and user setting.
+/// While paused, sleep is always allowed.
+/// Emits `sleep:state-changed` when the assertion is acquired or released.
+pub fn update_sleep_state<R: Runtime>(app: &AppHandle<R>, active_monitors: i32, feature_enabled: bool) {
+    let was_prevented = is_sleep_prevented();
+
+    let result = if feature_enabled && active_monitors > 0 && !is_paused() {
+        prevent_sleep().map_err(|e| format!("Failed to prevent sleep: {}", e))
     } else {
-        if let Err(e) = allow_sleep() {
-            eprintln!("Warning: Failed to allow sleep: {}", e);
-        }
+        allow_sleep().map_err(|e| format!("Failed to allow sleep: {}", e))
+    };
+
+    if let Err(e) = &result {
+        eprintln!("Warning: {}", e);
+    }
+
+    if let Ok(mut diag) = SLEEP_DIAGNOSTICS.lock() {
+        diag.last_active_count = Some(active_monitors);
+        diag.last_feature_enabled = Some(feature_enabled);
+        diag.last_error = result.err();
+    }
+
+    let prevented = is_sleep_prevented();
+    if prevented != was_prevented {
+        let _ = app.emit(
+            "sleep:state-changed",
+            SleepStatePayload {
+                prevented,
+                active_count: active_monitors,
+            },
+        );
+    }
+}
+
+/// Snapshot the sleep prevention state for diagnostics
+pub fn get_details(setting_enabled: bool) -> SleepPreventionDetails {
+    let assertion_ids = SLEEP_ASSERTION_ID
+        .lock()
+        .map(|guard| guard.iter().copied().collect())
+        .unwrap_or_default();
+
+    let (acquired_at, active_monitor_count, last_decision_enabled, last_error) = SLEEP_DIAGNOSTICS
+        .lock()
+        .map(|diag| {
+            (
+                diag.acquired_at.clone(),
+                diag.last_active_count,
+                diag.last_feature_enabled,
+                diag.last_error.clone(),
+            )
+        })
+        .unwrap_or((None, None, None, None));
+
+    SleepPreventionDetails {
+        enabled: setting_enabled,
+        prevented: is_sleep_prevented(),
+        paused: is_paused(),
+        assertion_ids,
+        acquired_at,
+        active_monitor_count,
+        last_decision_enabled,
+        last_error,
     }
 }
@@ -0,0 +1,182 @@
+//! Stacked PR grouping
+//!
+//! A PR is stacked on another when its base branch is the other PR's head
+//! branch (see `db::link_stacked_prs`). Stacks are returned root-first.
+
+use crate::db::AppState;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use tauri::State;
+
+/// One PR in a stack
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackEntry {
+    pub pr_id: String,
+    pub number: i32,
+    pub title: String,
+    pub branch: String,
+    pub base_branch: String,
+    /// 0 for the bottom of the stack, 1 for PRs on top of it, ...
+    pub depth: usize,
+    pub needs_rebase: bool,
+}
+
+/// Get stacked PR chains for a repo, each ordered from the bottom PR up.
+/// PRs that aren't part of a stack are omitted.
+#[tauri::command]
+pub fn get_pr_stacks(state: State<'_, AppState>, repo: String) -> Result<Vec<Vec<StackEntry>>, String> {
+    let conn = state.conn();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, number, title, branch, base_branch, parent_pr_id, needs_rebase
+             FROM pr_cache
+             WHERE repo = ?1 AND state = 'open'
+             ORDER BY number",
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+    let rows: Vec<(StackEntry, Option<String>)> = stmt
+        .query_map([&repo], |row| {
+            Ok((
+                StackEntry {
+                    pr_id: row.get(0)?,
+                    number: row.get(1)?,
+                    title: row.get(2)?,
+                    branch: row.get(3)?,
+                    base_branch: row.get(4)?,
+                    depth: 0,
+                    needs_rebase: row.get::<_, i32>(6)? != 0,
+                },
+                row.get(5)?,
+            ))
+        })
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read rows: {}", e))?;
+
+    let links: Vec<(String, Option<String>)> = rows
+        .iter()
+        .map(|(entry, parent)| (entry.pr_id.clone(), parent.clone()))
+        .collect();
+    let entries: HashMap<String, StackEntry> = rows
+        .into_iter()
+        .map(|(entry, _)| (entry.pr_id.clone(), entry))
+        .collect();
+
+    Ok(build_stacks(&links)
+        .into_iter()
+        .map(|stack| {
+            stack
+                .into_iter()
+                .filter_map(|(pr_id, depth)| {
+                    entries.get(&pr_id).map(|entry| StackEntry {
+                        depth,
+                        ..entry.clone()
+                    })
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Group (pr_id, parent_pr_id) links into stacks of (pr_id, depth), root first.
+/// Parents that aren't in `links` (e.g. merged and dismissed) are treated as
+/// absent, and cycles are broken at the first PR reached so nothing loops.
+fn build_stacks(links: &[(String, Option<String>)]) -> Vec<Vec<(String, usize)>> {
+    let known: HashSet<&str> = links.iter().map(|(id, _)| id.as_str()).collect();
+    let mut children: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (id, parent) in links {
+        if let Some(parent) = parent.as_deref().filter(|p| known.contains(p) && *p != id) {
+            children.entry(parent).or_default().push(id.as_str());
+        }
+    }
+
+    let has_parent = |id: &str| {
+        links.iter().any(|(child, parent)| {
+            child == id
+                && parent
+                    .as_deref()
+                    .is_some_and(|p| known.contains(p) && p != id)
+        })
+    };
+
+    // Real roots first, then any PR left unvisited (only possible inside a cycle)
+    let mut starts: Vec<&str> = links
+        .iter()
+        .map(|(id, _)| id.as_str())
+        .filter(|id| !has_parent(id))
+        .collect();
+    starts.extend(links.iter().map(|(id, _)| id.as_str()));
+
+    let mut visited: HashSet<&str> = HashSet::new();
+    let mut stacks = Vec::new();
+
+    for start in starts {
+        if visited.contains(start) || !children.contains_key(start) {
+            continue;
+        }
+
+        let mut stack = Vec::new();
+        let mut pending = vec![(start, 0usize)];
+        while let Some((id, depth)) = pending.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            stack.push((id.to_string(), depth));
+            if let Some(kids) = children.get(id) {
+                for kid in kids.iter().rev() {
+                    pending.push((kid, depth + 1));
+                }
+            }
+        }
+
+        if stack.len() > 1 {
+            stacks.push(stack);
+        }
+    }
+
+    stacks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(id: &str, parent: Option<&str>) -> (String, Option<String>) {
+        (id.to_string(), parent.map(|p| p.to_string()))
+    }
+
+    #[test]
+    fn test_build_stacks() {
+        let links = vec![
+            link("o/r#3", Some("o/r#2")),
+            link("o/r#2", Some("o/r#1")),
+            link("o/r#1", None),
+            link("o/r#4", None),
+            // Parent was merged and dismissed
+            link("o/r#5", Some("o/r#99")),
+            link("o/r#6", Some("o/r#5")),
+            // Cycle
+            link("o/r#7", Some("o/r#8")),
+            link("o/r#8", Some("o/r#7")),
+        ];
+
+        let stacks = build_stacks(&links);
+        assert_eq!(stacks.len(), 3);
+        assert_eq!(
+            stacks[0],
+            vec![
+                ("o/r#1".to_string(), 0),
+                ("o/r#2".to_string(), 1),
+                ("o/r#3".to_string(), 2)
+            ]
+        );
+        assert_eq!(
+            stacks[1],
+            vec![("o/r#5".to_string(), 0), ("o/r#6".to_string(), 1)]
+        );
+        assert_eq!(stacks[2].len(), 2);
+    }
+}
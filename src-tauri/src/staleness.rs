@@ -0,0 +1,91 @@
+//! Cached PR freshness
+//!
+//! `get_fetch_status` reports per repo when PRs were last fetched successfully
+//! and whether the latest attempt failed, plus the gh rate-limit budget left
+//! after the last fetch cycle. A background watcher emits
+//! `fetch:stale` whenever the set of repos whose data is older than
+//! `stale_data_threshold_minutes` changes, so the UI can badge old data even
+//! when no fetch is running (e.g. after a long offline stretch).
+
+use crate::db::{self, AppState};
+use chrono::Utc;
+use serde::Serialize;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// How often the watcher re-checks staleness
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Payload for `fetch:stale`
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StalePayload {
+    repos: Vec<String>,
+    threshold_minutes: i64,
+}
+
+/// Result of `get_fetch_status`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchStatusReport {
+    pub repos: Vec<db::FetchStatus>,
+    /// Budget sampled after the last fetch cycle (see rate_usage)
+    pub rate_limit: Option<db::RateLimitSample>,
+    /// Below rate_limit_warning_threshold; auto-refresh is stretched
+    pub rate_limit_low: bool,
+}
+
+/// Get per-repo fetch freshness (last successful fetch, last error, staleness)
+/// and the remaining rate-limit budget
+#[tauri::command]
+pub fn get_fetch_status(state: State<'_, AppState>) -> Result<FetchStatusReport, String> {
+    let conn = state.conn();
+    let threshold = db::get_stale_data_threshold_minutes(&conn);
+    let repos = db::get_fetch_status(&conn, Utc::now(), threshold)
+        .map_err(|e| format!("Failed to get fetch status: {}", e))?;
+    let rate_limit = db::get_rate_limit_samples(&conn, 1)
+        .map_err(|e| format!("Failed to get rate limit sample: {}", e))?
+        .pop();
+    Ok(FetchStatusReport {
+        repos,
+        rate_limit,
+        rate_limit_low: crate::rate_usage::is_low(),
+    })
+}
+
+/// Start the background thread that emits `fetch:stale`
+pub fn start_staleness_watcher(app: AppHandle) {
+    thread::spawn(move || {
+        let mut last_stale: Vec<String> = Vec::new();
+        loop {
+            thread::sleep(CHECK_INTERVAL);
+
+            let Some(state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            let (stale, threshold) = {
+                let conn = state.conn();
+                let threshold = db::get_stale_data_threshold_minutes(&conn);
+                let stale: Vec<String> = db::get_fetch_status(&conn, Utc::now(), threshold)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|status| status.stale)
+                    .map(|status| status.repo)
+                    .collect();
+                (stale, threshold)
+            };
+
+            if stale != last_stale {
+                let _ = app.emit(
+                    "fetch:stale",
+                    StalePayload {
+                        repos: stale.clone(),
+                        threshold_minutes: threshold,
+                    },
+                );
+                last_stale = stale;
+            }
+        }
+    });
+}
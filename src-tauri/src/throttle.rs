@@ -0,0 +1,141 @@
+//! Rate-limit aware pacing
+//!
+//! Bulk GitHub work (the batched comment fetch and PR refreshes) consults a
+//! cached GraphQL rate-limit reading before each request. Close to the
+//! `rate_limit_reserve` setting, requests are spaced out so the remaining budget
+//! lasts until the reset; at or below it they are deferred until the reset.
+//! The reading is refreshed when older than a few minutes.
+
+use crate::db::{self, AppState};
+use crate::github::{self, RateLimitStatus};
+use chrono::{DateTime, Utc};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a rate-limit reading is trusted
+const READING_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Longest pause inserted between two requests
+const MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// Last rate-limit reading and when it was taken
+static READING: Mutex<Option<(Instant, RateLimitStatus)>> = Mutex::new(None);
+
+/// What to do before the next request
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Throttle {
+    Proceed,
+    Delay(Duration),
+    /// Budget is down to the reserve; skip until it resets
+    Defer,
+}
+
+/// Remember a reading (e.g. one fetched for `get_rate_limit`)
+pub fn record(status: RateLimitStatus) {
+    if let Ok(mut reading) = READING.lock() {
+        *reading = Some((Instant::now(), status));
+    }
+}
+
+/// The cached reading, refreshed through gh when stale
+fn reading(state: &AppState) -> Option<RateLimitStatus> {
+    let cached = READING.lock().ok().and_then(|reading| reading.clone());
+    if let Some((at, status)) = &cached {
+        if at.elapsed() < READING_TTL {
+            return Some(status.clone());
+        }
+    }
+
+    let fetched = state
+        .github
+        .rate_limit()
+        .and_then(|json| github::parse_rate_limit(&json));
+    match fetched {
+        Ok(status) => {
+            record(status.clone());
+            Some(status)
+        }
+        Err(e) => {
+            eprintln!("Failed to refresh rate limit: {}", e);
+            // Better an old reading than none
+            cached.map(|(_, status)| status)
+        }
+    }
+}
+
+/// Pace the remaining budget above `reserve` evenly until `reset_at`
+fn decide(remaining: i64, reserve: i64, reset_at: DateTime<Utc>, now: DateTime<Utc>) -> Throttle {
+    // The reading predates the reset, so the budget is full again
+    if now >= reset_at {
+        return Throttle::Proceed;
+    }
+    if remaining <= reserve {
+        return Throttle::Defer;
+    }
+    // Plenty left: no pacing needed
+    if remaining > reserve * 3 {
+        return Throttle::Proceed;
+    }
+
+    let until_reset = (reset_at - now).to_std().unwrap_or_default();
+    let delay = until_reset / (remaining - reserve) as u32;
+    if delay.is_zero() {
+        Throttle::Proceed
+    } else {
+        Throttle::Delay(delay.min(MAX_DELAY))
+    }
+}
+
+/// Decide whether the next GraphQL request should go ahead, logging throttling.
+/// Proceeds when no reading is available.
+pub fn check(state: &AppState, what: &str) -> Throttle {
+    let Some(status) = reading(state) else {
+        return Throttle::Proceed;
+    };
+    let reserve = db::get_rate_limit_reserve(&state.conn());
+    let Ok(reset_at) = DateTime::parse_from_rfc3339(&status.graphql.reset_at) else {
+        return Throttle::Proceed;
+    };
+
+    let throttle = decide(
+        status.graphql.remaining,
+        reserve,
+        reset_at.with_timezone(&Utc),
+        Utc::now(),
+    );
+    match throttle {
+        Throttle::Proceed => {}
+        Throttle::Delay(delay) => println!(
+            "Throttling {}: {} GraphQL requests left, waiting {:?}",
+            what, status.graphql.remaining, delay
+        ),
+        Throttle::Defer => println!(
+            "Deferring {}: {} GraphQL requests left (reserve {}), resets at {}",
+            what, status.graphql.remaining, reserve, status.graphql.reset_at
+        ),
+    }
+    throttle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decide() {
+        let now = Utc::now();
+        let reset = now + chrono::Duration::minutes(10);
+
+        assert_eq!(decide(5000, 200, reset, now), Throttle::Proceed);
+        assert_eq!(decide(200, 200, reset, now), Throttle::Defer);
+        assert_eq!(decide(0, 200, reset, now), Throttle::Defer);
+        // 300 requests to spread over 10 minutes: one every 2s
+        assert_eq!(decide(500, 200, reset, now), Throttle::Delay(Duration::from_secs(2)));
+        // Capped, and nothing to wait for once the reset has passed
+        assert_eq!(decide(201, 200, reset, now), Throttle::Delay(MAX_DELAY));
+        assert_eq!(decide(500, 200, now - chrono::Duration::minutes(1), now), Throttle::Proceed);
+        // An exhausted budget stops deferring once its reset time has passed
+        assert_eq!(decide(0, 200, now - chrono::Duration::minutes(1), now), Throttle::Proceed);
+        assert_eq!(decide(0, 200, now, now), Throttle::Proceed);
+    }
+}
@@ -0,0 +1,158 @@
+//! Condensed PR timeline (opened, reviews, force-pushes, labels, ...)
+//!
+//! Uses the REST issue timeline via `gh api`. Results are cached briefly in
+//! memory, keyed on the cached PR's `updated_at`.
+
+use crate::db::AppState;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::State;
+
+/// How long a fetched timeline is reused while the PR is unchanged
+const TIMELINE_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// A normalized timeline entry
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEvent {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub actor: Option<String>,
+    pub at: String,
+    pub summary: String,
+}
+
+struct CachedTimeline {
+    updated_at: Option<String>,
+    fetched: Instant,
+    events: Vec<TimelineEvent>,
+}
+
+static TIMELINE_CACHE: Mutex<Option<HashMap<String, CachedTimeline>>> = Mutex::new(None);
+
+/// Get a condensed timeline of events for a PR
+#[tauri::command]
+pub fn get_pr_timeline(state: State<'_, AppState>, pr_id: String) -> Result<Vec<TimelineEvent>, String> {
+    // Resolve repo/number from the cache, falling back to parsing "owner/repo#123"
+    let cached: Option<(String, i32, String, String, String)> = {
+        let conn = state.conn();
+        conn.query_row(
+            "SELECT repo, number, author, created_at, updated_at FROM pr_cache WHERE id = ?1",
+            [&pr_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .ok()
+    };
+
+    let (repo, number, opened, updated_at) = match cached {
+        Some((repo, number, author, created_at, updated_at)) => {
+            (repo, number, Some((author, created_at)), Some(updated_at))
+        }
+        None => {
+            let (repo, number) = pr_id
+                .rsplit_once('#')
+                .and_then(|(repo, n)| n.parse::<i32>().ok().map(|n| (repo.to_string(), n)))
+                .ok_or_else(|| format!("Invalid PR ID: {}", pr_id))?;
+            (repo, number, None, None)
+        }
+    };
+
+    if let Ok(cache) = TIMELINE_CACHE.lock() {
+        if let Some(entry) = cache.as_ref().and_then(|c| c.get(&pr_id)) {
+            if entry.updated_at == updated_at && entry.fetched.elapsed() < TIMELINE_CACHE_TTL {
+                return Ok(entry.events.clone());
+            }
+        }
+    }
+
+    let pages = state
+        .github
+        .api_pages(&format!("repos/{}/issues/{}/timeline", repo, number))?;
+
+    let mut events: Vec<TimelineEvent> = Vec::new();
+    if let Some((author, created_at)) = opened {
+        events.push(TimelineEvent {
+            event_type: "opened".to_string(),
+            actor: Some(author),
+            at: created_at,
+            summary: "Opened the pull request".to_string(),
+        });
+    }
+
+    events.extend(
+        pages
+            .iter()
+            .filter_map(Value::as_array)
+            .flatten()
+            .filter_map(normalize_event),
+    );
+    events.sort_by(|a, b| a.at.cmp(&b.at));
+
+    if let Ok(mut cache) = TIMELINE_CACHE.lock() {
+        cache.get_or_insert_with(HashMap::new).insert(
+            pr_id,
+            CachedTimeline {
+                updated_at,
+                fetched: Instant::now(),
+                events: events.clone(),
+            },
+        );
+    }
+
+    Ok(events)
+}
+
+/// Map a raw timeline event to a TimelineEvent, ignoring uncommon types
+fn normalize_event(event: &Value) -> Option<TimelineEvent> {
+    let str_at = |path: &[&str]| -> Option<String> {
+        let mut value = event;
+        for key in path {
+            value = value.get(key)?;
+        }
+        value.as_str().map(|s| s.to_string())
+    };
+
+    let event_type = str_at(&["event"])?;
+    let actor = str_at(&["actor", "login"]).or_else(|| str_at(&["user", "login"]));
+    let created_at = str_at(&["created_at"]);
+
+    let (at, summary) = match event_type.as_str() {
+        "reviewed" => {
+            let review_state = str_at(&["state"]).unwrap_or_default().to_lowercase();
+            let summary = match review_state.as_str() {
+                "approved" => "Approved".to_string(),
+                "changes_requested" => "Requested changes".to_string(),
+                "commented" => "Reviewed with comments".to_string(),
+                "dismissed" => "Review dismissed".to_string(),
+                other => format!("Reviewed ({})", other),
+            };
+            (str_at(&["submitted_at"])?, summary)
+        }
+        "commented" => (created_at?, "Commented".to_string()),
+        "head_ref_force_pushed" => (created_at?, "Force-pushed the branch".to_string()),
+        "labeled" => (created_at?, format!("Added label {}", str_at(&["label", "name"])?)),
+        "unlabeled" => (created_at?, format!("Removed label {}", str_at(&["label", "name"])?)),
+        "review_requested" => {
+            let reviewer = str_at(&["requested_reviewer", "login"])
+                .or_else(|| str_at(&["requested_team", "name"]))
+                .unwrap_or_else(|| "unknown".to_string());
+            (created_at?, format!("Requested review from {}", reviewer))
+        }
+        "ready_for_review" => (created_at?, "Marked ready for review".to_string()),
+        "convert_to_draft" => (created_at?, "Converted to draft".to_string()),
+        "merged" => (created_at?, "Merged".to_string()),
+        "closed" => (created_at?, "Closed".to_string()),
+        "reopened" => (created_at?, "Reopened".to_string()),
+        _ => return None,
+    };
+
+    Some(TimelineEvent {
+        event_type,
+        actor,
+        at,
+        summary,
+    })
+}
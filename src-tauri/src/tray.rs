@@ -1,14 +1,30 @@
+use crate::db::{self, AppState};
+use crate::monitor;
 use tauri::{
-    menu::{Menu, MenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     AppHandle, Manager, Runtime,
 };
 
+const TRAY_ID: &str = "main";
+
+/// The tray's "Pause All Monitors" checkbox, kept so it can follow the setting
+struct PauseMenuItem<R: Runtime>(CheckMenuItem<R>);
+
 pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    let paused = app
+        .try_state::<AppState>()
+        .map(|state| db::get_monitors_paused(&state.conn()))
+        .unwrap_or(false);
+
     let show_item = MenuItem::with_id(app, "show", "Show Clanker Spanker", true, None::<&str>)?;
+    let pause_item =
+        CheckMenuItem::with_id(app, "pause_all", "Pause All Monitors", true, paused, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
     let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
 
-    let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+    let menu = Menu::with_items(app, &[&show_item, &pause_item, &separator, &quit_item])?;
+    app.manage(PauseMenuItem(pause_item));
 
     // Use app's default icon
     let icon = app
@@ -16,7 +32,7 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
         .cloned()
         .ok_or_else(|| tauri::Error::AssetNotFound("icon".into()))?;
 
-    let _tray = TrayIconBuilder::new()
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
         .icon(icon)
         .tooltip("Clanker Spanker")
         .menu(&menu)
@@ -28,6 +44,14 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
                     let _ = window.set_focus();
                 }
             }
+            "pause_all" => {
+                if let Some(state) = app.try_state::<AppState>() {
+                    let paused = !db::get_monitors_paused(&state.conn());
+                    if let Err(e) = monitor::apply_monitors_paused(app, &state, paused) {
+                        eprintln!("Failed to toggle pause: {}", e);
+                    }
+                }
+            }
             "quit" => {
                 app.exit(0);
             }
@@ -60,3 +84,18 @@ pub fn update_tray_status(_active_count: i32) {
     // This requires storing the tray handle with proper generic parameters
     // which is complex due to Rust's static lifetime requirements
 }
+
+/// Sync the tray's pause checkbox with the pause-all switch
+pub fn set_pause_checked<R: Runtime>(app: &AppHandle<R>, paused: bool) {
+    if let Some(item) = app.try_state::<PauseMenuItem<R>>() {
+        let _ = item.0.set_checked(paused);
+    }
+}
+
+/// Show the unread PR count next to the tray icon (macOS only; ignored elsewhere)
+pub fn update_tray_unread<R: Runtime>(app: &AppHandle<R>, unread: i32) {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let title = (unread > 0).then(|| unread.to_string());
+        let _ = tray.set_title(title);
+    }
+}
@@ -0,0 +1,149 @@
+//! Pre-flight check that a repo's configured local clone is usable for a PR
+//!
+//! Confirms the clone points at the right GitHub repo and that the PR's head
+//! branch can be fetched, so monitors don't run in the wrong directory.
+
+use crate::branch_update::run_git;
+use crate::db::AppState;
+use crate::process;
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::State;
+
+/// Outcome of one verification step
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneCheck {
+    /// "working_dir" | "remote" | "fetch" | "branch"
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// Result of `verify_clone`; `ok` is true only when every check passed
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyCloneResult {
+    pub ok: bool,
+    pub working_dir: Option<String>,
+    pub checks: Vec<CloneCheck>,
+}
+
+impl VerifyCloneResult {
+    fn push(&mut self, name: &str, result: Result<String, String>) -> bool {
+        let passed = result.is_ok();
+        self.checks.push(CloneCheck {
+            name: name.to_string(),
+            passed,
+            detail: result.unwrap_or_else(|e| e),
+        });
+        passed
+    }
+}
+
+/// Verify the local clone configured for `repo` is on the right remote and
+/// can resolve the PR's head branch. Later checks are skipped once one fails.
+#[tauri::command]
+pub fn verify_clone(
+    state: State<'_, AppState>,
+    repo: String,
+    pr_id: String,
+) -> Result<VerifyCloneResult, String> {
+    let (working_dir, branch) = {
+        let conn = state.conn();
+        let branch: String = conn
+            .query_row("SELECT branch FROM pr_cache WHERE id = ?1", [&pr_id], |row| {
+                row.get(0)
+            })
+            .map_err(|e| format!("PR not found in cache: {} ({})", pr_id, e))?;
+        let working_dir: Result<PathBuf, String> = process::configured_working_dir(&conn, &repo)
+            .and_then(|dir| dir.ok_or_else(|| format!("No working_dir configured for {}", repo)));
+        (working_dir, branch)
+    };
+
+    let mut result = VerifyCloneResult {
+        ok: false,
+        working_dir: working_dir.as_ref().ok().map(|d| d.display().to_string()),
+        checks: Vec::new(),
+    };
+
+    let dir = match working_dir {
+        Ok(dir) => dir,
+        Err(e) => {
+            result.push("working_dir", Err(e));
+            return Ok(result);
+        }
+    };
+    result.push("working_dir", Ok(format!("{} is a git repository", dir.display())));
+
+    let remote = run_git(&dir, &["remote", "get-url", "origin"]).and_then(|url| {
+        match repo_from_remote_url(&url) {
+            Some(found) if found.eq_ignore_ascii_case(&repo) => Ok(format!("origin is {}", url)),
+            Some(found) => Err(format!("origin points at {}, expected {}", found, repo)),
+            None => Err(format!("origin is not a GitHub remote: {}", url)),
+        }
+    });
+    if !result.push("remote", remote) {
+        return Ok(result);
+    }
+
+    let tracking_ref = format!("refs/remotes/origin/{}", branch);
+    let fetch = run_git(
+        &dir,
+        &["fetch", "origin", &format!("+refs/heads/{}:{}", branch, tracking_ref)],
+    )
+    .map(|_| format!("Fetched {}", branch));
+    if !result.push("fetch", fetch) {
+        return Ok(result);
+    }
+
+    let resolved = run_git(
+        &dir,
+        &["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", tracking_ref)],
+    )
+    .map(|sha| format!("origin/{} is at {}", branch, sha))
+    .map_err(|_| format!("origin/{} could not be resolved", branch));
+    result.ok = result.push("branch", resolved);
+
+    Ok(result)
+}
+
+/// Extract "owner/repo" from a GitHub remote URL (https, ssh, or scp-style)
+pub(crate) fn repo_from_remote_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    let path = url
+        .strip_prefix("git@github.com:")
+        .or_else(|| url.strip_prefix("ssh://git@github.com/"))
+        .or_else(|| url.strip_prefix("https://github.com/"))
+        .or_else(|| url.strip_prefix("http://github.com/"))
+        .or_else(|| url.strip_prefix("git://github.com/"))?;
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+
+    let mut parts = path.split('/');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(owner), Some(name), None) if !owner.is_empty() && !name.is_empty() => {
+            Some(format!("{}/{}", owner, name))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_from_remote_url() {
+        for url in [
+            "git@github.com:octo/widgets.git",
+            "ssh://git@github.com/octo/widgets.git",
+            "https://github.com/octo/widgets",
+            "https://github.com/octo/widgets.git\n",
+        ] {
+            assert_eq!(repo_from_remote_url(url), Some("octo/widgets".to_string()));
+        }
+        assert_eq!(repo_from_remote_url("https://gitlab.com/octo/widgets"), None);
+        assert_eq!(repo_from_remote_url("https://github.com/octo"), None);
+    }
+}
@@ -0,0 +1,197 @@
+//! System wake handling
+//!
+//! After the machine wakes, the PR board is stale and monitor sleeps have
+//! drifted (the monitor script's `sleep` does not count time spent asleep).
+//! On wake we nudge running monitors to check now, re-evaluate sleep
+//! prevention, and (if enabled) refresh PRs for all configured repos.
+//!
+//! macOS: NSWorkspaceDidWakeNotification. Linux: login1 PrepareForSleep(false)
+//! observed via `gdbus monitor`.
+
+use crate::connectivity;
+use crate::db::{self, AppState};
+use crate::monitor;
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Wake events within this window are treated as one
+const WAKE_DEBOUNCE: Duration = Duration::from_secs(60);
+
+static WAKE_APP: OnceLock<AppHandle> = OnceLock::new();
+static LAST_WAKE: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Start listening for system wake notifications
+pub fn start_wake_listener(app: AppHandle) {
+    if WAKE_APP.set(app).is_err() {
+        return; // Already listening
+    }
+    subscribe();
+}
+
+/// Stop listening (on exit), ending any helper process the listener started
+pub fn stop_wake_listener() {
+    unsubscribe();
+}
+
+/// Called from the platform observers whenever the system wakes
+fn on_wake() {
+    let Some(app) = WAKE_APP.get() else {
+        return;
+    };
+
+    if let Ok(mut last) = LAST_WAKE.lock() {
+        let now = Instant::now();
+        if last.is_some_and(|t| now.duration_since(t) < WAKE_DEBOUNCE) {
+            return;
+        }
+        *last = Some(now);
+    }
+
+    let app = app.clone();
+    thread::spawn(move || handle_wake(&app));
+}
+
+fn handle_wake(app: &AppHandle) {
+    println!("System woke from sleep");
+
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+
+    // Cut running monitors' sleeps short so they check right away
+    let signalled = state.processes.signal_check_now();
+    let refresh_enabled = {
+        let conn = state.conn();
+        let now = chrono::Utc::now().to_rfc3339();
+        for id in &signalled {
+            let _ = conn.execute(
+                "UPDATE monitors SET next_check_at = ?1 WHERE id = ?2 AND status IN ('running', 'sleeping')",
+                rusqlite::params![now, id],
+            );
+        }
+        db::get_refresh_on_wake(&conn)
+    };
+
+    // Re-evaluate tray/dock/sleep prevention
+    monitor::emit_state_change(app, &state);
+
+    // The network often lags behind wake; if it isn't back yet, the
+    // connectivity watcher refreshes once it is
+    if refresh_enabled && connectivity::check_now().0 {
+        connectivity::refresh_configured_repos(app);
+    }
+
+    let _ = app.emit("system:wake", refresh_enabled);
+}
+
+#[cfg(target_os = "macos")]
+fn subscribe() {
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::NSString;
+    use dispatch::Queue;
+    use objc::declare::ClassDecl;
+    use objc::runtime::{Object, Sel};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    extern "C" fn did_wake(_this: &Object, _cmd: Sel, _notification: id) {
+        on_wake();
+    }
+
+    // NSWorkspace notifications must be registered on the main thread
+    Queue::main().exec_async(|| unsafe {
+        let Some(mut decl) = ClassDecl::new("ClankerWakeObserver", class!(NSObject)) else {
+            return;
+        };
+        decl.add_method(
+            sel!(didWake:),
+            did_wake as extern "C" fn(&Object, Sel, id),
+        );
+        let observer_class = decl.register();
+
+        let observer: id = msg_send![observer_class, new];
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let center: id = msg_send![workspace, notificationCenter];
+        let name = NSString::alloc(nil).init_str("NSWorkspaceDidWakeNotification");
+        let _: () = msg_send![center, addObserver: observer selector: sel!(didWake:) name: name object: nil];
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unsubscribe() {
+    // The observers are torn down with the process
+}
+
+/// The `gdbus monitor` process the Linux listener reads from
+#[cfg(target_os = "linux")]
+static GDBUS: Mutex<Option<std::process::Child>> = Mutex::new(None);
+
+/// Set by `unsubscribe`, so a gdbus spawned during shutdown is killed too
+#[cfg(target_os = "linux")]
+static GDBUS_STOPPED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+#[cfg(target_os = "linux")]
+fn subscribe() {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+
+    thread::spawn(|| {
+        let child = Command::new("gdbus")
+            .args([
+                "monitor",
+                "--system",
+                "--dest",
+                "org.freedesktop.login1",
+                "--object-path",
+                "/org/freedesktop/login1",
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn();
+
+        let mut child = match child {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("Wake detection unavailable (gdbus): {}", e);
+                return;
+            }
+        };
+
+        let stdout = child.stdout.take();
+        if let Ok(mut gdbus) = GDBUS.lock() {
+            *gdbus = Some(child);
+        }
+        if GDBUS_STOPPED.load(std::sync::atomic::Ordering::SeqCst) {
+            unsubscribe();
+            return;
+        }
+
+        if let Some(stdout) = stdout {
+            // PrepareForSleep(true) fires before sleep, (false) after wake
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if line.contains("PrepareForSleep") && line.contains("false") {
+                    on_wake();
+                }
+            }
+        }
+        // gdbus exited on its own (or was killed by unsubscribe, which reaps it)
+        if let Some(mut child) = GDBUS.lock().ok().and_then(|mut gdbus| gdbus.take()) {
+            let _ = child.wait();
+        }
+    });
+}
+
+#[cfg(target_os = "linux")]
+fn unsubscribe() {
+    GDBUS_STOPPED.store(true, std::sync::atomic::Ordering::SeqCst);
+    if let Some(mut child) = GDBUS.lock().ok().and_then(|mut gdbus| gdbus.take()) {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn subscribe() {
+    // Wake detection not implemented on this platform
+}
@@ -0,0 +1,407 @@
+//! End-to-end tests of the subprocess paths, run against scripted stand-ins
+//! for `gh` and `monitor-pr-loop.sh` so they need neither network nor a real gh.
+#![cfg(unix)]
+
+use clanker_spanker_lib::db::{self, AppState};
+use clanker_spanker_lib::github::{self, GhCli, GithubClient};
+use clanker_spanker_lib::process;
+use std::ops::Deref;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+/// Scratch directory for one test, removed when dropped
+struct ScratchDir(PathBuf);
+
+impl Deref for ScratchDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Fresh scratch directory for one test
+fn scratch_dir() -> ScratchDir {
+    let dir = std::env::temp_dir().join(format!("clanker-test-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    ScratchDir(dir)
+}
+
+/// Write an executable shell script stand-in for a tool
+fn fake_tool(dir: &Path, name: &str, body: &str) -> PathBuf {
+    let path = dir.join(name);
+    std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+const PR_LIST_FIXTURE: &str = r#"[{
+    "number": 7,
+    "title": "Fix the thing",
+    "url": "https://github.com/o/r/pull/7",
+    "state": "OPEN",
+    "isDraft": false,
+    "author": {"login": "alice"},
+    "headRefName": "fix-thing",
+    "headRefOid": "abc123",
+    "baseRefName": "main",
+    "labels": [{"name": "bug"}],
+    "reviewRequests": [],
+    "reviewDecision": "APPROVED",
+    "statusCheckRollup": [],
+    "mergeable": "MERGEABLE",
+    "createdAt": "2024-01-01T00:00:00Z",
+    "updatedAt": "2024-01-02T00:00:00Z"
+}]"#;
+
+#[test]
+fn fetch_parses_gh_output() {
+    let dir = scratch_dir();
+    let gh = fake_tool(
+        &dir,
+        "gh",
+        &format!(
+            "[ \"$1 $2\" = \"pr list\" ] || {{ echo \"unexpected: $*\" >&2; exit 2; }}\ncat <<'JSON'\n{}\nJSON",
+            PR_LIST_FIXTURE
+        ),
+    );
+
+    let prs = GhCli::with_program(gh).list_prs("o/r", &None).unwrap();
+    assert_eq!(prs.len(), 1);
+    assert_eq!(prs[0].number, 7);
+    assert_eq!(prs[0].head_ref_oid.as_deref(), Some("abc123"));
+    assert_eq!(prs[0].labels[0].name, "bug");
+}
+
+#[test]
+fn fetch_reports_malformed_json() {
+    let dir = scratch_dir();
+    let gh = fake_tool(&dir, "gh", "echo '[{\"number\": 7,'");
+
+    let err = GhCli::with_program(gh).list_prs("o/r", &None).unwrap_err();
+    assert!(err.contains("Failed to parse JSON"), "{}", err);
+}
+
+#[test]
+fn fetch_surfaces_gh_stderr() {
+    let dir = scratch_dir();
+    let gh = fake_tool(&dir, "gh", "echo 'HTTP 401: Bad credentials' >&2\nexit 1");
+
+    let err = GhCli::with_program(gh).list_prs("o/r", &None).unwrap_err();
+    assert!(err.contains("gh CLI error for o/r"), "{}", err);
+    assert!(err.contains("Bad credentials"), "{}", err);
+}
+
+#[test]
+fn fetch_times_out_when_gh_hangs() {
+    let dir = scratch_dir();
+    let gh = fake_tool(&dir, "gh", "sleep 30");
+
+    let started = Instant::now();
+    let err = GhCli::with_program(gh)
+        .timeout(Duration::from_millis(300))
+        .list_prs("o/r", &None)
+        .unwrap_err();
+    assert!(err.contains("timed out"), "{}", err);
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn gh_path_setting_overrides_gh_binary() {
+    let dir = scratch_dir();
+    let gh = fake_tool(&dir, "gh", "echo fake/repo");
+
+    let state = AppState::new(dir.join("test.db")).unwrap();
+    let conn = state.conn();
+    db::init_schema(&conn).unwrap();
+    db::set_setting(&conn, db::GH_PATH_SETTING_KEY, gh.to_str().unwrap()).unwrap();
+    let override_guard = github::override_gh_path(db::get_gh_path(&conn).unwrap());
+
+    assert_eq!(GhCli::default().current_repo().as_deref(), Some("fake/repo"));
+    drop(override_guard);
+    assert_eq!(github::gh_program(), PathBuf::from("gh"));
+}
+
+/// A mock-runtime app whose monitors run `script` instead of monitor-pr-loop.sh,
+/// with monitor "m1" for o/r#1 registered as running
+fn monitor_app(dir: &Path, script: &str) -> tauri::App<tauri::test::MockRuntime> {
+    let script = fake_tool(dir, "monitor-pr-loop.sh", script);
+    let state = AppState::new(dir.join("test.db")).unwrap();
+    {
+        let conn = state.conn();
+        db::init_schema(&conn).unwrap();
+        db::set_setting(&conn, db::MONITOR_SCRIPT_PATH_SETTING_KEY, script.to_str().unwrap()).unwrap();
+        conn.execute(
+            "INSERT INTO monitors (id, pr_id, pr_number, repo, status, iteration, max_iterations, interval_minutes, started_at, log_file)
+             VALUES ('m1', 'o/r#1', 1, 'o/r', 'running', 0, 3, 15, datetime('now'), ?1)",
+            [dir.join("m1.log").to_str().unwrap()],
+        )
+        .unwrap();
+    }
+
+    let app = tauri::test::mock_app();
+    app.manage(state);
+    app
+}
+
+fn spawn(app: &tauri::App<tauri::test::MockRuntime>) {
+    app.state::<AppState>()
+        .processes
+        .spawn_monitor(app.handle(), "m1", "o/r#1", 1, "o/r", 3, 15, "claude", None, "stash", "false", "auto_fix", None)
+        .unwrap();
+}
+
+/// (status, exit_reason, iteration, next_check_at) of monitor m1
+fn monitor_row(app: &tauri::App<tauri::test::MockRuntime>) -> (String, Option<String>, i32, Option<String>) {
+    let state = app.state::<AppState>();
+    let conn = state.conn();
+    conn.query_row(
+        "SELECT status, exit_reason, iteration, next_check_at FROM monitors WHERE id = 'm1'",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    )
+    .unwrap()
+}
+
+/// Poll monitor m1 until `done` holds for its row, or fail after a few seconds
+fn wait_for_monitor(
+    app: &tauri::App<tauri::test::MockRuntime>,
+    done: impl Fn(&(String, Option<String>, i32, Option<String>)) -> bool,
+) -> (String, Option<String>, i32, Option<String>) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        let row = monitor_row(app);
+        if done(&row) {
+            return row;
+        }
+        assert!(Instant::now() < deadline, "monitor never reached expected state: {:?}", row);
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[test]
+fn monitor_markers_update_progress_and_clean_exit_completes() {
+    let dir = scratch_dir();
+    let app = monitor_app(
+        &dir,
+        "echo '@@ITERATION:1/3@@'\necho '@@HEAD:aaa111@@'\necho '@@SLEEPING:5@@'\necho '@@PUSHED:bbb222@@'\necho '@@ITERATION:2/3@@'\necho '@@HEAD:bbb222@@'\necho 'oops' >&2\necho '@@STATUS:clean@@'",
+    );
+    spawn(&app);
+
+    let (status, exit_reason, iteration, next_check_at) = wait_for_monitor(&app, |row| row.0 != "running");
+    assert_eq!(status, "completed");
+    assert_eq!(exit_reason.as_deref(), Some("pr_clean"));
+    assert_eq!(iteration, 2);
+    assert!(next_check_at.is_some());
+
+    let state = app.state::<AppState>();
+    let conn = state.conn();
+    let head: Option<String> = conn
+        .query_row("SELECT head_sha FROM monitors WHERE id = 'm1'", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(head.as_deref(), Some("bbb222"));
+    let logged: Vec<(i32, String)> = conn
+        .prepare(
+            "SELECT iteration, head_sha FROM monitor_logs
+             WHERE monitor_id = 'm1' AND action = 'check_started' ORDER BY id",
+        )
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(logged, [(1, "aaa111".to_string()), (2, "bbb222".to_string())]);
+
+    // Output is mirrored to the log file; stderr is read on its own thread, so allow it a moment
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let log = loop {
+        let log = std::fs::read_to_string(dir.join("m1.log")).unwrap_or_default();
+        if log.contains("[stderr] oops") || Instant::now() > deadline {
+            break log;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    assert!(log.contains("@@ITERATION:1/3@@\n"));
+    assert!(log.contains("@@STATUS:clean@@\n"));
+    assert!(log.contains("[stderr] oops\n"));
+}
+
+#[test]
+fn monitor_gets_the_configured_clone_as_its_argument() {
+    let dir = scratch_dir();
+    let clone = dir.join("my clone [1]");
+    std::fs::create_dir_all(&clone).unwrap();
+    let app = monitor_app(&dir, "echo \"arg:$5\"\necho \"pwd:$(pwd)\"\necho '@@STATUS:clean@@'");
+    app.state::<AppState>()
+        .processes
+        .spawn_monitor(app.handle(), "m1", "o/r#1", 1, "o/r", 3, 15, "claude", None, "stash", "false", "auto_fix", Some(&clone))
+        .unwrap();
+
+    let (status, ..) = wait_for_monitor(&app, |row| row.0 != "running");
+    assert_eq!(status, "completed");
+    // The path reaches the script whole, spaces and glob characters included
+    let log = std::fs::read_to_string(dir.join("m1.log")).unwrap();
+    assert!(log.contains(&format!("arg:{}\n", clone.display())), "{}", log);
+    let pwd = log.lines().find_map(|line| line.strip_prefix("pwd:")).unwrap();
+    assert_eq!(Path::new(pwd).canonicalize().unwrap(), clone.canonicalize().unwrap());
+}
+
+#[test]
+fn monitor_crash_mid_run_is_marked_failed() {
+    let dir = scratch_dir();
+    let app = monitor_app(&dir, "echo '@@ITERATION:1/3@@'\nexit 3");
+    spawn(&app);
+
+    let (status, exit_reason, iteration, _) = wait_for_monitor(&app, |row| row.0 != "running");
+    assert_eq!(status, "failed");
+    assert_eq!(exit_reason.as_deref(), Some("process_exited"));
+    assert_eq!(iteration, 1);
+}
+
+#[test]
+fn kill_stops_the_whole_process_group() {
+    let dir = scratch_dir();
+    // The background sleep shares stdout, so the monitor only finishes once it's gone too
+    let app = monitor_app(&dir, "echo '@@ITERATION:1/3@@'\nsleep 300 &\nwait");
+    spawn(&app);
+    wait_for_monitor(&app, |row| row.2 == 1);
+
+    let state = app.state::<AppState>();
+    assert!(state.processes.is_running("m1"));
+    state.processes.kill("m1").unwrap();
+    assert!(!state.processes.is_running("m1"));
+
+    let (status, ..) = wait_for_monitor(&app, |row| row.0 != "running");
+    assert_eq!(status, "failed");
+}
+
+#[test]
+fn stopped_group_makes_no_progress_until_continued() {
+    let dir = scratch_dir();
+    let app = monitor_app(&dir, "echo '@@ITERATION:1/3@@'\nsleep 0.3\necho '@@ITERATION:2/3@@'");
+    spawn(&app);
+    wait_for_monitor(&app, |row| row.2 == 1);
+
+    let state = app.state::<AppState>();
+    state.processes.set_group_stopped("m1", true).unwrap();
+    std::thread::sleep(Duration::from_millis(800));
+    assert_eq!(monitor_row(&app).2, 1);
+    assert!(state.processes.is_running("m1"));
+
+    state.processes.set_group_stopped("m1", false).unwrap();
+    let (_, _, iteration, _) = wait_for_monitor(&app, |row| row.0 != "running");
+    assert_eq!(iteration, 2);
+}
+
+#[test]
+fn external_push_pauses_the_monitor() {
+    let dir = scratch_dir();
+    // bbb222 shows up without the script having reported pushing it
+    let app = monitor_app(
+        &dir,
+        "echo '@@ITERATION:1/3@@'\necho '@@HEAD:aaa111@@'\necho '@@HEAD:bbb222@@'\nsleep 0.3\necho '@@ITERATION:2/3@@'",
+    );
+    spawn(&app);
+
+    let state = app.state::<AppState>();
+    let paused = || -> bool {
+        state
+            .conn()
+            .query_row("SELECT paused FROM monitors WHERE id = 'm1'", [], |row| row.get(0))
+            .unwrap()
+    };
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !paused() {
+        assert!(Instant::now() < deadline, "monitor was never paused");
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    std::thread::sleep(Duration::from_millis(800));
+    assert_eq!(monitor_row(&app).2, 1);
+    let event: String = state
+        .conn()
+        .query_row(
+            "SELECT message FROM monitor_logs WHERE monitor_id = 'm1' AND action = 'external_push'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(event, "External push: aaa111 -> bbb222");
+
+    state.processes.set_group_stopped("m1", false).unwrap();
+    let (_, _, iteration, _) = wait_for_monitor(&app, |row| row.0 != "running");
+    assert_eq!(iteration, 2);
+}
+
+#[test]
+fn leftovers_of_an_exited_monitor_are_orphans() {
+    let dir = scratch_dir();
+    let app = monitor_app(&dir, "sleep 300 &\necho '@@ITERATION:1/3@@'");
+    spawn(&app);
+    wait_for_monitor(&app, |row| row.2 == 1);
+
+    let state = app.state::<AppState>();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while state.processes.is_running("m1") {
+        assert!(Instant::now() < deadline, "monitor script never exited");
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    let orphans: Vec<_> = state
+        .processes
+        .list_app_processes(&["m1".to_string()])
+        .unwrap()
+        .into_iter()
+        .filter(|p| p.orphaned)
+        .collect();
+    assert_eq!(orphans.len(), 1, "{:?}", orphans);
+    assert_eq!(orphans[0].monitor_id.as_deref(), Some("m1"));
+    assert!(orphans[0].command.contains("sleep"), "{:?}", orphans[0]);
+
+    // Killing the leftover closes the monitor's stdout, so it finishes
+    process::terminate_processes(&orphans);
+    let (status, ..) = wait_for_monitor(&app, |row| row.0 != "running");
+    assert_eq!(status, "failed");
+}
+
+#[test]
+fn monitor_stdin_takes_auto_answers_and_manual_input() {
+    let dir = scratch_dir();
+    let app = monitor_app(
+        &dir,
+        "echo 'Proceed? [y/N]'\nread answer\necho \"got:$answer\"\nread manual\necho \"got:$manual\"\necho '@@STATUS:clean@@'",
+    );
+    db::set_setting(
+        &app.state::<AppState>().conn(),
+        db::MONITOR_AUTO_ANSWERS_SETTING_KEY,
+        r#"[{"pattern": "Proceed?", "response": "y"}]"#,
+    )
+    .unwrap();
+    spawn(&app);
+
+    let log_has = |text: &str| {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if std::fs::read_to_string(dir.join("m1.log")).unwrap_or_default().contains(text) {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        false
+    };
+    assert!(log_has("got:y\n"));
+    let processes = &app.state::<AppState>().processes;
+    processes.send_input("m1", "typed").unwrap();
+    assert!(log_has("got:typed\n"));
+
+    let (status, ..) = wait_for_monitor(&app, |row| row.0 != "running");
+    assert_eq!(status, "completed");
+    assert!(processes.send_input("m1", "late").is_err());
+}
@@ -20,6 +20,7 @@ import { useDismissedPRs } from "@/hooks/useDismissedPRs";
 import { usePROrder } from "@/hooks/usePROrder";
 import { Button } from "@/components/ui/button";
 import { filterPRs, collectLabels, collectAuthors } from "@/lib/filters";
+import { clearPRCiOverride } from "@/lib/tauri";
 import type { PR } from "@/lib/types";
 
 function App() {
@@ -77,8 +78,12 @@ function App() {
   const handleStartMonitor = useCallback(
     async (pr: PR) => {
       try {
-        await startMonitor(pr);
-        showToast(`Monitoring PR #${pr.number}`, "info");
+        const monitor = await startMonitor(pr);
+        if (monitor.warning) {
+          showToast(`Monitoring PR #${pr.number}: ${monitor.warning}`, "warning");
+        } else {
+          showToast(`Monitoring PR #${pr.number}`, "info");
+        }
       } catch (err) {
         console.error("Failed to start monitor:", err);
         showToast("Failed to start monitor", "error");
@@ -108,6 +113,20 @@ function App() {
     [dismiss, showToast]
   );
 
+  const handleClearCiOverride = useCallback(
+    async (pr: PR) => {
+      try {
+        await clearPRCiOverride(pr.id);
+        await refresh();
+        showToast(`Cleared CI override on PR #${pr.number}`, "info");
+      } catch (err) {
+        console.error("Failed to clear CI override:", err);
+        showToast("Failed to clear CI override", "error");
+      }
+    },
+    [refresh, showToast]
+  );
+
   const handleExpand = useCallback((pr: PR) => {
     setExpandedPRId(pr.id);
   }, []);
@@ -342,6 +361,7 @@ function App() {
             onOpenInGitHub={(pr) => openUrl(pr.url)}
             onExpand={handleExpand}
             onDismiss={handleDismiss}
+            onClearCiOverride={handleClearCiOverride}
             completedMonitors={completedMonitors}
           />
         )}
@@ -25,6 +25,7 @@ interface CardGridProps {
   onOpenInGitHub?: (pr: PR) => void;
   onExpand?: (pr: PR) => void;
   onDismiss?: (pr: PR) => void;
+  onClearCiOverride?: (pr: PR) => void;
   completedMonitors?: Record<string, { monitorId: string; prNumber: number; iteration: number; maxIterations: number; exitReason: string }>;
 }
 
@@ -54,6 +55,7 @@ export function CardGrid({
   onOpenInGitHub,
   onExpand,
   onDismiss,
+  onClearCiOverride,
   completedMonitors = {},
 }: CardGridProps) {
   // Configure sensors with activation constraints to allow clicking
@@ -123,6 +125,7 @@ export function CardGrid({
                   onOpenInGitHub={onOpenInGitHub}
                   onExpand={onExpand}
                   onDismiss={onDismiss}
+                  onClearCiOverride={onClearCiOverride}
                   hasCompletedMonitor={!!completedMonitors[pr.id]}
                   completedMonitorData={completedMonitors[pr.id]}
                 />
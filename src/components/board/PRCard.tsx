@@ -17,6 +17,7 @@ interface PRCardProps {
   onOpenInGitHub?: (pr: PR) => void;
   onExpand?: (pr: PR) => void;
   onDismiss?: (pr: PR) => void;
+  onClearCiOverride?: (pr: PR) => void;
 }
 
 export function PRCard({
@@ -32,6 +33,7 @@ export function PRCard({
   onOpenInGitHub,
   onExpand,
   onDismiss,
+  onClearCiOverride,
 }: PRCardProps) {
   const isMonitoring = pr.category === "monitoring" && monitor;
 
@@ -142,6 +144,20 @@ export function PRCard({
             ) : (
               <>
                 <StatusBadge status={pr.ciStatus} type="ci" />
+                {pr.ciOverrideUntil && (
+                  <button
+                    type="button"
+                    className="flex items-center gap-1 text-amber-400 bg-amber-500/15 px-2 py-0.5 rounded font-medium text-xs"
+                    title={`CI status set by hand until ${new Date(pr.ciOverrideUntil).toLocaleTimeString()}. Click to clear.`}
+                    onClick={(e) => {
+                      e.stopPropagation();
+                      onClearCiOverride?.(pr);
+                    }}
+                  >
+                    Override
+                    <X className="h-3 w-3" />
+                  </button>
+                )}
                 <StatusBadge status={pr.reviewStatus} type="review" />
               </>
             )}
@@ -312,6 +328,7 @@ function StatusBadge({
       passing: { bg: "bg-emerald-500/15", text: "text-emerald-400", label: "CI Passing" },
       failing: { bg: "bg-red-500/15", text: "text-red-400", label: "CI Failing" },
       pending: { bg: "bg-yellow-500/15", text: "text-yellow-400", label: "CI Pending", pulse: true },
+      action_required: { bg: "bg-orange-500/15", text: "text-orange-400", label: "CI Needs Approval" },
     },
     review: {
       approved: { bg: "bg-emerald-500/15", text: "text-emerald-400", label: "Approved" },
@@ -14,6 +14,7 @@ interface SortablePRCardProps {
   onOpenInGitHub?: (pr: PR) => void;
   onExpand?: (pr: PR) => void;
   onDismiss?: (pr: PR) => void;
+  onClearCiOverride?: (pr: PR) => void;
   hasCompletedMonitor?: boolean;
   completedMonitorData?: { iteration: number; maxIterations: number; exitReason: string };
 }
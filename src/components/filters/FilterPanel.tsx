@@ -1,5 +1,6 @@
 import { X, Search, Filter } from "lucide-react";
 import type { PRFilters } from "@/lib/filters";
+import { repoShortName } from "@/lib/utils";
 
 interface FilterPanelProps {
   filters: PRFilters;
@@ -120,6 +121,7 @@ export function FilterPanel({
               <option value="passing">Passing</option>
               <option value="failing">Failing</option>
               <option value="pending">Pending</option>
+              <option value="action_required">Action Required</option>
             </select>
           </div>
           <div className="flex items-end pb-1">
@@ -197,7 +199,7 @@ export function FilterPanel({
                     className="h-3.5 w-3.5 rounded border-[#262626] bg-[#1a1a1a] text-[#8b5cf6]"
                   />
                   <span className="text-sm text-[#a1a1a1]">
-                    {repo.split("/")[1] || repo}
+                    {repoShortName(repo, availableRepos)}
                   </span>
                 </label>
               ))}
@@ -1,7 +1,7 @@
 import { useState, useEffect, useCallback } from "react";
 import { listen } from "@tauri-apps/api/event";
 import { fetchPRs } from "@/lib/tauri";
-import type { PR } from "@/lib/types";
+import type { PR, PRUpdate } from "@/lib/types";
 
 interface UsePRsOptions {
   repo?: string;
@@ -10,6 +10,9 @@ interface UsePRsOptions {
   refreshInterval?: number;
 }
 
+/** Auto-refresh slows down this much while the gh rate limit is low */
+const LOW_RATE_LIMIT_INTERVAL_FACTOR = 4;
+
 interface RefreshOptions {
   forceRefresh?: boolean;
   showVisualFeedback?: boolean; // Only true for user-initiated refreshes
@@ -21,6 +24,8 @@ interface UsePRsReturn {
   error: string | null;
   refresh: (options?: boolean | RefreshOptions) => Promise<void>;
   lastRefreshTime: Date | null;
+  /** The last fetch found GitHub unreachable and served cached PRs */
+  offline: boolean;
 }
 
 export function usePRs(options: UsePRsOptions = {}): UsePRsReturn {
@@ -30,6 +35,8 @@ export function usePRs(options: UsePRsOptions = {}): UsePRsReturn {
   const [isLoading, setIsLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [lastRefreshTime, setLastRefreshTime] = useState<Date | null>(null);
+  const [rateLimitLow, setRateLimitLow] = useState(false);
+  const [offline, setOffline] = useState(false);
 
   const refresh = useCallback(async (options?: boolean | RefreshOptions) => {
     // Handle both old API (boolean) and new API (options object)
@@ -80,7 +87,8 @@ export function usePRs(options: UsePRsOptions = {}): UsePRsReturn {
         }
       }
 
-      setPRs(data);
+      setPRs(data.prs);
+      setOffline(data.offline);
       setLastRefreshTime(new Date());
     } catch (err) {
       let message: string;
@@ -103,28 +111,55 @@ export function usePRs(options: UsePRsOptions = {}): UsePRsReturn {
     refresh({ forceRefresh: false, showVisualFeedback: false });
   }, [refresh]);
 
-  // Auto-refresh (incremental, no visual feedback)
+  // Each fetch cycle reports whether the gh rate limit is running low
+  useEffect(() => {
+    const unlisten = listen<{ low: boolean }>("rate-limit:sampled", (event) => {
+      setRateLimitLow(event.payload.low);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Auto-refresh (incremental, no visual feedback), stretched while the rate limit is low
   useEffect(() => {
     if (!autoRefresh) return;
 
     const interval = setInterval(
       () => refresh({ forceRefresh: false, showVisualFeedback: false }),
-      refreshInterval
+      rateLimitLow ? refreshInterval * LOW_RATE_LIMIT_INTERVAL_FACTOR : refreshInterval
     );
     return () => clearInterval(interval);
-  }, [autoRefresh, refreshInterval, refresh]);
+  }, [autoRefresh, refreshInterval, rateLimitLow, refresh]);
 
-  // Listen for refresh events (e.g., from API when monitor starts, no visual feedback)
+  // Patch cards in place from the backend's batched PR change events
   useEffect(() => {
-    const unlisten = listen("pr:refresh", () => {
-      console.log("PR refresh event received");
-      refresh({ forceRefresh: true, showVisualFeedback: false });
-    });
+    const inScope = (pr: PR) =>
+      repo ? pr.repo === repo : !repos?.length || repos.includes(pr.repo);
+
+    const unlisteners = [
+      listen<PR[]>("pr:added", (event) => {
+        const added = event.payload.filter(inScope);
+        if (added.length === 0) return;
+        const ids = new Set(added.map((pr) => pr.id));
+        setPRs((prev) => [...prev.filter((pr) => !ids.has(pr.id)), ...added]);
+      }),
+      listen<PRUpdate[]>("pr:updated", (event) => {
+        const updates = new Map(event.payload.map((update) => [update.prId, update.changes]));
+        setPRs((prev) =>
+          prev.map((pr) => (updates.has(pr.id) ? { ...pr, ...updates.get(pr.id) } : pr))
+        );
+      }),
+      listen<string[]>("pr:removed", (event) => {
+        const removed = new Set(event.payload);
+        setPRs((prev) => prev.filter((pr) => !removed.has(pr.id)));
+      }),
+    ];
 
     return () => {
-      unlisten.then((fn) => fn());
+      unlisteners.forEach((unlisten) => unlisten.then((fn) => fn()));
     };
-  }, [refresh]);
+  }, [repo, repos]);
 
-  return { prs, isLoading, error, refresh, lastRefreshTime };
+  return { prs, isLoading, error, refresh, lastRefreshTime, offline };
 }
@@ -1,5 +1,5 @@
 import { useState, useEffect, useCallback } from "react";
-import { getRepos, addRepo, removeRepo, getSelectedRepo, setSelectedRepo } from "@/lib/tauri";
+import { getBoardSnapshot, addRepo, removeRepo, setSelectedRepo } from "@/lib/tauri";
 
 interface UseReposReturn {
   repos: string[];
@@ -18,12 +18,10 @@ export function useRepos(): UseReposReturn {
 
   const loadRepos = useCallback(async () => {
     try {
-      const [repoList, current] = await Promise.all([
-        getRepos(),
-        getSelectedRepo().catch(() => ""),
-      ]);
-      setRepos(repoList);
-      setCurrentRepoState(current);
+      // One backend call (one DB lock) instead of a request per field
+      const snapshot = await getBoardSnapshot();
+      setRepos(snapshot.repos);
+      setCurrentRepoState(snapshot.selectedRepo);
     } catch (err) {
       console.error("Failed to load repos:", err);
     } finally {
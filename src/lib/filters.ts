@@ -10,7 +10,7 @@ export interface PRFilters {
 
   // Review & CI
   reviewStatus: "all" | "pending" | "approved" | "changes_requested";
-  ciStatus: "all" | "pending" | "passing" | "failing";
+  ciStatus: "all" | "pending" | "passing" | "failing" | "action_required";
 
   // Search
   search: string;            // Matches title, number, branch
@@ -1,5 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { PR, Monitor, PRComment } from "./types";
+import type { PR, Monitor, PRComment, FetchPRsResult, BoardSnapshot } from "./types";
 
 /**
  * Safe invoke wrapper that handles errors gracefully
@@ -37,8 +37,8 @@ export async function fetchPRs(options?: {
   repo?: string;
   repos?: string[];
   forceRefresh?: boolean;
-}): Promise<PR[]> {
-  return safeInvoke<PR[]>("fetch_prs", {
+}): Promise<FetchPRsResult> {
+  return safeInvoke<FetchPRsResult>("fetch_prs", {
     repo: options?.repo ?? null,
     repos: options?.repos ?? null,
     forceRefresh: options?.forceRefresh ?? false,
@@ -58,6 +58,27 @@ export async function getCachedPRs(options?: {
   });
 }
 
+/**
+ * Repos, selected repo, settings, cached PRs and monitors in one call
+ */
+export async function getBoardSnapshot(): Promise<BoardSnapshot> {
+  return safeInvoke<BoardSnapshot>("get_board_snapshot", {});
+}
+
+/**
+ * Whether the backend runs in demo mode (fixture GitHub data, scripted monitors)
+ */
+export async function isDemoMode(): Promise<boolean> {
+  return invoke<boolean>("is_demo_mode");
+}
+
+/**
+ * Populate the board with the demo repos' PRs (demo mode only)
+ */
+export async function seedDemoData(): Promise<PR[]> {
+  return invoke<PR[]>("seed_demo_data");
+}
+
 /**
  * Clear the PR cache
  * @param repo - Optional repo to clear. If not provided, clears all.
@@ -74,6 +95,25 @@ export async function dismissPR(prId: string): Promise<void> {
   return invoke<void>("dismiss_pr", { prId });
 }
 
+/**
+ * Override a PR's CI status by hand until the override expires
+ * (ttlMinutes, default 30) or is cleared
+ */
+export async function setPRCiOverride(
+  prId: string,
+  status: "passing" | "failing" | "pending" | "action_required",
+  ttlMinutes?: number
+): Promise<{ prId: string; ciStatus: string; until: string }> {
+  return safeInvoke("set_pr_ci_override", { prId, status, ttlMinutes: ttlMinutes ?? null });
+}
+
+/**
+ * Clear a PR's CI status override, restoring the status from GitHub
+ */
+export async function clearPRCiOverride(prId: string): Promise<boolean> {
+  return invoke<boolean>("clear_pr_ci_override", { prId });
+}
+
 // ============ Repo Management Commands ============
 
 /**
@@ -135,6 +175,8 @@ export interface StartMonitorParams {
   repo: string;
   maxIterations?: number;
   intervalMinutes?: number;
+  /** Overrides the global notification settings for this monitor; unset follows them */
+  notificationLevel?: "default" | "all" | "important" | "completion_only" | "silent";
 }
 
 /**
@@ -147,9 +189,17 @@ export async function startMonitor(params: StartMonitorParams): Promise<Monitor>
     repo: params.repo,
     maxIterations: params.maxIterations ?? null,
     intervalMinutes: params.intervalMinutes ?? null,
+    notificationLevel: params.notificationLevel ?? null,
   });
 }
 
+/**
+ * Resume a paused monitor, e.g. one paused after an external push
+ */
+export async function resumeMonitor(monitorId: string): Promise<Monitor> {
+  return invoke<Monitor>("resume_monitor", { monitorId });
+}
+
 /**
  * Stop a running monitor
  */
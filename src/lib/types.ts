@@ -1,5 +1,5 @@
 export type PRState = "open" | "merged" | "closed";
-export type CIStatus = "pending" | "passing" | "failing" | null;
+export type CIStatus = "pending" | "passing" | "failing" | "action_required" | null;
 export type ReviewStatus =
   | "pending"
   | "approved"
@@ -28,6 +28,7 @@ export interface PR {
 
   // CI
   ciStatus: CIStatus;
+  ciOverrideUntil: string | null; // set while ciStatus is a manual override
   ciUrl: string | null;
 
   // Reviews
@@ -49,6 +50,18 @@ export interface PR {
   category: Category;
 }
 
+/** Payload entry of the `pr:updated` event: the changed fields of one PR */
+export interface PRUpdate {
+  prId: string;
+  changes: Partial<PR>;
+}
+
+export interface FetchPRsResult {
+  prs: PR[];
+  /** GitHub was unreachable, so `prs` is the cached data */
+  offline: boolean;
+}
+
 export interface PRComment {
   id: string;
   threadId: string;
@@ -88,4 +101,44 @@ export interface Monitor {
 
   // Logs
   logFile: string;
+
+  // Set on a just-started monitor when there is something to flag (e.g. a fork PR)
+  warning?: string;
+}
+
+export interface UnreadCounts {
+  total: number;
+  byRepo: Record<string, number>;
+  byCategory: Record<string, number>;
+}
+
+export interface FetchStatus {
+  repo: string;
+  lastFetchAt: string | null;
+  lastFetchFailed: boolean;
+  lastError: string | null;
+  lastErrorAt: string | null;
+  stale: boolean;
+}
+
+/** Everything the board shows on startup, read in one backend call */
+export interface BoardSnapshot {
+  repos: string[];
+  /** Empty when the board shows every repo */
+  selectedRepo: string;
+  settings: {
+    theme: string | null;
+    prFilters: unknown;
+    sleepPreventionEnabled: boolean;
+    aiProvider: string;
+    aiModel: string | null;
+    monitorDirtyWorktreePolicy: string;
+    skipCiFix: string;
+    monitorsPaused: boolean;
+    staleDataThresholdMinutes: number;
+  };
+  prs: PR[];
+  activeMonitors: Monitor[];
+  unread: UnreadCounts;
+  fetchStatus: FetchStatus[];
 }
@@ -4,3 +4,12 @@ import { twMerge } from "tailwind-merge";
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs));
 }
+
+/** Bare repo name, with the owner added when another repo shares the name ("api (acme)") */
+export function repoShortName(repo: string, repos: string[]): string {
+  const [owner, name] = repo.includes("/") ? repo.split("/", 2) : ["", repo];
+  const shared = repos.filter(
+    (other) => (other.split("/")[1] ?? other).toLowerCase() === name.toLowerCase()
+  ).length > 1;
+  return shared && owner ? `${name} (${owner})` : name;
+}